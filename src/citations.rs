@@ -0,0 +1,217 @@
+use crate::tools::{Citation, ToolResult};
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::ops::Range;
+
+lazy_static::lazy_static! {
+    static ref MARKER: Regex = Regex::new(r"\[(\d+)\]").unwrap();
+}
+
+/// Source the answer referred to, numbered as in the prompt's source list
+#[derive(Debug, Clone, Serialize)]
+pub struct NumberedCitation {
+    pub number: usize,
+    #[serde(flatten)]
+    pub citation: Citation,
+}
+
+/// Answer with `[n]` markers after the sources it mentions, plus those sources
+pub struct CitedAnswer {
+    pub text: String,
+    pub sources: Vec<NumberedCitation>,
+}
+
+/// Citations from every web search in `results`, in order, each URL once
+pub fn collect_citations(results: &[ToolResult]) -> Vec<Citation> {
+    let mut seen = HashSet::new();
+    results
+        .iter()
+        .filter_map(|result| result.web_search_result.as_ref())
+        .flat_map(|search| search.citations.iter())
+        .filter(|citation| seen.insert(citation.url.trim_end_matches('/').to_string()))
+        .cloned()
+        .collect()
+}
+
+/// Numbered source list for the summary prompt, so the model can cite as `[n]`
+pub fn sources_prompt(citations: &[Citation]) -> String {
+    let mut prompt = String::from("Sources:\n");
+    for (i, citation) in citations.iter().enumerate() {
+        prompt.push_str(&format!("[{}] {} - {}\n", i + 1, citation.title, citation.url));
+    }
+    prompt.push_str(
+        "When a statement comes from one of these sources, cite it with its number in square brackets, like [1].\n",
+    );
+    prompt
+}
+
+/// Where the answer refers to a source: one of the model's own `[n]` markers, or
+/// the first time it mentions the source's URL or domain
+struct Mention {
+    range: Range<usize>,
+    index: usize,
+    explicit: bool,
+}
+
+/// Tie `answer` to `citations`: keeps the model's `[n]` markers, adds one after the
+/// first mention of a cited URL or domain, and lists every source referred to.
+/// An answer that mentions none of them comes back unchanged.
+pub fn cite_answer(answer: &str, citations: &[Citation]) -> CitedAnswer {
+    let code = code_block_ranges(answer);
+    let outside_code = |range: &Range<usize>| !code.iter().any(|block| block.contains(&range.start));
+
+    let mut mentions: Vec<Mention> = MARKER
+        .captures_iter(answer)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            let number: usize = captures[1].parse().ok()?;
+            // `[1](url)` is a Markdown link, not a marker
+            let is_link = answer[whole.end()..].starts_with('(');
+            (1..=citations.len()).contains(&number).then_some(Mention {
+                range: whole.range(),
+                index: number - 1,
+                explicit: true,
+            })
+            .filter(|mention| !is_link && outside_code(&mention.range))
+        })
+        .collect();
+
+    let explicit: HashSet<usize> = mentions.iter().map(|mention| mention.index).collect();
+    let mut claimed_domains = HashSet::new();
+    for (index, citation) in citations.iter().enumerate() {
+        let domain = citation.domain.trim_start_matches("www.").to_lowercase();
+        // Several results from one site: a bare domain mention goes to the first of them
+        let domain_unclaimed = !domain.is_empty() && claimed_domains.insert(domain.clone());
+        if explicit.contains(&index) {
+            continue;
+        }
+
+        let url = citation.url.trim_end_matches('/');
+        let mention = find_all(answer, url)
+            .find(|range| outside_code(range))
+            .or_else(|| {
+                domain_unclaimed
+                    .then(|| find_domain(answer, &domain).find(|range| outside_code(range)))
+                    .flatten()
+            });
+        if let Some(range) = mention {
+            mentions.push(Mention { range, index, explicit: false });
+        }
+    }
+
+    if mentions.is_empty() {
+        return CitedAnswer {
+            text: answer.to_string(),
+            sources: Vec::new(),
+        };
+    }
+
+    // A URL mention covers the domain inside it; keep the earliest, longest one
+    mentions.sort_by_key(|mention| (mention.range.start, std::cmp::Reverse(mention.range.end)));
+    let mut text = String::with_capacity(answer.len() + mentions.len() * 4);
+    let mut copied = 0;
+    let mut cited = HashSet::new();
+    for mention in &mentions {
+        if mention.range.start < copied {
+            continue;
+        }
+        cited.insert(mention.index);
+        if mention.explicit {
+            continue;
+        }
+        let end = after_closing_marks(answer, &mention.range);
+        text.push_str(&answer[copied..end]);
+        text.push_str(&format!(" [{}]", mention.index + 1));
+        copied = end;
+    }
+    text.push_str(&answer[copied..]);
+
+    let mut sources: Vec<NumberedCitation> = cited
+        .into_iter()
+        .map(|index| NumberedCitation {
+            number: index + 1,
+            citation: citations[index].clone(),
+        })
+        .collect();
+    sources.sort_by_key(|source| source.number);
+    CitedAnswer { text, sources }
+}
+
+/// Print the sources an answer cited, after the answer itself
+pub fn print_sources(sources: &[NumberedCitation]) {
+    if sources.is_empty() {
+        return;
+    }
+    println!();
+    println!("{} Sources:", "📚".cyan());
+    for source in sources {
+        println!(
+            "  [{}] {} - {}",
+            source.number,
+            source.citation.title.bold(),
+            source.citation.url.blue()
+        );
+    }
+}
+
+fn find_all<'a>(text: &'a str, needle: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+    text.match_indices(needle)
+        .filter(move |_| !needle.is_empty())
+        .map(|(start, matched)| start..start + matched.len())
+}
+
+/// Case-insensitive `domain` as a whole word, not part of a longer host or a path
+fn find_domain<'a>(text: &'a str, domain: &str) -> impl Iterator<Item = Range<usize>> + 'a {
+    let pattern = Regex::new(&format!("(?i){}", regex::escape(domain))).ok();
+    pattern
+        .into_iter()
+        .flat_map(move |pattern| pattern.find_iter(text).map(|found| found.range()).collect::<Vec<_>>())
+        .filter(move |range| {
+            let before = text[..range.start].chars().next_back();
+            let mut after = text[range.end..].chars();
+            let next = after.next();
+            let starts_word = before.is_none_or(|c| !(c.is_alphanumeric() || ".-/@:".contains(c)));
+            let ends_word = match next {
+                None => true,
+                // A full stop ends the sentence; a dot followed by more text is a longer host
+                Some('.') => after.next().is_none_or(|c| !c.is_alphanumeric()),
+                Some(c) => !(c.is_alphanumeric() || "-/]".contains(c)),
+            };
+            starts_word && ends_word
+        })
+}
+
+/// End of a mention, moved past the `)`, `` ` ``, `*` or quote that closes it, so a
+/// marker doesn't land inside a Markdown link or code span
+fn after_closing_marks(text: &str, range: &Range<usize>) -> usize {
+    let mut end = range.end;
+    for c in text[range.end..].chars() {
+        if !")`*_>\"'".contains(c) {
+            break;
+        }
+        end += c.len_utf8();
+    }
+    end
+}
+
+/// Byte ranges of fenced code blocks, which are never given markers
+fn code_block_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut open = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            match open.take() {
+                Some(start) => ranges.push(start..offset + line.len()),
+                None => open = Some(offset),
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = open {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
@@ -1,20 +1,27 @@
+use crate::error::AgentError;
+use crate::input::{fuzzy_score, fuzzy_select};
+use crate::render::MarkdownStream;
+use crate::thinking::{strip_thinking, Segment, ThinkingSettings, ThinkingSplitter};
+use crate::tools::errors::{send_with_retry, RetryConfig};
 use colored::Colorize;
-use dialoguer::Select;
 use futures::StreamExt as FuturesStreamExt;
+use indicatif::{HumanBytes, HumanDuration, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Write};
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::select;
-use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Deserialize, Debug)]
 pub struct ModelsResponse {
     pub models: Vec<Model>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Model {
     pub name: String,
     pub size: u64,
@@ -24,28 +31,68 @@ pub struct Model {
     pub details: Option<ModelDetails>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ModelDetails {
     pub format: Option<String>,
     pub family: Option<String>,
     pub parameter_size: Option<String>,
+    pub quantization_level: Option<String>,
+}
+
+/// A model's definition as reported by `/api/show`
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ModelInfo {
+    #[serde(default)]
+    pub modelfile: String,
+    /// `PARAMETER` lines of the Modelfile as `name value` pairs, one per line
+    #[serde(default)]
+    pub parameters: String,
+    #[serde(default)]
+    pub template: String,
+    #[serde(default)]
+    pub details: Option<ModelDetails>,
+    #[serde(default)]
+    pub license: String,
+    #[serde(default)]
+    pub system: String,
+    /// Missing on Ollama versions that don't report capabilities
+    #[serde(default)]
+    pub capabilities: Option<Vec<String>>,
+}
+
+impl ModelInfo {
+    /// Parameters as `(name, value)` with quotes removed; repeated names such as
+    /// `stop` appear once per value
+    pub fn parameter_values(&self) -> Vec<(String, String)> {
+        self.parameters
+            .lines()
+            .filter_map(|line| {
+                let (name, value) = line.trim().split_once(char::is_whitespace)?;
+                Some((name.to_string(), value.trim().trim_matches('"').to_string()))
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct SelectedModel {
     pub name: String,
+    pub digest: String,
     pub size_gb: f64,
     pub modified_at: String,
     pub details: Option<ModelDetails>,
+    pub options: OllamaOptions,
 }
 
 impl From<Model> for SelectedModel {
     fn from(model: Model) -> Self {
         SelectedModel {
             name: model.name,
+            digest: model.digest,
             size_gb: model.size as f64 / 1_000_000_000.0,
             modified_at: model.modified_at,
             details: model.details,
+            options: OllamaOptions::default(),
         }
     }
 }
@@ -76,6 +123,11 @@ impl SelectedModel {
         &self.name
     }
 
+    pub fn with_options(mut self, options: OllamaOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     pub fn is_code_model(&self) -> bool {
         self.name.to_lowercase().contains("code")
             || self.name.to_lowercase().contains("codellama")
@@ -92,9 +144,11 @@ pub struct OllamaRequest {
     pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<OllamaOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<KeepAlive>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone, Default)]
 pub struct OllamaOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
@@ -108,139 +162,600 @@ pub struct OllamaOptions {
     pub num_predict: Option<u32>, // max_tokens in Ollama
     #[serde(skip_serializing_if = "Option::is_none")]
     pub num_ctx: Option<u32>, // context_length in Ollama
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Generation stops before any of these strings would be produced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+}
+
+impl OllamaOptions {
+    /// Layer these options over a base set, keeping base values where ours are unset
+    pub fn merged_over(&self, base: &OllamaOptions) -> OllamaOptions {
+        OllamaOptions {
+            temperature: self.temperature.or(base.temperature),
+            top_p: self.top_p.or(base.top_p),
+            top_k: self.top_k.or(base.top_k),
+            repeat_penalty: self.repeat_penalty.or(base.repeat_penalty),
+            num_predict: self.num_predict.or(base.num_predict),
+            num_ctx: self.num_ctx.or(base.num_ctx),
+            seed: self.seed.or(base.seed),
+            stop: self.stop.clone().or_else(|| base.stop.clone()),
+        }
+    }
+}
+
+/// How long Ollama keeps a model loaded after a request: a duration such as `5m`
+/// or `1h30m`, or a number of seconds where `-1` keeps it loaded indefinitely and
+/// `0` unloads it right away
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeepAlive {
+    Duration(String),
+    Seconds(i64),
+}
+
+impl std::fmt::Display for KeepAlive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeepAlive::Duration(duration) => write!(f, "{}", duration),
+            KeepAlive::Seconds(seconds) => write!(f, "{}", seconds),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref KEEP_ALIVE_DURATION: regex::Regex =
+        regex::Regex::new(r"^-?(\d+(\.\d+)?(ns|us|µs|ms|s|m|h))+$").unwrap();
+}
+
+/// Validate a keep-alive value such as `5m`, `-1` or `0`
+pub fn parse_keep_alive(value: &str) -> Result<KeepAlive, String> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<i64>() {
+        return Ok(KeepAlive::Seconds(seconds));
+    }
+    if KEEP_ALIVE_DURATION.is_match(value) {
+        Ok(KeepAlive::Duration(value.to_string()))
+    } else {
+        Err(format!(
+            "'{}' is not a keep-alive value (expected a duration like 5m or 1h, or seconds like -1)",
+            value
+        ))
+    }
+}
+
+// `--keep-alive` if given, else the config file's `keep_alive`; resolved once per run
+// so generate requests don't each re-read the config
+static KEEP_ALIVE: OnceLock<Option<KeepAlive>> = OnceLock::new();
+
+/// `--keep-alive` from the command line; it takes precedence over the config file
+pub fn set_keep_alive_override(keep_alive: Option<KeepAlive>) {
+    if let Some(keep_alive) = keep_alive {
+        let _ = KEEP_ALIVE.set(Some(keep_alive));
+    }
+}
+
+/// Keep-alive to send with generate requests; `None` leaves Ollama's default (5m)
+pub fn keep_alive() -> Option<KeepAlive> {
+    KEEP_ALIVE
+        .get_or_init(|| crate::tools::ToolExecutor::new().get_keep_alive())
+        .clone()
 }
 
 #[derive(Deserialize, Debug)]
 pub struct OllamaResponse {
     pub response: Option<String>,
     pub done: bool,
+    /// `length` when generation hit `num_predict`, `stop` otherwise
+    #[serde(default)]
+    pub done_reason: Option<String>,
     #[serde(default)]
     pub total_duration: Option<u64>,
     #[serde(default)]
     pub eval_count: Option<u32>,
     #[serde(default)]
     pub eval_duration: Option<u64>,
+    #[serde(default)]
+    pub prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    pub prompt_eval_duration: Option<u64>,
+}
+
+/// Token counts and timings Ollama reports in the final chunk of a response
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenUsage {
+    pub requests: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub prompt_eval_ms: u64,
+    pub eval_ms: u64,
+    pub total_ms: u64,
+}
+
+impl TokenUsage {
+    fn from_response(response: &OllamaResponse) -> Self {
+        let ms = |ns: Option<u64>| ns.unwrap_or(0) / 1_000_000;
+        Self {
+            requests: 1,
+            prompt_tokens: response.prompt_eval_count.unwrap_or(0) as u64,
+            completion_tokens: response.eval_count.unwrap_or(0) as u64,
+            prompt_eval_ms: ms(response.prompt_eval_duration),
+            eval_ms: ms(response.eval_duration),
+            total_ms: ms(response.total_duration),
+        }
+    }
+
+    fn add(&mut self, other: &TokenUsage) {
+        self.requests += other.requests;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.prompt_eval_ms += other.prompt_eval_ms;
+        self.eval_ms += other.eval_ms;
+        self.total_ms += other.total_ms;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    /// One-line summary, e.g. `1204 prompt + 356 completion tokens, 2 requests, 4.31s`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} prompt + {} completion tokens, {} request{}, {:.2}s",
+            self.prompt_tokens,
+            self.completion_tokens,
+            self.requests,
+            if self.requests == 1 { "" } else { "s" },
+            self.total_ms as f64 / 1000.0
+        )
+    }
 }
 
-pub async fn fetch_models() -> Result<Vec<Model>, Box<dyn std::error::Error>> {
+lazy_static::lazy_static! {
+    /// Usage of every Ollama generation made by this process
+    static ref USAGE_TOTALS: Mutex<TokenUsage> = Mutex::new(TokenUsage::default());
+}
+
+fn record_usage(response: &OllamaResponse) -> TokenUsage {
+    let usage = TokenUsage::from_response(response);
+    USAGE_TOTALS.lock().unwrap().add(&usage);
+    usage
+}
+
+/// Accumulated token usage since the process started
+pub fn usage_totals() -> TokenUsage {
+    *USAGE_TOTALS.lock().unwrap()
+}
+
+pub async fn fetch_models() -> Result<Vec<Model>, AgentError> {
     let client = Client::new();
     let response = client.get("http://localhost:11434/api/tags").send().await?;
 
     if !response.status().is_success() {
-        return Err(format!("API request failed: {}", response.status()).into());
+        return Err(AgentError::OllamaApi(format!("API request failed: {}", response.status())));
     }
 
     let models_response: ModelsResponse = response.json().await?;
     Ok(models_response.models)
 }
 
-pub fn select_model(models: &[Model]) -> Result<SelectedModel, Box<dyn std::error::Error>> {
+/// Picker line for a model: type, name, size and family
+fn model_label(model: &Model) -> String {
+    let size_gb = model.size as f64 / 1_000_000_000.0;
+    let name = model.name.to_lowercase();
+    let model_type = if name.contains("code") {
+        "📝 Code"
+    } else if name.contains("chat") || name.contains("instruct") {
+        "💬 Chat"
+    } else {
+        "🤖 General"
+    };
+
+    match model.details.as_ref().and_then(|details| details.family.as_deref()) {
+        Some(family) => format!("{} {} ({:.1} GB, {})", model_type, model.name, size_gb, family),
+        None => format!("{} {} ({:.1} GB)", model_type, model.name, size_gb),
+    }
+}
+
+fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Let the user pick a model with a search-as-you-type list. Without a terminal
+/// the first model is used.
+pub fn select_model(models: &[Model]) -> Result<SelectedModel, AgentError> {
+    pick_model(models, "")
+}
+
+fn pick_model(models: &[Model], query: &str) -> Result<SelectedModel, AgentError> {
     if models.is_empty() {
-        return Err("No models available".into());
+        return Err(AgentError::ModelSelection("No models available".to_string()));
+    }
+
+    if !is_interactive() {
+        let model = &models[0];
+        eprintln!("{} No terminal for model selection, using '{}'", "ℹ️".blue(), model.name);
+        return Ok(SelectedModel::from(model.clone()));
     }
 
-    let model_options: Vec<String> = models
+    let labels: Vec<String> = models.iter().map(model_label).collect();
+    match fuzzy_select("Select a model", &labels, query)? {
+        Some(index) => Ok(SelectedModel::from(models[index].clone())),
+        None => Err(AgentError::ModelSelection("Model selection cancelled".to_string())),
+    }
+}
+
+/// Models matching `query`: an exact name (`:latest` may be omitted), else the single
+/// best fuzzy match, else every fuzzy match best first when the top scores tie.
+pub fn match_models<'a>(models: &'a [Model], query: &str) -> Vec<&'a Model> {
+    let latest = format!("{}:latest", query);
+    if let Some(model) = models
         .iter()
-        .map(|model| {
-            let size_gb = model.size as f64 / 1_000_000_000.0;
-            let model_type = if model.name.to_lowercase().contains("code") {
-                "📝 Code"
-            } else if model.name.to_lowercase().contains("chat")
-                || model.name.to_lowercase().contains("instruct")
-            {
-                "💬 Chat"
-            } else {
-                "🤖 General"
-            };
+        .find(|m| m.name.eq_ignore_ascii_case(query) || m.name.eq_ignore_ascii_case(&latest))
+    {
+        return vec![model];
+    }
 
-            format!("{} {} ({:.1} GB)", model_type, model.name, size_gb)
-        })
+    let mut scored: Vec<(i64, &Model)> = models
+        .iter()
+        .filter_map(|model| fuzzy_score(query, &model.name).map(|score| (score, model)))
         .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    if let [(best, model), (next, _), ..] = scored.as_slice() {
+        if best > next {
+            return vec![*model];
+        }
+    }
+    scored.into_iter().map(|(_, model)| model).collect()
+}
+
+/// Resolve a `--model` value with [`match_models`]. Ambiguous names open the picker
+/// on a terminal and are an error otherwise.
+pub fn find_model(models: &[Model], query: &str) -> Result<SelectedModel, AgentError> {
+    match match_models(models, query).as_slice() {
+        [] => Err(AgentError::ModelNotFound(query.to_string())),
+        [model] => Ok(SelectedModel::from((*model).clone())),
+        _ if is_interactive() => pick_model(models, query),
+        matches => {
+            let names: Vec<&str> = matches.iter().map(|model| model.name.as_str()).collect();
+            Err(AgentError::ModelSelection(format!(
+                "Multiple models match '{}' ({}), please be more specific",
+                query,
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Model for a command: `name` resolved with [`find_model`], else the configured
+/// `default_model`, else the picker. With `--pull-if-missing` or `pull_if_missing` set
+/// the name must match exactly instead, and is pulled when nothing does, so that asking
+/// for `llama3:70b` doesn't quietly run an installed `llama3:8b`.
+pub async fn resolve_model(name: Option<String>) -> Result<SelectedModel, AgentError> {
+    let models = fetch_models().await?;
+    match name.or_else(|| crate::tools::ToolExecutor::new().get_default_model()) {
+        Some(name) if pull_if_missing() => {
+            if let Some(model) = installed_exactly(&models, &name) {
+                return Ok(SelectedModel::from(model.clone()));
+            }
+            pull_missing_model(&name).await?;
+            installed_exactly(&fetch_models().await?, &name)
+                .map(|model| SelectedModel::from(model.clone()))
+                .ok_or(AgentError::ModelNotFound(name))
+        }
+        Some(name) => find_model(&models, &name),
+        None => select_model(&models),
+    }
+}
 
-    let selection = Select::new()
-        .with_prompt("Select a model")
-        .items(&model_options)
-        .default(0)
-        .interact()?;
+/// The installed model called exactly `name`, where a name without a tag means `:latest`
+fn installed_exactly<'a>(models: &'a [Model], name: &str) -> Option<&'a Model> {
+    let tagged = if name.contains(':') { name.to_string() } else { format!("{}:latest", name) };
+    models.iter().find(|model| model.name == name || model.name == tagged)
+}
+
+static PULL_IF_MISSING: AtomicBool = AtomicBool::new(false);
 
-    Ok(SelectedModel::from(models[selection].clone()))
+/// `--pull-if-missing`: download a requested model that isn't installed
+pub fn set_pull_if_missing(pull: bool) {
+    PULL_IF_MISSING.store(pull, Ordering::Relaxed);
+}
+
+fn pull_if_missing() -> bool {
+    PULL_IF_MISSING.load(Ordering::Relaxed) || crate::tools::ToolExecutor::new().get_pull_if_missing()
+}
+
+/// Pull a model that isn't installed, asking first on a terminal since models are often several GB
+async fn pull_missing_model(name: &str) -> Result<(), AgentError> {
+    if is_interactive() {
+        use dialoguer::{theme::ColorfulTheme, Confirm};
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "{} Model '{}' is not installed. Download it now? This can be several GB",
+                "⬇️".cyan(),
+                name
+            ))
+            .default(true)
+            .interact()
+            .map_err(|dialoguer::Error::IO(e)| e)?;
+        if !confirmed {
+            return Err(AgentError::ModelNotFound(name.to_string()));
+        }
+    } else {
+        eprintln!("{} Model '{}' is not installed, pulling it", "ℹ️".blue(), name);
+    }
+    pull_model(name).await
 }
 
 pub async fn generate_response_silent(
     model: &SelectedModel,
     prompt: &str,
-) -> Result<String, Box<dyn std::error::Error>> {
+) -> Result<String, AgentError> {
     let client = Client::new();
 
     // Use enhanced request with current model configuration but without streaming
-    let request =
-        crate::tools::model_config::create_enhanced_request(model.get_name(), prompt, false);
-
-    let response = client
-        .post("http://localhost:11434/api/generate")
-        .json(&request)
-        .send()
-        .await?;
+    let mut request =
+        crate::tools::model_config::create_enhanced_request(model.get_name(), prompt, false, &model.options);
+    // --max-tokens and --stop shape answers for the user; cutting an internal call
+    // (tool planning, summaries, checks) short only leaves something unparseable
+    request.options.num_predict = None;
+    request.options.stop = None;
+
+    let response = send_with_retry(&ollama_retry_config(), Some("Ollama request"), |_| {
+        client.post("http://localhost:11434/api/generate").json(&request)
+    })
+    .await?;
 
     if !response.status().is_success() {
-        return Err(format!("API request failed: {}", response.status()).into());
+        return Err(generation_error(response, model.get_name()).await);
     }
 
     let response_text = response.text().await?;
     let ollama_response: OllamaResponse = serde_json::from_str(&response_text)?;
-    
-    Ok(ollama_response.response.unwrap_or_default())
+    record_usage(&ollama_response);
+    report_truncation(&ollama_response, None);
+
+    // Callers parse the answer, so a reasoning model's thinking never reaches them
+    let answer = ollama_response.response.unwrap_or_default();
+    Ok(strip_thinking(&answer, &thinking_settings().tags))
+}
+
+fn ollama_retry_config() -> RetryConfig {
+    crate::tools::ToolExecutor::new().get_ollama_retry_config()
+}
+
+lazy_static::lazy_static! {
+    static ref ACTIVE_GENERATION: Mutex<Option<CancellationToken>> = Mutex::new(None);
+    static ref IDLE_INTERRUPT: tokio::sync::Notify = tokio::sync::Notify::new();
+}
+
+static INTERRUPT_HANDLER: Once = Once::new();
+static EXIT_ON_IDLE_INTERRUPT: AtomicBool = AtomicBool::new(true);
+
+/// Install the process-wide Ctrl+C handler once. While a response is being
+/// generated it cancels that generation; otherwise it exits as usual, or wakes
+/// [`idle_interrupt`] once something waits on it.
+fn install_interrupt_handler() {
+    INTERRUPT_HANDLER.call_once(|| {
+        tokio::spawn(async {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                let active = ACTIVE_GENERATION.lock().unwrap().clone();
+                match active {
+                    Some(token) => token.cancel(),
+                    None if EXIT_ON_IDLE_INTERRUPT.load(Ordering::Relaxed) => {
+                        println!();
+                        std::process::exit(130);
+                    }
+                    None => IDLE_INTERRUPT.notify_one(),
+                }
+            }
+        });
+    });
+}
+
+/// Wait for a Ctrl+C pressed while no response is being generated. From the first
+/// call on, such a Ctrl+C no longer exits the process, so the caller can stop cleanly;
+/// Ctrl+C during a generation still only cancels that response.
+pub async fn idle_interrupt() {
+    EXIT_ON_IDLE_INTERRUPT.store(false, Ordering::Relaxed);
+    install_interrupt_handler();
+    IDLE_INTERRUPT.notified().await;
+}
+
+/// Registers the current generation for Ctrl+C cancellation until dropped
+struct ActiveGeneration {
+    token: CancellationToken,
+}
+
+impl ActiveGeneration {
+    fn start() -> Self {
+        install_interrupt_handler();
+        let token = CancellationToken::new();
+        *ACTIVE_GENERATION.lock().unwrap() = Some(token.clone());
+        Self { token }
+    }
+}
+
+impl Drop for ActiveGeneration {
+    fn drop(&mut self) {
+        *ACTIVE_GENERATION.lock().unwrap() = None;
+    }
+}
+
+static STREAMING_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// `--no-stream`: print each response once it is complete instead of token by token
+pub fn set_streaming_disabled(disabled: bool) {
+    STREAMING_DISABLED.store(disabled, Ordering::Relaxed);
+}
+
+static SHOW_THINKING: AtomicBool = AtomicBool::new(false);
+
+/// `--show-thinking`: print reasoning models' thinking even when the config hides it
+pub fn set_show_thinking(show: bool) {
+    SHOW_THINKING.store(show, Ordering::Relaxed);
+}
+
+/// Thinking settings from the config, with `--show-thinking` applied
+pub fn thinking_settings() -> ThinkingSettings {
+    let mut settings = crate::tools::ToolExecutor::new().get_thinking_settings();
+    settings.show |= SHOW_THINKING.load(Ordering::Relaxed);
+    settings
+}
+
+/// Prints a response: the answer as Markdown, thinking blocks as a dim
+/// placeholder or, when shown, as dim text
+pub struct ResponseOutput {
+    markdown: MarkdownStream,
+    splitter: ThinkingSplitter,
+    show_thinking: bool,
+    /// A live "thinking…" line is on screen and needs clearing
+    placeholder: bool,
+}
+
+impl ResponseOutput {
+    pub fn new() -> Self {
+        let settings = thinking_settings();
+        Self {
+            markdown: MarkdownStream::new(),
+            splitter: ThinkingSplitter::new(settings.tags),
+            show_thinking: settings.show,
+            placeholder: false,
+        }
+    }
+
+    pub fn push(&mut self, token: &str) {
+        for segment in self.splitter.push(token) {
+            self.render(segment);
+        }
+    }
+
+    pub fn finish(&mut self) {
+        for segment in self.splitter.finish() {
+            self.render(segment);
+        }
+        self.clear_placeholder();
+        self.markdown.finish();
+    }
+
+    fn render(&mut self, segment: Segment) {
+        match segment {
+            Segment::Answer(text) => self.markdown.push(&text),
+            Segment::Thinking(text) if self.show_thinking => {
+                print!("{}", text.dimmed());
+                io::stdout().flush().ok();
+            }
+            Segment::Thinking(_) => {}
+            Segment::ThinkingStarted => {
+                self.markdown.finish();
+                if self.show_thinking {
+                    println!("{}", "💭 thinking:".dimmed());
+                } else if crate::output::live_progress() && io::stdout().is_terminal() {
+                    print!("{}", "💭 thinking…".dimmed());
+                    io::stdout().flush().ok();
+                    self.placeholder = true;
+                } else {
+                    println!("{}", "💭 thinking…".dimmed());
+                }
+            }
+            Segment::ThinkingEnded => {
+                if self.show_thinking {
+                    println!();
+                    println!("{}", "─".repeat(40).dimmed());
+                }
+                self.clear_placeholder();
+            }
+        }
+    }
+
+    fn clear_placeholder(&mut self) {
+        if self.placeholder {
+            print!("\r\x1b[2K");
+            io::stdout().flush().ok();
+            self.placeholder = false;
+        }
+    }
+}
+
+impl Default for ResponseOutput {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub async fn stream_response(
     model: &SelectedModel,
     prompt: &str,
-) -> Result<String, Box<dyn std::error::Error>> {
+) -> Result<String, AgentError> {
     let client = Client::new();
+    let generation = ActiveGeneration::start();
+    let streaming = !STREAMING_DISABLED.load(Ordering::Relaxed);
 
     // Use enhanced request with current model configuration
     let request =
-        crate::tools::model_config::create_enhanced_request(model.get_name(), prompt, true);
+        crate::tools::model_config::create_enhanced_request(model.get_name(), prompt, streaming, &model.options);
 
-    let response = client
-        .post("http://localhost:11434/api/generate")
-        .json(&request)
-        .send()
-        .await?;
+    println!("{}", "Press Ctrl+C to stop response generation...".dimmed());
+
+    let spinner = WaitSpinner::start(&client, model.get_name()).await;
+
+    let retry_config = ollama_retry_config();
+    let response = select! {
+        _ = generation.token.cancelled() => {
+            spinner.finish();
+            println!("{}", "(cancelled)".yellow());
+            return Ok(String::new());
+        }
+        response = send_with_retry(&retry_config, Some("Ollama request"), |_| {
+            client.post("http://localhost:11434/api/generate").json(&request)
+        }) => response?,
+    };
 
     if !response.status().is_success() {
-        return Err(format!("API request failed: {}", response.status()).into());
+        spinner.finish();
+        return Err(generation_error(response, model.get_name()).await);
     }
 
-    let mut stream = response.bytes_stream();
-    let mut full_response = String::new();
     let mut stats = ResponseStats::new();
+    let mut printer = ResponseOutput::new();
+
+    if !streaming {
+        let body = select! {
+            _ = generation.token.cancelled() => {
+                spinner.finish();
+                println!("{}", "(cancelled)".yellow());
+                return Ok(String::new());
+            }
+            body = response.text() => body?,
+        };
+        let ollama_response: OllamaResponse = serde_json::from_str(&body)?;
+        let full_response = ollama_response.response.clone().unwrap_or_default();
+        spinner.finish();
+        printer.push(&full_response);
+        printer.finish();
+        println!();
+        report_completion(&ollama_response, request.options.num_predict, &mut stats);
+        return Ok(full_response);
+    }
 
-    // Setup interrupt handling
-    let interrupted = Arc::new(AtomicBool::new(false));
-    let interrupted_clone = interrupted.clone();
-    
-    // Setup Ctrl+C handler
-    let _handle = tokio::spawn(async move {
-        let _ = tokio::signal::ctrl_c().await;
-        interrupted_clone.store(true, Ordering::Relaxed);
-    });
-
-    println!("{}", "Press Ctrl+C to stop response generation...".dimmed());
+    let mut stream = response.bytes_stream();
+    let mut full_response = String::new();
 
     loop {
-        // Check for interrupt before processing
-        if interrupted.load(Ordering::Relaxed) {
-            println!();
-            println!("{}", "Response generation stopped by user".yellow());
-            if !full_response.is_empty() {
-                println!(); // New line after response
-                stats.print_stats();
-            }
-            return Ok(full_response);
-        }
-
         select! {
+            // Dropping the stream closes the connection, which stops generation in Ollama
+            _ = generation.token.cancelled() => {
+                spinner.finish();
+                printer.finish();
+                println!();
+                println!("{}", "(cancelled)".yellow());
+                if !full_response.is_empty() {
+                    stats.print_stats();
+                }
+                return Ok(full_response);
+            }
             chunk_result = FuturesStreamExt::next(&mut stream) => {
                 match chunk_result {
                     Some(Ok(chunk)) => {
@@ -251,41 +766,20 @@ pub async fn stream_response(
                                 continue;
                             }
 
-                            // Check for interrupt during line processing
-                            if interrupted.load(Ordering::Relaxed) {
-                                println!();
-                                println!("{}", "Response generation stopped by user".yellow());
-                                if !full_response.is_empty() {
-                                    println!(); // New line after response
-                                    stats.print_stats();
-                                }
-                                return Ok(full_response);
-                            }
-
                             match serde_json::from_str::<OllamaResponse>(line) {
                                 Ok(ollama_response) => {
-                                    if let Some(token) = ollama_response.response {
-                                        print!("{}", token);
-                                        io::stdout().flush().unwrap();
-                                        full_response.push_str(&token);
+                                    if let Some(token) = ollama_response.response.as_deref() {
+                                        spinner.finish();
+                                        printer.push(token);
+                                        full_response.push_str(token);
                                         stats.tokens_generated += 1;
                                     }
 
                                     if ollama_response.done {
-                                        // Extract performance statistics
-                                        if let Some(total_duration) = ollama_response.total_duration {
-                                            stats.total_duration_ns = total_duration;
-                                        }
-                                        if let Some(eval_count) = ollama_response.eval_count {
-                                            stats.eval_count = eval_count;
-                                        }
-                                        if let Some(eval_duration) = ollama_response.eval_duration {
-                                            stats.eval_duration_ns = eval_duration;
-                                        }
-
-                                        // Print performance stats
+                                        spinner.finish();
+                                        printer.finish();
                                         println!(); // New line after response
-                                        stats.print_stats();
+                                        report_completion(&ollama_response, request.options.num_predict, &mut stats);
                                         return Ok(full_response);
                                     }
                                 }
@@ -297,16 +791,131 @@ pub async fn stream_response(
                     None => break,
                 }
             }
-            _ = sleep(Duration::from_millis(50)) => {
-                // Check interrupt more frequently
-                continue;
-            }
         }
     }
 
+    printer.finish();
     Ok(full_response)
 }
 
+/// Record usage from the final response of a generation and print its performance stats
+fn report_completion(ollama_response: &OllamaResponse, num_predict: Option<u32>, stats: &mut ResponseStats) {
+    if let Some(total_duration) = ollama_response.total_duration {
+        stats.total_duration_ns = total_duration;
+    }
+    if let Some(eval_count) = ollama_response.eval_count {
+        stats.eval_count = eval_count;
+    }
+    if let Some(eval_duration) = ollama_response.eval_duration {
+        stats.eval_duration_ns = eval_duration;
+    }
+    stats.prompt_eval_count = record_usage(ollama_response).prompt_tokens as u32;
+
+    report_truncation(ollama_response, num_predict);
+    stats.print_stats();
+}
+
+/// Say so when generation stopped at the token limit rather than at the end of the answer
+fn report_truncation(ollama_response: &OllamaResponse, num_predict: Option<u32>) {
+    if ollama_response.done_reason.as_deref() == Some("length") {
+        let limit = num_predict.or(ollama_response.eval_count).unwrap_or_default();
+        println!("{}", format!("(output truncated at {} tokens)", limit).yellow());
+    }
+}
+
+/// Elapsed-time spinner covering the wait between sending a prompt and its first token
+struct WaitSpinner {
+    bar: ProgressBar,
+    // Polls `/api/ps` while the model loads to switch the message to "Generating"
+    load_watch: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl WaitSpinner {
+    async fn start(client: &Client, model: &str) -> Self {
+        // Only on an interactive terminal; JSON mode sends stdout to stderr
+        let interactive = io::stdout().is_terminal()
+            && crate::output::live_progress()
+            && !crate::output::json_output();
+        if !interactive {
+            return Self { bar: ProgressBar::hidden(), load_watch: None };
+        }
+
+        let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr());
+        bar.set_style(
+            ProgressStyle::with_template("{spinner:.cyan} {msg} {elapsed:.dim}")
+                .unwrap()
+                .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "),
+        );
+        bar.enable_steady_tick(Duration::from_millis(100));
+
+        let load_watch = match model_loaded(client, model).await {
+            Some(false) => {
+                bar.set_message(format!("Loading model {}...", model));
+                let (bar, client, model) = (bar.clone(), client.clone(), model.to_string());
+                Some(tokio::spawn(async move {
+                    loop {
+                        tokio::time::sleep(Duration::from_millis(500)).await;
+                        if model_loaded(&client, &model).await != Some(false) {
+                            bar.set_message("Generating...");
+                            return;
+                        }
+                    }
+                }))
+            }
+            _ => {
+                bar.set_message("Generating...");
+                None
+            }
+        };
+
+        Self { bar, load_watch }
+    }
+
+    fn finish(&self) {
+        if let Some(watch) = &self.load_watch {
+            watch.abort();
+        }
+        if !self.bar.is_finished() {
+            self.bar.finish_and_clear();
+        }
+    }
+}
+
+impl Drop for WaitSpinner {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[derive(Deserialize)]
+struct RunningModels {
+    #[serde(default)]
+    models: Vec<RunningModel>,
+}
+
+#[derive(Deserialize)]
+struct RunningModel {
+    name: String,
+    #[serde(default)]
+    model: String,
+}
+
+/// Whether Ollama has `model` in memory; `None` if `/api/ps` can't tell (older servers)
+async fn model_loaded(client: &Client, model: &str) -> Option<bool> {
+    let response = client
+        .get("http://localhost:11434/api/ps")
+        .timeout(Duration::from_secs(1))
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let running: RunningModels = response.json().await.ok()?;
+    let tagged = |name: &str| if name.contains(':') { name.to_string() } else { format!("{}:latest", name) };
+    let wanted = tagged(model);
+    Some(running.models.iter().any(|m| tagged(&m.name) == wanted || tagged(&m.model) == wanted))
+}
 
 #[derive(Debug)]
 struct ResponseStats {
@@ -314,6 +923,7 @@ struct ResponseStats {
     total_duration_ns: u64,
     eval_count: u32,
     eval_duration_ns: u64,
+    prompt_eval_count: u32,
 }
 
 impl ResponseStats {
@@ -323,6 +933,7 @@ impl ResponseStats {
             total_duration_ns: 0,
             eval_count: 0,
             eval_duration_ns: 0,
+            prompt_eval_count: 0,
         }
     }
 
@@ -338,6 +949,9 @@ impl ResponseStats {
             println!();
             println!("{}", "Performance Stats:".dimmed());
             println!("  {} {:.2}s", "Total time:".dimmed(), total_seconds);
+            if self.prompt_eval_count > 0 {
+                println!("  {} {}", "Prompt tokens:".dimmed(), self.prompt_eval_count);
+            }
             println!("  {} {}", "Tokens generated:".dimmed(), self.eval_count);
             if tokens_per_second > 0.0 {
                 println!("  {} {:.1} tokens/s", "Speed:".dimmed(), tokens_per_second);
@@ -347,48 +961,450 @@ impl ResponseStats {
 }
 
 // Model management functions
-pub async fn pull_model(model_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// Per-layer progress bars for `ollama pull`, plus an overall bar kept last. Without a
+/// terminal (or in CI mode) the bars stay hidden and progress is logged as plain lines.
+struct PullProgress {
+    multi: MultiProgress,
+    overall: Option<ProgressBar>,
+    layers: HashMap<String, ProgressBar>,
+    last_logged: Option<(u64, Instant)>,
+}
+
+/// Hidden progress is logged every this many percent, or after this long without a line
+const PULL_LOG_STEP_PERCENT: u64 = 10;
+const PULL_LOG_INTERVAL: Duration = Duration::from_secs(15);
+
+impl PullProgress {
+    fn new() -> Self {
+        let multi = if crate::output::live_progress() {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        };
+        Self {
+            multi,
+            overall: None,
+            layers: HashMap::new(),
+            last_logged: None,
+        }
+    }
+
+    /// Status lines go above the bars, or straight to stdout when bars are hidden
+    fn message(&self, message: &str) {
+        if self.multi.is_hidden() {
+            println!("  {}", message);
+        } else {
+            let _ = self.multi.println(format!("  {}", message));
+        }
+    }
+
+    fn downloaded(&self) -> u64 {
+        self.layers.values().map(ProgressBar::position).sum()
+    }
+
+    fn total(&self) -> u64 {
+        self.layers.values().filter_map(ProgressBar::length).sum()
+    }
+
+    fn update(&mut self, digest: &str, total: u64, completed: u64) {
+        let overall = self.overall.get_or_insert_with(|| {
+            let bar = self.multi.add(ProgressBar::new(0));
+            bar.set_style(
+                ProgressStyle::with_template(
+                    "{prefix:>14.bold} [{bar:30.green/white}] {percent:>3}% {binary_bytes_per_sec} ETA {eta}",
+                )
+                .unwrap()
+                .progress_chars("=> "),
+            );
+            bar.set_prefix("total");
+            bar
+        });
+
+        let bar = self.layers.entry(digest.to_string()).or_insert_with(|| {
+            let bar = self.multi.insert_before(overall, ProgressBar::new(total));
+            bar.set_style(
+                ProgressStyle::with_template(
+                    "{prefix:>14.cyan} [{bar:30.cyan/blue}] {bytes}/{total_bytes} {binary_bytes_per_sec}",
+                )
+                .unwrap()
+                .progress_chars("=> "),
+            );
+            let short = digest.trim_start_matches("sha256:");
+            bar.set_prefix(short[..short.len().min(12)].to_string());
+            bar
+        });
+        bar.set_length(total);
+        bar.set_position(completed.min(total));
+        if completed >= total && !bar.is_finished() {
+            bar.finish();
+        }
+
+        let (downloaded, total) = (self.downloaded(), self.total());
+        if let Some(overall) = &self.overall {
+            overall.set_length(total);
+            overall.set_position(downloaded);
+        }
+        if self.multi.is_hidden() {
+            self.log_progress(downloaded, total);
+        }
+    }
+
+    fn log_progress(&mut self, downloaded: u64, total: u64) {
+        let percent = (downloaded * 100).checked_div(total).unwrap_or(0);
+        let due = match self.last_logged {
+            None => true,
+            Some((last_percent, at)) => {
+                percent >= last_percent + PULL_LOG_STEP_PERCENT
+                    || (percent == 100 && last_percent < 100)
+                    || (percent != last_percent && at.elapsed() >= PULL_LOG_INTERVAL)
+            }
+        };
+        if due {
+            println!("  pulling: {}% ({}/{})", percent, HumanBytes(downloaded), HumanBytes(total));
+            self.last_logged = Some((percent, Instant::now()));
+        }
+    }
+
+    fn finish(&self) {
+        for bar in self.layers.values() {
+            bar.finish();
+        }
+        if let Some(overall) = &self.overall {
+            overall.finish();
+        }
+    }
+}
+
+/// How a single pull request ended
+enum PullOutcome {
+    Done,
+    /// The connection dropped before Ollama reported success
+    Interrupted(String),
+}
+
+/// Pull a model with progress bars. If the connection drops the pull is requested
+/// again; Ollama keeps partially downloaded layers, so it resumes where it stopped.
+pub async fn pull_model(model_name: &str) -> Result<(), AgentError> {
     println!("{} Pulling model: {}", "⬇️".cyan(), model_name.yellow());
 
     let client = Client::new();
-    let request = serde_json::json!({
-        "name": model_name
-    });
+    let retry = ollama_retry_config();
+    let started = Instant::now();
+    let mut progress = PullProgress::new();
+    let mut attempt = 0;
 
-    let response = client
-        .post("http://localhost:11434/api/pull")
-        .json(&request)
-        .send()
-        .await?;
+    loop {
+        let downloaded_before = progress.downloaded();
+        match pull_stream(&client, model_name, &retry, &mut progress).await? {
+            PullOutcome::Done => break,
+            PullOutcome::Interrupted(reason) => {
+                // Only consecutive attempts without progress count towards the limit
+                if progress.downloaded() > downloaded_before {
+                    attempt = 0;
+                }
+                if attempt >= retry.max_retries {
+                    progress.finish();
+                    return Err(AgentError::OllamaConnection(format!("pull interrupted: {}", reason)));
+                }
+                attempt += 1;
+                progress.message(&format!(
+                    "{} Connection lost ({}), resuming ({}/{})…",
+                    "⚠️".yellow(),
+                    reason,
+                    attempt,
+                    retry.max_retries
+                ));
+                tokio::time::sleep(retry.delay_for(attempt)).await;
+            }
+        }
+    }
+
+    progress.finish();
+    println!(
+        "{} Model pulled successfully: {} in {}",
+        "✅".green(),
+        HumanBytes(progress.total()),
+        HumanDuration(started.elapsed())
+    );
+    Ok(())
+}
+
+async fn pull_stream(
+    client: &Client,
+    model_name: &str,
+    retry: &RetryConfig,
+    progress: &mut PullProgress,
+) -> Result<PullOutcome, AgentError> {
+    let request = serde_json::json!({ "name": model_name });
+    let response = match send_with_retry(retry, Some("Pull request"), |_| {
+        client.post("http://localhost:11434/api/pull").json(&request)
+    })
+    .await
+    {
+        Ok(response) => response,
+        Err(e) if e.is_connect() || e.is_timeout() => return Ok(PullOutcome::Interrupted(e.to_string())),
+        Err(e) => return Err(e.into()),
+    };
 
     if !response.status().is_success() {
-        return Err(format!("Failed to pull model: {}", response.status()).into());
+        return Err(AgentError::OllamaApi(format!("Failed to pull model: {}", error_detail(response).await)));
     }
 
-    // Handle streaming pull response
+    // Lines can be split across chunks, so only parse complete ones
     let mut stream = response.bytes_stream();
-    while let Some(chunk_result) = FuturesStreamExt::next(&mut stream).await {
-        let chunk = chunk_result?;
-        let text = String::from_utf8_lossy(&chunk);
-
-        for line in text.lines() {
-            if line.trim().is_empty() {
+    let mut pending = String::new();
+    while let Some(chunk) = FuturesStreamExt::next(&mut stream).await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => return Ok(PullOutcome::Interrupted(e.to_string())),
+        };
+        pending.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(newline) = pending.find('\n') {
+            let line: String = pending.drain(..=newline).collect();
+            let Ok(event) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
                 continue;
+            };
+
+            if let Some(error) = event["error"].as_str() {
+                return Err(AgentError::OllamaApi(format!("Failed to pull model: {}", error)));
+            }
+            let status = event["status"].as_str().unwrap_or_default();
+            match (event["digest"].as_str(), event["total"].as_u64()) {
+                (Some(digest), Some(total)) => {
+                    progress.update(digest, total, event["completed"].as_u64().unwrap_or(0));
+                }
+                _ if status == "success" => return Ok(PullOutcome::Done),
+                _ if !status.is_empty() => progress.message(&status.blue().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(PullOutcome::Interrupted("stream ended before the pull finished".to_string()))
+}
+
+/// Status code plus Ollama's `error` message (or the raw body) of a failed response
+async fn error_detail(response: reqwest::Response) -> String {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let detail = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json["error"].as_str().map(str::to_string))
+        .unwrap_or(body);
+    format!("{} {}", status, detail.trim())
+}
+
+/// Error for a failed generate/embeddings request; Ollama answers 404 when the model isn't installed
+async fn generation_error(response: reqwest::Response, model: &str) -> AgentError {
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return AgentError::ModelNotFound(model.to_string());
+    }
+    AgentError::OllamaApi(format!("API request failed: {}", error_detail(response).await))
+}
+
+/// Turn a Modelfile into the fields `/api/create` takes (`from`, `system`,
+/// `template`, `parameters`, ...). Only `FROM <model>` is supported; weights from
+/// local files have to be uploaded with `ollama create`.
+pub fn parse_modelfile(modelfile: &str) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    use serde_json::{json, Map, Value};
+
+    let mut fields = Map::new();
+    let mut parameters = Map::new();
+    let mut messages = Vec::new();
+    let mut lines = modelfile.lines().enumerate();
+
+    while let Some((number, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (instruction, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let mut rest = rest.trim().to_string();
+
+        // """ quotes a value that may span several lines
+        if let Some(opened) = rest.strip_prefix("\"\"\"") {
+            let mut parts = vec![opened.to_string()];
+            while !parts.last().is_some_and(|part| part.ends_with("\"\"\"")) {
+                let (_, next) = lines
+                    .next()
+                    .ok_or_else(|| format!("Line {}: unterminated \"\"\" value", number + 1))?;
+                parts.push(next.to_string());
+            }
+            let value = parts.join("\n");
+            rest = value[..value.len() - 3].trim_start_matches('\n').to_string();
+        } else if rest.len() >= 2 && rest.starts_with('"') && rest.ends_with('"') {
+            rest = rest[1..rest.len() - 1].to_string();
+        }
+
+        match instruction.to_uppercase().as_str() {
+            "FROM" => {
+                let path = std::path::Path::new(&rest);
+                if rest.starts_with('.') || rest.starts_with('/') || rest.starts_with('~') || path.exists() {
+                    return Err(format!(
+                        "Line {}: FROM {} points at local weights; create it with `ollama create` instead",
+                        number + 1,
+                        rest
+                    ));
+                }
+                fields.insert("from".to_string(), json!(rest));
+            }
+            "SYSTEM" => {
+                fields.insert("system".to_string(), json!(rest));
+            }
+            "TEMPLATE" => {
+                fields.insert("template".to_string(), json!(rest));
+            }
+            "LICENSE" => {
+                fields.insert("license".to_string(), json!(rest));
+            }
+            "PARAMETER" => {
+                let (key, value) = rest
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| format!("Line {}: PARAMETER needs a name and a value", number + 1))?;
+                let value = value.trim().trim_matches('"');
+                let value = value
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .or_else(|_| value.parse::<f64>().map(Value::from))
+                    .or_else(|_| value.parse::<bool>().map(Value::from))
+                    .unwrap_or_else(|_| Value::from(value));
+                // `stop` may be given several times
+                if key == "stop" {
+                    let stops = parameters.entry("stop").or_insert_with(|| json!([]));
+                    if let Some(stops) = stops.as_array_mut() {
+                        stops.push(value);
+                    }
+                } else {
+                    parameters.insert(key.to_string(), value);
+                }
             }
+            "MESSAGE" => {
+                let (role, content) = rest
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| format!("Line {}: MESSAGE needs a role and content", number + 1))?;
+                messages.push(json!({ "role": role, "content": content.trim() }));
+            }
+            "ADAPTER" => {
+                return Err(format!(
+                    "Line {}: ADAPTER needs a local file upload; create it with `ollama create` instead",
+                    number + 1
+                ));
+            }
+            other => return Err(format!("Line {}: unknown instruction {}", number + 1, other)),
+        }
+    }
+
+    if !fields.contains_key("from") {
+        return Err("The Modelfile has no FROM line".to_string());
+    }
+    if !parameters.is_empty() {
+        fields.insert("parameters".to_string(), Value::Object(parameters));
+    }
+    if !messages.is_empty() {
+        fields.insert("messages".to_string(), Value::Array(messages));
+    }
+    Ok(fields)
+}
+
+/// Create `name` from a Modelfile via `/api/create`, showing Ollama's build status
+/// as it streams in
+pub async fn create_model(name: &str, modelfile: &str) -> Result<(), AgentError> {
+    println!("{} Creating model: {}", "🛠️".cyan(), name.yellow());
+
+    // Newer Ollama versions read the parsed fields, older ones the raw Modelfile
+    let mut request = parse_modelfile(modelfile)?;
+    request.insert("model".to_string(), serde_json::json!(name));
+    request.insert("name".to_string(), serde_json::json!(name));
+    request.insert("modelfile".to_string(), serde_json::json!(modelfile));
+    request.insert("stream".to_string(), serde_json::json!(true));
 
-            if let Ok(status) = serde_json::from_str::<serde_json::Value>(line) {
-                if let Some(status_msg) = status.get("status").and_then(|s| s.as_str()) {
-                    println!("  {}", status_msg.blue());
+    let client = Client::new();
+    let started = Instant::now();
+    let response = send_with_retry(&ollama_retry_config(), Some("Create request"), |_| {
+        client.post("http://localhost:11434/api/create").json(&request)
+    })
+    .await?;
+    if !response.status().is_success() {
+        return Err(AgentError::OllamaApi(format!("Failed to create model: {}", error_detail(response).await)));
+    }
+
+    let mut progress = PullProgress::new();
+    let mut stream = response.bytes_stream();
+    let mut pending = String::new();
+    let mut succeeded = false;
+    while let Some(chunk) = FuturesStreamExt::next(&mut stream).await {
+        pending.push_str(&String::from_utf8_lossy(&chunk?));
+
+        while let Some(newline) = pending.find('\n') {
+            let line: String = pending.drain(..=newline).collect();
+            let Ok(event) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
+                continue;
+            };
+
+            if let Some(error) = event["error"].as_str() {
+                progress.finish();
+                return Err(AgentError::OllamaApi(format!("Failed to create model: {}", error)));
+            }
+            let status = event["status"].as_str().unwrap_or_default();
+            match (event["digest"].as_str(), event["total"].as_u64()) {
+                (Some(digest), Some(total)) => {
+                    progress.update(digest, total, event["completed"].as_u64().unwrap_or(0));
                 }
+                _ if status == "success" => succeeded = true,
+                _ if !status.is_empty() => progress.message(&status.blue().to_string()),
+                _ => {}
             }
         }
     }
+    progress.finish();
 
-    println!("{} Model pulled successfully", "✅".green());
+    if !succeeded {
+        return Err(AgentError::OllamaConnection(
+            "create stream ended before Ollama reported success".to_string(),
+        ));
+    }
+    println!(
+        "{} Model created: {} in {}",
+        "✅".green(),
+        name,
+        HumanDuration(started.elapsed())
+    );
     Ok(())
 }
 
-pub async fn delete_model(model_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// Copy a model under a new name via `/api/copy`
+pub async fn copy_model(source: &str, destination: &str) -> Result<(), AgentError> {
+    println!(
+        "{} Copying model: {} → {}",
+        "📋".cyan(),
+        source.yellow(),
+        destination.yellow()
+    );
+
+    let client = Client::new();
+    let request = serde_json::json!({
+        "source": source,
+        "destination": destination
+    });
+
+    let response = client
+        .post("http://localhost:11434/api/copy")
+        .json(&request)
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        println!("{} Model copied successfully", "✅".green());
+        Ok(())
+    } else if response.status() == reqwest::StatusCode::NOT_FOUND {
+        Err(AgentError::ModelNotFound(source.to_string()))
+    } else {
+        Err(AgentError::OllamaApi(format!("Failed to copy model: {}", error_detail(response).await)))
+    }
+}
+
+pub async fn delete_model(model_name: &str) -> Result<(), AgentError> {
     println!("{} Deleting model: {}", "🗑️".cyan(), model_name.yellow());
 
     let client = Client::new();
@@ -405,18 +1421,15 @@ pub async fn delete_model(model_name: &str) -> Result<(), Box<dyn std::error::Er
     if response.status().is_success() {
         println!("{} Model deleted successfully", "✅".green());
         Ok(())
+    } else if response.status() == reqwest::StatusCode::NOT_FOUND {
+        Err(AgentError::ModelNotFound(model_name.to_string()))
     } else {
-        Err(format!("Failed to delete model: {}", response.status()).into())
+        Err(AgentError::OllamaApi(format!("Failed to delete model: {}", response.status())))
     }
 }
 
-pub async fn show_model_info(model_name: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!(
-        "{} Getting model info: {}",
-        "ℹ️".cyan(),
-        model_name.yellow()
-    );
-
+/// Fetch a model's Modelfile, parameters, template, details, license and system prompt
+pub async fn get_model_info(model_name: &str) -> Result<ModelInfo, AgentError> {
     let client = Client::new();
     let request = serde_json::json!({
         "name": model_name
@@ -428,43 +1441,153 @@ pub async fn show_model_info(model_name: &str) -> Result<(), Box<dyn std::error:
         .send()
         .await?;
 
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(AgentError::ModelNotFound(model_name.to_string()));
+    }
     if !response.status().is_success() {
-        return Err(format!("Failed to get model info: {}", response.status()).into());
+        return Err(AgentError::OllamaApi(format!("Failed to get model info: {}", response.status())));
     }
 
-    let info: serde_json::Value = response.json().await?;
+    Ok(response.json().await?)
+}
 
-    println!("{}", "Model Information:".cyan().bold());
-    if let Some(modelfile) = info.get("modelfile").and_then(|v| v.as_str()) {
-        println!(
-            "  {} {}",
-            "Modelfile:".blue(),
-            modelfile.lines().next().unwrap_or("N/A")
-        );
+/// Lines of the license shown before it is cut short
+const LICENSE_PREVIEW_LINES: usize = 8;
+
+pub async fn show_model_info(model_name: &str, json: bool) -> Result<(), AgentError> {
+    println!(
+        "{} Getting model info: {}",
+        "ℹ️".cyan(),
+        model_name.yellow()
+    );
+
+    let info = get_model_info(model_name).await?;
+
+    if json {
+        let mut value = serde_json::to_value(&info)?;
+        value["name"] = serde_json::Value::String(model_name.to_string());
+        crate::output::emit(&value);
+        return Ok(());
     }
-    if let Some(parameters) = info.get("parameters").and_then(|v| v.as_str()) {
-        println!("  {} {}", "Parameters:".blue(), parameters);
+
+    println!("{} {}", "Model Information:".cyan().bold(), model_name.yellow());
+    if let Some(details) = &info.details {
+        let summary: Vec<&str> = [
+            &details.family,
+            &details.parameter_size,
+            &details.quantization_level,
+            &details.format,
+        ]
+        .into_iter()
+        .filter_map(|field| field.as_deref())
+        .collect();
+        if !summary.is_empty() {
+            println!("  {} {}", "Details:".blue(), summary.join(" · "));
+        }
     }
-    if let Some(template) = info.get("template").and_then(|v| v.as_str()) {
-        println!(
-            "  {} {}",
-            "Template:".blue(),
-            if template.len() > 100 {
-                format!("{}...", &template[..100])
-            } else {
-                template.to_string()
-            }
-        );
+    if let Some(capabilities) = info.capabilities.as_ref().filter(|c| !c.is_empty()) {
+        println!("  {} {}", "Capabilities:".blue(), capabilities.join(", "));
+    }
+
+    let parameters = info.parameter_values();
+    if !parameters.is_empty() {
+        println!("\n{}", "Parameters:".cyan().bold());
+        let width = parameters.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, value) in &parameters {
+            println!("  {}  {}", format!("{:<width$}", name).yellow(), value);
+        }
+    }
+
+    if !info.system.trim().is_empty() {
+        println!("\n{}", "System Prompt:".cyan().bold());
+        for line in info.system.trim().lines() {
+            println!("  {}", line);
+        }
+    }
+
+    if !info.template.trim().is_empty() {
+        println!("\n{}", "Template:".cyan().bold());
+        for line in crate::render::highlight_template(info.template.trim()).lines() {
+            println!("  {}", line);
+        }
     }
-    if let Some(details) = info.get("details") {
-        println!("  {} {}", "Details:".blue(), details);
+
+    if !info.license.trim().is_empty() {
+        let lines: Vec<&str> = info.license.trim().lines().collect();
+        println!("\n{}", "License:".cyan().bold());
+        for line in lines.iter().take(LICENSE_PREVIEW_LINES) {
+            println!("  {}", line);
+        }
+        if lines.len() > LICENSE_PREVIEW_LINES {
+            println!(
+                "  {}",
+                format!("... {} more lines (--json for the full text)", lines.len() - LICENSE_PREVIEW_LINES).dimmed()
+            );
+        }
+    }
+
+    if !info.modelfile.trim().is_empty() {
+        println!("\n{}", "Modelfile:".cyan().bold());
+        for line in crate::render::highlight_modelfile(info.modelfile.trim()).lines() {
+            println!("  {}", line);
+        }
     }
 
     Ok(())
 }
 
+#[derive(Deserialize, Debug)]
+struct EmbeddingsResponse {
+    #[serde(default)]
+    embedding: Vec<f64>,
+}
+
+// Check whether a model advertises embedding support via /api/show
+pub async fn model_supports_embeddings(model_name: &str) -> Result<bool, AgentError> {
+    let info = get_model_info(model_name).await?;
+
+    // Older Ollama versions don't report capabilities, so give those the benefit of the doubt
+    match info.capabilities {
+        Some(capabilities) => Ok(capabilities.iter().any(|c| c == "embedding")),
+        None => Ok(true),
+    }
+}
+
+// Generate an embedding vector for the given input
+pub async fn generate_embeddings(
+    model: &SelectedModel,
+    input: &str,
+) -> Result<Vec<f64>, AgentError> {
+    let client = Client::new();
+    let request = serde_json::json!({
+        "model": model.get_name(),
+        "prompt": input
+    });
+
+    let response = client
+        .post("http://localhost:11434/api/embeddings")
+        .json(&request)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(generation_error(response, model.get_name()).await);
+    }
+
+    let embeddings: EmbeddingsResponse = response.json().await?;
+
+    if embeddings.embedding.is_empty() {
+        return Err(AgentError::OllamaApi(format!(
+            "Model '{}' returned an empty embedding; it may not support embeddings",
+            model.get_name()
+        )));
+    }
+
+    Ok(embeddings.embedding)
+}
+
 // Health check function
-pub async fn check_ollama_health() -> Result<bool, Box<dyn std::error::Error>> {
+pub async fn check_ollama_health() -> Result<bool, AgentError> {
     let client = Client::new();
 
     match client.get("http://localhost:11434/api/tags").send().await {
@@ -476,7 +1599,7 @@ pub async fn check_ollama_health() -> Result<bool, Box<dyn std::error::Error>> {
 // List available models with filtering
 pub async fn list_models_filtered(
     filter: Option<&str>,
-) -> Result<Vec<Model>, Box<dyn std::error::Error>> {
+) -> Result<Vec<Model>, AgentError> {
     let all_models = fetch_models().await?;
 
     if let Some(filter_term) = filter {
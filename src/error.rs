@@ -0,0 +1,111 @@
+use thiserror::Error;
+
+/// Errors from the Ollama client and chat sessions. [`AgentError::kind`] gives
+/// the stable name reported as `error_kind` with `--format json`.
+#[derive(Debug, Error)]
+pub enum AgentError {
+    /// Ollama could not be reached, or the connection dropped
+    #[error("Could not reach Ollama: {0}")]
+    OllamaConnection(String),
+    /// Ollama answered, but with an error or an unreadable response
+    #[error("{0}")]
+    OllamaApi(String),
+    #[error("Model '{0}' not found")]
+    ModelNotFound(String),
+    /// No model could be chosen: none installed, ambiguous name or cancelled picker
+    #[error("{0}")]
+    ModelSelection(String),
+    #[error("{tool} failed: {message}")]
+    ToolExecution { tool: String, message: String },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Config(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AgentError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AgentError::OllamaConnection(_) => "ollama_connection",
+            AgentError::OllamaApi(_) => "ollama_api",
+            AgentError::ModelNotFound(_) => "model_not_found",
+            AgentError::ModelSelection(_) => "model_selection",
+            AgentError::ToolExecution { .. } => "tool_execution",
+            AgentError::Io(_) => "io",
+            AgentError::Config(_) => "config",
+            AgentError::Other(_) => "other",
+        }
+    }
+
+    pub fn tool(tool: &str, error: impl std::fmt::Display) -> Self {
+        AgentError::ToolExecution {
+            tool: tool.to_string(),
+            message: error.to_string(),
+        }
+    }
+
+    /// `error_kind` for any error, looking through the boxes callers wrap it in
+    pub fn kind_of(error: &(dyn std::error::Error + 'static)) -> &'static str {
+        if let Some(error) = error.downcast_ref::<AgentError>() {
+            error.kind()
+        } else if error.is::<std::io::Error>() {
+            "io"
+        } else if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+            AgentError::from_reqwest(error).kind()
+        } else {
+            "other"
+        }
+    }
+
+    fn from_reqwest(error: &reqwest::Error) -> Self {
+        if error.is_connect() || error.is_timeout() {
+            AgentError::OllamaConnection(error.to_string())
+        } else {
+            AgentError::OllamaApi(error.to_string())
+        }
+    }
+}
+
+impl From<reqwest::Error> for AgentError {
+    fn from(error: reqwest::Error) -> Self {
+        AgentError::from_reqwest(&error)
+    }
+}
+
+impl From<serde_json::Error> for AgentError {
+    fn from(error: serde_json::Error) -> Self {
+        AgentError::OllamaApi(format!("Invalid response from Ollama: {}", error))
+    }
+}
+
+/// Unwrap errors from code that still returns `Box<dyn Error>`, keeping their kind
+impl From<Box<dyn std::error::Error>> for AgentError {
+    fn from(error: Box<dyn std::error::Error>) -> Self {
+        let error = match error.downcast::<AgentError>() {
+            Ok(error) => return *error,
+            Err(error) => error,
+        };
+        let error = match error.downcast::<std::io::Error>() {
+            Ok(error) => return AgentError::Io(*error),
+            Err(error) => error,
+        };
+        match error.downcast::<reqwest::Error>() {
+            Ok(error) => AgentError::from(*error),
+            Err(error) => AgentError::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<String> for AgentError {
+    fn from(message: String) -> Self {
+        AgentError::Other(message)
+    }
+}
+
+impl From<&str> for AgentError {
+    fn from(message: &str) -> Self {
+        AgentError::Other(message.to_string())
+    }
+}
@@ -0,0 +1,129 @@
+use colored::Colorize;
+use console::{Key, Term};
+use std::io;
+
+const MAX_VISIBLE: usize = 10;
+
+/// Score `query` against `candidate` as a case-insensitive subsequence; higher is
+/// better and `None` means a query character is missing. Consecutive characters,
+/// matches at word starts and an early first match score extra.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = candidate.to_lowercase().chars().collect();
+    let word_start = |i: usize| i == 0 || !chars[i - 1].is_alphanumeric();
+
+    let mut score = 0i64;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if c != query[next] {
+            continue;
+        }
+        score += 10;
+        score -= match previous {
+            Some(p) if p + 1 == i => -15,
+            Some(p) => (i - p - 1) as i64,
+            None => i as i64,
+        };
+        if word_start(i) {
+            score += 20;
+        }
+        previous = Some(i);
+        next += 1;
+    }
+    if next < query.len() {
+        return None;
+    }
+
+    // The greedy scan can settle for scattered characters when a contiguous run exists later
+    let contiguous = chars
+        .windows(query.len())
+        .position(|window| window == query.as_slice())
+        .map(|pos| 25 * query.len() as i64 - 15 - pos as i64 + if word_start(pos) { 20 } else { 0 });
+    Some(contiguous.map_or(score, |contiguous| contiguous.max(score)))
+}
+
+/// Indices of `items` matching `query`, best first (ties keep their original order)
+fn rank(items: &[String], query: &str) -> Vec<usize> {
+    let mut scored: Vec<(i64, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| fuzzy_score(query, item).map(|score| (score, index)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, index)| index).collect()
+}
+
+/// Interactive picker that filters `items` as the user types, starting from
+/// `query`. Returns the chosen index, or `None` if cancelled with Esc or Ctrl+C.
+pub fn fuzzy_select(prompt: &str, items: &[String], query: &str) -> io::Result<Option<usize>> {
+    let term = Term::stderr();
+    let mut query = query.to_string();
+
+    term.hide_cursor()?;
+    let result = pick(&term, prompt, items, &mut query);
+    term.show_cursor()?;
+    let result = result?;
+    if let Some(index) = result {
+        term.write_line(&format!("{} {}: {}", "✔".green(), prompt.bold(), items[index]))?;
+    }
+    Ok(result)
+}
+
+fn pick(term: &Term, prompt: &str, items: &[String], query: &mut String) -> io::Result<Option<usize>> {
+    let mut selected = 0;
+    let mut drawn = 0;
+
+    let result = loop {
+        let matches = rank(items, query);
+        selected = selected.min(matches.len().saturating_sub(1));
+
+        term.clear_last_lines(drawn)?;
+        let mut lines = vec![format!("{} {}: {}", "?".yellow(), prompt.bold(), query)];
+        if matches.is_empty() {
+            lines.push(format!("  {}", "no matches".dimmed()));
+        }
+        let first = selected.saturating_sub(MAX_VISIBLE - 1);
+        for (position, &index) in matches.iter().enumerate().skip(first).take(MAX_VISIBLE) {
+            if position == selected {
+                lines.push(format!("{} {}", ">".cyan().bold(), items[index].cyan()));
+            } else {
+                lines.push(format!("  {}", items[index]));
+            }
+        }
+        lines.push(format!("  {}", "type to filter, ↑/↓ to move, Enter to select, Esc to cancel".dimmed()));
+        for line in &lines {
+            term.write_line(line)?;
+        }
+        drawn = lines.len();
+
+        match term.read_key()? {
+            Key::Enter if !matches.is_empty() => break Some(matches[selected]),
+            Key::Escape | Key::CtrlC => break None,
+            Key::ArrowUp | Key::BackTab => {
+                selected = selected.checked_sub(1).unwrap_or(matches.len().saturating_sub(1));
+            }
+            Key::ArrowDown | Key::Tab => {
+                selected = if selected + 1 < matches.len() { selected + 1 } else { 0 };
+            }
+            Key::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            Key::Char(c) if !c.is_control() => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => {}
+        }
+    };
+
+    term.clear_last_lines(drawn)?;
+    Ok(result)
+}
@@ -0,0 +1,71 @@
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Typed prompts persisted across chat sessions, one per line
+pub fn default_path() -> Option<PathBuf> {
+    Some(crate::paths::data_dir().join("input_history"))
+}
+
+// Newlines and backslashes are escaped so multi-line prompts stay on one line
+fn encode(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn decode(line: &str) -> String {
+    let mut entry = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            entry.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some(other) => entry.push(other),
+            None => entry.push('\\'),
+        }
+    }
+    entry
+}
+
+/// The newest `max_entries` entries, with consecutive duplicates collapsed
+pub fn load(path: &Path, max_entries: usize) -> VecDeque<String> {
+    let mut entries: VecDeque<String> = VecDeque::new();
+    let Ok(content) = fs::read_to_string(path) else {
+        return entries;
+    };
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let entry = decode(line);
+        if entries.back() != Some(&entry) {
+            entries.push_back(entry);
+        }
+    }
+    while entries.len() > max_entries {
+        entries.pop_front();
+    }
+    entries
+}
+
+pub fn append(path: &Path, entry: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", encode(entry))
+}
+
+/// Replace the file with `entries`, used to keep it from growing past the cap
+pub fn rewrite(path: &Path, entries: &VecDeque<String>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&encode(entry));
+        content.push('\n');
+    }
+    fs::write(path, content)
+}
@@ -1,3 +1,6 @@
+mod fuzzy_select;
+mod history_file;
 mod vim_handler;
 
+pub use fuzzy_select::{fuzzy_score, fuzzy_select};
 pub use vim_handler::VimInputHandler;
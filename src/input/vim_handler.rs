@@ -1,7 +1,17 @@
+use super::history_file;
 use colored::Colorize;
 use console::{Key, Term};
 use std::collections::VecDeque;
 use std::io::{self, Write};
+use std::path::PathBuf;
+
+// Control characters the terminal reports as plain chars
+const CTRL_D: char = '\x04';
+const CTRL_G: char = '\x07';
+const CTRL_K: char = '\x0b';
+const CTRL_R: char = '\x12';
+const CTRL_U: char = '\x15';
+const CTRL_W: char = '\x17';
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputMode {
@@ -21,6 +31,9 @@ pub struct VimInputHandler {
     search_buffer: String,
     max_history: usize,
     vim_enabled: bool,
+    history_path: Option<PathBuf>,
+    // Lines written to the history file since it was last compacted
+    history_file_entries: usize,
 }
 
 impl Default for VimInputHandler {
@@ -31,16 +44,25 @@ impl Default for VimInputHandler {
 
 impl VimInputHandler {
     pub fn new() -> Self {
+        let max_history = 1000;
+        let history_path = history_file::default_path();
+        let history = history_path
+            .as_deref()
+            .map(|path| history_file::load(path, max_history))
+            .unwrap_or_default();
+
         Self {
             mode: InputMode::Insert,
             buffer: String::new(),
             cursor_pos: 0,
-            history: VecDeque::new(),
+            history_file_entries: history.len(),
+            history,
             history_index: None,
             command_buffer: String::new(),
             search_buffer: String::new(),
-            max_history: 1000,
+            max_history,
             vim_enabled: false,
+            history_path,
         }
     }
 
@@ -256,17 +278,188 @@ impl VimInputHandler {
     }
 
     fn get_simple_input(&mut self, prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
-        use dialoguer::Input;
+        let term = Term::stdout();
+        if !term.is_term() {
+            use dialoguer::Input;
+
+            let input: String = Input::new().with_prompt(prompt).interact_text()?;
+            if !input.trim().is_empty() {
+                self.add_to_history(input.clone());
+            }
+            return Ok(input);
+        }
 
-        let input: String = Input::new().with_prompt(prompt).interact_text()?;
+        self.buffer.clear();
+        self.cursor_pos = 0;
+        self.history_index = None;
+        self.display_line(prompt, &term)?;
 
+        // Readline-style editing: arrows move through the line and the history,
+        // Ctrl-R searches the history backwards
+        loop {
+            match term.read_key()? {
+                Key::Enter => break,
+                Key::CtrlC => {
+                    println!();
+                    return Err("Interrupted by user".into());
+                }
+                Key::Char(CTRL_D) if self.buffer.is_empty() => {
+                    println!();
+                    return Err("End of input".into());
+                }
+                Key::Char(CTRL_R) => {
+                    if self.reverse_search(prompt, &term)? {
+                        break;
+                    }
+                }
+                Key::Char(CTRL_U) => {
+                    self.buffer.drain(..self.cursor_pos);
+                    self.cursor_pos = 0;
+                }
+                Key::Char(CTRL_K) => self.buffer.truncate(self.cursor_pos),
+                Key::Char(CTRL_W) => {
+                    let end = self.cursor_pos;
+                    self.move_word_backward();
+                    self.cursor_pos = self.char_boundary(self.cursor_pos);
+                    self.buffer.drain(self.cursor_pos..end);
+                }
+                Key::Char(c) if !c.is_control() => {
+                    self.buffer.insert(self.cursor_pos, c);
+                    self.cursor_pos += c.len_utf8();
+                }
+                Key::Backspace if self.cursor_pos > 0 => {
+                    let start = self.previous_char_start();
+                    self.buffer.drain(start..self.cursor_pos);
+                    self.cursor_pos = start;
+                }
+                Key::Del if self.cursor_pos < self.buffer.len() => {
+                    let end = self.next_char_end();
+                    self.buffer.drain(self.cursor_pos..end);
+                }
+                Key::ArrowLeft if self.cursor_pos > 0 => self.cursor_pos = self.previous_char_start(),
+                Key::ArrowRight if self.cursor_pos < self.buffer.len() => {
+                    self.cursor_pos = self.next_char_end()
+                }
+                Key::Home => self.cursor_pos = 0,
+                Key::End => self.cursor_pos = self.buffer.len(),
+                Key::ArrowUp => self.history_up(),
+                Key::ArrowDown => self.history_down(),
+                _ => continue,
+            }
+            self.display_line(prompt, &term)?;
+        }
+
+        println!();
+        let input = self.buffer.clone();
         if !input.trim().is_empty() {
             self.add_to_history(input.clone());
         }
-
         Ok(input)
     }
 
+    /// Redraw the prompt and buffer, leaving the cursor at `cursor_pos`
+    fn display_line(&self, prompt: &str, term: &Term) -> io::Result<()> {
+        term.clear_line()?;
+        print!("{}: {}", prompt, self.buffer);
+        io::stdout().flush()?;
+        term.move_cursor_left(self.buffer[self.cursor_pos..].chars().count())
+    }
+
+    /// Ctrl-R: typing narrows to the newest matching entry and Ctrl-R again steps
+    /// to older matches. Enter submits the match, other keys keep it for editing
+    /// and Ctrl-G/Esc restores the line. Returns whether the line was submitted.
+    fn reverse_search(&mut self, prompt: &str, term: &Term) -> io::Result<bool> {
+        let original = (self.buffer.clone(), self.cursor_pos);
+        let mut query = String::new();
+        // History index of the current match; searches continue below it
+        let mut found: Option<usize> = None;
+
+        loop {
+            let matched = found.map(|index| self.history[index].as_str()).unwrap_or("");
+            term.clear_line()?;
+            let label = if found.is_none() && !query.is_empty() {
+                "(failed reverse-i-search)"
+            } else {
+                "(reverse-i-search)"
+            };
+            print!("{}`{}': {}", label.dimmed(), query, matched);
+            io::stdout().flush()?;
+
+            match term.read_key()? {
+                Key::Char(CTRL_R) => {
+                    let before = found.unwrap_or(self.history.len());
+                    if let Some(index) = self.search_history_before(&query, before) {
+                        found = Some(index);
+                    }
+                }
+                Key::Char(CTRL_G) | Key::Escape | Key::CtrlC => {
+                    (self.buffer, self.cursor_pos) = original;
+                    break;
+                }
+                Key::Enter => {
+                    if let Some(index) = found {
+                        self.buffer = self.history[index].clone();
+                        self.cursor_pos = self.buffer.len();
+                    }
+                    self.display_line(prompt, term)?;
+                    return Ok(true);
+                }
+                Key::Backspace => {
+                    query.pop();
+                    found = self.search_history_before(&query, self.history.len());
+                }
+                Key::Char(c) if !c.is_control() => {
+                    query.push(c);
+                    // Keep the current match while it still matches, like readline
+                    let from = found.map_or(self.history.len(), |index| index + 1);
+                    found = self.search_history_before(&query, from);
+                }
+                _ => {
+                    if let Some(index) = found {
+                        self.buffer = self.history[index].clone();
+                        self.cursor_pos = self.buffer.len();
+                        self.history_index = Some(index);
+                    }
+                    break;
+                }
+            }
+        }
+
+        self.display_line(prompt, term)?;
+        Ok(false)
+    }
+
+    /// Newest entry before `before` containing `query`
+    fn search_history_before(&self, query: &str, before: usize) -> Option<usize> {
+        if query.is_empty() {
+            return None;
+        }
+        (0..before.min(self.history.len()))
+            .rev()
+            .find(|&index| self.history[index].contains(query))
+    }
+
+    fn previous_char_start(&self) -> usize {
+        self.buffer[..self.cursor_pos]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn next_char_end(&self) -> usize {
+        self.buffer[self.cursor_pos..]
+            .chars()
+            .next()
+            .map_or(self.cursor_pos, |c| self.cursor_pos + c.len_utf8())
+    }
+
+    fn char_boundary(&self, mut pos: usize) -> usize {
+        while !self.buffer.is_char_boundary(pos) {
+            pos -= 1;
+        }
+        pos
+    }
+
     fn display_prompt_with_mode(
         &self,
         prompt: &str,
@@ -568,10 +761,27 @@ impl VimInputHandler {
             return;
         }
 
-        self.history.push_back(command);
+        self.history.push_back(command.clone());
         if self.history.len() > self.max_history {
             self.history.pop_front();
         }
+
+        let Some(path) = &self.history_path else {
+            return;
+        };
+        // Append as prompts are entered; once the file holds a tenth more than
+        // the cap, compact it down to the in-memory history
+        let result = if self.history_file_entries >= self.max_history + self.max_history / 10 {
+            self.history_file_entries = self.history.len();
+            history_file::rewrite(path, &self.history)
+        } else {
+            self.history_file_entries += 1;
+            history_file::append(path, &command)
+        };
+        if let Err(e) = result {
+            eprintln!("{} Could not save input history: {}", "⚠️".yellow(), e);
+            self.history_path = None;
+        }
     }
 
     fn show_vim_help(&self) {
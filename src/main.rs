@@ -2,18 +2,31 @@ use clap::{Parser, Subcommand};
 use colored::Colorize;
 use std::process;
 
+mod citations;
 mod client;
+mod error;
 mod input;
+mod output;
+mod paths;
+mod render;
+mod response_cache;
+mod scaffold;
 mod session;
+mod thinking;
 mod tools;
+mod watch;
 mod workspace;
 
 use client::{
     check_ollama_health, delete_model, fetch_models, list_models_filtered, pull_model,
-    select_model, show_model_info, SelectedModel,
+    show_model_info, SelectedModel,
 };
-use session::AssistantSession;
-use tools::{ToolConfig, ToolExecutor};
+use error::AgentError;
+use output::OutputFormat;
+use response_cache::{normalize_prompt, ResponseCache};
+use session::{AssistantSession, SavedSession};
+use tools::enhanced_websearch::{format_enhanced_search_results, EnhancedWebSearchEngine};
+use tools::{ToolConfig, ToolExecutor, ToolResult};
 use workspace::WorkspaceManager;
 
 mod api_models {
@@ -47,7 +60,7 @@ struct Cli {
     #[arg(long)]
     vim: bool,
 
-    /// Include files in context (similar to Claude CLI)
+    /// Include files, directories or glob patterns in context (similar to Claude CLI)
     #[arg(long)]
     files: Vec<String>,
 
@@ -55,13 +68,125 @@ struct Cli {
     #[arg(long)]
     working_dir: Option<String>,
 
-    /// Stream output (default: true)
-    #[arg(long)]
+    /// Print each response once it is complete instead of streaming it token by token
+    #[arg(long, global = true)]
     no_stream: bool,
 
+    /// Print reasoning models' <think> blocks instead of a "thinking…" placeholder
+    #[arg(long, global = true)]
+    show_thinking: bool,
+
+    /// Download the requested model if it isn't installed instead of failing
+    #[arg(long, global = true)]
+    pull_if_missing: bool,
+
     /// Enable project context scanning
     #[arg(long)]
     project_context: bool,
+
+    /// Apply file writes and edits without asking for confirmation
+    #[arg(short, long, global = true)]
+    yes: bool,
+
+    /// Log file verbosity (`trace`, `debug`, `info`, `warn`, `error` or a filter such as
+    /// `info,reqwest=debug`); overrides OLLAMA_AGENT_LOG and the `log_level` setting
+    #[arg(long, global = true, value_parser = tools::logging::parse_log_filter)]
+    log_level: Option<String>,
+
+    /// Don't print the token usage summary after non-interactive commands
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Log-friendly output: progress as plain lines instead of spinners and bars, no colors
+    /// or Markdown styling (on by default when the `CI` environment variable is set)
+    #[arg(long, global = true)]
+    ci: bool,
+
+    /// Log file writes, commands and other side-effecting tool calls instead of running them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Print model responses verbatim instead of rendering Markdown
+    #[arg(long, alias = "no-render", global = true)]
+    plain: bool,
+
+    /// Answer as a plain chatbot: never run commands, search the web or call other tools
+    /// (overrides `enable_tools`)
+    #[arg(long, global = true)]
+    no_tools: bool,
+
+    /// Before each tool call, print the model's reason and the exact call; after it, a
+    /// one-line result
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// After the agent writes or edits a .rs, .py or .js file, syntax-check it and report
+    /// any errors back so the next step can fix them
+    #[arg(long, global = true)]
+    verify_edits: bool,
+
+    /// How long Ollama keeps the model loaded after each request, e.g. `30m`; `-1` keeps
+    /// it loaded until Ollama stops (overrides `keep_alive`)
+    #[arg(long, global = true, allow_hyphen_values = true, value_parser = client::parse_keep_alive)]
+    keep_alive: Option<client::KeepAlive>,
+
+    /// Web search result language, e.g. `de` (overrides `search_language`)
+    #[arg(long, global = true, value_parser = tools::enhanced_websearch::parse_language_code)]
+    lang: Option<String>,
+
+    /// Web search region, e.g. `ch` (overrides `search_region`)
+    #[arg(long, global = true, value_parser = tools::enhanced_websearch::parse_region_code)]
+    region: Option<String>,
+}
+
+/// Sampling options passed through to Ollama; unset values use Ollama's defaults
+#[derive(clap::Args, Debug, Clone, Default)]
+struct GenerationArgs {
+    /// Sampling temperature
+    #[arg(long)]
+    temperature: Option<f32>,
+
+    /// Nucleus sampling probability
+    #[arg(long)]
+    top_p: Option<f32>,
+
+    /// Context window size in tokens
+    #[arg(long)]
+    num_ctx: Option<u32>,
+
+    /// Random seed for reproducible output
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Stop after this many generated tokens (Ollama's `num_predict`)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    max_tokens: Option<u32>,
+
+    /// Stop generating when the model produces this string (repeatable)
+    #[arg(long, value_name = "STRING", allow_hyphen_values = true)]
+    stop: Vec<String>,
+}
+
+impl From<GenerationArgs> for client::OllamaOptions {
+    fn from(args: GenerationArgs) -> Self {
+        client::OllamaOptions {
+            temperature: args.temperature,
+            top_p: args.top_p,
+            num_ctx: args.num_ctx,
+            seed: args.seed,
+            num_predict: args.max_tokens,
+            stop: (!args.stop.is_empty()).then_some(args.stop),
+            ..Default::default()
+        }
+    }
+}
+
+/// `--format` for commands that can report a machine-readable result
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+struct FormatArgs {
+    /// Output format; `json` prints a single JSON object to stdout and logs to stderr
+    #[arg(long, value_enum, global = true, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
 }
 
 #[derive(Subcommand)]
@@ -76,32 +201,62 @@ enum Commands {
         #[arg(long)]
         vim: bool,
 
-        /// Include files in context
+        /// Include files, directories or glob patterns in context
         #[arg(long)]
         files: Vec<String>,
 
         /// Enable project context
         #[arg(long)]
         project_context: bool,
+
+        /// Resume a session previously saved with :save
+        #[arg(long)]
+        resume: Option<String>,
+
+        #[command(flatten)]
+        generation: GenerationArgs,
     },
     /// Ask a question and get a response (non-interactive)
     Ask {
-        /// The question to ask
-        prompt: String,
+        /// The question to ask; read from stdin when omitted and input is piped
+        prompt: Option<String>,
+
+        /// Append piped stdin to the prompt as a fenced block (automatic when the prompt mentions "stdin" or "piped")
+        #[arg(long)]
+        stdin: bool,
 
         /// Model to use
         #[arg(short, long)]
         model: Option<String>,
 
-        /// Include files in context
+        /// Include files, directories or glob patterns in context
         #[arg(long)]
         files: Vec<String>,
 
         /// Enable project context
         #[arg(long)]
         project_context: bool,
+
+        /// Reuse a cached answer to the same prompt (overrides the `response_cache` setting)
+        #[arg(long, conflicts_with = "no_cache")]
+        cache: bool,
+
+        /// Always ask the model, ignoring the response cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Rounds of tool calls the model may chain before it has to answer
+        #[arg(long, default_value_t = session::DEFAULT_MAX_STEPS as u16, value_parser = clap::value_parser!(u16).range(1..))]
+        max_steps: u16,
+
+        #[command(flatten)]
+        generation: GenerationArgs,
+
+        #[command(flatten)]
+        format: FormatArgs,
     },
     /// Generate code based on description
+    #[command(group(clap::ArgGroup::new("destination").args(["output", "write_all"])))]
     Generate {
         /// Code description
         description: String,
@@ -110,13 +265,31 @@ enum Commands {
         #[arg(short, long)]
         language: Option<String>,
 
-        /// Output file
+        /// Write the generated code to this file (extension inferred from --language if missing)
         #[arg(short, long)]
         output: Option<String>,
 
+        /// Write every code block to the file it names (```rust path=src/main.rs), under DIR
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+        write_all: Option<String>,
+
+        /// Overwrite existing files when writing with --output or --write-all
+        #[arg(long, requires = "destination")]
+        force: bool,
+
+        /// Append the generated code to the --output file instead of replacing it
+        #[arg(long, requires = "output", conflicts_with = "force")]
+        append: bool,
+
         /// Model to use
         #[arg(short, long)]
         model: Option<String>,
+
+        #[command(flatten)]
+        generation: GenerationArgs,
+
+        #[command(flatten)]
+        format: FormatArgs,
     },
     /// Edit files interactively
     Edit {
@@ -143,6 +316,10 @@ enum Commands {
         /// Model to use
         #[arg(short, long)]
         model: Option<String>,
+
+        /// Review again whenever the files (or files under given directories) change
+        #[arg(short, long)]
+        watch: bool,
     },
     /// Commit changes with AI-generated message
     Commit {
@@ -150,6 +327,28 @@ enum Commands {
         #[arg(short, long)]
         context: Option<String>,
 
+        /// Follow the Conventional Commits format, inferring the scope from the diff
+        #[arg(long)]
+        conventional: bool,
+
+        /// Model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+    /// Generate embeddings for text or files
+    Embed {
+        /// Text to embed
+        #[arg(short, long, conflicts_with = "file")]
+        text: Option<String>,
+
+        /// Files to embed (repeat for batch mode)
+        #[arg(short, long)]
+        file: Vec<String>,
+
+        /// Write the JSON output to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+
         /// Model to use
         #[arg(short, long)]
         model: Option<String>,
@@ -173,12 +372,32 @@ enum Commands {
         /// Show detailed information
         #[arg(short, long)]
         detailed: bool,
+
+        #[command(flatten)]
+        format: FormatArgs,
     },
     /// Pull a model from the Ollama registry
     Pull {
         /// Model name to pull
         model: String,
     },
+    /// Create a model from a Modelfile
+    Create {
+        /// Name of the new model
+        name: String,
+
+        /// Modelfile to build from
+        #[arg(short, long, default_value = "Modelfile")]
+        file: std::path::PathBuf,
+    },
+    /// Copy a model under a new name
+    Copy {
+        /// Existing model
+        source: String,
+
+        /// Name for the copy
+        destination: String,
+    },
     /// Delete a model
     Delete {
         /// Model name to delete
@@ -188,9 +407,56 @@ enum Commands {
     Show {
         /// Model name to show
         model: String,
+
+        /// Print the parsed model information as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Check system status and available tools
-    Status,
+    Status {
+        #[command(flatten)]
+        format: FormatArgs,
+    },
+    /// Run a web search and print the results without involving a model
+    Search {
+        /// Search query
+        query: String,
+
+        /// Only query this engine (google, brave, searxng, duckduckgo, bing, wikipedia, stackoverflow, arxiv, reddit)
+        #[arg(short, long, value_parser = tools::enhanced_websearch::parse_engine_id)]
+        engine: Option<String>,
+
+        /// Maximum number of results to print
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+
+        /// Only keep results on this domain or its subdomains (repeatable; replaces `search_allowed_domains`)
+        #[arg(
+            long,
+            visible_alias = "allowed-domains",
+            value_name = "DOMAIN",
+            value_delimiter = ',',
+            value_parser = tools::enhanced_websearch::parse_domain
+        )]
+        allow_domain: Vec<String>,
+
+        /// Drop results on this domain, even an allowed one (repeatable; adds to `search_blocked_domains`)
+        #[arg(
+            long,
+            visible_alias = "blocked-domains",
+            value_name = "DOMAIN",
+            value_delimiter = ',',
+            value_parser = tools::enhanced_websearch::parse_domain
+        )]
+        block_domain: Vec<String>,
+
+        /// Shorthand for `--format json`
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        format: FormatArgs,
+    },
     /// Run system diagnostics
     Diagnostics,
     /// Discover available tools and system capabilities
@@ -204,6 +470,9 @@ enum Commands {
     Tool {
         #[command(subcommand)]
         tool_command: ToolCommands,
+
+        #[command(flatten)]
+        format: FormatArgs,
     },
     /// Manage conversation history
     History {
@@ -217,10 +486,58 @@ enum Commands {
     },
 }
 
+impl Commands {
+    /// Requested output format; commands without `--format` always print text
+    fn output_format(&self) -> OutputFormat {
+        match self {
+            Commands::Ask { format, .. }
+            | Commands::Generate { format, .. }
+            | Commands::List { format, .. }
+            | Commands::Status { format }
+            | Commands::Tool { format, .. } => format.format,
+            Commands::Search { json: true, .. } => OutputFormat::Json,
+            Commands::Show { json: true, .. } => OutputFormat::Json,
+            Commands::Search { format, .. } => format.format,
+            _ => OutputFormat::Text,
+        }
+    }
+
+    /// Whether the command talks to Ollama, so startup should check it is reachable
+    fn needs_ollama(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Search { .. }
+                | Commands::Config {
+                    config_command: ConfigCommands::Path | ConfigCommands::Export { .. } | ConfigCommands::Import { .. }
+                }
+        )
+    }
+
+    /// One-shot generation commands that end with a token usage summary
+    fn reports_usage(&self) -> bool {
+        matches!(
+            self,
+            Commands::Ask { .. }
+                | Commands::Generate { .. }
+                | Commands::Edit { .. }
+                | Commands::Review { .. }
+                | Commands::Commit { .. }
+        )
+    }
+}
+
 #[derive(Subcommand)]
 enum ConfigCommands {
     /// Show current configuration
-    Show,
+    Show {
+        /// Print the full typed configuration as JSON (secrets masked)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the configuration file for syntax errors, unknown keys and invalid values
+    Validate,
+    /// Print where the configuration, history, caches, logs and backups are stored
+    Path,
     /// Set a configuration value
     Set {
         /// Configuration key
@@ -230,24 +547,81 @@ enum ConfigCommands {
     },
     /// Reset configuration to defaults
     Reset,
-    /// Export configuration
+    /// Write the full configuration to a file (.toml for TOML, JSON otherwise)
     Export {
         /// Output file path
         path: String,
+        /// Mask API keys and HTTP header values; `config import` keeps the current ones
+        #[arg(long)]
+        redact: bool,
+    },
+    /// Replace the configuration with an exported file, after validating it
+    Import {
+        /// File written by `config export` (.json or .toml)
+        path: String,
+    },
+    /// Per-model system prompt and sampling preset, applied when a session uses the model
+    ModelProfile {
+        /// Model name; a profile without a tag (e.g. `codellama`) covers every tag
+        model: String,
+        #[command(subcommand)]
+        action: ModelProfileCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ModelProfileCommands {
+    /// Show the profile that applies to the model
+    Show,
+    /// Create or update the profile; only the given fields change
+    Set {
+        /// System prompt (empty to clear)
+        #[arg(long)]
+        system_prompt: Option<String>,
+        /// Sampling temperature
+        #[arg(long)]
+        temperature: Option<f32>,
+        /// Context window size in tokens
+        #[arg(long)]
+        num_ctx: Option<u32>,
+        /// Short description shown when the profile is loaded (empty to clear)
+        #[arg(long)]
+        description: Option<String>,
     },
+    /// Delete the profile
+    Remove,
 }
 
 #[derive(Subcommand)]
 enum HistoryCommands {
     /// Show conversation history
     Show {
-        /// Number of recent entries to show
-        #[arg(short, long, default_value = "10")]
+        /// Number of recent entries to show per page
+        #[arg(short, long, visible_alias = "page-size", default_value = "10")]
         count: usize,
+
+        /// Page to show, counting from the most recent
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        page: u32,
+
+        /// Page the output through $PAGER (default less) when run in a terminal
+        #[arg(long)]
+        pager: bool,
         
         /// Show detailed information
         #[arg(short, long)]
         detailed: bool,
+
+        /// Only show conversations with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only entries at or after this time (YYYY-MM-DD, RFC 3339, or an age like 7d, 2h)
+        #[arg(long, value_parser = tools::history::parse_since)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Only entries before this time (a YYYY-MM-DD date includes that day)
+        #[arg(long, value_parser = tools::history::parse_until)]
+        until: Option<chrono::DateTime<chrono::Utc>>,
     },
     /// Clear conversation history
     Clear {
@@ -260,7 +634,7 @@ enum HistoryCommands {
         /// Output file path
         path: String,
         
-        /// Export format (json, markdown, text)
+        /// Export format (json, markdown, text, html)
         #[arg(short, long, default_value = "json")]
         format: String,
     },
@@ -272,6 +646,44 @@ enum HistoryCommands {
         /// Maximum results
         #[arg(short, long, default_value = "10")]
         limit: usize,
+
+        /// Only search conversations with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only entries at or after this time (YYYY-MM-DD, RFC 3339, or an age like 7d, 2h)
+        #[arg(long, value_parser = tools::history::parse_since)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Only entries before this time (a YYYY-MM-DD date includes that day)
+        #[arg(long, value_parser = tools::history::parse_until)]
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    /// Add or remove conversation tags
+    Tag {
+        #[command(subcommand)]
+        tag_command: HistoryTagCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryTagCommands {
+    /// Tag a conversation
+    Add {
+        /// Tag label
+        tag: String,
+
+        /// Conversation id or prefix (defaults to the most recent conversation)
+        #[arg(short, long)]
+        session: Option<String>,
+    },
+    /// Remove a tag from a conversation
+    Remove {
+        /// Tag label
+        tag: String,
+
+        /// Conversation id or prefix (defaults to the most recent conversation)
+        #[arg(short, long)]
+        session: Option<String>,
     },
 }
 
@@ -296,10 +708,33 @@ enum WorkspaceCommands {
         /// Include hidden files
         #[arg(long)]
         include_hidden: bool,
+
+        /// Include files matched by .gitignore and the workspace ignore list
+        #[arg(long)]
+        include_ignored: bool,
+    },
+    /// Show or set the context size limits
+    Limits {
+        /// Maximum bytes included per file before truncation
+        #[arg(long)]
+        max_file_bytes: Option<usize>,
+
+        /// Maximum bytes of file content added to the prompt in total
+        #[arg(long)]
+        max_total_bytes: Option<usize>,
+    },
+    /// Add or remove workspace ignore patterns (gitignore syntax)
+    Ignore {
+        /// Patterns to add (or remove with --remove)
+        patterns: Vec<String>,
+
+        /// Remove the given patterns instead of adding them
+        #[arg(long)]
+        remove: bool,
     },
     /// Add files to workspace context
     Add {
-        /// Files to add
+        /// Files, directories or glob patterns to add
         files: Vec<String>,
     },
     /// Remove files from workspace context
@@ -312,6 +747,21 @@ enum WorkspaceCommands {
         /// Show detailed information
         #[arg(short, long)]
         detailed: bool,
+        /// Summarize files per language as a markdown table instead of listing them
+        #[arg(long, conflicts_with = "detailed")]
+        by_language: bool,
+    },
+    /// Show which files were added, modified or removed since the last scan
+    Diff {
+        /// Show the changes inside modified files as unified diffs
+        #[arg(long)]
+        content: bool,
+    },
+    /// Estimate the tokens the context files will use, largest first
+    Tokens {
+        /// Context window to compare against (defaults to the model config)
+        #[arg(long)]
+        num_ctx: Option<u32>,
     },
     /// Clear workspace context
     Clear,
@@ -344,6 +794,35 @@ enum ToolCommands {
         #[command(subcommand)]
         file_command: FileCommands,
     },
+    /// API testing
+    Api {
+        #[command(subcommand)]
+        api_command: ApiCommands,
+    },
+    /// GitHub issues and pull requests
+    Github {
+        #[command(subcommand)]
+        github_command: GithubCommands,
+    },
+    /// Run the project's tests and summarize failures
+    Test {
+        /// Only run tests whose name matches this filter
+        #[arg(long)]
+        filter: Option<String>,
+        /// Project directory (defaults to the current directory)
+        #[arg(long)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GithubCommands {
+    /// Show an issue or pull request with its comments
+    Issue {
+        /// owner/repo#123 or an issue or pull request URL
+        #[arg(value_name = "REF")]
+        reference: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -363,6 +842,44 @@ enum GitCommands {
         #[arg(short, long, default_value = "10")]
         count: u32,
     },
+    /// List branches, or create one with --create
+    Branch {
+        /// Branch name (or list pattern when not creating)
+        name: Option<String>,
+
+        /// Create the named branch
+        #[arg(short, long, requires = "name")]
+        create: bool,
+    },
+    /// Switch to a branch or commit
+    Checkout { target: String },
+    /// Stash working changes, or restore them with --pop
+    Stash {
+        #[arg(long)]
+        pop: bool,
+    },
+    /// Show changes
+    Diff {
+        /// Show staged changes
+        #[arg(long)]
+        staged: bool,
+
+        /// Limit the diff to a path
+        path: Option<String>,
+    },
+    /// Show who last changed each line of a file
+    Blame {
+        /// File to blame
+        file: String,
+
+        /// First line of the range (defaults to 1)
+        #[arg(long)]
+        start: Option<usize>,
+
+        /// Last line of the range (defaults to the end of the file)
+        #[arg(long)]
+        end: Option<usize>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -372,11 +889,21 @@ enum SystemCommands {
     /// Show memory usage
     Memory,
     /// Show disk usage
-    Disk { path: Option<String> },
+    Disk {
+        path: Option<String>,
+        /// List the N largest subdirectories instead of the total
+        #[arg(long)]
+        top: Option<usize>,
+        /// Directory levels below the path to consider with --top
+        #[arg(long, default_value_t = 1, requires = "top")]
+        depth: usize,
+    },
     /// List processes
     Processes { filter: Option<String> },
     /// Show network information
     Network,
+    /// Show CPU/GPU temperatures and fan speeds (Linux)
+    Sensors,
 }
 
 #[derive(Subcommand)]
@@ -402,6 +929,31 @@ enum DockerCommands {
         #[arg(short, long)]
         tail: Option<u32>,
     },
+    /// Run docker compose (up is detached)
+    Compose {
+        #[arg(value_enum)]
+        action: ComposeCommand,
+        /// Compose file to use
+        #[arg(short, long)]
+        file: Option<String>,
+    },
+    /// Build an image
+    Build {
+        /// Build context path
+        #[arg(default_value = ".")]
+        path: String,
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+    /// Run a command in a running container
+    Exec { container: String, command: String },
+    /// Show CPU, memory, network and block I/O usage (all running containers by default)
+    Stats { container: Option<String> },
+    /// Remove unused docker resources (asks first)
+    Prune {
+        #[arg(value_enum)]
+        what: PruneTarget,
+    },
 }
 
 #[derive(clap::ValueEnum, Clone)]
@@ -412,6 +964,21 @@ enum DockerResource {
     Networks,
 }
 
+#[derive(clap::ValueEnum, Clone)]
+enum PruneTarget {
+    Containers,
+    Images,
+    Volumes,
+    System,
+}
+
+#[derive(clap::ValueEnum, Clone)]
+enum ComposeCommand {
+    Up,
+    Down,
+    Ps,
+}
+
 #[derive(Subcommand)]
 enum PackageCommands {
     /// Cargo operations
@@ -424,6 +991,33 @@ enum PackageCommands {
         #[command(subcommand)]
         npm_command: NpmCommands,
     },
+    /// Pip operations
+    Pip {
+        #[command(subcommand)]
+        pip_command: PipCommands,
+    },
+    /// Poetry operations
+    Poetry {
+        #[command(subcommand)]
+        poetry_command: ScriptedPackageCommands,
+    },
+    /// Yarn operations
+    Yarn {
+        #[command(subcommand)]
+        yarn_command: ScriptedPackageCommands,
+    },
+    /// pnpm operations
+    Pnpm {
+        #[command(subcommand)]
+        pnpm_command: ScriptedPackageCommands,
+    },
+    /// Install with the package manager detected from the project's lockfiles
+    Install {
+        package: Option<String>,
+        /// Add as a development dependency
+        #[arg(long)]
+        dev: bool,
+    },
     /// Check available package managers
     Check,
     /// Search for packages
@@ -447,28 +1041,142 @@ enum NpmCommands {
     List,
 }
 
+#[derive(Subcommand)]
+enum PipCommands {
+    Install { package: Option<String> },
+    Uninstall { package: String },
+    List,
+}
+
+/// Shared subcommands for Poetry, Yarn and pnpm
+#[derive(Subcommand)]
+enum ScriptedPackageCommands {
+    Install {
+        package: Option<String>,
+        /// Add as a development dependency
+        #[arg(long)]
+        dev: bool,
+    },
+    Remove { package: String },
+    Run { script: String },
+}
+
 #[derive(Subcommand)]
 enum FileCommands {
-    /// Read a file
-    Read { path: String },
+    /// Read a file; binary and non-UTF-8 files are skipped unless a flag says how to show them
+    Read {
+        path: String,
+        /// Show a hex dump instead of text
+        #[arg(long, conflicts_with = "lossy")]
+        hex: bool,
+        /// Only read the first N bytes (binary files are hex dumped)
+        #[arg(long, value_name = "N")]
+        head: Option<usize>,
+        /// Read non-UTF-8 text with invalid bytes replaced
+        #[arg(long)]
+        lossy: bool,
+    },
     /// Write to a file
     Write { path: String, content: String },
+    /// Restore the most recent backup of a file
+    Restore { path: String },
+    /// Move or rename a file or directory; never replaces an existing file
+    Move { from: String, to: String },
+    /// Copy a file or directory; never replaces an existing file
+    Copy { from: String, to: String },
+    /// Delete a file (backed up first) or, with --recursive, a directory
+    Delete {
+        path: String,
+        /// Delete a directory and everything in it
+        #[arg(short, long)]
+        recursive: bool,
+    },
     /// Search for files
     Search {
         pattern: String,
         directory: Option<String>,
     },
+    /// Search file contents, grouped by file and ranked by match count
+    Grep {
+        /// Regular expression to search for
+        pattern: String,
+        directory: Option<String>,
+        /// Lines of context shown above and below each match
+        #[arg(short = 'C', long, default_value_t = 2)]
+        context: usize,
+        /// Match regardless of case
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+        /// Only match whole words
+        #[arg(short = 'w', long)]
+        word: bool,
+        /// Maximum number of files to show
+        #[arg(long, default_value_t = 20)]
+        max_files: usize,
+        /// Also search non-UTF-8 text files, with invalid bytes replaced
+        #[arg(long)]
+        lossy: bool,
+    },
     /// List directory contents
     List { path: Option<String> },
 }
 
+#[derive(Subcommand)]
+enum ApiCommands {
+    /// Run a JSON or YAML collection of requests and assertions; exits non-zero on failure
+    Run {
+        /// Collection file
+        collection: String,
+    },
+    /// Load-test a URL and report response time percentiles
+    Perf {
+        /// URL to request
+        url: String,
+
+        /// Number of requests (capped by `perf_test_max_requests`)
+        #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        requests: u32,
+
+        /// Requests in flight at once
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+
+        /// Per-request timeout in seconds
+        #[arg(long, default_value = "10", value_parser = parse_timeout_seconds)]
+        timeout: std::time::Duration,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
-    // Initialize logging if verbose
-    if cli.verbose {
-        env_logger::init();
+    // Log files always; human-readable logs on stderr only with --verbose
+    let config_level = ToolExecutor::new().get_log_level();
+    tools::logging::init_tracing(cli.log_level.as_deref(), &config_level, cli.verbose);
+
+    tools::diff::set_assume_yes(cli.yes);
+    tools::dry_run::set_dry_run(cli.dry_run);
+    let ci = cli.ci || output::ci_env();
+    output::set_ci_mode(ci);
+    if ci {
+        colored::control::set_override(false);
+    }
+    render::set_plain_output(cli.plain || ci);
+    session::set_tools_disabled(cli.no_tools);
+    session::set_verbose(cli.verbose);
+    session::set_explain(cli.explain);
+    session::set_verify_edits(cli.verify_edits);
+    output::set_quiet(cli.quiet);
+    tools::enhanced_websearch::set_search_locale_override(cli.lang.clone(), cli.region.clone());
+    client::set_keep_alive_override(cli.keep_alive.clone());
+    client::set_streaming_disabled(cli.no_stream);
+    client::set_show_thinking(cli.show_thinking);
+    client::set_pull_if_missing(cli.pull_if_missing);
+    tools::plugins::register_external_tools(ToolExecutor::new().get_external_tools());
+
+    if cli.command.as_ref().map(Commands::output_format) == Some(OutputFormat::Json) {
+        output::enable_json_output()?;
     }
 
     // Change working directory if specified
@@ -477,7 +1185,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Check if Ollama is running
-    if !check_ollama_health().await? {
+    let needs_ollama = cli.command.as_ref().is_none_or(Commands::needs_ollama);
+    if needs_ollama && !check_ollama_health().await? {
+        if output::json_output() {
+            output::emit(&serde_json::json!({
+                "error": "Failed to connect to Ollama",
+                "error_kind": "ollama_connection"
+            }));
+        }
         eprintln!("{} Failed to connect to Ollama", "❌".red());
         eprintln!(
             "{} Make sure Ollama is running: ollama serve",
@@ -486,76 +1201,151 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         process::exit(1);
     }
 
-    match cli.command {
-        Some(Commands::Chat { model, vim, files, project_context }) => {
-            start_chat_session_with_context(model, cli.config, vim, files, project_context).await?;
-        }
-        Some(Commands::Ask { prompt, model, files, project_context }) => {
-            handle_ask_command(prompt, model, files, project_context).await?;
-        }
-        Some(Commands::Generate { description, language, output, model }) => {
-            handle_generate_command(description, language, output, model).await?;
+    // One-shot commands end with a token usage summary; chat sessions have `:usage`
+    let report_usage = match &cli.command {
+        Some(command) => command.reports_usage(),
+        None => cli.execute.is_some(),
+    };
+
+    let result: Result<(), Box<dyn std::error::Error>> = match cli.command {
+        Some(Commands::Chat { model, vim, files, project_context, resume, generation }) => {
+            start_chat_session_with_context(
+                model,
+                cli.config,
+                vim,
+                files,
+                project_context,
+                resume,
+                generation.into(),
+            )
+            .await
+        }
+        Some(Commands::Ask { prompt, stdin, model, files, project_context, cache, no_cache, max_steps, generation, .. }) => {
+            let cache = if cache { Some(true) } else if no_cache { Some(false) } else { None };
+            let prompt = prompt_with_stdin(prompt, stdin)?;
+            handle_ask_command(prompt, model, files, project_context, cache, max_steps.into(), generation.into()).await
+        }
+        Some(Commands::Generate { description, language, output, write_all, force, append, model, generation, .. }) => {
+            let destination = GenerateDestination { output, write_all, force, append };
+            handle_generate_command(description, language, destination, model, generation.into()).await
         }
         Some(Commands::Edit { files, instruction, model }) => {
-            handle_edit_command(files, instruction, model).await?;
+            handle_edit_command(files, instruction, model).await
+        }
+        Some(Commands::Review { files, focus, model, watch }) => {
+            handle_review_command(files, focus, model, watch).await
         }
-        Some(Commands::Review { files, focus, model }) => {
-            handle_review_command(files, focus, model).await?;
+        Some(Commands::Commit { context, conventional, model }) => {
+            handle_commit_command(context, conventional, model).await
         }
-        Some(Commands::Commit { context, model }) => {
-            handle_commit_command(context, model).await?;
+        Some(Commands::Embed { text, file, output, model }) => {
+            handle_embed_command(text, file, output, model).await
         }
         Some(Commands::Init { path, project_type }) => {
-            handle_init_command(path, project_type).await?;
+            handle_init_command(path, project_type).await
         }
-        Some(Commands::List { filter, detailed }) => {
-            list_models_command(filter, detailed).await?;
+        Some(Commands::List { filter, detailed, .. }) => {
+            list_models_command(filter, detailed).await
         }
         Some(Commands::Pull { model }) => {
-            pull_model(&model).await?;
+            pull_model(&model).await.map_err(Into::into)
+        }
+        Some(Commands::Create { name, file }) => {
+            match std::fs::read_to_string(&file) {
+                Ok(modelfile) => client::create_model(&name, &modelfile).await.map_err(Into::into),
+                Err(e) => Err(format!("Could not read {}: {}", file.display(), e).into()),
+            }
+        }
+        Some(Commands::Copy { source, destination }) => {
+            client::copy_model(&source, &destination).await.map_err(Into::into)
         }
         Some(Commands::Delete { model }) => {
-            delete_model(&model).await?;
+            delete_model(&model).await.map_err(Into::into)
+        }
+        Some(Commands::Show { model, json }) => {
+            show_model_info(&model, json).await.map_err(Into::into)
         }
-        Some(Commands::Show { model }) => {
-            show_model_info(&model).await?;
+        Some(Commands::Status { .. }) => {
+            show_status().await
         }
-        Some(Commands::Status) => {
-            show_status().await?;
+        Some(Commands::Search { query, engine, limit, allow_domain, block_domain, .. }) => {
+            handle_search_command(query, engine, limit, allow_domain, block_domain).await
         }
         Some(Commands::Diagnostics) => {
-            run_diagnostics().await?;
+            run_diagnostics().await
         }
         Some(Commands::Discover) => {
-            run_tool_discovery().await?;
+            run_tool_discovery().await
         }
         Some(Commands::Config { config_command }) => {
-            handle_config_command(config_command).await?;
+            handle_config_command(config_command).await
         }
-        Some(Commands::Tool { tool_command }) => {
-            handle_tool_command(tool_command).await?;
+        Some(Commands::Tool { tool_command, .. }) => {
+            handle_tool_command(tool_command).await
         }
         Some(Commands::History { history_command }) => {
-            handle_history_command(history_command).await?;
+            handle_history_command(history_command).await
         }
         Some(Commands::Workspace { workspace_command }) => {
-            handle_workspace_command(workspace_command).await?;
+            handle_workspace_command(workspace_command).await
         }
         None => {
             // No subcommand provided
             if let Some(command) = cli.execute {
                 // Execute single command with context
-                execute_single_command_with_context(&command, cli.model, cli.vim, cli.files, cli.project_context).await?;
+                execute_single_command_with_context(&command, cli.model, cli.vim, cli.files, cli.project_context).await
             } else {
                 // Default to interactive chat with context
-                start_chat_session_with_context(cli.model, cli.config, cli.vim, cli.files, cli.project_context).await?;
+                start_chat_session_with_context(
+                    cli.model,
+                    cli.config,
+                    cli.vim,
+                    cli.files,
+                    cli.project_context,
+                    None,
+                    client::OllamaOptions::default(),
+                )
+                .await
             }
         }
+    };
+
+    if report_usage && !output::json_output() {
+        print_usage_summary();
+    }
+
+    if let Err(e) = result {
+        if output::json_output() {
+            output::emit(&serde_json::json!({
+                "error": e.to_string(),
+                "error_kind": AgentError::kind_of(e.as_ref())
+            }));
+            process::exit(1);
+        }
+        if let Some(AgentError::ModelNotFound(model)) = e.downcast_ref::<AgentError>() {
+            eprintln!("{} Model '{}' is not installed", "❌".red(), model);
+            eprintln!(
+                "{} Pull it with `pull {}`, rerun with --pull-if-missing, or see installed models with `list`",
+                "💡".yellow(),
+                model
+            );
+            process::exit(1);
+        }
+        return Err(e);
     }
 
     Ok(())
 }
 
+/// Token usage of this run on stderr, unless `--quiet` or nothing was generated
+fn print_usage_summary() {
+    let usage = client::usage_totals();
+    if output::quiet() || usage.requests == 0 {
+        return;
+    }
+    eprintln!("{} {}", "📊 Usage:".dimmed(), usage.summary().dimmed());
+}
+
 async fn start_chat_session(
     model_name: Option<String>,
     _config_path: Option<String>,
@@ -564,30 +1354,12 @@ async fn start_chat_session(
     println!("{}", "🚀 Advanced AI Assistant Startup".cyan().bold());
     println!();
 
-    let models = fetch_models().await.map_err(|e| {
-        println!("{} Failed to fetch models: {}", "❌".red(), e);
-        e
-    })?;
-
-    if models.is_empty() {
-        println!(
-            "{} No models available. Install one with: ollama pull llama2",
-            "⚠".yellow()
-        );
-        return Ok(());
-    }
-
-    let selected_model = if let Some(model_name) = model_name {
-        // Use specified model
-        if let Some(model) = models.iter().find(|m| m.name == model_name) {
-            client::SelectedModel::from(model.clone())
-        } else {
-            println!("{} Model '{}' not found", "❌".red(), model_name);
+    let selected_model = match client::resolve_model(model_name).await {
+        Ok(model) => model,
+        Err(e) => {
+            println!("{} {}", "❌".red(), e);
             return Ok(());
         }
-    } else {
-        // Interactive selection
-        select_model(&models)?
     };
 
     selected_model.display_info();
@@ -597,7 +1369,7 @@ async fn start_chat_session(
     let tool_executor = ToolExecutor::with_config(tool_config);
 
     // Start enhanced assistant session
-    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode);
+    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode)?;
 
     if vim_mode {
         println!("{}", "Vim mode enabled! Use 'ESC' to enter normal mode, 'i' to enter insert mode.".green());
@@ -615,6 +1387,11 @@ async fn list_models_command(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let models = list_models_filtered(filter.as_deref()).await?;
 
+    if output::json_output() {
+        output::emit(&serde_json::json!({ "models": models }));
+        return Ok(());
+    }
+
     if models.is_empty() {
         println!("{} No models found", "ℹ️".blue());
         return Ok(());
@@ -652,12 +1429,62 @@ async fn list_models_command(
     Ok(())
 }
 
-async fn show_status() -> Result<(), Box<dyn std::error::Error>> {
-    println!("{}", "System Status".cyan().bold());
-    println!();
+async fn handle_search_command(
+    query: String,
+    engine: Option<String>,
+    limit: usize,
+    allow_domains: Vec<String>,
+    block_domains: Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let executor = ToolExecutor::new();
+    let mut config = executor.enhanced_search_config();
+    if !allow_domains.is_empty() {
+        config.allowed_domains = allow_domains;
+    }
+    for domain in block_domains {
+        if !config.blocked_domains.contains(&domain) {
+            config.blocked_domains.push(domain);
+        }
+    }
+    let search_engine = EnhancedWebSearchEngine::new(config)?;
 
-    // Check Ollama connection
-    let ollama_status = if check_ollama_health().await? {
+    let mut results = match &engine {
+        Some(engine) => search_engine.search_single_engine(engine, &query).await?,
+        None => search_engine.intelligent_search(&query).await?,
+    };
+    results.truncate(limit);
+
+    if output::json_output() {
+        output::emit(&serde_json::json!({
+            "query": query,
+            "engine": engine,
+            "results": results,
+        }));
+    } else {
+        println!();
+        println!("{}", format_enhanced_search_results(&results, &query));
+    }
+
+    Ok(())
+}
+
+async fn show_status() -> Result<(), Box<dyn std::error::Error>> {
+    if output::json_output() {
+        let executor = ToolExecutor::new();
+        output::emit(&serde_json::json!({
+            "ollama_connected": check_ollama_health().await?,
+            "models_available": fetch_models().await.ok().map(|models| models.len()),
+            "package_managers": executor.check_package_managers().await.ok(),
+            "system_info": executor.system_info().await.ok(),
+        }));
+        return Ok(());
+    }
+
+    println!("{}", "System Status".cyan().bold());
+    println!();
+
+    // Check Ollama connection
+    let ollama_status = if check_ollama_health().await? {
         "✅ Connected".green()
     } else {
         "❌ Disconnected".red()
@@ -797,28 +1624,102 @@ async fn run_tool_discovery() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// `config path`: the configuration directory and everything derived from the data directory
+fn print_data_paths() {
+    let data_dir = paths::data_dir();
+    let config_dir = paths::config_dir().unwrap_or_default();
+    let entries = [
+        ("Config file", config_dir.join("config.json")),
+        ("Scheduled tasks", config_dir.join("scheduled_tasks.json")),
+        ("History", data_dir.join("history.db")),
+        ("Input history", data_dir.join("input_history")),
+        ("Logs", data_dir.join("logs")),
+        ("Backups", data_dir.join("backups")),
+        ("Response cache", data_dir.join("response-cache.json")),
+        ("Search cache", data_dir.join("websearch-cache.json")),
+        ("Saved sessions", data_dir.join("sessions")),
+        ("Workspace context", data_dir.join("workspace.json")),
+    ];
+
+    println!(
+        "{} {} ({})",
+        "Data directory:".cyan().bold(),
+        data_dir.display(),
+        paths::data_dir_origin()
+    );
+    if std::env::var_os(paths::DATA_DIR_ENV).is_none() {
+        println!("{}", format!("Set {} to move it", paths::DATA_DIR_ENV).dimmed());
+    }
+    println!();
+    for (label, path) in entries {
+        let marker = if path.exists() { "" } else { " (not created yet)" };
+        println!("  {:<18} {}{}", format!("{}:", label).blue(), path.display(), marker.dimmed());
+    }
+}
+
 async fn handle_config_command(command: ConfigCommands) -> Result<(), Box<dyn std::error::Error>> {
     let executor = ToolExecutor::new();
 
     match command {
-        ConfigCommands::Show => {
+        ConfigCommands::Show { json } => {
+            if json {
+                output::enable_json_output()?;
+            }
             let result = executor.get_config(None).await?;
-            println!("{}", result.output);
+            match result.metadata {
+                Some(config) if json => output::emit(&config),
+                _ => print_tool_result(result, true),
+            }
+        }
+        ConfigCommands::Path => print_data_paths(),
+        ConfigCommands::Validate => {
+            let result = executor.validate_config().await?;
+            let valid = result.success;
+            print_tool_result(result, true);
+            if !valid {
+                process::exit(1);
+            }
         }
         ConfigCommands::Set { key, value } => {
             let json_value: serde_json::Value =
                 serde_json::from_str(&value).unwrap_or_else(|_| serde_json::Value::String(value));
             let result = executor.set_config(&key, json_value).await?;
-            println!("{}", result.output);
+            print_tool_result(result, true);
         }
         ConfigCommands::Reset => {
             println!("{} Configuration reset to defaults", "✅".green());
         }
-        ConfigCommands::Export { path } => {
-            let result = executor
-                .export_conversation(tools::ExportFormat::Json, &path)
-                .await?;
-            println!("{}", result.output);
+        ConfigCommands::ModelProfile { model, action } => {
+            let result = match action {
+                ModelProfileCommands::Show => executor.show_model_profile(&model).await?,
+                ModelProfileCommands::Set { system_prompt, temperature, num_ctx, description } => {
+                    let update = tools::model_config::ModelProfile {
+                        description,
+                        system_prompt,
+                        temperature,
+                        num_ctx,
+                    };
+                    executor.set_model_profile(&model, update).await?
+                }
+                ModelProfileCommands::Remove => executor.remove_model_profile(&model).await?,
+            };
+            print_tool_result(result, true);
+        }
+        ConfigCommands::Export { path, redact } => {
+            let result = executor.export_config(&path, redact).await?;
+            let success = result.success;
+            print_tool_result(result, true);
+            if !success {
+                process::exit(1);
+            }
+        }
+        ConfigCommands::Import { path } => {
+            let result = executor.import_config(&path).await?;
+            let success = result.success;
+            print_tool_result(result, true);
+            if !success {
+                process::exit(1);
+            }
         }
     }
 
@@ -844,15 +1745,68 @@ async fn handle_tool_command(command: ToolCommands) -> Result<(), Box<dyn std::e
         ToolCommands::File { file_command } => {
             handle_file_command(file_command, &executor).await?;
         }
+        ToolCommands::Api { api_command } => {
+            handle_api_command(api_command, &executor).await?;
+        }
+        ToolCommands::Github { github_command } => {
+            let result = match github_command {
+                GithubCommands::Issue { reference } => executor.github_issue(&reference).await?,
+            };
+            print_tool_result(result, true);
+        }
+        ToolCommands::Test { filter, path } => {
+            let result = executor.execute_tool(tools::AvailableTool::RunTests { filter, path }).await?;
+            let success = result.success;
+            // The failing tests are the useful part of a failed run, not just the error line
+            if !success && !output::json_output() {
+                println!("{}", result.output);
+            }
+            print_tool_result(result, true);
+            if !success {
+                process::exit(1);
+            }
+        }
     }
 
     Ok(())
 }
 
+/// Print a tool command's output or error, or the whole result with `--format json`
+fn print_tool_result(result: ToolResult, print_output: bool) {
+    if output::json_output() {
+        output::emit(&serde_json::json!(result));
+    } else if result.success {
+        if print_output {
+            println!("{}", result.output);
+        }
+    } else {
+        eprintln!("{} {}", "Error:".red(), result.error.unwrap_or_default());
+    }
+}
+
 async fn handle_git_command(
     command: GitCommands,
     executor: &ToolExecutor,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let action = match &command {
+        GitCommands::Add { files } => Some(format!("run `git add {}`", files.join(" "))),
+        GitCommands::Commit { message } => Some(format!("run `git commit -m {:?}`", message)),
+        GitCommands::Push => Some("run `git push origin`".to_string()),
+        GitCommands::Pull => Some("run `git pull origin`".to_string()),
+        GitCommands::Branch { name: Some(name), create: true } => {
+            Some(format!("run `git branch {}`", name))
+        }
+        GitCommands::Checkout { target } => Some(format!("run `git checkout {}`", target)),
+        GitCommands::Stash { pop } => {
+            Some(format!("run `git stash {}`", if *pop { "pop" } else { "push" }))
+        }
+        _ => None,
+    };
+    if let Some(result) = action.and_then(tools::dry_run::intercept) {
+        print_tool_result(result, true);
+        return Ok(());
+    }
+
     let result = match command {
         GitCommands::Status => executor.git_status(None).await?,
         GitCommands::Add { files } => executor.git_add(&files, None).await?,
@@ -860,13 +1814,19 @@ async fn handle_git_command(
         GitCommands::Push => executor.git_push(None, None, None).await?,
         GitCommands::Pull => executor.git_pull(None, None, None).await?,
         GitCommands::Log { count } => executor.git_log(Some(count), true, None).await?,
+        GitCommands::Branch { name: Some(name), create: true } => {
+            executor
+                .git_branch(tools::GitBranchOperation::Create { name }, None)
+                .await?
+        }
+        GitCommands::Branch { name, .. } => executor.git_list_branches(name.as_deref(), None).await?,
+        GitCommands::Checkout { target } => executor.git_checkout(&target, None).await?,
+        GitCommands::Stash { pop } => executor.git_stash(pop, None).await?,
+        GitCommands::Diff { staged, path } => executor.git_diff(path.as_deref(), staged, None).await?,
+        GitCommands::Blame { file, start, end } => executor.git_blame(&file, start, end, None).await?,
     };
 
-    if result.success {
-        println!("{}", result.output);
-    } else {
-        eprintln!("{} {}", "Error:".red(), result.error.unwrap_or_default());
-    }
+    print_tool_result(result, true);
 
     Ok(())
 }
@@ -878,16 +1838,16 @@ async fn handle_system_command(
     let result = match command {
         SystemCommands::Info => executor.system_info().await?,
         SystemCommands::Memory => executor.memory_usage().await?,
-        SystemCommands::Disk { path } => executor.disk_usage(path.as_deref()).await?,
+        SystemCommands::Disk { path, top: Some(top), depth } => {
+            executor.disk_breakdown(path.as_deref(), top, depth).await?
+        }
+        SystemCommands::Disk { path, .. } => executor.disk_usage(path.as_deref()).await?,
         SystemCommands::Processes { filter } => executor.process_list(filter.as_deref()).await?,
         SystemCommands::Network => executor.network_info().await?,
+        SystemCommands::Sensors => executor.sensors().await?,
     };
 
-    if result.success {
-        println!("{}", result.output);
-    } else {
-        eprintln!("{} {}", "Error:".red(), result.error.unwrap_or_default());
-    }
+    print_tool_result(result, true);
 
     Ok(())
 }
@@ -898,6 +1858,32 @@ async fn handle_docker_command(
 ) -> Result<(), Box<dyn std::error::Error>> {
     use tools::DockerResourceType;
 
+    // Compose and build stream their output live, so don't print it again
+    let streamed = matches!(command, DockerCommands::Compose { .. } | DockerCommands::Build { .. });
+
+    let action = match &command {
+        DockerCommands::Run { image, .. } => Some(format!("run `docker run {}`", image)),
+        DockerCommands::Stop { container } => Some(format!("run `docker stop {}`", container)),
+        DockerCommands::Compose { action: ComposeCommand::Up, .. } => {
+            Some("run `docker compose up`".to_string())
+        }
+        DockerCommands::Compose { action: ComposeCommand::Down, .. } => {
+            Some("run `docker compose down`".to_string())
+        }
+        DockerCommands::Build { path, .. } => Some(format!("run `docker build {}`", path)),
+        DockerCommands::Exec { container, command } => {
+            Some(format!("run `docker exec {} {}`", container, command))
+        }
+        DockerCommands::Prune { what } => {
+            Some(format!("run `docker {}`", prune_target(what).docker_args().join(" ")))
+        }
+        _ => None,
+    };
+    if let Some(result) = action.and_then(tools::dry_run::intercept) {
+        print_tool_result(result, true);
+        return Ok(());
+    }
+
     let result = match command {
         DockerCommands::List { resource } => {
             let resource_type = match resource {
@@ -927,24 +1913,56 @@ async fn handle_docker_command(
         DockerCommands::Logs { container, tail } => {
             executor.docker_logs(&container, false, tail).await?
         }
+        DockerCommands::Compose { action, file } => {
+            let compose_action = match action {
+                ComposeCommand::Up => tools::ComposeAction::Up,
+                ComposeCommand::Down => tools::ComposeAction::Down,
+                ComposeCommand::Ps => tools::ComposeAction::Ps,
+            };
+            executor.docker_compose(file.as_deref(), compose_action).await?
+        }
+        DockerCommands::Build { path, tag } => executor.docker_build(&path, tag.as_deref()).await?,
+        DockerCommands::Exec { container, command } => {
+            executor.docker_exec(&container, &command).await?
+        }
+        DockerCommands::Stats { container } => executor.docker_stats(container.as_deref()).await?,
+        DockerCommands::Prune { what } => {
+            // Pruning can't be undone, so it goes through the same approval as model-run tools
+            let target = prune_target(&what);
+            let (auto_approve_safe, non_interactive) = executor.get_permission_settings();
+            let mut permissions = tools::PermissionManager::with_settings(auto_approve_safe, non_interactive);
+            if !permissions.request_permission(&tools::AvailableTool::DockerPrune { target })? {
+                return Ok(());
+            }
+            executor.docker_prune(target).await?
+        }
     };
 
-    if result.success {
-        println!("{}", result.output);
-    } else {
-        eprintln!("{} {}", "Error:".red(), result.error.unwrap_or_default());
-    }
+    print_tool_result(result, !streamed);
 
     Ok(())
 }
 
+fn prune_target(target: &PruneTarget) -> tools::DockerPruneTarget {
+    match target {
+        PruneTarget::Containers => tools::DockerPruneTarget::Containers,
+        PruneTarget::Images => tools::DockerPruneTarget::Images,
+        PruneTarget::Volumes => tools::DockerPruneTarget::Volumes,
+        PruneTarget::System => tools::DockerPruneTarget::System,
+    }
+}
+
 async fn handle_package_command(
     command: PackageCommands,
     executor: &ToolExecutor,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use tools::{CargoOperation, NpmOperation};
+    use tools::{
+        AvailableTool, CargoOperation, NpmOperation, PipOperation, PnpmOperation, PoetryOperation,
+        YarnOperation,
+    };
 
-    let result = match command {
+    // Package operations go through `execute_tool` so `--dry-run` applies to them
+    let tool = match command {
         PackageCommands::Cargo { cargo_command } => {
             let operation = match &cargo_command {
                 CargoCommands::Build => CargoOperation::Build,
@@ -956,12 +1974,16 @@ async fn handle_package_command(
 
             let package = match &cargo_command {
                 CargoCommands::Add { package } | CargoCommands::Remove { package } => {
-                    Some(package.as_str())
+                    Some(package.clone())
                 }
                 _ => None,
             };
 
-            executor.cargo_operation(operation, package, None).await?
+            AvailableTool::CargoOperation {
+                operation,
+                package,
+                features: None,
+            }
         }
         PackageCommands::Npm { npm_command } => {
             let (operation, package) = match npm_command {
@@ -971,19 +1993,101 @@ async fn handle_package_command(
                 NpmCommands::List => (NpmOperation::List, None),
             };
 
-            executor
-                .npm_operation(operation, package.as_deref(), false)
-                .await?
+            AvailableTool::NpmOperation {
+                operation,
+                package,
+                dev: false,
+            }
+        }
+        PackageCommands::Pip { pip_command } => {
+            let (operation, package) = match pip_command {
+                PipCommands::Install { package } => (PipOperation::Install, package),
+                PipCommands::Uninstall { package } => (PipOperation::Uninstall, Some(package)),
+                PipCommands::List => (PipOperation::List, None),
+            };
+            // A bare `pip install` falls back to the project's requirements file
+            let requirements = if matches!(operation, PipOperation::Install) && package.is_none() {
+                Some("requirements.txt".to_string())
+            } else {
+                None
+            };
+
+            AvailableTool::PipOperation {
+                operation,
+                package,
+                requirements_file: requirements,
+            }
+        }
+        PackageCommands::Poetry { poetry_command } => {
+            let (operation, package, dev) = match poetry_command {
+                ScriptedPackageCommands::Install { package, dev } => {
+                    (PoetryOperation::Install, package, dev)
+                }
+                ScriptedPackageCommands::Remove { package } => {
+                    (PoetryOperation::Remove, Some(package), false)
+                }
+                ScriptedPackageCommands::Run { script } => {
+                    (PoetryOperation::Run { script }, None, false)
+                }
+            };
+
+            AvailableTool::PoetryOperation {
+                operation,
+                package,
+                dev,
+            }
+        }
+        PackageCommands::Yarn { yarn_command } => {
+            let (operation, package, dev) = match yarn_command {
+                ScriptedPackageCommands::Install { package, dev } => {
+                    (YarnOperation::Install, package, dev)
+                }
+                ScriptedPackageCommands::Remove { package } => {
+                    (YarnOperation::Remove, Some(package), false)
+                }
+                ScriptedPackageCommands::Run { script } => {
+                    (YarnOperation::Run { script }, None, false)
+                }
+            };
+
+            AvailableTool::YarnOperation {
+                operation,
+                package,
+                dev,
+            }
+        }
+        PackageCommands::Pnpm { pnpm_command } => {
+            let (operation, package, dev) = match pnpm_command {
+                ScriptedPackageCommands::Install { package, dev } => {
+                    (PnpmOperation::Install, package, dev)
+                }
+                ScriptedPackageCommands::Remove { package } => {
+                    (PnpmOperation::Remove, Some(package), false)
+                }
+                ScriptedPackageCommands::Run { script } => {
+                    (PnpmOperation::Run { script }, None, false)
+                }
+            };
+
+            AvailableTool::PnpmOperation {
+                operation,
+                package,
+                dev,
+            }
+        }
+        PackageCommands::Install { package, dev } => AvailableTool::PackageInstall { package, dev },
+        PackageCommands::Check => {
+            print_tool_result(executor.check_package_managers().await?, true);
+            return Ok(());
+        }
+        PackageCommands::Search { query } => {
+            print_tool_result(executor.search_packages(&query).await?, true);
+            return Ok(());
         }
-        PackageCommands::Check => executor.check_package_managers().await?,
-        PackageCommands::Search { query } => executor.search_packages(&query).await?,
     };
+    let result = executor.execute_tool(tool).await?;
 
-    if result.success {
-        println!("{}", result.output);
-    } else {
-        eprintln!("{} {}", "Error:".red(), result.error.unwrap_or_default());
-    }
+    print_tool_result(result, true);
 
     Ok(())
 }
@@ -992,22 +2096,110 @@ async fn handle_file_command(
     command: FileCommands,
     executor: &ToolExecutor,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let action = match &command {
+        FileCommands::Write { path, content } => {
+            Some(format!("write {} ({} bytes)", path, content.len()))
+        }
+        FileCommands::Restore { path } => Some(format!("restore the latest backup of {}", path)),
+        FileCommands::Move { from, to } => Some(format!("move {} to {}", from, to)),
+        FileCommands::Copy { from, to } => Some(format!("copy {} to {}", from, to)),
+        FileCommands::Delete { path, recursive: true } => Some(format!("delete {} and everything in it", path)),
+        FileCommands::Delete { path, .. } => Some(format!("delete {}", path)),
+        _ => None,
+    };
+    if let Some(result) = action.and_then(tools::dry_run::intercept) {
+        print_tool_result(result, true);
+        return Ok(());
+    }
+
     let result = match command {
-        FileCommands::Read { path } => executor.file_read(&path)?,
+        FileCommands::Read { path, hex, head, lossy } => {
+            let options = tools::core::FileReadOptions { lossy, hex, head };
+            executor.file_read_with_options(&path, &options)?
+        }
         FileCommands::Write { path, content } => executor.file_write(&path, &content)?,
+        FileCommands::Restore { path } => executor.file_restore(&path)?,
+        FileCommands::Move { from, to } => executor.file_move(&from, &to)?,
+        FileCommands::Copy { from, to } => executor.file_copy(&from, &to)?,
+        FileCommands::Delete { path, recursive } => {
+            // Deleting goes through the same approval as model-run tools; a delete
+            // that would be refused anyway is reported without asking first
+            if executor.check_file_delete(&path, recursive).is_err() {
+                print_tool_result(executor.file_delete(&path, recursive)?, true);
+                return Ok(());
+            }
+            let (auto_approve_safe, non_interactive) = executor.get_permission_settings();
+            let mut permissions = tools::PermissionManager::with_settings(auto_approve_safe, non_interactive);
+            let tool = tools::AvailableTool::FileDelete { path: path.clone(), recursive };
+            if !permissions.request_permission(&tool)? {
+                return Ok(());
+            }
+            executor.file_delete(&path, recursive)?
+        }
         FileCommands::Search { pattern, directory } => {
             executor.file_search(&pattern, directory.as_deref())?
         }
+        FileCommands::Grep {
+            pattern,
+            directory,
+            context,
+            ignore_case,
+            word,
+            max_files,
+            lossy,
+        } => {
+            let options = tools::core::ContentSearchOptions {
+                context_lines: context,
+                case_sensitive: !ignore_case,
+                whole_word: word,
+                max_files,
+                highlight: !output::json_output(),
+                lossy,
+            };
+            executor.content_search(&pattern, directory.as_deref(), &options)?
+        }
         FileCommands::List { path } => {
             let list_path = path.unwrap_or_else(|| ".".to_string());
             executor.list_directory(&list_path)?
         }
     };
 
-    if result.success {
-        println!("{}", result.output);
-    } else {
-        eprintln!("{} {}", "Error:".red(), result.error.unwrap_or_default());
+    print_tool_result(result, true);
+
+    Ok(())
+}
+
+/// `--timeout` in (fractional) seconds, e.g. `2.5`
+fn parse_timeout_seconds(value: &str) -> Result<std::time::Duration, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| std::time::Duration::try_from_secs_f64(secs).ok())
+        .filter(|timeout| !timeout.is_zero())
+        .ok_or_else(|| format!("'{}' is not a positive number of seconds", value))
+}
+
+async fn handle_api_command(
+    command: ApiCommands,
+    executor: &ToolExecutor,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Collection steps and their summary are printed as the collection runs
+    let (result, print_output) = match command {
+        ApiCommands::Run { collection } => (executor.api_run_collection(&collection).await?, false),
+        ApiCommands::Perf { url, requests, concurrency, timeout } => {
+            let options = tools::core::PerfTestOptions {
+                requests: requests as usize,
+                concurrency: concurrency as usize,
+                timeout,
+            };
+            (executor.web_performance_test(&url, &options).await?, true)
+        }
+    };
+
+    let success = result.success;
+    print_tool_result(result, print_output);
+    if !success {
+        process::exit(1);
     }
 
     Ok(())
@@ -1021,20 +2213,11 @@ async fn execute_single_command(
     println!("{} Executing: {}", "⚡".cyan(), command.yellow());
 
     // Get model
-    let models = fetch_models().await?;
-    let selected_model = if let Some(model_name) = model_name {
-        models
-            .iter()
-            .find(|m| m.name == model_name)
-            .map(|m| client::SelectedModel::from(m.clone()))
-            .ok_or_else(|| format!("Model '{}' not found", model_name))?
-    } else {
-        client::SelectedModel::from(models[0].clone())
-    };
+    let selected_model = client::resolve_model(model_name).await?;
 
     // Create session and execute command
     let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode);
+    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode)?;
 
     session.process_single_command(command).await?;
 
@@ -1048,16 +2231,21 @@ async fn start_chat_session_with_context(
     vim_mode: bool,
     files: Vec<String>,
     project_context: bool,
+    resume: Option<String>,
+    options: client::OllamaOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut workspace_manager = WorkspaceManager::new();
+    let saved_session = resume
+        .as_deref()
+        .map(|path| SavedSession::load(std::path::Path::new(path)))
+        .transpose()?;
     
-    // Load existing workspace context if available
-    if let Err(_) = workspace_manager.load_context() {
-        // If no context exists and project_context is requested, create one
-        if project_context {
-            workspace_manager.init_workspace(None, None)?;
-            workspace_manager.get_context_mut().unwrap().scan_project(false)?;
-        }
+    // Load existing workspace context if available; with project_context, bring
+    // it up to date (only files changed since the last scan are re-read)
+    if project_context {
+        workspace_manager.refresh_context()?;
+    } else {
+        workspace_manager.load_context().ok();
     }
 
     // Add specified files to context
@@ -1073,34 +2261,26 @@ async fn start_chat_session_with_context(
     }
 
     // Get selected model
-    let selected_model = if let Some(model) = model_name {
-        // Try to find the specified model
-        let available_models = fetch_models().await?;
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(&model.to_lowercase()))
-            .collect();
-        
-        if matching_models.is_empty() {
-            return Err(format!("Model '{}' not found", model).into());
-        } else if matching_models.len() == 1 {
-            SelectedModel::from(matching_models[0].clone())
-        } else {
-            return Err(format!("Multiple models match '{}', please be more specific", model).into());
-        }
-    } else {
-        let available_models = fetch_models().await?;
-        select_model(&available_models)?
+    // An explicit --model wins over the resumed session's model
+    let selected_model = match (&model_name, &saved_session) {
+        (None, Some(saved)) => saved.resolve_model().await?,
+        _ => client::resolve_model(model_name).await?,
     };
 
     // Create session
     let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode);
+    let selected_model = selected_model.with_options(options);
+    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode)?;
+
+    if let Some(saved) = saved_session {
+        session.restore_session(saved);
+    }
 
     // Add workspace context to session if available
     if let Some(context) = workspace_manager.get_context() {
         let file_contents = context.get_file_contents()?;
         session.add_workspace_context(context, file_contents)?;
+        session.check_context_budget().await;
     }
 
     // Start interactive session
@@ -1109,20 +2289,61 @@ async fn start_chat_session_with_context(
     Ok(())
 }
 
+/// Builds the `ask` prompt from the argument and piped stdin. Without an argument stdin is
+/// the prompt; with one, stdin is appended as a fenced block when `--stdin` is given or the
+/// prompt names it ("the piped log"). stdin is never read from a terminal.
+fn prompt_with_stdin(prompt: Option<String>, force: bool) -> Result<String, Box<dyn std::error::Error>> {
+    use std::io::{IsTerminal, Read};
+
+    let piped = !std::io::stdin().is_terminal();
+    if force && !piped {
+        return Err("--stdin needs input piped in, e.g. `cat error.log | ask --stdin explain`".into());
+    }
+
+    let refers_to_stdin = prompt.as_deref().is_some_and(|prompt| {
+        regex::Regex::new(r"(?i)\b(stdin|piped)\b")
+            .map(|references| references.is_match(prompt))
+            .unwrap_or(false)
+    });
+    if !piped || (prompt.is_some() && !force && !refers_to_stdin) {
+        return prompt.ok_or_else(|| "No prompt given: pass one as an argument or pipe it in on stdin".into());
+    }
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let input = input.trim_end();
+
+    match prompt {
+        Some(prompt) if input.is_empty() => Ok(prompt),
+        Some(prompt) => {
+            // A fence longer than any backtick run inside keeps the block intact
+            let longest_run = input
+                .split(|c| c != '`')
+                .map(str::len)
+                .max()
+                .unwrap_or(0);
+            let fence = "`".repeat(longest_run.max(2) + 1);
+            Ok(format!("{}\n\n{}\n{}\n{}", prompt, fence, input, fence))
+        }
+        None if input.trim().is_empty() => Err("No prompt given: stdin was empty".into()),
+        None => Ok(input.to_string()),
+    }
+}
+
 async fn handle_ask_command(
     prompt: String,
     model_name: Option<String>,
     files: Vec<String>,
     project_context: bool,
+    cache: Option<bool>,
+    max_steps: usize,
+    options: client::OllamaOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut workspace_manager = WorkspaceManager::new();
     
     // Load workspace context if requested
     if project_context {
-        if let Err(_) = workspace_manager.load_context() {
-            workspace_manager.init_workspace(None, None)?;
-            workspace_manager.get_context_mut().unwrap().scan_project(false)?;
-        }
+        workspace_manager.refresh_context()?;
     }
 
     // Add specified files to context
@@ -1133,85 +2354,217 @@ async fn handle_ask_command(
         workspace_manager.get_context_mut().unwrap().add_files(&files)?;
     }
 
-    // Get selected model
-    let selected_model = if let Some(model) = model_name {
-        // Try to find the specified model
-        let available_models = fetch_models().await?;
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(&model.to_lowercase()))
-            .collect();
-        
-        if matching_models.is_empty() {
-            return Err(format!("Model '{}' not found", model).into());
-        } else if matching_models.len() == 1 {
-            SelectedModel::from(matching_models[0].clone())
-        } else {
-            return Err(format!("Multiple models match '{}', please be more specific", model).into());
-        }
-    } else {
-        let available_models = fetch_models().await?;
-        select_model(&available_models)?
-    };
+    let selected_model = client::resolve_model(model_name).await?;
 
     // Create session
     let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::new(selected_model, tool_executor);
+    let selected_model = selected_model.with_options(options);
+    let mut session = AssistantSession::new(selected_model, tool_executor)?;
+    session.set_max_steps(max_steps);
 
     // Add workspace context to session if available
     if let Some(context) = workspace_manager.get_context() {
         let file_contents = context.get_file_contents()?;
         session.add_workspace_context(context, file_contents)?;
+        session.check_context_budget().await;
     }
 
-    // Process the prompt
-    session.process_single_command(&prompt).await?;
+    let (cache_enabled, ttl_hours) = ToolExecutor::new().get_response_cache_settings();
+    if !cache.unwrap_or(cache_enabled) {
+        let outcome = session.process_single_command(&prompt).await.map_err(Into::into);
+        return report_request_result(&session, outcome);
+    }
+
+    // The key covers the model, the full prompt (workspace context included) and
+    // the generation options, so changing any of them misses the cache
+    let mut cache = ResponseCache::load(ttl_hours);
+    let key = ResponseCache::key(
+        session.selected_model(),
+        &session.create_context_aware_prompt(&normalize_prompt(&prompt)),
+    );
+    if let Some(response) = cache.get(&key) {
+        session.show_cached_response(response);
+        return report_request_result(&session, Ok(()));
+    }
 
+    let outcome = session.process_single_command(&prompt).await.map_err(Into::into);
+    // Answers that ran tools depend on the machine's state, so only plain
+    // conversation is cached
+    if outcome.is_ok() && !session.last_request_used_tools() && !session.last_response().trim().is_empty() {
+        cache.insert(key, session.model_name(), session.last_response());
+        if let Err(e) = cache.save() {
+            eprintln!("{} Could not save the response cache: {}", "⚠️".yellow(), e);
+        }
+    }
+    report_request_result(&session, outcome)
+}
+
+/// With `--format json`, report an `ask`/`generate` run as a single JSON object
+fn report_request_result(
+    session: &AssistantSession,
+    outcome: Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !output::json_output() {
+        return outcome;
+    }
+
+    let error = outcome.err();
+    let mut report = serde_json::json!({
+        "model": session.model_name(),
+        "response": session.last_response(),
+        "tool_results": session.last_tool_results(),
+        "citations": session.last_citations(),
+        "cached": session.last_response_cached(),
+        "error": error.as_ref().map(|e| e.to_string()),
+        "error_kind": error.as_ref().map(|e| AgentError::kind_of(e.as_ref())),
+    });
+    if !output::quiet() {
+        report["usage"] = serde_json::json!(client::usage_totals());
+    }
+    output::emit(&report);
+
+    if error.is_some() {
+        process::exit(1);
+    }
     Ok(())
 }
 
+/// Where `generate` saves the code: `--output`, `--write-all` and how existing files are treated
+struct GenerateDestination {
+    output: Option<String>,
+    write_all: Option<String>,
+    force: bool,
+    append: bool,
+}
+
 async fn handle_generate_command(
     description: String,
     language: Option<String>,
-    output: Option<String>,
+    destination: GenerateDestination,
     model_name: Option<String>,
+    options: client::OllamaOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let selected_model = if let Some(model) = model_name {
-        // Try to find the specified model
-        let available_models = fetch_models().await?;
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(&model.to_lowercase()))
-            .collect();
-        
-        if matching_models.is_empty() {
-            return Err(format!("Model '{}' not found", model).into());
-        } else if matching_models.len() == 1 {
-            SelectedModel::from(matching_models[0].clone())
-        } else {
-            return Err(format!("Multiple models match '{}', please be more specific", model).into());
-        }
-    } else {
-        let available_models = fetch_models().await?;
-        select_model(&available_models)?
-    };
+    let GenerateDestination { output, write_all, force, append } = destination;
+    // Settle the output path first so an existing file is refused before generating
+    let output_path = output.map(|out| generate_output_path(&out, language.as_deref()));
+    if let Some(path) = output_path.as_ref().filter(|_| !append) {
+        refuse_overwrite(path, force)?;
+    }
+
+    let selected_model = client::resolve_model(model_name).await?.with_options(options);
 
     let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::new(selected_model, tool_executor);
+    let mut session = AssistantSession::new(selected_model, tool_executor)?;
 
     // Construct the generation prompt
     let mut prompt = format!("Generate code based on this description: {}", description);
     
-    if let Some(lang) = language {
+    if let Some(lang) = &language {
         prompt.push_str(&format!(" Use {} programming language.", lang));
     }
     
-    if let Some(out) = output {
-        prompt.push_str(&format!(" Save the code to file: {}", out));
+    if output_path.is_some() {
+        // The code is saved from the response, so the model shouldn't write files itself
+        prompt.push_str(" Reply with the complete code in a single fenced code block and do not write any files.");
+    } else if write_all.is_some() {
+        prompt.push_str(scaffold::LABEL_FILES_INSTRUCTION);
     }
 
-    session.process_single_command(&prompt).await?;
+    let mut outcome = session.process_single_command(&prompt).await.map_err(Into::into);
+    if let (Ok(()), Some(path)) = (&outcome, &output_path) {
+        outcome = write_generated_code(session.last_response(), path, language.as_deref(), force, append);
+    }
+    if let (Ok(()), Some(dir)) = (&outcome, &write_all) {
+        outcome = scaffold::save_all_code_blocks(session.last_response(), std::path::Path::new(dir), force).map(|_| ());
+    }
+    report_request_result(&session, outcome)
+}
+
+/// `output`, with an extension inferred from `language` when it has none
+fn generate_output_path(output: &str, language: Option<&str>) -> std::path::PathBuf {
+    let path = std::path::PathBuf::from(output);
+    match language.and_then(render::language_extension) {
+        Some(extension) if path.extension().is_none() => path.with_extension(extension),
+        _ => path,
+    }
+}
 
+fn refuse_overwrite(path: &std::path::Path, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if path.exists() && !force {
+        return Err(format!("{} already exists; pass --force to overwrite it", path.display()).into());
+    }
+    Ok(())
+}
+
+/// Save the generated code block from `response` to `path`. The block tagged with
+/// the requested language wins; otherwise the longest block is used. With `append`
+/// the block is added after the existing contents, separated by a blank line.
+fn write_generated_code(
+    response: &str,
+    path: &std::path::Path,
+    language: Option<&str>,
+    force: bool,
+    append: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let blocks = render::extract_code_blocks(response);
+    let wanted = language.and_then(render::language_extension);
+    let block = blocks
+        .iter()
+        .find(|block| wanted.is_some() && Some(block.file_extension()) == wanted)
+        .or_else(|| blocks.iter().max_by_key(|block| block.code.len()))
+        .ok_or("The response contained no code block; nothing was written")?;
+
+    let mut code = block.code.clone();
+    if !code.ends_with('\n') {
+        code.push('\n');
+    }
+
+    if append {
+        return append_generated_code(path, &code);
+    }
+
+    let action = format!("write {} ({} bytes)", path.display(), code.len());
+    if tools::dry_run::intercept(action).is_some() {
+        return Ok(());
+    }
+
+    // The file may have appeared while the model was generating
+    refuse_overwrite(path, force)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, &code)?;
+    println!("{} Wrote {} bytes to {}", "💾".green(), code.len(), path.display());
+    Ok(())
+}
+
+/// `--append`: add `code` to the end of `path`, creating it if it doesn't exist
+fn append_generated_code(path: &std::path::Path, code: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+
+    let existing = match std::fs::read(path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("Could not read {}: {}", path.display(), e).into()),
+    };
+    let separator = match existing.last() {
+        None => "",
+        Some(b'\n') => "\n",
+        Some(_) => "\n\n",
+    };
+
+    let action = format!("append {} bytes to {}", code.len(), path.display());
+    if tools::dry_run::intercept(action).is_some() {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(separator.as_bytes())?;
+    file.write_all(code.as_bytes())?;
+    println!("{} Appended {} bytes to {}", "💾".green(), code.len(), path.display());
     Ok(())
 }
 
@@ -1225,35 +2578,16 @@ async fn handle_edit_command(
         return Ok(());
     }
 
-    let selected_model = if let Some(model) = model_name {
-        // Try to find the specified model
-        let available_models = fetch_models().await?;
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(&model.to_lowercase()))
-            .collect();
-        
-        if matching_models.is_empty() {
-            return Err(format!("Model '{}' not found", model).into());
-        } else if matching_models.len() == 1 {
-            SelectedModel::from(matching_models[0].clone())
-        } else {
-            return Err(format!("Multiple models match '{}', please be more specific", model).into());
-        }
-    } else {
-        let available_models = fetch_models().await?;
-        select_model(&available_models)?
-    };
+    let selected_model = client::resolve_model(model_name).await?;
 
     let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::new(selected_model, tool_executor);
+    let mut session = AssistantSession::new(selected_model, tool_executor)?;
 
     // Load file contents
-    let mut file_contents = std::collections::HashMap::new();
+    let mut file_contents = Vec::new();
     for file in &files {
-        if let Ok(content) = std::fs::read_to_string(file) {
-            file_contents.insert(file.clone(), content);
-        }
+        let content = std::fs::read_to_string(file).map_err(|e| format!("Could not read {}: {}", file, e))?;
+        file_contents.push((file.clone(), content));
     }
 
     // Construct the editing prompt
@@ -1263,13 +2597,110 @@ async fn handle_edit_command(
     } else {
         prompt.push_str("Edit the following files:\n\n");
     }
+    prompt.push_str(EDIT_FORMAT_INSTRUCTIONS);
 
-    for (file, content) in file_contents {
+    for (file, content) in &file_contents {
         prompt.push_str(&format!("File: {}\n```\n{}\n```\n\n", file, content));
     }
 
     session.process_single_command(&prompt).await?;
+    apply_edit_blocks(session.last_response(), file_contents).await
+}
 
+const EDIT_FORMAT_INSTRUCTIONS: &str = "Do not rewrite whole files and do not call any tools. \
+Describe each change as a search/replace block: the file path on its own line, then\n\
+<<<<<<< SEARCH\n\
+<exact existing lines, copied verbatim, with enough context to be unique>\n\
+=======\n\
+<the new lines>\n\
+>>>>>>> REPLACE\n\n";
+
+/// Parse search/replace blocks from the model's reply and apply them with
+/// `file_edit`. Every block is checked against the files first; if any block
+/// doesn't match exactly one location, nothing is changed.
+async fn apply_edit_blocks(
+    response: &str,
+    file_contents: Vec<(String, String)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let blocks = tools::parse_search_replace_blocks(response)?;
+    if blocks.is_empty() {
+        println!("{} The response contained no search/replace blocks; no files changed", "⚠️".yellow());
+        return Ok(());
+    }
+
+    let only_file = match file_contents.as_slice() {
+        [(file, _)] => Some(file.clone()),
+        _ => None,
+    };
+    let mut working: std::collections::HashMap<String, String> = file_contents.into_iter().collect();
+    let mut edits = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for (index, block) in blocks.into_iter().enumerate() {
+        let target = match &block.path {
+            Some(path) => working
+                .keys()
+                .find(|file| std::path::Path::new(file) == std::path::Path::new(path))
+                .cloned()
+                .ok_or_else(|| format!("{} is not one of the files being edited", path)),
+            None => only_file.clone().ok_or_else(|| "no file name before the block".to_string()),
+        };
+        let applied = target.and_then(|file| {
+            let updated = tools::apply_search_replace_block(&working[&file], &block)?;
+            working.insert(file.clone(), updated);
+            Ok(file)
+        });
+        match applied {
+            Ok(file) => edits.push((file, block)),
+            Err(reason) => conflicts.push(format!(
+                "Block {}{}: {}",
+                index + 1,
+                block.path.as_deref().map(|p| format!(" ({})", p)).unwrap_or_default(),
+                reason
+            )),
+        }
+    }
+
+    if !conflicts.is_empty() {
+        eprintln!("{} {} block(s) could not be applied:", "❌".red(), conflicts.len());
+        for conflict in &conflicts {
+            eprintln!("  {}", conflict);
+        }
+        return Err(format!("No files changed: {} conflicting edit block(s)", conflicts.len()).into());
+    }
+
+    // Every file this edit run changes is undone together
+    tools::backup::begin_backup_group();
+    let executor = ToolExecutor::new();
+    let mut changed_files = std::collections::BTreeSet::new();
+    for (applied, (file, block)) in edits.iter().enumerate() {
+        let result = executor
+            .execute_tool(tools::AvailableTool::FileEdit {
+                path: file.clone(),
+                operation: tools::EditOperation::Replace {
+                    old: block.search.clone(),
+                    new: block.replace.clone(),
+                },
+            })
+            .await?;
+        if !result.success {
+            return Err(format!(
+                "Stopped after {} of {} edits: {}",
+                applied,
+                edits.len(),
+                result.error.unwrap_or_default()
+            )
+            .into());
+        }
+        changed_files.insert(file.as_str());
+    }
+
+    println!(
+        "{} Applied {} edit(s) to {} file(s)",
+        "✅".green(),
+        edits.len(),
+        changed_files.len()
+    );
     Ok(())
 }
 
@@ -1277,39 +2708,42 @@ async fn handle_review_command(
     files: Vec<String>,
     focus: Option<String>,
     model_name: Option<String>,
+    watch: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if files.is_empty() {
         eprintln!("{} No files specified for review", "❌".red());
         return Ok(());
     }
 
-    let selected_model = if let Some(model) = model_name {
-        // Try to find the specified model
-        let available_models = fetch_models().await?;
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(&model.to_lowercase()))
-            .collect();
-        
-        if matching_models.is_empty() {
-            return Err(format!("Model '{}' not found", model).into());
-        } else if matching_models.len() == 1 {
-            SelectedModel::from(matching_models[0].clone())
-        } else {
-            return Err(format!("Multiple models match '{}', please be more specific", model).into());
-        }
-    } else {
-        let available_models = fetch_models().await?;
-        select_model(&available_models)?
-    };
+    let selected_model = client::resolve_model(model_name).await?;
 
-    let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::new(selected_model, tool_executor);
+    if watch {
+        return watch::watch(&files, || review_files(&selected_model, &files, focus.as_deref())).await;
+    }
 
-    // Load file contents
-    let mut file_contents = std::collections::HashMap::new();
-    for file in &files {
-        if let Ok(content) = std::fs::read_to_string(file) {
+    review_files(&selected_model, &files, focus.as_deref()).await
+}
+
+async fn review_files(
+    selected_model: &SelectedModel,
+    files: &[String],
+    focus: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tool_executor = ToolExecutor::new();
+    let mut session = AssistantSession::new(selected_model.clone(), tool_executor)?;
+
+    // Load file contents; directories are expanded skipping hidden and gitignored files
+    let mut file_contents = std::collections::BTreeMap::new();
+    for file in files {
+        if std::path::Path::new(file).is_dir() {
+            for entry in ignore::WalkBuilder::new(file).require_git(false).build().filter_map(|e| e.ok()) {
+                if entry.file_type().is_some_and(|t| t.is_file()) {
+                    if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                        file_contents.insert(entry.path().display().to_string(), content);
+                    }
+                }
+            }
+        } else if let Ok(content) = std::fs::read_to_string(file) {
             file_contents.insert(file.clone(), content);
         }
     }
@@ -1333,30 +2767,13 @@ async fn handle_review_command(
 
 async fn handle_commit_command(
     context: Option<String>,
+    conventional: bool,
     model_name: Option<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let selected_model = if let Some(model) = model_name {
-        // Try to find the specified model
-        let available_models = fetch_models().await?;
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(&model.to_lowercase()))
-            .collect();
-        
-        if matching_models.is_empty() {
-            return Err(format!("Model '{}' not found", model).into());
-        } else if matching_models.len() == 1 {
-            SelectedModel::from(matching_models[0].clone())
-        } else {
-            return Err(format!("Multiple models match '{}', please be more specific", model).into());
-        }
-    } else {
-        let available_models = fetch_models().await?;
-        select_model(&available_models)?
-    };
+    let selected_model = client::resolve_model(model_name).await?;
 
     let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::new(selected_model, tool_executor);
+    let mut session = AssistantSession::new(selected_model.clone(), tool_executor)?;
 
     // Get git diff
     let git_tool_executor = ToolExecutor::new();
@@ -1366,6 +2783,20 @@ async fn handle_commit_command(
         return Ok(());
     }
 
+    if conventional {
+        let stat_result = git_tool_executor.git_diff_stat(false, None).await?;
+        let scopes = tools::git::scopes_from_diff_stat(&stat_result.output);
+        let message = generate_conventional_commit_message(
+            &selected_model,
+            &diff_result.output,
+            &scopes,
+            context.as_deref(),
+        )
+        .await?;
+        println!("{}", message);
+        return Ok(());
+    }
+
     // Construct commit message generation prompt
     let mut prompt = "Generate a concise and descriptive commit message based on the following git diff:\n\n".to_string();
     prompt.push_str(&diff_result.output);
@@ -1379,6 +2810,183 @@ async fn handle_commit_command(
     Ok(())
 }
 
+async fn handle_embed_command(
+    text: Option<String>,
+    files: Vec<String>,
+    output: Option<String>,
+    model_name: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if text.is_none() && files.is_empty() {
+        return Err("Provide either --text or at least one --file to embed".into());
+    }
+
+    let selected_model = client::resolve_model(model_name).await?;
+
+    if !client::model_supports_embeddings(selected_model.get_name()).await? {
+        return Err(format!(
+            "Model '{}' does not support embeddings. Try an embedding model such as nomic-embed-text",
+            selected_model.get_name()
+        )
+        .into());
+    }
+
+    let json = if let Some(text) = text {
+        let embedding = client::generate_embeddings(&selected_model, &text).await?;
+        serde_json::to_value(embedding)?
+    } else if files.len() == 1 {
+        let content = std::fs::read_to_string(&files[0])
+            .map_err(|e| format!("Failed to read '{}': {}", files[0], e))?;
+        let embedding = client::generate_embeddings(&selected_model, &content).await?;
+        serde_json::to_value(embedding)?
+    } else {
+        // Batch mode: an array with one {"path", "embedding"} object per file, in argument order
+        let mut embeddings = Vec::new();
+        for path in &files {
+            eprintln!("{} Embedding {}", "🧮".cyan(), path.yellow());
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+            let embedding = client::generate_embeddings(&selected_model, &content).await?;
+            embeddings.push(serde_json::json!({ "path": path, "embedding": embedding }));
+        }
+        serde_json::Value::Array(embeddings)
+    };
+
+    let rendered = serde_json::to_string(&json)?;
+    if let Some(output_path) = output {
+        std::fs::write(&output_path, rendered)?;
+        println!("{} Embeddings written to {}", "✅".green(), output_path.yellow());
+    } else {
+        println!("{}", rendered);
+    }
+
+    Ok(())
+}
+
+async fn generate_conventional_commit_message(
+    model: &SelectedModel,
+    diff: &str,
+    scopes: &[String],
+    context: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut prompt = format!(
+        "Write a git commit message for the following diff using the Conventional Commits format.\n\n\
+        Rules:\n\
+        - The first line must be `type(scope): summary`\n\
+        - type is one of: {}\n\
+        - scope should be one of these (most relevant first): {}\n\
+        - summary is imperative, lowercase, at most 72 characters, no trailing period\n\
+        - Optionally add a blank line and a short body\n\
+        - Reply with the commit message only\n\n",
+        tools::git::CONVENTIONAL_COMMIT_TYPES.join(", "),
+        if scopes.is_empty() {
+            "(none detected, omit the scope)".to_string()
+        } else {
+            scopes.join(", ")
+        }
+    );
+    if let Some(ctx) = context {
+        prompt.push_str(&format!("Additional context: {}\n\n", ctx));
+    }
+    prompt.push_str(&format!("Diff:\n{}\n", diff));
+
+    println!("{} Generating conventional commit message...", "🤖".cyan());
+    let first = clean_commit_message(&client::generate_response_silent(model, &prompt).await?);
+    if tools::git::is_conventional_commit(&first) {
+        return Ok(first);
+    }
+
+    // Re-prompt once with the rejected attempt before giving up
+    println!("{} Message didn't follow Conventional Commits, retrying...", "🔁".yellow());
+    let retry_prompt = format!(
+        "{}\nYour previous answer was:\n{}\n\n\
+        It does not match `type(scope): summary`. Reply again with a conforming commit message only.\n",
+        prompt, first
+    );
+    let second = clean_commit_message(&client::generate_response_silent(model, &retry_prompt).await?);
+    if tools::git::is_conventional_commit(&second) {
+        return Ok(second);
+    }
+
+    println!(
+        "{} Could not get a conforming message; using the model's raw output",
+        "⚠".yellow()
+    );
+    Ok(first)
+}
+
+// Strip reasoning blocks, code fences and quotes that models like to wrap messages in
+fn clean_commit_message(raw: &str) -> String {
+    let without_think = match raw.find("</think>") {
+        Some(end) => &raw[end + "</think>".len()..],
+        None => raw,
+    };
+
+    without_think
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .trim_matches(|c| c == '"' || c == '`')
+        .trim()
+        .to_string()
+}
+
+/// Markdown table of per-language totals with a grand total row
+fn language_table(languages: &[workspace::LanguageStats]) -> String {
+    let mut rows: Vec<[String; 4]> = languages
+        .iter()
+        .map(|stats| {
+            [
+                stats.language.clone(),
+                stats.files.to_string(),
+                stats.lines.to_string(),
+                stats.bytes.to_string(),
+            ]
+        })
+        .collect();
+    rows.push([
+        "**Total**".to_string(),
+        languages.iter().map(|stats| stats.files).sum::<usize>().to_string(),
+        languages.iter().map(|stats| stats.lines).sum::<usize>().to_string(),
+        languages.iter().map(|stats| stats.bytes).sum::<u64>().to_string(),
+    ]);
+
+    let headers = ["Language", "Files", "Lines", "Bytes"];
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    // Language left-aligned, counts right-aligned, matching the separator row
+    let format_row = |cells: [&str; 4]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                0 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let separator: Vec<String> = widths
+        .iter()
+        .enumerate()
+        .map(|(column, width)| match column {
+            0 => "-".repeat(*width),
+            _ => format!("{}:", "-".repeat(width - 1)),
+        })
+        .collect();
+
+    let mut lines = vec![format_row(headers), format!("| {} |", separator.join(" | "))];
+    lines.extend(rows.iter().map(|row| format_row([&row[0], &row[1], &row[2], &row[3]].map(String::as_str))));
+    lines.join("\n")
+}
+
 async fn handle_init_command(
     path: Option<String>,
     project_type: Option<String>,
@@ -1388,22 +2996,72 @@ async fn handle_init_command(
     workspace_manager.init_workspace(path, project_type)?;
     
     if let Some(context) = workspace_manager.get_context_mut() {
-        context.scan_project(false)?;
+        context.scan_project(false, false)?;
         workspace_manager.save_context()?;
     }
     
     Ok(())
 }
 
+/// List what changed since the last scan, with unified diffs for modified
+/// files when `content` is set
+fn print_workspace_diff(
+    context: &workspace::WorkspaceContext,
+    content: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let diff = context.diff_since_scan()?;
+    let since = context.metadata.get("last_scan").unwrap_or(&context.last_updated);
+    if diff.is_empty() {
+        println!("{} No changes since the last scan ({})", "✅".green(), since);
+        return Ok(());
+    }
+
+    println!("{} Changes since the last scan ({})", "📊".cyan(), since);
+    for file in &diff.added {
+        println!("  {} {}", "+".green(), file.display());
+    }
+    for file in &diff.modified {
+        let current = std::fs::read_to_string(context.root_path.join(file)).unwrap_or_default();
+        match context.snapshot(file) {
+            Some(old) => {
+                let (added, removed) = tools::diff::diff_stats(Some(&old), &current);
+                println!("  {} {} (+{} -{})", "~".yellow(), file.display(), added, removed);
+                if content {
+                    tools::diff::print_diff(&tools::diff::unified_diff(&file.display().to_string(), Some(&old), &current));
+                }
+            }
+            None => {
+                println!("  {} {}", "~".yellow(), file.display());
+                if content {
+                    println!("    {} No copy from the last scan to compare against", "ℹ️".blue());
+                }
+            }
+        }
+    }
+    for file in &diff.removed {
+        println!("  {} {}", "-".red(), file.display());
+    }
+    println!(
+        "{} added, {} modified, {} removed, {} unchanged",
+        diff.added.len(),
+        diff.modified.len(),
+        diff.removed.len(),
+        diff.unchanged
+    );
+    println!("{} Run `workspace scan` to update the context", "💡".yellow());
+    Ok(())
+}
+
 async fn handle_history_command(
     command: HistoryCommands,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut history_manager = tools::history::HistoryManager::new();
+    let mut history_manager = tools::history::HistoryManager::new()?;
     
     match command {
-        HistoryCommands::Show { count, detailed } => {
-            let entries = history_manager.get_recent(count);
-            history_manager.show_entries(&entries, detailed);
+        HistoryCommands::Show { count, page, pager, detailed, tag, since, until } => {
+            let range = tools::history::TimeRange::new(since, until)?;
+            let page = history_manager.get_page(page as usize, count, tag.as_deref(), range)?;
+            history_manager.show_page(&page, detailed, pager);
         }
         HistoryCommands::Clear { all: _ } => {
             history_manager.clear();
@@ -1413,11 +3071,27 @@ async fn handle_history_command(
             history_manager.export(&path, &format)?;
             println!("{} Conversation history exported to: {} (format: {})", "📤".cyan(), path, format);
         }
-        HistoryCommands::Search { query, limit } => {
-            let entries = history_manager.search(&query, limit);
+        HistoryCommands::Search { query, limit, tag, since, until } => {
+            let range = tools::history::TimeRange::new(since, until)?;
+            let entries = history_manager.search_filtered(&query, limit, tag.as_deref(), range);
             println!("{} Search results for '{}':", "🔍".cyan(), query);
             history_manager.show_entries(&entries, true);
         }
+        HistoryCommands::Tag { tag_command } => match tag_command {
+            HistoryTagCommands::Add { tag, session } => {
+                let session_id = history_manager.resolve_session(session.as_deref())?;
+                history_manager.add_tag(&session_id, "", &tag)?;
+                println!("{} Tagged conversation {} with '{}'", "🏷️".cyan(), session_id, tag);
+            }
+            HistoryTagCommands::Remove { tag, session } => {
+                let session_id = history_manager.resolve_session(session.as_deref())?;
+                if history_manager.remove_tag(&session_id, &tag)? {
+                    println!("{} Removed tag '{}' from conversation {}", "🏷️".cyan(), tag, session_id);
+                } else {
+                    println!("{} Conversation {} is not tagged '{}'", "ℹ️".blue(), session_id, tag);
+                }
+            }
+        },
     }
     
     Ok(())
@@ -1432,7 +3106,7 @@ async fn handle_workspace_command(
         WorkspaceCommands::Init { path, project_type } => {
             workspace_manager.init_workspace(path, project_type)?;
             if let Some(context) = workspace_manager.get_context_mut() {
-                context.scan_project(false)?;
+                context.scan_project(false, false)?;
             }
             workspace_manager.save_context()?;
         }
@@ -1442,28 +3116,89 @@ async fn handle_workspace_command(
                 println!("{} Workspace Information", "📁".cyan());
                 println!("Root path: {}", context.root_path.display());
                 println!("Project type: {}", context.project_type.as_deref().unwrap_or("unknown"));
+                if !context.projects.is_empty() {
+                    println!("Projects:");
+                    for project in &context.projects {
+                        let location = if project.path.as_os_str().is_empty() {
+                            ".".to_string()
+                        } else {
+                            project.path.display().to_string()
+                        };
+                        println!("  {} {} ({})", location.yellow(), project.project_type, project.build_tool);
+                        for (label, command) in project.known_commands() {
+                            println!("      {:<6} {}", format!("{}:", label).dimmed(), command);
+                        }
+                    }
+                }
                 println!("Files in context: {}", context.included_files.len());
+                println!(
+                    "Size limits: {} bytes per file, {} bytes total",
+                    context.max_file_bytes, context.max_total_context_bytes
+                );
                 println!("Created: {}", context.created_at);
                 println!("Last updated: {}", context.last_updated);
             } else {
                 println!("{} No workspace context found", "❌".red());
             }
         }
-        WorkspaceCommands::Scan { path, include_hidden } => {
-            if workspace_manager.get_context().is_none() {
-                workspace_manager.init_workspace(path, None)?;
+        WorkspaceCommands::Scan { path, include_hidden, include_ignored } => {
+            // Reuse the saved context so unchanged files aren't re-read
+            workspace_manager.open_workspace(path)?;
+            if let Some(context) = workspace_manager.get_context_mut() {
+                context.scan_project(include_hidden, include_ignored)?;
+                workspace_manager.save_context()?;
             }
+        }
+        WorkspaceCommands::Limits { max_file_bytes, max_total_bytes } => {
+            workspace_manager.load_context()?;
             if let Some(context) = workspace_manager.get_context_mut() {
-                context.scan_project(include_hidden)?;
+                if let Some(bytes) = max_file_bytes {
+                    context.max_file_bytes = bytes;
+                }
+                if let Some(bytes) = max_total_bytes {
+                    context.max_total_context_bytes = bytes;
+                }
+                println!(
+                    "{} Size limits: {} bytes per file, {} bytes total",
+                    "📏".cyan(),
+                    context.max_file_bytes,
+                    context.max_total_context_bytes
+                );
                 workspace_manager.save_context()?;
+            } else {
+                println!("{} No workspace context found. Run 'init' first.", "❌".red());
+            }
+        }
+        WorkspaceCommands::Ignore { patterns, remove } => {
+            workspace_manager.load_context()?;
+            if let Some(context) = workspace_manager.get_context_mut() {
+                if patterns.is_empty() {
+                    println!("{} Workspace ignore patterns:", "🙈".cyan());
+                    for pattern in &context.excluded_patterns {
+                        println!("  {}", pattern);
+                    }
+                    return Ok(());
+                }
+                if remove {
+                    context.remove_ignore_patterns(&patterns);
+                    println!("{} Removed {} ignore pattern(s)", "✅".green(), patterns.len());
+                } else {
+                    context.add_ignore_patterns(&patterns);
+                    println!("{} Added {} ignore pattern(s)", "✅".green(), patterns.len());
+                }
+                workspace_manager.save_context()?;
+            } else {
+                println!("{} No workspace context found. Run 'init' first.", "❌".red());
             }
         }
         WorkspaceCommands::Add { files } => {
             workspace_manager.load_context()?;
             if let Some(context) = workspace_manager.get_context_mut() {
+                let before = context.included_files.len();
                 context.add_files(&files)?;
+                let added = context.included_files.len() - before;
                 workspace_manager.save_context()?;
-                println!("{} Added {} files to workspace context", "✅".green(), files.len());
+                println!("{} Added {} files to workspace context", "✅".green(), added);
             } else {
                 println!("{} No workspace context found. Run 'init' first.", "❌".red());
             }
@@ -1478,7 +3213,15 @@ async fn handle_workspace_command(
                 println!("{} No workspace context found", "❌".red());
             }
         }
-        WorkspaceCommands::List { detailed } => {
+        WorkspaceCommands::List { by_language: true, .. } => {
+            workspace_manager.load_context()?;
+            if let Some(context) = workspace_manager.get_context() {
+                println!("{}", language_table(&context.language_breakdown()));
+            } else {
+                println!("{} No workspace context found", "❌".red());
+            }
+        }
+        WorkspaceCommands::List { detailed, .. } => {
             workspace_manager.load_context()?;
             if let Some(context) = workspace_manager.get_context() {
                 println!("{} Workspace files ({})", "📁".cyan(), context.included_files.len());
@@ -1498,6 +3241,39 @@ async fn handle_workspace_command(
                 println!("{} No workspace context found", "❌".red());
             }
         }
+        WorkspaceCommands::Diff { content } => {
+            workspace_manager.load_context()?;
+            if let Some(context) = workspace_manager.get_context() {
+                print_workspace_diff(context, content)?;
+            } else {
+                println!("{} No workspace context found", "❌".red());
+            }
+        }
+        WorkspaceCommands::Tokens { num_ctx } => {
+            workspace_manager.load_context()?;
+            if let Some(context) = workspace_manager.get_context() {
+                let tool_executor = ToolExecutor::new();
+                let budget = session::context_token_budget(&tool_executor, num_ctx).await;
+                let file_contents = context.get_file_contents()?;
+                let file_tokens = session::file_token_counts(&file_contents);
+                let files = if file_contents.len() <= session::MAX_INLINED_FILES {
+                    file_tokens.iter().map(|(_, tokens)| tokens).sum()
+                } else {
+                    0
+                };
+                session::print_context_budget(
+                    &file_tokens,
+                    &[
+                        ("Workspace files", files),
+                        ("System prompt", session::system_prompt_tokens(&tool_executor).await),
+                        ("Reserved for reply", budget / session::RESPONSE_RESERVE_RATIO),
+                    ],
+                    budget,
+                );
+            } else {
+                println!("{} No workspace context found", "❌".red());
+            }
+        }
         WorkspaceCommands::Clear => {
             workspace_manager.clear_context()?;
             println!("{} Workspace context cleared", "🧹".cyan());
@@ -1518,10 +3294,7 @@ async fn execute_single_command_with_context(
     
     // Load workspace context if requested
     if project_context {
-        if let Err(_) = workspace_manager.load_context() {
-            workspace_manager.init_workspace(None, None)?;
-            workspace_manager.get_context_mut().unwrap().scan_project(false)?;
-        }
+        workspace_manager.refresh_context()?;
     }
 
     // Add specified files to context
@@ -1532,30 +3305,11 @@ async fn execute_single_command_with_context(
         workspace_manager.get_context_mut().unwrap().add_files(&files)?;
     }
 
-    // Get selected model
-    let selected_model = if let Some(model) = model_name {
-        // Try to find the specified model
-        let available_models = fetch_models().await?;
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(&model.to_lowercase()))
-            .collect();
-        
-        if matching_models.is_empty() {
-            return Err(format!("Model '{}' not found", model).into());
-        } else if matching_models.len() == 1 {
-            SelectedModel::from(matching_models[0].clone())
-        } else {
-            return Err(format!("Multiple models match '{}', please be more specific", model).into());
-        }
-    } else {
-        let available_models = fetch_models().await?;
-        select_model(&available_models)?
-    };
+    let selected_model = client::resolve_model(model_name).await?;
 
     // Create session
     let tool_executor = ToolExecutor::new();
-    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode);
+    let mut session = AssistantSession::with_vim_mode(selected_model, tool_executor, vim_mode)?;
 
     // Add workspace context to session if available
     if let Some(context) = workspace_manager.get_context() {
@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+// Set by the global `--quiet` flag to drop end-of-command summaries
+static QUIET: AtomicBool = AtomicBool::new(false);
+// Set by `--ci` or a `CI` environment variable: no spinners, progress bars or colors
+static CI_MODE: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    /// The original stdout, kept aside while decorative output is sent to stderr
+    static ref JSON_SINK: Mutex<Option<File>> = Mutex::new(None);
+}
+
+/// Output format selected with `--format`
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Whether `CI` is set to something other than `false`/`0`, as CI services do
+pub fn ci_env() -> bool {
+    std::env::var("CI").is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"))
+}
+
+pub fn set_ci_mode(ci: bool) {
+    CI_MODE.store(ci, Ordering::Relaxed);
+}
+
+pub fn ci_mode() -> bool {
+    CI_MODE.load(Ordering::Relaxed)
+}
+
+/// Whether spinners and progress bars may redraw in place; otherwise progress is
+/// reported as plain log lines
+pub fn live_progress() -> bool {
+    !ci_mode() && io::stderr().is_terminal()
+}
+
+/// Switch to JSON output: everything printed from here on goes to stderr, and
+/// stdout only receives the objects passed to [`emit`].
+pub fn enable_json_output() -> io::Result<()> {
+    JSON_OUTPUT.store(true, Ordering::Relaxed);
+    *JSON_SINK.lock().unwrap() = redirect_stdout_to_stderr()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn redirect_stdout_to_stderr() -> io::Result<Option<File>> {
+    use std::os::unix::io::FromRawFd;
+
+    io::stdout().flush()?;
+    // SAFETY: plain descriptor duplication; the duplicate is owned by the returned File
+    unsafe {
+        let saved = libc::dup(libc::STDOUT_FILENO);
+        if saved < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+            let err = io::Error::last_os_error();
+            libc::close(saved);
+            return Err(err);
+        }
+        Ok(Some(File::from_raw_fd(saved)))
+    }
+}
+
+// Without descriptor redirection decorative output stays on stdout
+#[cfg(not(unix))]
+fn redirect_stdout_to_stderr() -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+/// Write one JSON document to the real stdout
+pub fn emit(value: &serde_json::Value) {
+    let text = serde_json::to_string_pretty(value).unwrap_or_else(|_| "null".to_string());
+    let mut sink = JSON_SINK.lock().unwrap();
+    match sink.as_mut() {
+        Some(file) => {
+            let _ = writeln!(file, "{}", text);
+            let _ = file.flush();
+        }
+        None => {
+            let mut stdout = io::stdout();
+            let _ = writeln!(stdout, "{}", text);
+            let _ = stdout.flush();
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use colored::Colorize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable that moves everything kept under [`data_dir`]
+pub const DATA_DIR_ENV: &str = "OLLAMA_AGENT_DATA_DIR";
+
+/// Data directory used before the platform directories were; moved on first run
+const LEGACY_DATA_DIR: &str = ".ollama-agent";
+
+static DATA_DIR: OnceLock<(PathBuf, &'static str)> = OnceLock::new();
+
+/// Root for history, caches, logs, backups, saved sessions and workspace context
+pub fn data_dir() -> PathBuf {
+    DATA_DIR.get_or_init(resolve_data_dir).0.clone()
+}
+
+/// Why [`data_dir`] points where it does, for `config path`
+pub fn data_dir_origin() -> &'static str {
+    DATA_DIR.get_or_init(resolve_data_dir).1
+}
+
+/// `OLLAMA_AGENT_DATA_DIR` if set, else `ollama-agent` in the platform data directory
+/// (`$XDG_DATA_HOME`, `%APPDATA%`, `~/Library/Application Support`). A `~/.ollama-agent`
+/// left by an earlier version is moved there the first time; if that fails before anything
+/// was moved it is used as is, otherwise the platform directory is kept.
+fn resolve_data_dir() -> (PathBuf, &'static str) {
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return (PathBuf::from(dir), "from OLLAMA_AGENT_DATA_DIR");
+    }
+    let Some(platform) = dirs::data_dir().map(|dir| dir.join("ollama-agent")) else {
+        return (
+            PathBuf::from(LEGACY_DATA_DIR),
+            "no home directory, relative to the working directory",
+        );
+    };
+    let Some(legacy) = dirs::home_dir()
+        .map(|home| home.join(LEGACY_DATA_DIR))
+        .filter(|dir| dir.is_dir())
+    else {
+        return (platform, "platform data directory");
+    };
+
+    let mut moved = 0;
+    match migrate_legacy_data_dir(&legacy, &platform, &mut moved) {
+        Ok(left_behind) => {
+            eprintln!(
+                "{} Moved data from {} to {}",
+                "📦".cyan(),
+                legacy.display(),
+                platform.display()
+            );
+            if let Some(left_behind) = left_behind {
+                eprintln!(
+                    "{} Files already present in the new location were kept; the old copies are in {}",
+                    "⚠️".yellow(),
+                    left_behind.display()
+                );
+            }
+            (platform, "platform data directory")
+        }
+        Err(e) if moved > 0 => {
+            // Part of the data already lives in the new place; going back would hide it
+            eprintln!(
+                "{} Could only partly move {} to {}: {} (the rest is still in the old directory)",
+                "⚠️".yellow(),
+                legacy.display(),
+                platform.display(),
+                e
+            );
+            (
+                platform,
+                "platform data directory, ~/.ollama-agent only partly moved",
+            )
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Could not move {} to {}: {} (still using the old directory)",
+                "⚠️".yellow(),
+                legacy.display(),
+                platform.display(),
+                e
+            );
+            (legacy, "existing ~/.ollama-agent, could not be moved")
+        }
+    }
+}
+
+/// Move everything in `legacy` into `platform`, never overwriting what is already
+/// there. Returns where clashing leftovers were parked, if there were any; `moved`
+/// counts the entries already moved in case a later one fails.
+fn migrate_legacy_data_dir(
+    legacy: &Path,
+    platform: &Path,
+    moved: &mut usize,
+) -> io::Result<Option<PathBuf>> {
+    fs::create_dir_all(platform)?;
+    for entry in fs::read_dir(legacy)? {
+        let entry = entry?;
+        let target = platform.join(entry.file_name());
+        if !target.exists() {
+            move_path(&entry.path(), &target)?;
+            *moved += 1;
+        }
+    }
+
+    if fs::read_dir(legacy)?.next().is_none() {
+        fs::remove_dir(legacy)?;
+        return Ok(None);
+    }
+    let parked = legacy.with_extension("migrated");
+    fs::rename(legacy, &parked)?;
+    Ok(Some(parked))
+}
+
+/// Rename, falling back to copy-and-delete when the two paths are on different filesystems
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_recursively(from, to)?;
+    if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+}
+
+fn copy_recursively(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Directory holding `config.json` and scheduled tasks: `~/.ollama_agent`
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ollama_agent"))
+}
@@ -0,0 +1,504 @@
+use colored::Colorize;
+use regex::Regex;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::SyntaxSet;
+use syntect::util::as_24_bit_terminal_escaped;
+
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_nonewlines();
+    static ref THEME: Theme = ThemeSet::load_defaults().themes["base16-ocean.dark"].clone();
+    static ref HEADING: Regex = Regex::new(r"^(#{1,6})\s+(.*)$").unwrap();
+    static ref BULLET: Regex = Regex::new(r"^(\s*)[-*+]\s+(.*)$").unwrap();
+    static ref NUMBERED: Regex = Regex::new(r"^(\s*)(\d+[.)])\s+(.*)$").unwrap();
+    static ref RULE: Regex = Regex::new(r"^\s*(?:(?:-\s*){3,}|(?:\*\s*){3,}|(?:_\s*){3,})$").unwrap();
+    static ref INLINE: Regex =
+        Regex::new(r"`([^`]+)`|\*\*([^*]+)\*\*|__([^_]+)__|\*([^*\s][^*]*)\*").unwrap();
+    static ref TEMPLATE_DIRECTIVE: Regex = Regex::new(r"\{\{-?.*?-?\}\}").unwrap();
+}
+
+/// Disable Markdown rendering (`--plain`) so responses print verbatim
+pub fn set_plain_output(plain: bool) {
+    PLAIN_OUTPUT.store(plain, Ordering::Relaxed);
+}
+
+fn rendering_enabled() -> bool {
+    !PLAIN_OUTPUT.load(Ordering::Relaxed) && io::stdout().is_terminal()
+}
+
+/// Show `text` through `$PAGER` (default `less`) when stdout is a terminal, otherwise
+/// print it. Falls back to printing if the pager can't be started.
+pub fn page_output(text: &str) {
+    if io::stdout().is_terminal() {
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| "less".to_string());
+        let mut parts = pager.split_whitespace();
+        if let Some(program) = parts.next() {
+            let mut command = Command::new(program);
+            command.args(parts).stdin(Stdio::piped());
+            // Like git: keep colors, quit if it fits on one screen, leave the text on exit
+            if std::env::var_os("LESS").is_none() {
+                command.env("LESS", "FRX");
+            }
+            if let Ok(mut child) = command.spawn() {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // Quitting the pager early closes the pipe; that's not an error
+                    let _ = stdin.write_all(text.as_bytes());
+                }
+                let _ = child.wait();
+                return;
+            }
+        }
+    }
+    print!("{}", text);
+}
+
+/// Renders streamed Markdown one completed line at a time. Tokens are buffered
+/// until a newline arrives; fenced code blocks are highlighted line by line.
+pub struct MarkdownStream {
+    enabled: bool,
+    pending: String,
+    code_block: Option<Option<HighlightLines<'static>>>,
+}
+
+impl Default for MarkdownStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownStream {
+    pub fn new() -> Self {
+        Self {
+            enabled: rendering_enabled(),
+            pending: String::new(),
+            code_block: None,
+        }
+    }
+
+    pub fn push(&mut self, token: &str) {
+        if !self.enabled {
+            print!("{}", token);
+            io::stdout().flush().unwrap();
+            return;
+        }
+
+        self.pending.push_str(token);
+        while let Some(newline) = self.pending.find('\n') {
+            let line: String = self.pending.drain(..=newline).collect();
+            self.render_line(line.trim_end_matches(['\n', '\r']));
+            println!();
+        }
+        io::stdout().flush().unwrap();
+    }
+
+    /// Render whatever is left of an unterminated final line
+    pub fn finish(&mut self) {
+        if !self.pending.is_empty() {
+            let line = std::mem::take(&mut self.pending);
+            self.render_line(&line);
+        }
+        self.code_block = None;
+        io::stdout().flush().unwrap();
+    }
+
+    fn render_line(&mut self, line: &str) {
+        let fence = line.trim_start().strip_prefix("```");
+
+        if let Some(highlighter) = &mut self.code_block {
+            if fence.is_some() {
+                self.code_block = None;
+                print!("{}", line.dimmed());
+                return;
+            }
+            match highlighter {
+                Some(highlighter) => match highlighter.highlight_line(line, &SYNTAX_SET) {
+                    Ok(ranges) => print!("{}\x1b[0m", as_24_bit_terminal_escaped(&ranges, false)),
+                    Err(_) => print!("{}", line),
+                },
+                None => print!("{}", line),
+            }
+            return;
+        }
+
+        if let Some(language) = fence {
+            let highlighter = SYNTAX_SET
+                .find_syntax_by_token(language.trim())
+                .map(|syntax| HighlightLines::new(syntax, &THEME));
+            self.code_block = Some(highlighter);
+            print!("{}", line.dimmed());
+            return;
+        }
+
+        if let Some(caps) = HEADING.captures(line) {
+            let text = render_inline(&caps[2]);
+            match caps[1].len() {
+                1 => print!("{}", text.cyan().bold().underline()),
+                2 => print!("{}", text.cyan().bold()),
+                _ => print!("{}", text.blue().bold()),
+            }
+        } else if RULE.is_match(line) {
+            print!("{}", "─".repeat(40).dimmed());
+        } else if let Some(caps) = BULLET.captures(line) {
+            print!("{}{} {}", &caps[1], "•".yellow(), render_inline(&caps[2]));
+        } else if let Some(caps) = NUMBERED.captures(line) {
+            print!("{}{} {}", &caps[1], caps[2].yellow(), render_inline(&caps[3]));
+        } else if let Some(quote) = line.strip_prefix('>') {
+            print!("{} {}", "│".dimmed(), render_inline(quote.trim_start()).italic());
+        } else {
+            print!("{}", render_inline(line));
+        }
+    }
+}
+
+/// Apply inline code, bold and italic styling
+fn render_inline(text: &str) -> String {
+    INLINE
+        .replace_all(text, |caps: &regex::Captures| {
+            if let Some(code) = caps.get(1) {
+                code.as_str().yellow().to_string()
+            } else if let Some(bold) = caps.get(2).or_else(|| caps.get(3)) {
+                bold.as_str().bold().to_string()
+            } else {
+                caps[4].italic().to_string()
+            }
+        })
+        .into_owned()
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render Markdown to an HTML fragment with the same rules as the terminal
+/// renderer. Code blocks are highlighted with inline styles so the output needs
+/// no external stylesheet.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut list: Option<&str> = None;
+    let mut code_block: Option<(Option<HighlightLines<'static>>, Vec<String>)> = None;
+
+    fn close_blocks(html: &mut String, paragraph: &mut Vec<String>, list: &mut Option<&str>) {
+        if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", paragraph.join("<br>")));
+            paragraph.clear();
+        }
+        if let Some(tag) = list.take() {
+            html.push_str(&format!("</{}>\n", tag));
+        }
+    }
+
+    for line in markdown.lines() {
+        let fence = line.trim_start().strip_prefix("```");
+
+        if let Some((highlighter, lines)) = &mut code_block {
+            if fence.is_some() {
+                html.push_str(&format!(
+                    "<pre class=\"code\"><code>{}</code></pre>\n",
+                    lines.join("\n")
+                ));
+                code_block = None;
+                continue;
+            }
+            let rendered = highlighter
+                .as_mut()
+                .and_then(|highlighter| highlighter.highlight_line(line, &SYNTAX_SET).ok())
+                .and_then(|ranges| styled_line_to_highlighted_html(&ranges, IncludeBackground::No).ok())
+                .unwrap_or_else(|| escape_html(line));
+            lines.push(rendered);
+            continue;
+        }
+
+        if let Some(language) = fence {
+            close_blocks(&mut html, &mut paragraph, &mut list);
+            let highlighter = SYNTAX_SET
+                .find_syntax_by_token(language.trim())
+                .map(|syntax| HighlightLines::new(syntax, &THEME));
+            code_block = Some((highlighter, Vec::new()));
+            continue;
+        }
+
+        let item = BULLET
+            .captures(line)
+            .map(|caps| ("ul", caps[2].to_string()))
+            .or_else(|| NUMBERED.captures(line).map(|caps| ("ol", caps[3].to_string())));
+        if let Some((tag, text)) = item {
+            if !paragraph.is_empty() || list.is_some_and(|open| open != tag) {
+                close_blocks(&mut html, &mut paragraph, &mut list);
+            }
+            if list.is_none() {
+                html.push_str(&format!("<{}>\n", tag));
+                list = Some(tag);
+            }
+            html.push_str(&format!("<li>{}</li>\n", inline_html(&text)));
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            close_blocks(&mut html, &mut paragraph, &mut list);
+        } else if let Some(caps) = HEADING.captures(line) {
+            close_blocks(&mut html, &mut paragraph, &mut list);
+            let level = caps[1].len();
+            html.push_str(&format!("<h{0}>{1}</h{0}>\n", level, inline_html(&caps[2])));
+        } else if RULE.is_match(line) {
+            close_blocks(&mut html, &mut paragraph, &mut list);
+            html.push_str("<hr>\n");
+        } else if let Some(quote) = line.strip_prefix('>') {
+            close_blocks(&mut html, &mut paragraph, &mut list);
+            html.push_str(&format!("<blockquote>{}</blockquote>\n", inline_html(quote.trim_start())));
+        } else {
+            if list.is_some() {
+                close_blocks(&mut html, &mut paragraph, &mut list);
+            }
+            paragraph.push(inline_html(line));
+        }
+    }
+
+    if let Some((_, lines)) = code_block {
+        html.push_str(&format!("<pre class=\"code\"><code>{}</code></pre>\n", lines.join("\n")));
+    }
+    close_blocks(&mut html, &mut paragraph, &mut list);
+    html
+}
+
+/// HTML counterpart of `render_inline`
+fn inline_html(text: &str) -> String {
+    let text = escape_html(text);
+    INLINE
+        .replace_all(&text, |caps: &regex::Captures| {
+            if let Some(code) = caps.get(1) {
+                format!("<code>{}</code>", code.as_str())
+            } else if let Some(bold) = caps.get(2).or_else(|| caps.get(3)) {
+                format!("<strong>{}</strong>", bold.as_str())
+            } else {
+                format!("<em>{}</em>", &caps[4])
+            }
+        })
+        .into_owned()
+}
+
+/// Modelfile instructions, highlighted at the start of a line
+const MODELFILE_INSTRUCTIONS: &[&str] =
+    &["FROM", "PARAMETER", "TEMPLATE", "SYSTEM", "ADAPTER", "LICENSE", "MESSAGE", "REQUIRES"];
+
+/// Highlight a Modelfile for the terminal: instructions, parameter names, comments,
+/// quoted values and template directives. The `LICENSE` block is collapsed to its
+/// line count since `show` prints the license separately.
+pub fn highlight_modelfile(modelfile: &str) -> String {
+    let enabled = rendering_enabled();
+    let mut lines = Vec::new();
+    // Instruction whose `"""` block is still open, and the lines skipped in a LICENSE block
+    let mut open_block: Option<(String, usize)> = None;
+
+    for line in modelfile.lines() {
+        if let Some((instruction, skipped)) = &mut open_block {
+            let closes = line.contains("\"\"\"");
+            if instruction == "LICENSE" {
+                let content = line.split("\"\"\"").next().unwrap_or_default();
+                if !closes || !content.trim().is_empty() {
+                    *skipped += 1;
+                }
+                if closes {
+                    let note = format!("\"\"\" ({} lines) \"\"\"", skipped);
+                    lines.push(format!("LICENSE {}", if enabled { note.dimmed().to_string() } else { note }));
+                }
+            } else if instruction == "TEMPLATE" {
+                lines.push(highlight_template(line));
+            } else {
+                lines.push(if enabled { line.green().to_string() } else { line.to_string() });
+            }
+            if closes {
+                open_block = None;
+            }
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let (keyword, rest) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+        let instruction = keyword.to_uppercase();
+        let opens_block = rest.trim_start().starts_with("\"\"\"") && rest.matches("\"\"\"").count() == 1;
+
+        if MODELFILE_INSTRUCTIONS.contains(&instruction.as_str()) && opens_block {
+            let first_line = rest.trim_start().trim_start_matches('"');
+            open_block = Some((instruction.clone(), usize::from(!first_line.trim().is_empty())));
+            if instruction == "LICENSE" {
+                continue;
+            }
+        }
+
+        if !enabled {
+            lines.push(line.to_string());
+        } else if trimmed.starts_with('#') {
+            lines.push(line.dimmed().to_string());
+        } else if instruction == "PARAMETER" {
+            let (name, value) = rest.trim_start().split_once(char::is_whitespace).unwrap_or((rest.trim(), ""));
+            lines.push(format!("{} {} {}", keyword.cyan().bold(), name.yellow(), highlight_quoted(value.trim_start())));
+        } else if instruction == "TEMPLATE" {
+            lines.push(format!("{} {}", keyword.cyan().bold(), highlight_template(rest)));
+        } else if MODELFILE_INSTRUCTIONS.contains(&instruction.as_str()) {
+            lines.push(format!("{} {}", keyword.cyan().bold(), highlight_quoted(rest)));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Highlight Go template directives (`{{ .Prompt }}`) in a chat template
+pub fn highlight_template(template: &str) -> String {
+    if !rendering_enabled() {
+        return template.to_string();
+    }
+    TEMPLATE_DIRECTIVE
+        .replace_all(template, |caps: &regex::Captures| caps[0].magenta().to_string())
+        .into_owned()
+}
+
+fn highlight_quoted(text: &str) -> String {
+    if text.starts_with('"') {
+        text.green().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// A fenced code block extracted from a Markdown response
+#[derive(Debug, Clone)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub code: String,
+    /// File the block is meant for, from `path=` in the fence info string or a
+    /// `// file: ...` first line (which is then left out of `code`)
+    pub path: Option<String>,
+}
+
+impl CodeBlock {
+    fn from_fence(info: &str, lines: Vec<&str>) -> Self {
+        let mut tokens = info.split_whitespace();
+        let first = tokens.next();
+        let language = first
+            .filter(|token| !token.contains('='))
+            .map(str::to_string);
+
+        let mut path = info.split_whitespace().find_map(|token| {
+            let (key, value) = token.split_once('=')?;
+            matches!(key, "path" | "file" | "filename")
+                .then(|| value.trim_matches(|c| c == '"' || c == '\'').to_string())
+        });
+        // ```rust src/main.rs
+        if path.is_none() {
+            path = tokens
+                .find(|token| !token.contains('=') && (token.contains('/') || token.contains('.')))
+                .map(str::to_string);
+        }
+
+        let mut body = lines.as_slice();
+        if path.is_none() {
+            if let Some(marked) = lines.first().and_then(|line| file_marker(line)) {
+                path = Some(marked);
+                body = &lines[1..];
+            }
+        }
+
+        CodeBlock {
+            language,
+            code: body.join("\n"),
+            path: path.filter(|path| !path.is_empty()),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.code.lines().count()
+    }
+
+    /// File extension used when the block has to be written to disk
+    pub fn file_extension(&self) -> &str {
+        self.language
+            .as_deref()
+            .and_then(language_extension)
+            .unwrap_or("txt")
+    }
+}
+
+/// File extension for a language name or fence tag, if it is one we know
+pub fn language_extension(language: &str) -> Option<&'static str> {
+    let extension = match language.to_lowercase().as_str() {
+        "rust" | "rs" => "rs",
+        "python" | "py" => "py",
+        "javascript" | "js" => "js",
+        "typescript" | "ts" => "ts",
+        "bash" | "sh" | "shell" | "zsh" => "sh",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "go" | "golang" => "go",
+        "java" => "java",
+        "kotlin" | "kt" => "kt",
+        "swift" => "swift",
+        "ruby" | "rb" => "rb",
+        "php" => "php",
+        "c#" | "csharp" | "cs" => "cs",
+        "c" => "c",
+        "cpp" | "c++" => "cpp",
+        "html" => "html",
+        "css" => "css",
+        "sql" => "sql",
+        "markdown" | "md" => "md",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+/// Path in a `// file: src/lib.rs` style comment (also `#`, `--`, `/* */`, `<!-- -->`)
+fn file_marker(line: &str) -> Option<String> {
+    let comment = line.trim();
+    let text = ["//", "#", "--", "/*", "<!--"]
+        .iter()
+        .find_map(|opener| comment.strip_prefix(opener))?
+        .trim_end_matches("-->")
+        .trim_end_matches("*/")
+        .trim();
+    let (key, path) = text.split_once(':')?;
+    if !matches!(key.trim().to_lowercase().as_str(), "file" | "filename" | "path") {
+        return None;
+    }
+    let path = path.trim();
+    (!path.is_empty() && !path.contains(char::is_whitespace)).then(|| path.to_string())
+}
+
+/// Collect fenced code blocks in order; an unterminated final block is included
+pub fn extract_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+
+    for line in markdown.lines() {
+        let fence = line.trim_start().strip_prefix("```");
+        match (&mut current, fence) {
+            (Some(_), Some(_)) => {
+                let (info, lines) = current.take().unwrap();
+                blocks.push(CodeBlock::from_fence(info, lines));
+            }
+            (Some((_, lines)), None) => lines.push(line),
+            (None, Some(info)) => current = Some((info, Vec::new())),
+            (None, None) => {}
+        }
+    }
+
+    if let Some((info, lines)) = current {
+        blocks.push(CodeBlock::from_fence(info, lines));
+    }
+    blocks
+}
@@ -0,0 +1,108 @@
+use crate::client::SelectedModel;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+// Oldest entries beyond this are dropped on save
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    model: String,
+    response: String,
+    cached_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Responses to earlier `ask` prompts, kept in `response-cache.json` in the data directory
+pub struct ResponseCache {
+    path: Option<PathBuf>,
+    ttl: chrono::Duration,
+    entries: HashMap<String, CachedResponse>,
+}
+
+/// Collapse whitespace so reformatted prompts share an entry
+pub fn normalize_prompt(prompt: &str) -> String {
+    prompt.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl ResponseCache {
+    /// Load the cache, dropping expired entries; an unreadable file starts it empty
+    pub fn load(ttl_hours: u64) -> Self {
+        let path = Some(crate::paths::data_dir().join("response-cache.json"));
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let mut cache = Self {
+            path,
+            ttl: chrono::Duration::hours(ttl_hours as i64),
+            entries,
+        };
+        cache.prune();
+        cache
+    }
+
+    /// Cache key for a prompt: the model (with its digest, so a re-pulled model
+    /// misses) and the generate request it would send, which carries the system
+    /// prompt and the effective options. SHA-256 keeps keys stable across builds,
+    /// since the file outlives the binary that wrote it.
+    pub fn key(model: &SelectedModel, context_prompt: &str) -> String {
+        let request = crate::tools::model_config::create_enhanced_request(
+            model.get_name(),
+            context_prompt,
+            false,
+            &model.options,
+        );
+        let keyed = serde_json::json!({
+            "model": model.name,
+            "digest": model.digest,
+            "request": request,
+        });
+        let digest = Sha256::digest(serde_json::to_vec(&keyed).unwrap_or_default());
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|entry| entry.response.as_str())
+    }
+
+    pub fn insert(&mut self, key: String, model: &str, response: &str) {
+        self.entries.insert(
+            key,
+            CachedResponse {
+                model: model.to_string(),
+                response: response.to_string(),
+                cached_at: chrono::Utc::now(),
+            },
+        );
+    }
+
+    pub fn save(&mut self) -> std::io::Result<()> {
+        self.prune();
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(&self.entries)?)
+    }
+
+    fn prune(&mut self) {
+        let now = chrono::Utc::now();
+        let ttl = self.ttl;
+        self.entries.retain(|_, entry| now - entry.cached_at < ttl);
+
+        if self.entries.len() > MAX_ENTRIES {
+            let mut ages: Vec<_> = self.entries.iter().map(|(key, entry)| (entry.cached_at, key.clone())).collect();
+            ages.sort();
+            for (_, key) in ages.into_iter().take(self.entries.len() - MAX_ENTRIES) {
+                self.entries.remove(&key);
+            }
+        }
+    }
+}
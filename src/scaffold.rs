@@ -0,0 +1,110 @@
+use colored::Colorize;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::render::{extract_code_blocks, CodeBlock};
+use crate::tools::dry_run;
+
+/// Prompt suffix asking the model to label each code block with its file
+pub const LABEL_FILES_INSTRUCTION: &str = " Put each file in its own fenced code block and give its path \
+    relative to the project root in the fence info string, e.g. ```rust path=src/main.rs. \
+    Do not write any files yourself.";
+
+/// Write every code block in `response` that names a file (```` ```rust path=src/lib.rs ````
+/// or a `// file: src/lib.rs` first line) to that path under `dir`, and print what
+/// was created, overwritten or skipped. Existing files are only replaced with `force`.
+/// Returns the number of files written.
+pub fn save_all_code_blocks(response: &str, dir: &Path, force: bool) -> Result<usize, Box<dyn std::error::Error>> {
+    let blocks = extract_code_blocks(response);
+    if blocks.is_empty() {
+        return Err("The response contained no code blocks; nothing was written".into());
+    }
+    if blocks.iter().all(|block| block.path.is_none()) {
+        return Err(format!(
+            "None of the {} code block(s) named a file; label them like ```rust path=src/main.rs",
+            blocks.len()
+        )
+        .into());
+    }
+
+    let mut written = 0;
+    let mut skipped = 0;
+    let mut seen: HashMap<PathBuf, usize> = HashMap::new();
+    for (index, block) in blocks.iter().enumerate() {
+        let number = index + 1;
+        match save_block(block, dir, force, &seen) {
+            Ok((path, existed)) => {
+                // Under --dry-run the intercept already logged the write
+                if !dry_run::dry_run() {
+                    let verb = if existed { "Overwrote" } else { "Created" };
+                    println!("{} {} {} ({} bytes)", "💾".green(), verb, path.display(), block.code.len());
+                }
+                seen.insert(path, number);
+                written += 1;
+            }
+            Err(reason) => {
+                println!(
+                    "{} Skipped block {} ({}): {}",
+                    "⏭".yellow(),
+                    number,
+                    block.language.as_deref().unwrap_or("text"),
+                    reason
+                );
+                skipped += 1;
+            }
+        }
+    }
+
+    let verb = if dry_run::dry_run() { "would be written" } else { "written" };
+    println!("{} {} file(s) {}, {} block(s) skipped", "📁".cyan(), written, verb, skipped);
+    Ok(written)
+}
+
+/// Write one block, returning its path and whether it replaced an existing file
+fn save_block(
+    block: &CodeBlock,
+    dir: &Path,
+    force: bool,
+    seen: &HashMap<PathBuf, usize>,
+) -> Result<(PathBuf, bool), String> {
+    let relative = block.path.as_deref().ok_or("no file path")?;
+    let relative = safe_relative_path(relative)?;
+    let path = if dir == Path::new(".") { relative.to_path_buf() } else { dir.join(relative) };
+    if let Some(earlier) = seen.get(&path) {
+        return Err(format!("{} was already written from block {}", path.display(), earlier));
+    }
+
+    let existed = path.exists();
+    if existed && !force {
+        return Err(format!("{} already exists; pass --force to overwrite it", path.display()));
+    }
+
+    let mut code = block.code.clone();
+    if !code.ends_with('\n') {
+        code.push('\n');
+    }
+
+    let action = format!("write {} ({} bytes)", path.display(), code.len());
+    if dry_run::intercept(action).is_some() {
+        return Ok((path, existed));
+    }
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("could not create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, &code).map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+    Ok((path, existed))
+}
+
+/// Model-supplied paths must stay inside the target directory
+fn safe_relative_path(path: &str) -> Result<&Path, String> {
+    let candidate = Path::new(path);
+    if candidate
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(candidate)
+    } else {
+        Err(format!("'{}' is not a relative path inside the target directory", path))
+    }
+}
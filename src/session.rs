@@ -1,22 +1,296 @@
 use colored::Colorize;
 use std::time::Instant;
 
-use crate::client::{generate_response_silent, stream_response, SelectedModel};
+use crate::citations::{cite_answer, collect_citations, print_sources, sources_prompt, NumberedCitation};
+use crate::error::AgentError;
+use crate::client::{generate_response_silent, stream_response, thinking_settings, ResponseOutput, SelectedModel};
 use crate::input::VimInputHandler;
+use crate::render::extract_code_blocks;
+use crate::thinking::strip_thinking;
 use crate::tools::{
     AsyncToolExecutor, AvailableTool, ConversationEntry, NaturalLanguageParser, PermissionManager,
-    ResourceLimits, ToolExecutor,
+    ResourceLimits, ToolExecutor, ToolPlan, ToolResult,
 };
 
 #[derive(Debug, Clone)]
 pub enum ResponseMode {
     CommandGeneration,
-    ToolExecution(Vec<AvailableTool>),
+    ToolExecution(ToolPlan),
     GeneralConversation,
 }
+use crate::tools::config::ToolResultStrategy;
+use crate::tools::history::{HistoryEntry, HistoryManager};
+use crate::tools::syntax_check::{check_syntax, SyntaxCheck};
 use crate::workspace::WorkspaceContext;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static TOOLS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// `--no-tools`: answer every request as plain conversation, whatever `enable_tools` says
+pub fn set_tools_disabled(disabled: bool) {
+    TOOLS_DISABLED.store(disabled, Ordering::Relaxed);
+}
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// `--verbose`: print the full context budget report before the first request
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+static EXPLAIN: AtomicBool = AtomicBool::new(false);
+
+/// `--explain`: print why each tool runs, its exact call and a one-line result
+pub fn set_explain(explain: bool) {
+    EXPLAIN.store(explain, Ordering::Relaxed);
+}
+
+static VERIFY_EDITS: AtomicBool = AtomicBool::new(false);
+
+/// `--verify-edits`: syntax-check each file the agent writes or edits and report errors back to it
+pub fn set_verify_edits(verify: bool) {
+    VERIFY_EDITS.store(verify, Ordering::Relaxed);
+}
+
+/// A single message in a saved session transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedMessage {
+    pub role: String,
+    pub content: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub tools_used: Vec<String>,
+}
+
+/// On-disk representation of a chat session for `--resume` and `:load`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub model: String,
+    pub saved_at: String,
+    pub messages: Vec<SavedMessage>,
+}
+
+impl SavedSession {
+    pub fn load(path: &Path) -> Result<Self, AgentError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AgentError::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to read session file '{}': {}", path.display(), e),
+            ))
+        })?;
+        let saved: SavedSession = serde_json::from_str(&content)
+            .map_err(|e| AgentError::Other(format!("Invalid session file '{}': {}", path.display(), e)))?;
+        Ok(saved)
+    }
+
+    /// Find the saved model, prompting for a replacement if it is no longer installed
+    pub async fn resolve_model(&self) -> Result<SelectedModel, AgentError> {
+        let available_models = crate::client::fetch_models().await?;
+
+        if let Some(model) = available_models.iter().find(|m| m.name == self.model) {
+            return Ok(SelectedModel::from(model.clone()));
+        }
+
+        println!(
+            "{} Saved model '{}' is no longer available, please choose a replacement",
+            "⚠".yellow(),
+            self.model
+        );
+        crate::client::select_model(&available_models)
+    }
+
+    fn into_entries(self) -> Vec<ConversationEntry> {
+        let mut entries = Vec::new();
+        let mut pending_user: Option<SavedMessage> = None;
+
+        for message in self.messages {
+            match message.role.as_str() {
+                "user" => {
+                    // A user message without a reply still carries context
+                    if let Some(user) = pending_user.take() {
+                        entries.push(ConversationEntry {
+                            timestamp: user.timestamp,
+                            user_input: user.content,
+                            assistant_response: String::new(),
+                            tools_used: user.tools_used,
+                            metadata: None,
+                        });
+                    }
+                    pending_user = Some(message);
+                }
+                "assistant" => {
+                    let (timestamp, user_input) = match pending_user.take() {
+                        Some(user) => (user.timestamp, user.content),
+                        None => (message.timestamp.clone(), String::new()),
+                    };
+                    entries.push(ConversationEntry {
+                        timestamp,
+                        user_input,
+                        assistant_response: message.content,
+                        tools_used: message.tools_used,
+                        metadata: None,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(user) = pending_user {
+            entries.push(ConversationEntry {
+                timestamp: user.timestamp,
+                user_input: user.content,
+                assistant_response: String::new(),
+                tools_used: user.tools_used,
+                metadata: None,
+            });
+        }
+
+        entries
+    }
+}
+
+/// Share of the context window kept free for the model's reply
+pub const RESPONSE_RESERVE_RATIO: usize = 4;
+
+/// Rounds of tool calls a single request may chain before the model has to answer
+pub const DEFAULT_MAX_STEPS: usize = 5;
+
+/// Workspace file contents are only inlined into the prompt up to this many files
+pub const MAX_INLINED_FILES: usize = 10;
+
+/// Most tool output sent to the model when asking it for a summary
+const SUMMARY_INPUT_CHARS: usize = 24_000;
+
+/// Rough token estimate (~4 characters per token for English text and code)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Estimated tokens per workspace file, largest first
+pub fn file_token_counts(files: &HashMap<PathBuf, String>) -> Vec<(PathBuf, usize)> {
+    let mut counts: Vec<(PathBuf, usize)> = files
+        .iter()
+        .map(|(path, content)| (path.clone(), estimate_tokens(content)))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Token budget: an explicit `--num-ctx`, else the configured `context_token_budget`,
+/// else the model config
+pub async fn context_token_budget(tool_executor: &ToolExecutor, num_ctx: Option<u32>) -> usize {
+    if let Some(num_ctx) = num_ctx {
+        return num_ctx as usize;
+    }
+    if let Ok(Some(budget)) = tool_executor.get_context_token_budget().await {
+        return budget as usize;
+    }
+
+    crate::tools::model_config::get_current_model_config()
+        .context_length
+        .unwrap_or(crate::tools::model_config::DEFAULT_CONTEXT_LENGTH) as usize
+}
+
+/// Tokens of the configured and per-model system prompts
+pub async fn system_prompt_tokens(tool_executor: &ToolExecutor) -> usize {
+    let mut tokens = 0;
+
+    if let Ok(Some(prompt)) = tool_executor.get_system_prompt().await {
+        tokens += estimate_tokens(&prompt);
+    }
+
+    let model_config = crate::tools::model_config::get_current_model_config();
+    if model_config.system_prompt != "You are a helpful AI assistant." {
+        tokens += estimate_tokens(&model_config.system_prompt);
+    }
+
+    tokens
+}
+
+/// Print the estimated size of each part of the prompt against the context window,
+/// with per-file counts largest first so it's clear what to drop. Returns whether the
+/// total exceeds the budget.
+pub fn print_context_budget(file_tokens: &[(PathBuf, usize)], parts: &[(&str, usize)], budget: usize) -> bool {
+    let total: usize = parts.iter().map(|(_, tokens)| tokens).sum();
+
+    println!("{}", "Context Token Usage (estimated):".cyan().bold());
+    if !file_tokens.is_empty() {
+        println!("  {}", "Files:".blue());
+        for (path, tokens) in file_tokens {
+            println!("    {:>8}  {}", tokens, path.display());
+        }
+        if file_tokens.len() > MAX_INLINED_FILES {
+            println!(
+                "    {}",
+                format!("(contents are only sent with {} files or fewer)", MAX_INLINED_FILES).dimmed()
+            );
+        }
+    }
+    for (label, tokens) in parts {
+        println!("  {} {}", format!("{}:", label).blue(), tokens);
+    }
+    println!(
+        "  {} {} / {} ({:.1}%)",
+        "Total:".blue(),
+        total,
+        budget,
+        if budget > 0 { total as f64 / budget as f64 * 100.0 } else { 0.0 }
+    );
+
+    let exceeded = total > budget;
+    if exceeded {
+        warn_context_exceeded(file_tokens, total, budget);
+    }
+    exceeded
+}
+
+fn warn_context_exceeded(file_tokens: &[(PathBuf, usize)], total: usize, budget: usize) {
+    println!(
+        "{} Estimated {} tokens exceed the {} token context window (num_ctx)",
+        "⚠️".yellow(),
+        total,
+        budget
+    );
+    let largest: Vec<String> = file_tokens
+        .iter()
+        .take(3)
+        .map(|(path, tokens)| format!("{} ({})", path.display(), tokens))
+        .collect();
+    if !largest.is_empty() {
+        println!("{} Largest files: {}", "💡".yellow(), largest.join(", "));
+    }
+}
+
+fn entry_tokens(entry: &ConversationEntry) -> usize {
+    // Small per-message overhead for the "User:"/"Assistant:" framing
+    estimate_tokens(&entry.user_input) + estimate_tokens(&entry.assistant_response) + 8
+}
+
+/// The first `limit` characters of `text`, marked as cut
+fn truncate_head(text: &str, limit: usize) -> String {
+    let kept: String = text.chars().take(limit).collect();
+    let omitted = text.chars().count().saturating_sub(limit);
+    format!("{}\n[... {} more characters truncated ...]", kept, omitted)
+}
+
+/// The last `limit` characters of `text`, marked as cut
+fn truncate_tail(text: &str, limit: usize) -> String {
+    let omitted = text.chars().count().saturating_sub(limit);
+    let kept: String = text.chars().skip(omitted).collect();
+    format!("[... {} earlier characters truncated ...]\n{}", omitted, kept)
+}
+
+// Default location for sessions saved without an explicit path
+fn default_session_path() -> PathBuf {
+    let dir = crate::paths::data_dir().join("sessions");
+    dir.join(format!(
+        "session-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
 
 pub struct AssistantSession {
     model: SelectedModel,
@@ -29,6 +303,20 @@ pub struct AssistantSession {
     vim_handler: VimInputHandler,
     workspace_context: Option<WorkspaceContext>,
     workspace_files: HashMap<PathBuf, String>,
+    history: HistoryManager,
+    session_id: String,
+    last_response: String,
+    // What `:retry` answers again
+    last_input: Option<String>,
+    last_tool_results: Vec<ToolResult>,
+    last_citations: Vec<NumberedCitation>,
+    // `last_tool_results` with long output shortened, as the model sees them
+    prompt_tool_results: Vec<ToolResult>,
+    last_request_used_tools: bool,
+    last_response_cached: bool,
+    max_steps: usize,
+    // Kept alive for the session: on X11 the copied text is served by this process
+    clipboard: Option<arboard::Clipboard>,
 }
 
 #[derive(Debug, Default)]
@@ -41,44 +329,63 @@ struct SessionStats {
 }
 
 impl AssistantSession {
-    pub fn new(model: SelectedModel, tool_executor: ToolExecutor) -> Self {
+    pub fn new(model: SelectedModel, tool_executor: ToolExecutor) -> Result<Self, AgentError> {
         // Initialize the global config with the selected model
-        Self::init_global_config(&model);
+        Self::init_global_config(&model, &tool_executor);
 
         let async_executor = AsyncToolExecutor::new(ResourceLimits::default());
+        let (auto_approve_safe, non_interactive) = tool_executor.get_permission_settings();
 
-        Self {
+        Ok(Self {
             model,
             tool_executor,
             async_executor,
-            permission_manager: PermissionManager::new(),
+            permission_manager: PermissionManager::with_settings(auto_approve_safe, non_interactive),
             parser: NaturalLanguageParser::new(),
             conversation_history: Vec::new(),
             session_stats: SessionStats::default(),
             vim_handler: VimInputHandler::new(),
             workspace_context: None,
             workspace_files: HashMap::new(),
-        }
+            history: HistoryManager::new()?,
+            session_id: uuid::Uuid::new_v4().to_string(),
+            last_response: String::new(),
+            last_input: None,
+            last_tool_results: Vec::new(),
+            last_citations: Vec::new(),
+            prompt_tool_results: Vec::new(),
+            last_request_used_tools: false,
+            last_response_cached: false,
+            max_steps: DEFAULT_MAX_STEPS,
+            clipboard: None,
+        })
     }
 
     pub fn with_vim_mode(
         model: SelectedModel,
         tool_executor: ToolExecutor,
         vim_enabled: bool,
-    ) -> Self {
-        let mut session = Self::new(model, tool_executor);
+    ) -> Result<Self, AgentError> {
+        let mut session = Self::new(model, tool_executor)?;
         if vim_enabled {
             session.vim_handler.enable_vim_mode();
         }
-        session
+        Ok(session)
     }
 
-    fn init_global_config(model: &SelectedModel) {
-        // Update the global model config to reflect the current model
-        crate::tools::model_config::set_current_model(&model.name);
+    fn init_global_config(model: &SelectedModel, tool_executor: &ToolExecutor) {
+        // Update the global model config to reflect the current model and its profile
+        let profile = tool_executor.get_model_profile(&model.name);
+        if let Some((name, profile)) = &profile {
+            match &profile.description {
+                Some(description) => println!("{} Using profile '{}': {}", "📋".cyan(), name, description),
+                None => println!("{} Using profile '{}'", "📋".cyan(), name),
+            }
+        }
+        crate::tools::model_config::activate_model(&model.name, profile.as_ref());
     }
 
-    pub async fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn run(&mut self) -> Result<(), AgentError> {
         self.show_welcome().await;
 
         loop {
@@ -128,6 +435,104 @@ impl AssistantSession {
                 continue;
             }
 
+            if self.is_tokens_command(&user_input) {
+                self.show_token_usage().await;
+                continue;
+            }
+
+            if self.is_usage_command(&user_input) {
+                self.show_usage_totals();
+                continue;
+            }
+
+            if let Some(path) = Self::parse_path_command(&user_input, ":undo") {
+                let result = match path {
+                    Some(path) => self.tool_executor.file_restore(path),
+                    None => self.tool_executor.undo_last_change(),
+                };
+                match result {
+                    Ok(result) if result.success => println!("{} {}", "⏪".green(), result.output),
+                    Ok(result) => println!("{} {}", "❌".red(), result.error.unwrap_or_default()),
+                    Err(e) => println!("{} Error undoing changes: {}", "❌".red(), e),
+                }
+                continue;
+            }
+
+            if let Some(path) = Self::parse_path_command(&user_input, ":save") {
+                let path = path.map(PathBuf::from).unwrap_or_else(default_session_path);
+                match self.save_session(&path) {
+                    Ok(()) => println!("{} Session saved to {}", "💾".green(), path.display()),
+                    Err(e) => println!("{} Error saving session: {}", "❌".red(), e),
+                }
+                continue;
+            }
+
+            if let Some(tag) = Self::parse_path_command(&user_input, ":tag") {
+                match tag {
+                    Some(tag) => match self.history.add_tag(&self.session_id, &self.model.name, tag) {
+                        Ok(()) => println!("{} Tagged this conversation with '{}'", "🏷️".cyan(), tag),
+                        Err(e) => println!("{} Error tagging conversation: {}", "❌".red(), e),
+                    },
+                    None => {
+                        let tags = self.history.session_tags(&self.session_id);
+                        if tags.is_empty() {
+                            println!("{} Usage: :tag <label>", "💡".yellow());
+                        } else {
+                            println!("{} Tags: {}", "🏷️".cyan(), tags.join(", "));
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if let Some(args) = Self::parse_path_command(&user_input, ":save-all") {
+                let (mut dir, mut force) = (".", false);
+                for arg in args.unwrap_or_default().split_whitespace() {
+                    if arg == "--force" {
+                        force = true;
+                    } else {
+                        dir = arg;
+                    }
+                }
+                if let Err(e) = crate::scaffold::save_all_code_blocks(&self.last_response, Path::new(dir), force) {
+                    println!("{} {}", "❌".red(), e);
+                }
+                continue;
+            }
+
+            if let Some(index) = Self::parse_path_command(&user_input, ":copy") {
+                if let Err(e) = self.copy_code_block(index) {
+                    println!("{} {}", "❌".red(), e);
+                }
+                continue;
+            }
+
+            if let Some(path) = Self::parse_path_command(&user_input, ":load") {
+                match path {
+                    Some(path) => {
+                        if let Err(e) = self.load_session(Path::new(path)).await {
+                            println!("{} Error loading session: {}", "❌".red(), e);
+                        }
+                    }
+                    None => println!("{} Usage: :load <file>", "💡".yellow()),
+                }
+                continue;
+            }
+
+            if let Some(retry) = Self::parse_retry_command(&user_input) {
+                match retry {
+                    Ok(temperature) => {
+                        if let Err(e) = self.retry_last_request(temperature).await {
+                            println!("{} {}", "Error processing request:".red(), e);
+                            self.session_stats.failed_operations += 1;
+                        }
+                        println!();
+                    }
+                    Err(e) => println!("{} {}", "❌".red(), e),
+                }
+                continue;
+            }
+
             if self.is_toggle_tool_mode_command(&user_input) {
                 if let Err(e) = self.handle_toggle_tool_mode().await {
                     println!("{} Error toggling tool mode: {}", "❌".red(), e);
@@ -146,13 +551,65 @@ impl AssistantSession {
         Ok(())
     }
 
-    pub async fn process_single_command(
-        &mut self,
-        command: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn process_single_command(&mut self, command: &str) -> Result<(), AgentError> {
         self.process_request(command).await
     }
 
+    /// Limit how many rounds of tool calls one request may chain
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        self.max_steps = max_steps.max(1);
+    }
+
+    pub fn selected_model(&self) -> &SelectedModel {
+        &self.model
+    }
+
+    pub fn model_name(&self) -> &str {
+        &self.model.name
+    }
+
+    /// Final response of the most recent request
+    pub fn last_response(&self) -> &str {
+        &self.last_response
+    }
+
+    /// Every tool result (successful or not) from the most recent request
+    pub fn last_tool_results(&self) -> &[ToolResult] {
+        &self.last_tool_results
+    }
+
+    /// Web search sources the most recent answer cited
+    pub fn last_citations(&self) -> &[NumberedCitation] {
+        &self.last_citations
+    }
+
+    /// Whether the most recent request was answered with tools or a generated
+    /// command rather than plain conversation
+    pub fn last_request_used_tools(&self) -> bool {
+        self.last_request_used_tools
+    }
+
+    /// Whether the most recent response came from the response cache
+    pub fn last_response_cached(&self) -> bool {
+        self.last_response_cached
+    }
+
+    /// Show a response from the response cache in place of asking the model
+    pub fn show_cached_response(&mut self, response: &str) {
+        let mut printer = ResponseOutput::new();
+        printer.push(response);
+        printer.finish();
+        println!();
+        println!("{}", "(cached)".dimmed());
+
+        self.last_response = response.to_string();
+        self.last_tool_results.clear();
+        self.last_citations.clear();
+        self.prompt_tool_results.clear();
+        self.last_request_used_tools = false;
+        self.last_response_cached = true;
+    }
+
     async fn show_welcome(&self) {
         println!(
             "{}",
@@ -174,6 +631,15 @@ impl AssistantSession {
         println!("  {} Show performance metrics", "performance".yellow());
         println!("  {} Show resource usage", "resources".yellow());
         println!("  {} Clear logs and metrics", "clear logs".yellow());
+        println!("  {} Show estimated context token usage", ":tokens".yellow());
+        println!("  {} Show tokens and time used by this session", ":usage".yellow());
+        println!("  {} Undo the last file changes (or one file)", ":undo [file]".yellow());
+        println!("  {} Save the conversation to a file", ":save [file]".yellow());
+        println!("  {} Load a saved conversation", ":load <file>".yellow());
+        println!("  {} Tag this conversation in history", ":tag <label>".yellow());
+        println!("  {} Copy a code block from the last response", ":copy [n]".yellow());
+        println!("  {} Write each code block of the last response to the file it names", ":save-all [dir] [--force]".yellow());
+        println!("  {} Regenerate the last response", ":retry [--temp T]".yellow());
         println!("  {} Show available commands", "help".yellow());
         println!("  {} Exit the session", "quit/exit".yellow());
         println!();
@@ -187,7 +653,12 @@ impl AssistantSession {
         );
         
         // Show proactive tool mode status
-        if let Ok(proactive_enabled) = self.tool_executor.is_proactive_tool_mode_enabled().await {
+        if !self.tools_enabled().await {
+            println!(
+                "{}",
+                "💬 Tools disabled - answering as a plain chatbot".dimmed()
+            );
+        } else if let Ok(proactive_enabled) = self.tool_executor.is_proactive_tool_mode_enabled().await {
             if proactive_enabled {
                 println!(
                     "{}",
@@ -297,28 +768,42 @@ impl AssistantSession {
     async fn process_request(
         &mut self,
         user_input: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), AgentError> {
         let start_time = Instant::now();
         self.session_stats.commands_processed += 1;
+        self.last_input = Some(user_input.to_string());
+        self.last_tool_results.clear();
+        self.last_citations.clear();
+        self.prompt_tool_results.clear();
+        self.last_response_cached = false;
+
+        // Files changed while handling this request are undone together
+        crate::tools::backup::begin_backup_group();
 
         // Create context-aware prompt
         let context_prompt = self.create_context_aware_prompt(user_input);
 
+        // Keep the replayed history within the model's context window
+        self.trim_history_to_budget(&context_prompt).await;
+
         // Use LLM to analyze and determine the best response approach
-        println!("{} Analyzing request with AI...", "🧠".cyan());
-        let response_decision = self
-            .analyze_request_with_llm(&context_prompt, user_input)
-            .await?;
+        let response_decision = if self.tools_enabled().await {
+            println!("{} Analyzing request with AI...", "🧠".cyan());
+            self.analyze_request_with_llm(&context_prompt, user_input).await?
+        } else {
+            ResponseMode::GeneralConversation
+        };
 
+        self.last_request_used_tools = !matches!(response_decision, ResponseMode::GeneralConversation);
         match response_decision {
             ResponseMode::CommandGeneration => {
                 self.handle_command_generation_request(user_input).await?;
             }
-            ResponseMode::ToolExecution(tools) => {
-                self.handle_tool_request(&context_prompt, tools).await?;
+            ResponseMode::ToolExecution(plan) => {
+                self.handle_tool_request(&context_prompt, plan).await?;
             }
             ResponseMode::GeneralConversation => {
-                self.handle_general_conversation(&context_prompt).await?;
+                self.handle_general_conversation(user_input, &context_prompt).await?;
             }
         }
 
@@ -335,14 +820,20 @@ impl AssistantSession {
         Ok(())
     }
 
+    async fn tools_enabled(&self) -> bool {
+        !TOOLS_DISABLED.load(Ordering::Relaxed)
+            && self.tool_executor.is_tools_enabled().await.unwrap_or(true)
+    }
+
     async fn handle_general_conversation(
         &mut self,
         user_input: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let context = self.build_conversation_context(user_input).await;
+        context_prompt: &str,
+    ) -> Result<(), AgentError> {
+        let context = self.build_conversation_context(context_prompt).await;
         let response = stream_response(&self.model, &context).await?;
 
-        // Create conversation entry
+        // Store the raw input so pinned workspace context isn't duplicated into history
         let entry = ConversationEntry {
             timestamp: chrono::Utc::now().to_rfc3339(),
             user_input: user_input.to_string(),
@@ -351,10 +842,7 @@ impl AssistantSession {
             metadata: None,
         };
 
-        self.conversation_history.push(entry);
-
-        // Implement proper memory management for conversation history
-        self.manage_conversation_history_memory();
+        self.record_conversation_entry(entry);
 
         Ok(())
     }
@@ -362,27 +850,180 @@ impl AssistantSession {
     async fn handle_tool_request(
         &mut self,
         user_input: &str,
-        tools: Vec<AvailableTool>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("{} Executing {} tool(s)", "🔧".cyan(), tools.len());
-
+        plan: ToolPlan,
+    ) -> Result<(), AgentError> {
         let mut tool_results = Vec::new();
-        let mut tools_used = Vec::new();
+        let mut tools_used: Vec<String> = Vec::new();
+        let mut plan = plan;
+        let mut step = 1;
+
+        // Agent loop: run the tools, then let the model either pick the next
+        // tool call from the results so far or move on to the final answer
+        loop {
+            if self.max_steps > 1 {
+                println!(
+                    "{} Step {}/{}: executing {} tool(s)",
+                    "🔧".cyan(),
+                    step,
+                    self.max_steps,
+                    plan.tools.len()
+                );
+            } else {
+                println!("{} Executing {} tool(s)", "🔧".cyan(), plan.tools.len());
+            }
+            let denied = self.execute_tools(&plan, &mut tool_results, &mut tools_used).await?;
+            if denied == plan.tools.len() {
+                println!("{} Every tool in this step was denied, stopping here", "⚠️".yellow());
+                break;
+            }
+
+            if step >= self.max_steps {
+                if self.max_steps > 1 {
+                    println!(
+                        "{} Reached the limit of {} steps, answering with the results so far",
+                        "⚠️".yellow(),
+                        self.max_steps
+                    );
+                }
+                break;
+            }
+
+            let next = self.next_agent_step(user_input).await?;
+            if next.tools.is_empty() {
+                break;
+            }
+            // A model that keeps asking for the same call would otherwise spin until the limit
+            if next.tools.iter().all(|tool| tools_used.contains(&format!("{:?}", tool))) {
+                println!("{} The model repeated an earlier tool call, stopping here", "⚠️".yellow());
+                break;
+            }
+            plan = next;
+            step += 1;
+            println!();
+        }
+
+        if !tool_results.is_empty() {
+            println!();
+            println!("{}", "🤖 Assistant Summary:".cyan().bold());
+            let citations = collect_citations(&tool_results);
+            let context = self.build_tool_context(user_input, &tool_results, &citations);
+            let response = stream_response(&self.model, &context).await?;
+            // Sources cited only inside a thinking block aren't cited by the answer
+            let answer = strip_thinking(&response, &thinking_settings().tags);
+            let cited = cite_answer(&answer, &citations);
+            print_sources(&cited.sources);
+            self.last_citations = cited.sources;
+
+            // Create conversation entry
+            let entry = ConversationEntry {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                user_input: user_input.to_string(),
+                assistant_response: cited.text,
+                tools_used,
+                metadata: Some(serde_json::json!({
+                    "tools_count": tool_results.len(),
+                    "successful_tools": tool_results.iter().filter(|r| r.success).count(),
+                    "steps": step
+                })),
+            };
+
+            self.record_conversation_entry(entry);
+        }
+
+        Ok(())
+    }
+
+    /// Ask the model whether the results so far answer the request; if not,
+    /// parse the tool call it wants next. An empty plan means answer now.
+    async fn next_agent_step(&self, user_input: &str) -> Result<ToolPlan, AgentError> {
+        let results = Self::format_tool_results(&self.prompt_tool_results);
+        let prompt = format!(
+            r#"You are working through a request step by step using tools.
+
+Request and context:
+{}
+
+Tool results so far:
+{}
+If another tool call is needed before you can answer, reply with `NEXT: <the next action, in one sentence>`.
+If the results are enough to answer, reply with only `FINAL`."#,
+            user_input, results
+        );
+
+        let response = generate_response_silent(&self.model, &prompt).await?;
+        let response = match response.find("</think>") {
+            Some(end) => &response[end + "</think>".len()..],
+            None => response.as_str(),
+        };
+        let Some(action) = response
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("NEXT:"))
+            .map(str::trim)
+            .filter(|action| !action.is_empty())
+        else {
+            return Ok(ToolPlan::default());
+        };
+
+        println!("{} Next step: {}", "🔁".cyan(), action);
+        let parse_input = format!(
+            "{}\n\nTool results so far:\n{}\nNext action: {}",
+            user_input, results, action
+        );
+        let mut plan = self.parser.parse_request_with_llm(&parse_input, &self.model).await;
+        // The step the model asked for says more than the restated request
+        plan.reasoning = Some(action.to_string());
+        Ok(plan)
+    }
 
-        for (i, tool) in tools.iter().enumerate() {
+    async fn execute_tools(
+        &mut self,
+        plan: &ToolPlan,
+        tool_results: &mut Vec<ToolResult>,
+        tools_used: &mut Vec<String>,
+    ) -> Result<usize, AgentError> {
+        let explain = EXPLAIN.load(Ordering::Relaxed);
+        let mut denied = 0;
+        for (i, tool) in plan.tools.iter().enumerate() {
             println!();
-            println!("{} Tool {} of {}", "📝".blue(), i + 1, tools.len());
+            println!("{} Tool {} of {}", "📝".blue(), i + 1, plan.tools.len());
+            if explain {
+                println!("{} Why: {}", "💭".cyan(), plan.rationale(i).unwrap_or("(the model gave no reason)"));
+                println!("{} Call: {:?}", "🛠".cyan(), tool);
+            }
 
-            if !self.permission_manager.request_permission(tool)? {
+            if !self.permission_manager.request_permission(tool).map_err(AgentError::from)? {
                 println!("{} Skipping tool execution", "⏭".yellow());
+                if explain {
+                    println!("{} Result: skipped, permission denied", "📌".cyan());
+                }
+                // Tell the next step, so the model doesn't just ask for the same call again
+                denied += 1;
+                tools_used.push(format!("{:?}", tool));
+                let refused = ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some("permission denied by user".to_string()),
+                    metadata: None,
+                    web_search_result: None,
+                };
+                self.last_tool_results.push(refused.clone());
+                self.prompt_tool_results.push(refused);
                 continue;
             }
 
             println!();
-            match self.tool_executor.execute_tool(tool.clone()).await {
+            let mut outcome = self.tool_executor.execute_tool(tool.clone()).await;
+            if let Ok(result) = &mut outcome {
+                Self::verify_edit(tool, result).await;
+            }
+            let summary = explain.then(|| Self::result_summary(&outcome));
+            match outcome {
                 Ok(result) => {
                     self.session_stats.tools_executed += 1;
                     tools_used.push(format!("{:?}", tool));
+                    self.last_tool_results.push(result.clone());
+                    let condensed = self.condense_for_prompt(&result).await;
+                    self.prompt_tool_results.push(condensed.clone());
 
                     if result.success {
                         println!("{} Tool executed successfully", "✅".green());
@@ -391,7 +1032,7 @@ impl AssistantSession {
                             println!("{}", "📄 Output:".blue().bold());
                             self.display_tool_output(&result.output);
                         }
-                        tool_results.push(result);
+                        tool_results.push(condensed);
                     } else {
                         println!("{} Tool execution failed", "❌".red());
                         if let Some(error) = &result.error {
@@ -401,35 +1042,137 @@ impl AssistantSession {
                 }
                 Err(e) => {
                     println!("{} Tool execution error: {}", "❌".red(), e);
+                    let failed = ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e.to_string()),
+                        metadata: None,
+                        web_search_result: None,
+                    };
+                    self.last_tool_results.push(failed.clone());
+                    self.prompt_tool_results.push(failed);
                 }
             }
+            if let Some(summary) = summary {
+                println!("{} Result: {}", "📌".cyan(), summary);
+            }
         }
 
-        if !tool_results.is_empty() {
-            println!();
-            println!("{}", "🤖 Assistant Summary:".cyan().bold());
-            let context = self.build_tool_context(user_input, &tool_results);
-            let response = stream_response(&self.model, &context).await?;
+        Ok(denied)
+    }
 
-            // Create conversation entry
-            let entry = ConversationEntry {
-                timestamp: chrono::Utc::now().to_rfc3339(),
-                user_input: user_input.to_string(),
-                assistant_response: response,
-                tools_used,
-                metadata: Some(serde_json::json!({
-                    "tools_count": tool_results.len(),
-                    "successful_tools": tool_results.iter().filter(|r| r.success).count()
-                })),
-            };
+    /// With `--verify-edits`, syntax-check the file a successful write or edit touched and
+    /// append the outcome to its result, so the next step can fix what it broke
+    async fn verify_edit(tool: &AvailableTool, result: &mut ToolResult) {
+        if !VERIFY_EDITS.load(Ordering::Relaxed) || !result.success || crate::tools::dry_run::dry_run() {
+            return;
+        }
+        let path = match tool {
+            AvailableTool::FileWrite { path, .. } | AvailableTool::FileEdit { path, .. } => path,
+            _ => return,
+        };
+
+        match check_syntax(path).await {
+            SyntaxCheck::Passed { command } => {
+                println!("{} Syntax check passed: {}", "🔎".green(), command.dimmed());
+                result.output.push_str(&format!("\n\nSyntax check passed ({})", command));
+            }
+            SyntaxCheck::Failed { command, errors } => {
+                println!("{} Syntax check failed: {}", "🔎".red(), command.dimmed());
+                println!("{}", errors);
+                result.output.push_str(&format!(
+                    "\n\nSyntax check failed ({}); fix these errors in {}:\n{}",
+                    command, path, errors
+                ));
+            }
+            SyntaxCheck::Skipped(reason) => println!("{} Syntax check skipped: {}", "🔎".dimmed(), reason.dimmed()),
+        }
+    }
 
-            self.conversation_history.push(entry);
-            
-            // Implement proper memory management for conversation history
-            self.manage_conversation_history_memory();
+    /// `result` with output over `max_tool_result_chars` cut down for prompts, by
+    /// keeping its head or tail or by summarizing it. The full output stays in
+    /// `last_tool_results`.
+    async fn condense_for_prompt(&self, result: &ToolResult) -> ToolResult {
+        let (limit, strategy) = self.tool_executor.get_tool_result_limit();
+        let length = result.output.chars().count();
+        if length <= limit {
+            return result.clone();
         }
 
-        Ok(())
+        let output = match strategy {
+            ToolResultStrategy::Head => truncate_head(&result.output, limit),
+            ToolResultStrategy::Tail => truncate_tail(&result.output, limit),
+            ToolResultStrategy::Summarize => {
+                println!("{} Summarizing {} characters of tool output", "📝".cyan(), length);
+                match self.summarize_tool_output(&result.output, limit).await {
+                    Ok(summary) => format!("[Summary of {} characters of output]\n{}", length, summary),
+                    Err(e) => {
+                        println!(
+                            "{} Could not summarize the output ({}), keeping the first {} characters",
+                            "⚠️".yellow(),
+                            e,
+                            limit
+                        );
+                        truncate_head(&result.output, limit)
+                    }
+                }
+            }
+        };
+        ToolResult { output, ..result.clone() }
+    }
+
+    async fn summarize_tool_output(&self, output: &str, limit: usize) -> Result<String, AgentError> {
+        // The summarizer has a context window too; keep both ends of very long output
+        let length = output.chars().count();
+        let input = if length > SUMMARY_INPUT_CHARS {
+            let half = SUMMARY_INPUT_CHARS / 2;
+            let head: String = output.chars().take(half).collect();
+            let tail: String = output.chars().skip(length - half).collect();
+            format!("{}\n[... {} characters omitted ...]\n{}", head, length - SUMMARY_INPUT_CHARS, tail)
+        } else {
+            output.to_string()
+        };
+        let prompt = format!(
+            "Summarize this tool output in at most {} characters. Keep error messages, warnings, \
+             file names, counts and other specifics. Reply with only the summary.\n\n{}",
+            limit, input
+        );
+
+        let response = generate_response_silent(&self.model, &prompt).await?;
+        let summary = match response.find("</think>") {
+            Some(end) => &response[end + "</think>".len()..],
+            None => response.as_str(),
+        }
+        .trim();
+        if summary.is_empty() {
+            return Err("the model returned an empty summary".into());
+        }
+        Ok(if summary.chars().count() > limit { truncate_head(summary, limit) } else { summary.to_string() })
+    }
+
+    /// One line for `--explain`: success and the first line of output, or the error
+    fn result_summary(outcome: &Result<ToolResult, Box<dyn std::error::Error>>) -> String {
+        fn first_line(text: &str) -> String {
+            let line = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("");
+            if line.chars().count() > 100 {
+                format!("{}...", line.chars().take(100).collect::<String>())
+            } else {
+                line.to_string()
+            }
+        }
+
+        match outcome {
+            Ok(result) if result.success => {
+                let lines = result.output.lines().count();
+                match lines {
+                    0 => "succeeded, no output".to_string(),
+                    1 => format!("succeeded: {}", first_line(&result.output)),
+                    _ => format!("succeeded, {} lines: {}", lines, first_line(&result.output)),
+                }
+            }
+            Ok(result) => format!("failed: {}", first_line(result.error.as_deref().unwrap_or("unknown error"))),
+            Err(e) => format!("error: {}", first_line(&e.to_string())),
+        }
     }
 
     fn display_tool_output(&self, output: &str) {
@@ -474,10 +1217,10 @@ impl AssistantSession {
             context.push_str(&format!("System: {}\n\n", model_config.system_prompt));
         }
 
-        // Add recent conversation history
+        // Replay conversation history (already trimmed to the token budget)
         if !self.conversation_history.is_empty() {
             context.push_str("Recent conversation:\n");
-            for entry in self.conversation_history.iter().rev().take(3).rev() {
+            for entry in &self.conversation_history {
                 context.push_str(&format!(
                     "User: {}\nAssistant: {}\n\n",
                     entry.user_input,
@@ -500,15 +1243,30 @@ impl AssistantSession {
         &self,
         user_input: &str,
         results: &[crate::tools::core::ToolResult],
+        citations: &[crate::tools::Citation],
     ) -> String {
         let mut context = format!("User requested: {}\n\n", user_input);
 
         context.push_str("Tool execution results:\n");
+        context.push_str(&Self::format_tool_results(results));
+        if !citations.is_empty() {
+            context.push_str(&sources_prompt(citations));
+        }
+
+        context.push_str(
+            "\nPlease provide a helpful summary and analysis of these results for the user. ",
+        );
+        context.push_str("Focus on the key findings and actionable insights.\nAssistant: ");
+        context
+    }
+
+    /// Numbered tool results, for prompts
+    fn format_tool_results(results: &[crate::tools::core::ToolResult]) -> String {
+        let mut context = String::new();
         for (i, result) in results.iter().enumerate() {
-            let preview = if result.output.chars().count() > 800 {
-                // Truncate at character boundary, not byte boundary
-                let truncated: String = result.output.chars().take(800).collect();
-                format!("{}...", truncated)
+            // Long output was already shortened by `condense_for_prompt`
+            let preview = if let (false, Some(error)) = (result.success, &result.error) {
+                format!("Error: {}", error)
             } else {
                 result.output.clone()
             };
@@ -528,11 +1286,6 @@ impl AssistantSession {
                 preview
             ));
         }
-
-        context.push_str(
-            "\nPlease provide a helpful summary and analysis of these results for the user. ",
-        );
-        context.push_str("Focus on the key findings and actionable insights.\nAssistant: ");
         context
     }
 
@@ -596,6 +1349,15 @@ impl AssistantSession {
         )
     }
 
+    fn is_tokens_command(&self, input: &str) -> bool {
+        let lower = input.trim().to_lowercase();
+        matches!(lower.as_str(), ":tokens" | "tokens")
+    }
+
+    fn is_usage_command(&self, input: &str) -> bool {
+        input.trim().eq_ignore_ascii_case(":usage")
+    }
+
     fn is_toggle_tool_mode_command(&self, input: &str) -> bool {
         let lower = input.trim().to_lowercase();
         matches!(
@@ -604,6 +1366,156 @@ impl AssistantSession {
         )
     }
 
+    // Parse `<command> [path]`, e.g. `:save` or `:load session.json`
+    fn parse_path_command<'a>(input: &'a str, command: &str) -> Option<Option<&'a str>> {
+        let rest = input.trim().strip_prefix(command)?;
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            return None;
+        }
+        let path = rest.trim();
+        Some(if path.is_empty() { None } else { Some(path) })
+    }
+
+    /// `:retry` / `:regenerate`, optionally with `--temp <t>` for that one request
+    fn parse_retry_command(input: &str) -> Option<Result<Option<f32>, String>> {
+        let rest = Self::parse_path_command(input, ":retry")
+            .or_else(|| Self::parse_path_command(input, ":regenerate"))?;
+        let Some(rest) = rest else {
+            return Some(Ok(None));
+        };
+
+        let mut args = rest.split_whitespace();
+        let parsed = match (args.next(), args.next(), args.next()) {
+            (Some("--temp" | "--temperature"), Some(value), None) => value
+                .parse::<f32>()
+                .ok()
+                .filter(|temperature| (0.0..=2.0).contains(temperature))
+                .map(Some)
+                .ok_or_else(|| format!("Invalid temperature: {} (expected 0.0 to 2.0)", value)),
+            _ => Err("Usage: :retry [--temp <0.0-2.0>]".to_string()),
+        };
+        Some(parsed)
+    }
+
+    /// Answer the last message again. The new answer replaces the old one in the
+    /// conversation and saved history, so the model never sees two competing
+    /// responses; if the retry fails the old answer is kept.
+    async fn retry_last_request(&mut self, temperature: Option<f32>) -> Result<(), AgentError> {
+        let Some(input) = self.last_input.clone() else {
+            println!("{} Nothing to retry yet", "💡".yellow());
+            return Ok(());
+        };
+        // A request that failed left no answer behind, so there is nothing to replace
+        let previous = match self.conversation_history.last() {
+            Some(entry) if entry.user_input == input => self.conversation_history.pop(),
+            _ => None,
+        };
+        let previous_response = std::mem::take(&mut self.last_response);
+        if previous.is_some() {
+            if let Err(e) = self.history.remove_last_entry(&self.session_id, &input) {
+                eprintln!("{} Failed to update history: {}", "⚠️".yellow(), e);
+            }
+        }
+
+        match temperature {
+            Some(temperature) => println!(
+                "{} Regenerating at temperature {}: {}",
+                "🔄".cyan(),
+                temperature,
+                input.dimmed()
+            ),
+            None => println!("{} Regenerating: {}", "🔄".cyan(), input.dimmed()),
+        }
+
+        let saved_temperature = self.model.options.temperature;
+        if temperature.is_some() {
+            self.model.options.temperature = temperature;
+        }
+        let outcome = self.process_request(&input).await;
+        self.model.options.temperature = saved_temperature;
+
+        if outcome.is_err() {
+            if let Some(previous) = previous {
+                self.history.add_entry(HistoryEntry {
+                    timestamp: previous.timestamp.clone(),
+                    user_input: previous.user_input.clone(),
+                    assistant_response: previous.assistant_response.clone(),
+                    tools_used: previous.tools_used.clone(),
+                    session_id: self.session_id.clone(),
+                    model: self.model.name.clone(),
+                    tags: self.history.session_tags(&self.session_id),
+                });
+                self.conversation_history.push(previous);
+            }
+            self.last_response = previous_response;
+        }
+        outcome
+    }
+
+    /// Copy code block `index` (1-based) from the last response to the clipboard,
+    /// falling back to a temp file when no clipboard is available
+    fn copy_code_block(&mut self, index: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let blocks = extract_code_blocks(&self.last_response);
+        if blocks.is_empty() {
+            return Err("No code blocks in the last response".into());
+        }
+
+        let number = match index {
+            Some(index) => index
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid block number: {}", index))?,
+            None if blocks.len() == 1 => 1,
+            None => {
+                println!("{} The last response has {} code blocks:", "📋".cyan(), blocks.len());
+                for (i, block) in blocks.iter().enumerate() {
+                    println!(
+                        "  {} {} ({} lines)",
+                        (i + 1).to_string().yellow(),
+                        block.language.as_deref().unwrap_or("text"),
+                        block.line_count()
+                    );
+                }
+                println!("{} Usage: :copy <n>", "💡".yellow());
+                return Ok(());
+            }
+        };
+
+        let block = blocks
+            .get(number.wrapping_sub(1))
+            .ok_or_else(|| format!("No code block {} (found {})", number, blocks.len()))?;
+        let description = format!(
+            "{} block ({} lines)",
+            block.language.as_deref().unwrap_or("text"),
+            block.line_count()
+        );
+
+        if self.clipboard.is_none() {
+            self.clipboard = arboard::Clipboard::new().ok();
+        }
+        let copied = self
+            .clipboard
+            .as_mut()
+            .is_some_and(|clipboard| clipboard.set_text(block.code.clone()).is_ok());
+
+        if copied {
+            println!("{} Copied {} to clipboard", "📋".green(), description);
+        } else {
+            let path = std::env::temp_dir().join(format!(
+                "ollama-agent-block-{}.{}",
+                chrono::Utc::now().format("%Y%m%d%H%M%S"),
+                block.file_extension()
+            ));
+            std::fs::write(&path, &block.code)?;
+            println!(
+                "{} Clipboard unavailable; wrote {} to {}",
+                "📝".yellow(),
+                description,
+                path.display()
+            );
+        }
+        Ok(())
+    }
+
     async fn handle_model_switch(&mut self, input: &str) -> Result<(), Box<dyn std::error::Error>> {
         let lower = input.trim().to_lowercase();
 
@@ -617,71 +1529,46 @@ impl AssistantSession {
             return Err("Invalid model switch command".into());
         };
 
-        // Get available models
-        let available_models = crate::client::fetch_models().await?;
-
-        // Find the model (support partial matching)
-        let matching_models: Vec<_> = available_models
-            .iter()
-            .filter(|m| m.name.to_lowercase().contains(model_name))
-            .collect();
-
-        match matching_models.len() {
-            0 => {
-                let available_names: Vec<String> =
-                    available_models.iter().map(|m| m.name.clone()).collect();
+        // Same resolution as --model: exact name or fuzzy match, e.g. "codel" for codellama
+        let model = match crate::client::resolve_model(Some(model_name.to_string())).await {
+            Ok(model) => model,
+            Err(AgentError::ModelNotFound(_)) => {
                 println!(
                     "{} Model '{}' not found. Available models:",
                     "❌".red(),
                     model_name
                 );
-                for name in available_names {
-                    println!("  • {}", name.yellow());
+                for model in crate::client::fetch_models().await? {
+                    println!("  • {}", model.name.yellow());
                 }
+                return Ok(());
             }
-            1 => {
-                let model = matching_models[0];
-                let old_model = self.model.name.clone();
-
-                // Don't switch if it's the same model
-                if old_model == model.name {
-                    println!("{} Already using model '{}'", "ℹ️".blue(), model.name);
-                    return Ok(());
-                }
+            Err(e) => return Err(e.into()),
+        };
 
-                // Update the global model config first
-                let result = self.tool_executor.switch_model(&model.name).await?;
+        // Don't switch if it's the same model
+        if self.model.name == model.name {
+            println!("{} Already using model '{}'", "ℹ️".blue(), model.name);
+            return Ok(());
+        }
 
-                if result.success {
-                    // Update the session's model
-                    self.model = crate::client::SelectedModel::from(model.clone());
+        // Update the global model config first
+        let result = self.tool_executor.switch_model(&model.name).await?;
 
-                    println!(
-                        "{} Successfully switched from '{}' to '{}'",
-                        "✅".green(),
-                        old_model,
-                        model.name
-                    );
+        if result.success {
+            // Update the session's model
+            let options = self.model.options.clone();
+            self.model = model.with_options(options);
 
-                    // Show brief model info
-                    println!("{} Model ready for your next request", "🤖".cyan());
-                } else {
-                    return Err(result
-                        .error
-                        .unwrap_or("Unknown error switching model".to_string())
-                        .into());
-                }
-            }
-            _ => {
-                println!(
-                    "{} Multiple models match '{}'. Please be more specific:",
-                    "⚠️".yellow(),
-                    model_name
-                );
-                for model in matching_models {
-                    println!("  • {}", model.name.yellow());
-                }
-            }
+            println!("{} {}", "✅".green(), result.output);
+
+            // Show brief model info
+            println!("{} Model ready for your next request", "🤖".cyan());
+        } else {
+            return Err(result
+                .error
+                .unwrap_or("Unknown error switching model".to_string())
+                .into());
         }
 
         Ok(())
@@ -795,8 +1682,11 @@ impl AssistantSession {
         &self,
         format: crate::tools::ExportFormat,
         path: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        self.tool_executor.export_conversation(format, path).await?;
+    ) -> Result<(), AgentError> {
+        self.tool_executor
+            .export_conversation(format, path)
+            .await
+            .map_err(|e| AgentError::tool("export_conversation", e))?;
         Ok(())
     }
 
@@ -805,6 +1695,70 @@ impl AssistantSession {
         &self.conversation_history
     }
 
+    // Save the conversation, with roles and the selected model, as JSON
+    pub fn save_session(&self, path: &Path) -> Result<(), AgentError> {
+        let mut messages = Vec::new();
+        for entry in &self.conversation_history {
+            messages.push(SavedMessage {
+                role: "user".to_string(),
+                content: entry.user_input.clone(),
+                timestamp: entry.timestamp.clone(),
+                tools_used: Vec::new(),
+            });
+            messages.push(SavedMessage {
+                role: "assistant".to_string(),
+                content: entry.assistant_response.clone(),
+                timestamp: entry.timestamp.clone(),
+                tools_used: entry.tools_used.clone(),
+            });
+        }
+
+        let saved = SavedSession {
+            model: self.model.name.clone(),
+            saved_at: chrono::Utc::now().to_rfc3339(),
+            messages,
+        };
+
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let json = serde_json::to_string_pretty(&saved).map_err(|e| AgentError::Other(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    // Load a saved session, switching to its model and replaying its messages
+    pub async fn load_session(&mut self, path: &Path) -> Result<(), AgentError> {
+        let saved = SavedSession::load(path)?;
+
+        if saved.model != self.model.name {
+            let options = self.model.options.clone();
+            self.model = saved.resolve_model().await?.with_options(options);
+            Self::init_global_config(&self.model, &self.tool_executor);
+        }
+
+        self.restore_session(saved);
+        Ok(())
+    }
+
+    // Replace the conversation history with a previously saved one
+    pub fn restore_session(&mut self, saved: SavedSession) {
+        self.conversation_history = saved.into_entries();
+        self.last_response = self
+            .conversation_history
+            .last()
+            .map(|entry| entry.assistant_response.clone())
+            .unwrap_or_default();
+        println!(
+            "{} Restored {} conversation turns (model: {})",
+            "📂".cyan(),
+            self.conversation_history.len(),
+            self.model.get_name().yellow()
+        );
+    }
+
     // Clear conversation history
     pub fn clear_history(&mut self) {
         self.conversation_history.clear();
@@ -816,10 +1770,11 @@ impl AssistantSession {
         &self,
         parameter: crate::tools::ModelParameter,
         value: serde_json::Value,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), AgentError> {
         self.tool_executor
             .set_model_parameter(parameter, value)
-            .await?;
+            .await
+            .map_err(|e| AgentError::tool("set_model_parameter", e))?;
         Ok(())
     }
 
@@ -828,7 +1783,7 @@ impl AssistantSession {
         &mut self,
         context: &WorkspaceContext,
         files: HashMap<PathBuf, String>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), AgentError> {
         self.workspace_context = Some(context.clone());
         self.workspace_files = files;
 
@@ -898,7 +1853,7 @@ impl AssistantSession {
         &self,
         context_prompt: &str,
         user_input: &str,
-    ) -> Result<ResponseMode, Box<dyn std::error::Error>> {
+    ) -> Result<ResponseMode, AgentError> {
         let is_proactive_tool_mode_enabled = self
             .tool_executor
             .is_proactive_tool_mode_enabled()
@@ -980,14 +1935,14 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
             }
             "TOOL_EXECUTION" => {
                 // Use existing tool parsing logic
-                let tools = self
+                let plan = self
                     .parser
                     .parse_request_with_llm(context_prompt, &self.model)
                     .await;
-                if tools.is_empty() {
+                if plan.tools.is_empty() {
                     Ok(ResponseMode::GeneralConversation)
                 } else {
-                    Ok(ResponseMode::ToolExecution(tools))
+                    Ok(ResponseMode::ToolExecution(plan))
                 }
             }
             _ => Ok(ResponseMode::GeneralConversation),
@@ -1069,7 +2024,7 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
     async fn handle_command_generation_request(
         &mut self,
         user_input: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), AgentError> {
         println!("{} Generating command for your request...", "🤖".cyan());
 
         // Get workspace context as additional context
@@ -1090,7 +2045,8 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
         let generation_result = self
             .tool_executor
             .generate_command(user_input, context.as_deref())
-            .await?;
+            .await
+            .map_err(|e| AgentError::tool("generate_command", e))?;
 
         if !generation_result.success {
             println!(
@@ -1106,6 +2062,7 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
         // Use the generated prompt to get a command from the LLM
         let command_prompt = generation_result.output;
         let generated_command = stream_response(&self.model, &command_prompt).await?;
+        let generated_command = strip_thinking(&generated_command, &thinking_settings().tags);
 
         // Clean up the response to get just the command
         let clean_command = generated_command
@@ -1126,7 +2083,8 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
         let should_execute = Confirm::with_theme(&ColorfulTheme::default())
             .with_prompt(format!("{} Execute this command?", "🚀".green()))
             .default(false)
-            .interact()?;
+            .interact()
+            .map_err(|dialoguer::Error::IO(e)| e)?;
 
         if should_execute {
             // Execute the command
@@ -1134,9 +2092,14 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
                 command: clean_command.to_string(),
             };
 
-            if self.permission_manager.request_permission(&tool)? {
+            if self.permission_manager.request_permission(&tool).map_err(AgentError::from)? {
                 println!("{} Executing command...", "⚡".cyan());
-                let result = self.tool_executor.execute_tool(tool).await?;
+                let result = self
+                    .tool_executor
+                    .execute_tool(tool)
+                    .await
+                    .map_err(|e| AgentError::tool("execute_command", e))?;
+                self.last_tool_results.push(result.clone());
 
                 if result.success {
                     println!("{} Command executed successfully!", "✅".green());
@@ -1169,12 +2132,143 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
             })),
         };
 
+        self.record_conversation_entry(entry);
+
+        Ok(())
+    }
+
+    // Token budget: the session's num_ctx, else the configured override, else the model config
+    async fn context_token_budget(&self) -> usize {
+        context_token_budget(&self.tool_executor, self.model.options.num_ctx).await
+    }
+
+    // Tokens always sent regardless of history: system prompts plus the pinned prompt
+    async fn fixed_prompt_tokens(&self, context_prompt: &str) -> usize {
+        estimate_tokens(context_prompt) + system_prompt_tokens(&self.tool_executor).await
+    }
+
+    fn history_tokens(&self) -> usize {
+        self.conversation_history.iter().map(entry_tokens).sum()
+    }
+
+    // Drop the oldest turns until history plus the fixed prompt fits the budget
+    async fn trim_history_to_budget(&mut self, context_prompt: &str) {
+        let budget = self.context_token_budget().await;
+        let reserved = self.fixed_prompt_tokens(context_prompt).await + budget / RESPONSE_RESERVE_RATIO;
+        let available = budget.saturating_sub(reserved);
+
+        let mut history_tokens = self.history_tokens();
+        let mut removed = 0;
+        while history_tokens > available && removed < self.conversation_history.len() {
+            history_tokens -= entry_tokens(&self.conversation_history[removed]);
+            removed += 1;
+        }
+
+        if removed > 0 {
+            self.conversation_history.drain(0..removed);
+            println!(
+                "{} Trimmed {} oldest message(s) to fit the {} token context budget",
+                "✂".dimmed(),
+                removed,
+                budget
+            );
+        }
+    }
+
+    async fn show_token_usage(&self) {
+        if self.print_context_report().await {
+            println!(
+                "{}",
+                "  Oldest messages will be trimmed before the next request".yellow()
+            );
+        }
+    }
+
+    /// Pre-flight check of the loaded files and conversation against the context
+    /// window: the full report with `--verbose`, otherwise only a warning when over budget
+    pub async fn check_context_budget(&self) {
+        if VERBOSE.load(Ordering::Relaxed) {
+            self.print_context_report().await;
+            return;
+        }
+
+        let budget = self.context_token_budget().await;
+        let total = self.fixed_prompt_tokens(&self.create_context_aware_prompt("")).await
+            + self.history_tokens()
+            + budget / RESPONSE_RESERVE_RATIO;
+        if total > budget {
+            warn_context_exceeded(&file_token_counts(&self.workspace_files), total, budget);
+        }
+    }
+
+    async fn print_context_report(&self) -> bool {
+        let budget = self.context_token_budget().await;
+        let file_tokens = file_token_counts(&self.workspace_files);
+        let fixed = self.fixed_prompt_tokens(&self.create_context_aware_prompt("")).await;
+        let files = if self.workspace_files.len() <= MAX_INLINED_FILES {
+            file_tokens.iter().map(|(_, tokens)| tokens).sum()
+        } else {
+            0
+        };
+        let history = format!("History ({} turns)", self.conversation_history.len());
+
+        print_context_budget(
+            &file_tokens,
+            &[
+                ("Workspace files", files),
+                ("System + prompt", fixed.saturating_sub(files)),
+                (history.as_str(), self.history_tokens()),
+                ("Reserved for reply", budget / RESPONSE_RESERVE_RATIO),
+            ],
+            budget,
+        )
+    }
+
+    /// Running total of the token counts Ollama reported for this session
+    fn show_usage_totals(&self) {
+        let usage = crate::client::usage_totals();
+        println!("{}", "Session Usage:".cyan().bold());
+        println!("  {} {}", "Requests:".blue(), usage.requests);
+        println!("  {} {}", "Prompt tokens:".blue(), usage.prompt_tokens);
+        println!("  {} {}", "Completion tokens:".blue(), usage.completion_tokens);
+        println!("  {} {}", "Total tokens:".blue(), usage.total_tokens());
+        println!(
+            "  {} {:.2}s (prompt {:.2}s, generation {:.2}s)",
+            "Model time:".blue(),
+            usage.total_ms as f64 / 1000.0,
+            usage.prompt_eval_ms as f64 / 1000.0,
+            usage.eval_ms as f64 / 1000.0
+        );
+        if usage.requests > 0 {
+            println!(
+                "  {} {:.0} prompt tokens per request",
+                "Average:".blue(),
+                usage.prompt_tokens as f64 / usage.requests as f64
+            );
+        }
+    }
+
+    /// Keep an exchange in the in-memory conversation and persist it to the history database
+    fn record_conversation_entry(&mut self, mut entry: ConversationEntry) {
+        let thinking = thinking_settings();
+        // `:copy` and friends work on the answer itself, whatever history keeps
+        self.last_response = strip_thinking(&entry.assistant_response, &thinking.tags);
+        if !thinking.keep_in_history {
+            entry.assistant_response = self.last_response.clone();
+        }
+        self.history.add_entry(HistoryEntry {
+            timestamp: entry.timestamp.clone(),
+            user_input: entry.user_input.clone(),
+            assistant_response: entry.assistant_response.clone(),
+            tools_used: entry.tools_used.clone(),
+            session_id: self.session_id.clone(),
+            model: self.model.name.clone(),
+            tags: Vec::new(),
+        });
         self.conversation_history.push(entry);
-        
+
         // Implement proper memory management for conversation history
         self.manage_conversation_history_memory();
-
-        Ok(())
     }
 
     fn manage_conversation_history_memory(&mut self) {
@@ -1227,15 +2321,34 @@ Respond with only one of: COMMAND_GENERATION, TOOL_EXECUTION, or GENERAL_CONVERS
             if let Some(project_type) = &context.project_type {
                 prompt.push_str(&format!("- Project type: {}\n", project_type));
             }
+            // Lets the model suggest `cargo test` rather than `npm test`, per subproject
+            for project in &context.projects {
+                let commands: Vec<String> = project
+                    .known_commands()
+                    .into_iter()
+                    .map(|(label, command)| format!("{} `{}`", label, project.command_in_root(command)))
+                    .collect();
+                if !commands.is_empty() {
+                    prompt.push_str(&format!(
+                        "- {} project ({}): {}\n",
+                        project.project_type,
+                        project.build_tool,
+                        commands.join(", ")
+                    ));
+                }
+            }
             prompt.push_str(&format!(
                 "- Files in context: {}\n\n",
                 context.included_files.len()
             ));
 
             // Add file contents if not too many
-            if self.workspace_files.len() <= 10 {
+            if self.workspace_files.len() <= MAX_INLINED_FILES {
                 prompt.push_str("Relevant files:\n");
-                for (path, content) in &self.workspace_files {
+                // Sorted so the same files always give the same prompt
+                let mut files: Vec<_> = self.workspace_files.iter().collect();
+                files.sort_by_key(|(path, _)| *path);
+                for (path, content) in files {
                     prompt.push_str(&format!("\n## {}\n```\n{}\n```\n", path.display(), content));
                 }
             } else {
@@ -0,0 +1,136 @@
+/// Markers around the reasoning that models like deepseek-r1 emit before their answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThinkingTags {
+    pub open: String,
+    pub close: String,
+}
+
+impl Default for ThinkingTags {
+    fn default() -> Self {
+        Self {
+            open: "<think>".to_string(),
+            close: "</think>".to_string(),
+        }
+    }
+}
+
+/// How thinking blocks are shown while streaming and whether history keeps them
+#[derive(Debug, Clone, Default)]
+pub struct ThinkingSettings {
+    pub tags: ThinkingTags,
+    /// Print the thinking dimmed instead of a placeholder
+    pub show: bool,
+    /// Store responses in history with their thinking blocks
+    pub keep_in_history: bool,
+}
+
+/// Piece of a response, in the order it arrived
+#[derive(Debug, PartialEq, Eq)]
+pub enum Segment {
+    Answer(String),
+    Thinking(String),
+    ThinkingStarted,
+    ThinkingEnded,
+}
+
+/// Splits streamed tokens into answer and thinking, holding back text that may be
+/// the start of a tag split across tokens
+pub struct ThinkingSplitter {
+    tags: ThinkingTags,
+    buffer: String,
+    thinking: bool,
+    /// Drop the blank lines models put between the closing tag and the answer
+    trim_answer: bool,
+}
+
+impl ThinkingSplitter {
+    pub fn new(tags: ThinkingTags) -> Self {
+        Self {
+            tags,
+            buffer: String::new(),
+            thinking: false,
+            trim_answer: false,
+        }
+    }
+
+    pub fn push(&mut self, token: &str) -> Vec<Segment> {
+        if self.tags.open.is_empty() || self.tags.close.is_empty() {
+            return vec![Segment::Answer(token.to_string())];
+        }
+
+        self.buffer.push_str(token);
+        let mut segments = Vec::new();
+        loop {
+            let tag = if self.thinking { &self.tags.close } else { &self.tags.open };
+            match self.buffer.find(tag.as_str()) {
+                Some(start) => {
+                    let end = start + tag.len();
+                    let text: String = self.buffer.drain(..end).take(start).collect();
+                    self.emit(text, &mut segments);
+                    self.thinking = !self.thinking;
+                    if self.thinking {
+                        segments.push(Segment::ThinkingStarted);
+                    } else {
+                        segments.push(Segment::ThinkingEnded);
+                        self.trim_answer = true;
+                    }
+                }
+                None => {
+                    let keep = partial_tag_len(&self.buffer, tag);
+                    let text: String = self.buffer.drain(..self.buffer.len() - keep).collect();
+                    self.emit(text, &mut segments);
+                    return segments;
+                }
+            }
+        }
+    }
+
+    /// Release held-back text once the response is complete
+    pub fn finish(&mut self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let text = std::mem::take(&mut self.buffer);
+        self.emit(text, &mut segments);
+        segments
+    }
+
+    fn emit(&mut self, text: String, segments: &mut Vec<Segment>) {
+        if self.thinking {
+            if !text.is_empty() {
+                segments.push(Segment::Thinking(text));
+            }
+            return;
+        }
+        let text = if self.trim_answer {
+            let trimmed = text.trim_start();
+            self.trim_answer = trimmed.is_empty();
+            trimmed.to_string()
+        } else {
+            text
+        };
+        if !text.is_empty() {
+            segments.push(Segment::Answer(text));
+        }
+    }
+}
+
+/// Length of the longest end of `text` that `tag` starts with
+fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len().min(text.len() + 1))
+        .rev()
+        .find(|&len| text.is_char_boundary(text.len() - len) && tag.starts_with(&text[text.len() - len..]))
+        .unwrap_or(0)
+}
+
+/// The response without its thinking blocks; an unclosed block runs to the end
+pub fn strip_thinking(response: &str, tags: &ThinkingTags) -> String {
+    let mut splitter = ThinkingSplitter::new(tags.clone());
+    let mut segments = splitter.push(response);
+    segments.extend(splitter.finish());
+    segments
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Answer(text) => Some(text),
+            _ => None,
+        })
+        .collect()
+}
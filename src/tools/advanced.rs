@@ -1,77 +1,260 @@
+use super::complexity::rust_function_complexity;
 use super::core::{
     AvailableTool, CodeAnalysisType, MonitorOperation, NetworkScanType, SecurityScanDepth,
     ToolExecutor, ToolResult,
 };
+use super::search::ErrorStrategy;
 use colored::Colorize;
+use serde::Serialize;
 use std::process::Command;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// Per-tool limits for [`ToolExecutor::parallel_execution`]
+#[derive(Debug, Clone)]
+pub struct ParallelOptions {
+    /// A tool still running after this long is abandoned and counted as failed
+    pub timeout: Option<Duration>,
+    /// Failed tools are retried under `RetryWithBackoff`; other strategies run
+    /// each tool once. A timed-out tool is never retried, since the abandoned
+    /// attempt may still be running
+    pub error_strategy: ErrorStrategy,
+}
+
+/// How one tool of a parallel batch ended up
+#[derive(Debug, Clone, Serialize)]
+pub struct ParallelToolOutcome {
+    pub index: usize,
+    pub tool: &'static str,
+    pub success: bool,
+    pub attempts: u32,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub result: ToolResult,
+}
+
+impl ParallelToolOutcome {
+    /// The tool's error, falling back to its output for tools that report
+    /// failures there
+    fn error_message(&self) -> String {
+        self.result
+            .error
+            .clone()
+            .or_else(|| Some(self.result.output.trim().to_string()).filter(|o| !o.is_empty()))
+            .unwrap_or_else(|| "Unknown error".to_string())
+    }
+}
+
+fn failed_result(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        metadata: None,
+        web_search_result: None,
+    }
+}
 
 impl ToolExecutor {
+    /// Run `tools` concurrently. Each tool gets its own timeout and retries, so one
+    /// slow or flaky tool only fails itself rather than holding up the batch.
     pub async fn parallel_execution(
         &self,
-        tools: Vec<AvailableTool>,
+        tools: &[AvailableTool],
+        options: &ParallelOptions,
     ) -> Result<ToolResult, Box<dyn std::error::Error>> {
         println!("{} Executing {} tools in parallel", "⚡".cyan(), tools.len());
 
         let start_time = Instant::now();
-        let mut results = Vec::new();
-        let mut all_success = true;
-
-        // Execute tools concurrently using futures::future::join_all for better performance
-        use futures::future::join_all;
-        
-        // Create futures for all tools
-        let futures = tools.into_iter().map(|tool| {
-            self.execute_tool(tool)
-        });
+        let futures = tools
+            .iter()
+            .enumerate()
+            .map(|(index, tool)| self.run_parallel_tool(index, tool, options));
+        let outcomes = futures::future::join_all(futures).await;
+        let duration = start_time.elapsed();
 
-        // Execute all tools concurrently
-        let parallel_results = join_all(futures).await;
+        let successful = outcomes.iter().filter(|o| o.success).count();
+        let failed = outcomes.len() - successful;
+        let retried = outcomes.iter().filter(|o| o.attempts > 1).count();
+        let timed_out = outcomes.iter().filter(|o| o.timed_out).count();
+        let overall_success = failed == 0;
 
-        // Process results
-        for result in parallel_results {
-            match result {
-                Ok(tool_result) => {
-                    if !tool_result.success {
-                        all_success = false;
-                    }
-                    results.push(tool_result);
-                }
-                Err(e) => {
-                    all_success = false;
-                    results.push(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(e.to_string()),
-                        metadata: None,
-                web_search_result: None,
-                    });
-                }
-            }
-        }
+        let summary = if overall_success {
+            format!("{} All {} tools executed successfully", "✓".green(), outcomes.len())
+        } else {
+            format!(
+                "{} {}/{} tools completed successfully",
+                if successful > failed { "⚠️".yellow() } else { "✗".red() },
+                successful,
+                outcomes.len()
+            )
+        };
 
-        let duration = start_time.elapsed();
-        let summary = format!(
-            "Parallel execution completed in {:?}. {} tools executed, {} successful",
-            duration,
-            results.len(),
-            results.iter().filter(|r| r.success).count()
-        );
+        let details: Vec<String> = outcomes
+            .iter()
+            .map(|outcome| {
+                let status = match (outcome.success, outcome.timed_out) {
+                    (true, _) => "SUCCESS",
+                    (false, true) => "TIMED OUT",
+                    (false, false) => "FAILED",
+                };
+                let body = if outcome.success {
+                    outcome.result.output.clone()
+                } else {
+                    outcome.error_message()
+                };
+                format!(
+                    "Tool {} ({}): {} after {} attempt{}\n{}",
+                    outcome.index + 1,
+                    outcome.tool,
+                    status,
+                    outcome.attempts,
+                    if outcome.attempts == 1 { "" } else { "s" },
+                    body
+                )
+            })
+            .collect();
+
+        let errors: Vec<String> = outcomes
+            .iter()
+            .filter(|o| !o.success)
+            .map(|o| format!("Tool {}: {}", o.index + 1, o.error_message()))
+            .collect();
 
         Ok(ToolResult {
-            success: all_success,
-            output: summary,
-            error: None,
+            success: overall_success,
+            output: format!(
+                "{}\n\nExecution Summary:\n- Total tools: {}\n- Successful: {}\n- Failed: {}\n- Timed out: {}\n- Needed retries: {}\n- Duration: {:.2}s\n\n--- Detailed Results ---\n{}",
+                summary,
+                outcomes.len(),
+                successful,
+                failed,
+                timed_out,
+                retried,
+                duration.as_secs_f64(),
+                details.join("\n\n---\n\n")
+            ),
+            error: if errors.is_empty() {
+                None
+            } else {
+                Some(format!("Failed tools: {}", errors.join("; ")))
+            },
             metadata: Some(serde_json::json!({
-                "parallel_results": results,
+                "parallel_execution": true,
+                "outcomes": outcomes,
                 "execution_time_ms": duration.as_millis(),
-                "total_tools": results.len(),
-                "successful_tools": results.iter().filter(|r| r.success).count()
+                "total_tools": outcomes.len(),
+                "successful_tools": successful,
+                "failed_tools": failed,
+                "timed_out_tools": timed_out,
+                "retried_tools": retried,
+                "timeout_seconds": options.timeout.map(|t| t.as_secs()),
+                "max_retries": options.error_strategy.retry_budget().0
             })),
             web_search_result: None,
         })
     }
 
+    /// Run one tool of a parallel batch, retrying failures with the same fixed
+    /// backoff tool chains use. Timeouts end the tool: its blocking thread can't be
+    /// cancelled, and a second copy could repeat a write or command alongside it.
+    async fn run_parallel_tool(
+        &self,
+        index: usize,
+        tool: &AvailableTool,
+        options: &ParallelOptions,
+    ) -> ParallelToolOutcome {
+        let (max_retries, backoff_ms) = options.error_strategy.retry_budget();
+        let start_time = Instant::now();
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let (result, timed_out) = self.run_parallel_attempt(tool, options.timeout).await;
+
+            if result.success || timed_out || attempts > max_retries {
+                let duration = start_time.elapsed();
+                let outcome = ParallelToolOutcome {
+                    index,
+                    tool: tool.name(),
+                    success: result.success,
+                    attempts,
+                    timed_out,
+                    duration_ms: duration.as_millis() as u64,
+                    result,
+                };
+                if outcome.success {
+                    println!(
+                        "{} Tool {} completed in {:.2}s",
+                        "✓".green(),
+                        index + 1,
+                        duration.as_secs_f64()
+                    );
+                } else {
+                    println!(
+                        "{} Tool {} failed in {:.2}s: {}",
+                        "✗".red(),
+                        index + 1,
+                        duration.as_secs_f64(),
+                        outcome.error_message()
+                    );
+                }
+                return outcome;
+            }
+
+            println!(
+                "{} Tool {} failed (attempt {}/{}), retrying in {}ms",
+                "🔄".yellow(),
+                index + 1,
+                attempts,
+                max_retries + 1,
+                backoff_ms
+            );
+            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+        }
+    }
+
+    /// One attempt at a tool, reporting whether it was abandoned for running too long.
+    /// The tool runs on its own thread so a blocking call cannot stall the timeout, and
+    /// on timeout its future is dropped, which kills any process it started.
+    async fn run_parallel_attempt(
+        &self,
+        tool: &AvailableTool,
+        timeout: Option<Duration>,
+    ) -> (ToolResult, bool) {
+        let executor = self.clone();
+        let tool = tool.clone();
+        let cancel = tokio_util::sync::CancellationToken::new();
+        let cancelled = cancel.clone();
+        // Tool futures aren't `Send`, so drive each one on a blocking thread
+        let runtime = tokio::runtime::Handle::current();
+        let mut handle = tokio::task::spawn_blocking(move || {
+            runtime.block_on(async {
+                tokio::select! {
+                    result = executor.execute_tool(tool) => result.map_err(|e| e.to_string()),
+                    _ = cancelled.cancelled() => Err("cancelled".to_string()),
+                }
+            })
+        });
+
+        let joined = match timeout {
+            Some(limit) => match tokio::time::timeout(limit, &mut handle).await {
+                Ok(joined) => joined,
+                Err(_) => {
+                    cancel.cancel();
+                    let error = format!("Timed out after {}s", limit.as_secs_f64());
+                    return (failed_result(error), true);
+                }
+            },
+            None => handle.await,
+        };
+
+        match joined {
+            Ok(Ok(result)) => (result, false),
+            Ok(Err(e)) => (failed_result(e), false),
+            Err(e) => (failed_result(format!("Tool task failed: {}", e)), false),
+        }
+    }
+
     pub async fn smart_suggestion(
         &self,
         context: &str,
@@ -321,31 +504,10 @@ impl ToolExecutor {
         Ok(0.0)
     }
 
+    /// Share of CPU time spent busy over `cpu_sample_interval_ms`. Counters
+    /// since boot would average away current load, so two samples are compared.
     async fn get_cpu_usage(&self) -> Result<f64, Box<dyn std::error::Error>> {
-        // This is a simplified CPU usage calculation
-        // In a real implementation, you'd want to sample over time
-        
-        #[cfg(target_os = "linux")]
-        {
-            if let Ok(stat) = std::fs::read_to_string("/proc/stat") {
-                if let Some(cpu_line) = stat.lines().find(|line| line.starts_with("cpu ")) {
-                    let values: Vec<u64> = cpu_line
-                        .split_whitespace()
-                        .skip(1)
-                        .filter_map(|s| s.parse().ok())
-                        .collect();
-                    
-                    if values.len() >= 4 {
-                        let idle = values[3];
-                        let total: u64 = values.iter().sum();
-                        let usage = 100.0 - (idle as f64 / total as f64 * 100.0);
-                        return Ok(usage);
-                    }
-                }
-            }
-        }
-
-        Ok(0.0)
+        sample_cpu_usage(self.get_cpu_sample_interval()).await
     }
 
     pub async fn code_analysis(
@@ -385,13 +547,22 @@ impl ToolExecutor {
     async fn analyze_complexity(&self, path: &str) -> Result<String, Box<dyn std::error::Error>> {
         let mut results = vec!["Code Complexity Analysis".to_string(), "=".repeat(30)];
         
-        // Simple complexity analysis based on file structure
+        // Rust functions are parsed and scored; other languages fall back to a
+        // simple analysis based on file structure, and mixed projects get both
         let mut total_lines = 0;
         let mut total_files = 0;
         let mut function_count = 0;
         let mut class_count = 0;
-
-        for entry in walkdir::WalkDir::new(path) {
+        let mut rust_functions = Vec::new();
+        let mut unparsed_rust_files = 0;
+        let mut heuristic_files = 0;
+        let mut heuristic_lines = 0;
+
+        let entries = walkdir::WalkDir::new(path).into_iter().filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0 || !(name.starts_with('.') || name == "target" || name == "node_modules")
+        });
+        for entry in entries {
             let entry = entry?;
             if entry.file_type().is_file() {
                 if let Some(extension) = entry.path().extension() {
@@ -399,6 +570,20 @@ impl ToolExecutor {
                         total_files += 1;
                         if let Ok(content) = std::fs::read_to_string(entry.path()) {
                             total_lines += content.lines().count();
+                            if extension == "rs" {
+                                let file = entry.path().strip_prefix(path).unwrap_or(entry.path());
+                                match rust_function_complexity(&file.display().to_string(), &content) {
+                                    Ok(functions) => {
+                                        function_count += functions.len();
+                                        class_count += content.matches("struct ").count();
+                                        rust_functions.extend(functions);
+                                        continue;
+                                    }
+                                    Err(_) => unparsed_rust_files += 1,
+                                }
+                            }
+                            heuristic_files += 1;
+                            heuristic_lines += content.lines().count();
                             function_count += content.matches("fn ").count()
                                 + content.matches("function ").count()
                                 + content.matches("def ").count();
@@ -419,16 +604,72 @@ impl ToolExecutor {
             results.push(format!("Average Lines per File: {:.1}", total_lines as f64 / total_files as f64));
         }
 
-        // Simple complexity assessment
-        let complexity = if total_lines > 10000 {
-            "High"
-        } else if total_lines > 5000 {
-            "Medium"
-        } else {
-            "Low"
-        };
-        results.push(format!("Overall Complexity: {}", complexity));
+        // Ratings from 0 (Low) to 2 (High); the report gives the worse of the two analyses
+        const RATINGS: [&str; 3] = ["Low", "Medium", "High"];
+        let mut rating = None;
+
+        if !rust_functions.is_empty() {
+            let average = rust_functions.iter().map(|f| f.score as f64).sum::<f64>()
+                / rust_functions.len() as f64;
+            let over_threshold = rust_functions.iter().filter(|f| f.score > 10).count();
+
+            results.push(String::new());
+            results.push("Rust Cyclomatic Complexity".to_string());
+            results.push("-".repeat(26));
+            results.push(format!("Functions Analyzed: {}", rust_functions.len()));
+            results.push(format!("Average Complexity: {:.1}", average));
+            results.push(format!("Functions Above 10: {}", over_threshold));
+            if unparsed_rust_files > 0 {
+                results.push(format!(
+                    "Files Not Parsed: {} (counted with the line-based heuristic)",
+                    unparsed_rust_files
+                ));
+            }
 
+            rust_functions.sort_by_key(|f| std::cmp::Reverse(f.score));
+            results.push("\nMost Complex Functions:".to_string());
+            for (rank, function) in rust_functions.iter().take(10).enumerate() {
+                results.push(format!(
+                    "  {:>2}. {:>3}  {} ({})",
+                    rank + 1,
+                    function.score,
+                    function.name,
+                    function.file
+                ));
+            }
+
+            let rust_rating = if average > 10.0 {
+                2
+            } else if average > 5.0 {
+                1
+            } else {
+                0
+            };
+            results.push(format!("Rust Complexity: {} (average cyclomatic complexity)", RATINGS[rust_rating]));
+            rating = Some(rust_rating);
+        }
+
+        // Simple complexity assessment of everything that wasn't scored per function
+        if heuristic_files > 0 || rating.is_none() {
+            let heuristic_rating = if heuristic_lines > 10000 {
+                2
+            } else if heuristic_lines > 5000 {
+                1
+            } else {
+                0
+            };
+            if rating.is_some() {
+                results.push(String::new());
+                results.push("Other Files (line-based heuristic)".to_string());
+                results.push("-".repeat(34));
+                results.push(format!("Files: {}", heuristic_files));
+                results.push(format!("Lines: {}", heuristic_lines));
+                results.push(format!("Complexity: {}", RATINGS[heuristic_rating]));
+            }
+            rating = rating.max(Some(heuristic_rating));
+        }
+
+        results.push(format!("\nOverall Complexity: {}", RATINGS[rating.unwrap_or(0)]));
         Ok(results.join("\n"))
     }
 
@@ -1155,4 +1396,74 @@ impl Clone for ToolExecutor {
             execution_depth: self.execution_depth.clone(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Cumulative jiffies from the aggregate `cpu` line of `/proc/stat`
+#[cfg(target_os = "linux")]
+struct CpuTimes {
+    total: u64,
+    idle: u64,
+}
+
+#[cfg(target_os = "linux")]
+fn read_cpu_times() -> Result<CpuTimes, Box<dyn std::error::Error>> {
+    let stat = std::fs::read_to_string("/proc/stat")?;
+    let line = stat
+        .lines()
+        .find(|line| line.starts_with("cpu "))
+        .ok_or("No cpu line in /proc/stat")?;
+    // user nice system idle iowait irq softirq steal; guest time is already in user
+    let values: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .filter_map(|value| value.parse().ok())
+        .collect();
+    if values.len() < 4 {
+        return Err("Unexpected cpu line in /proc/stat".into());
+    }
+    Ok(CpuTimes {
+        total: values.iter().sum(),
+        idle: values[3] + values.get(4).copied().unwrap_or(0),
+    })
+}
+
+#[cfg(target_os = "linux")]
+async fn sample_cpu_usage(interval: Duration) -> Result<f64, Box<dyn std::error::Error>> {
+    let before = read_cpu_times()?;
+    tokio::time::sleep(interval).await;
+    let after = read_cpu_times()?;
+
+    let total = after.total.saturating_sub(before.total);
+    let idle = after.idle.saturating_sub(before.idle).min(total);
+    if total == 0 {
+        return Ok(0.0);
+    }
+    Ok((total - idle) as f64 / total as f64 * 100.0)
+}
+
+#[cfg(target_os = "macos")]
+async fn sample_cpu_usage(interval: Duration) -> Result<f64, Box<dyn std::error::Error>> {
+    // top's first sample covers the time since boot and the second the `-s`
+    // seconds in between, which only takes whole seconds
+    let seconds = interval.as_secs_f64().ceil().max(1.0) as u64;
+    let output = tokio::process::Command::new("top")
+        .args(["-l", "2", "-n", "0", "-s", &seconds.to_string()])
+        .output()
+        .await?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    // "CPU usage: 7.31% user, 5.12% sys, 87.56% idle"
+    let idle = text
+        .lines()
+        .filter(|line| line.starts_with("CPU usage:"))
+        .last()
+        .and_then(|line| line.split(',').find(|part| part.contains("idle")))
+        .and_then(|part| part.trim().split('%').next()?.trim().parse::<f64>().ok())
+        .ok_or("Could not read CPU usage from top")?;
+    Ok((100.0 - idle).clamp(0.0, 100.0))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+async fn sample_cpu_usage(_interval: Duration) -> Result<f64, Box<dyn std::error::Error>> {
+    Ok(0.0)
+}
@@ -129,6 +129,7 @@ impl AsyncToolExecutor {
             git_default_remote: "origin".to_string(),
             database_connections: std::collections::HashMap::new(),
             api_keys: std::collections::HashMap::new(),
+            command_policy: super::permissions::CommandPolicy::default(),
         };
         
         let executor = ToolExecutor::with_config(tool_config);
@@ -0,0 +1,265 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::core::{ToolExecutor, ToolResult};
+
+// Backups taken while a group is active are undone together (one user request, one `Edit` run)
+lazy_static::lazy_static! {
+    static ref CURRENT_GROUP: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Start a new backup group; later backups share it until the next call
+pub fn begin_backup_group() {
+    if let Ok(mut group) = CURRENT_GROUP.lock() {
+        *group = Some(uuid::Uuid::new_v4().to_string());
+    }
+}
+
+fn current_group() -> String {
+    CURRENT_GROUP
+        .lock()
+        .ok()
+        .and_then(|group| group.clone())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRecord {
+    pub original_path: PathBuf,
+    /// None when the file did not exist before the write; undo deletes it
+    pub backup_path: Option<PathBuf>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub group: String,
+}
+
+fn backups_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(crate::paths::data_dir().join("backups"))
+}
+
+fn journal_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(backups_dir()?.join("journal.json"))
+}
+
+fn load_journal() -> Result<Vec<BackupRecord>, Box<dyn std::error::Error>> {
+    let path = journal_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let backups = backups_dir()?;
+    let mut records: Vec<BackupRecord> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    for record in &mut records {
+        record.backup_path = record
+            .backup_path
+            .take()
+            .map(|stored| resolve_backup_path(&backups, stored));
+    }
+    Ok(records)
+}
+
+fn save_journal(records: &[BackupRecord]) -> Result<(), Box<dyn std::error::Error>> {
+    let path = journal_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Stored relative to the backups directory so the journal survives the data directory moving
+    let backups = backups_dir()?;
+    let stored: Vec<BackupRecord> = records
+        .iter()
+        .cloned()
+        .map(|mut record| {
+            record.backup_path = record.backup_path.map(|backup_path| {
+                backup_path
+                    .strip_prefix(&backups)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(backup_path)
+            });
+            record
+        })
+        .collect();
+    fs::write(path, serde_json::to_string_pretty(&stored)?)?;
+    Ok(())
+}
+
+// Absolute paths come from journals written before paths were stored relative; if the
+// data directory has moved since, look for the same `<hash>/<file>` under the new one
+fn resolve_backup_path(backups: &Path, stored: PathBuf) -> PathBuf {
+    if stored.is_relative() {
+        return backups.join(stored);
+    }
+    if stored.exists() {
+        return stored;
+    }
+    let moved = stored
+        .parent()
+        .and_then(Path::file_name)
+        .zip(stored.file_name())
+        .map(|(dir, file)| backups.join(dir).join(file));
+    match moved {
+        Some(moved) if moved.exists() => moved,
+        _ => stored,
+    }
+}
+
+fn absolute_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+// Directory name for a file's backups; SHA-256 so it stays the same across Rust versions
+fn path_hash(path: &Path) -> String {
+    let digest = Sha256::digest(path.as_os_str().as_encoded_bytes());
+    digest[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Copy a backup back over its original, or remove a file that was newly created
+fn restore_record(record: &BackupRecord) -> Result<(), Box<dyn std::error::Error>> {
+    match &record.backup_path {
+        Some(backup_path) => {
+            if let Some(parent) = record.original_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(backup_path, &record.original_path)?;
+            let _ = fs::remove_file(backup_path);
+        }
+        None => {
+            if record.original_path.exists() {
+                fs::remove_file(&record.original_path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl ToolExecutor {
+    /// Snapshot a file before it is modified, pruning old backups beyond the configured limit
+    pub fn backup_before_write(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let (enabled, max_backups) = self.get_backup_settings();
+        if !enabled {
+            return Ok(());
+        }
+
+        let original_path = absolute_path(path);
+        let backup_path = if original_path.exists() {
+            let file_name = original_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "file".to_string());
+            let dir = backups_dir()?.join(path_hash(&original_path));
+            fs::create_dir_all(&dir)?;
+
+            let backup_path = dir.join(format!(
+                "{}-{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S%.3f"),
+                file_name
+            ));
+            fs::copy(&original_path, &backup_path)?;
+            Some(backup_path)
+        } else {
+            None
+        };
+
+        let mut journal = load_journal()?;
+        journal.push(BackupRecord {
+            original_path: original_path.clone(),
+            backup_path,
+            created_at: chrono::Utc::now(),
+            group: current_group(),
+        });
+
+        // Keep only the newest backups for this file
+        let file_backups = journal
+            .iter()
+            .filter(|r| r.original_path == original_path && r.backup_path.is_some())
+            .count();
+        let mut to_prune = file_backups.saturating_sub(max_backups);
+        journal.retain(|record| {
+            if to_prune > 0 && record.original_path == original_path && record.backup_path.is_some() {
+                to_prune -= 1;
+                if let Some(backup_path) = &record.backup_path {
+                    let _ = fs::remove_file(backup_path);
+                }
+                false
+            } else {
+                true
+            }
+        });
+
+        save_journal(&journal)
+    }
+
+    /// Restore the most recent backup of a single file
+    pub fn file_restore(&self, path: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Restoring file: {}", "⏪".cyan(), path.yellow());
+
+        let original_path = absolute_path(Path::new(path));
+        let mut journal = load_journal()?;
+
+        let Some(index) = journal.iter().rposition(|r| r.original_path == original_path) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("No backups found for {}", path)),
+                metadata: None,
+                web_search_result: None,
+            });
+        };
+
+        let record = journal.remove(index);
+        restore_record(&record)?;
+        save_journal(&journal)?;
+
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "Restored {} from backup taken at {}",
+                path,
+                record.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")
+            ),
+            error: None,
+            metadata: None,
+            web_search_result: None,
+        })
+    }
+
+    /// Undo the most recent change set, restoring every file it touched
+    pub fn undo_last_change(&self) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let mut journal = load_journal()?;
+
+        let Some(group) = journal.last().map(|r| r.group.clone()) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Nothing to undo".to_string()),
+                metadata: None,
+                web_search_result: None,
+            });
+        };
+
+        // Restore newest-first so a file edited twice ends at its earliest state
+        let mut restored = Vec::new();
+        for record in journal.iter().rev().filter(|r| r.group == group) {
+            restore_record(record)?;
+            let display = record.original_path.display().to_string();
+            if !restored.contains(&display) {
+                restored.push(display);
+            }
+        }
+        journal.retain(|r| r.group != group);
+        save_journal(&journal)?;
+
+        Ok(ToolResult {
+            success: true,
+            output: format!("Restored {} file(s):\n{}", restored.len(), restored.join("\n")),
+            error: None,
+            metadata: None,
+            web_search_result: None,
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use syn::visit::{self, Visit};
+use syn::{BinOp, Expr};
+
+/// Cyclomatic complexity of one Rust function
+#[derive(Debug, Clone)]
+pub struct FunctionComplexity {
+    pub file: String,
+    pub name: String,
+    pub score: u32,
+}
+
+/// Cyclomatic complexity of every function and method in a Rust source file.
+/// Methods are named `Type::method`; closures count toward their enclosing function.
+pub fn rust_function_complexity(file: &str, source: &str) -> syn::Result<Vec<FunctionComplexity>> {
+    let syntax = syn::parse_file(source)?;
+    let mut collector = FunctionCollector {
+        file,
+        owner: None,
+        functions: Vec::new(),
+    };
+    collector.visit_file(&syntax);
+    Ok(collector.functions)
+}
+
+/// Walks items, scoring each function body separately (nested functions included)
+struct FunctionCollector<'a> {
+    file: &'a str,
+    owner: Option<String>,
+    functions: Vec<FunctionComplexity>,
+}
+
+impl FunctionCollector<'_> {
+    fn record(&mut self, name: &syn::Ident, block: &syn::Block) {
+        let name = match &self.owner {
+            Some(owner) => format!("{}::{}", owner, name),
+            None => name.to_string(),
+        };
+        let mut counter = BranchCounter { branches: 0 };
+        counter.visit_block(block);
+        self.functions.push(FunctionComplexity {
+            file: self.file.to_string(),
+            name,
+            score: 1 + counter.branches,
+        });
+    }
+
+    fn with_owner(&mut self, owner: Option<String>, visit: impl FnOnce(&mut Self)) {
+        let previous = std::mem::replace(&mut self.owner, owner);
+        visit(self);
+        self.owner = previous;
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionCollector<'_> {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.record(&node.sig.ident, &node.block);
+        // Functions nested in the body are free functions of their own
+        self.with_owner(None, |collector| visit::visit_item_fn(collector, node));
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        let owner = match node.self_ty.as_ref() {
+            syn::Type::Path(type_path) => type_path.path.segments.last().map(|s| s.ident.to_string()),
+            _ => None,
+        };
+        self.with_owner(owner, |collector| visit::visit_item_impl(collector, node));
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.record(&node.sig.ident, &node.block);
+        self.with_owner(None, |collector| visit::visit_impl_item_fn(collector, node));
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+        let owner = Some(node.ident.to_string());
+        self.with_owner(owner, |collector| visit::visit_item_trait(collector, node));
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        if let Some(block) = &node.default {
+            self.record(&node.sig.ident, block);
+        }
+        self.with_owner(None, |collector| visit::visit_trait_item_fn(collector, node));
+    }
+}
+
+/// Counts branch points in one function body: `if`, extra `match` arms and
+/// guards, `&&`/`||`, loops and `?`
+struct BranchCounter {
+    branches: u32,
+}
+
+impl<'ast> Visit<'ast> for BranchCounter {
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        self.branches += match node {
+            Expr::If(_) | Expr::ForLoop(_) | Expr::While(_) | Expr::Loop(_) | Expr::Try(_) => 1,
+            Expr::Match(expr) => {
+                let guards = expr.arms.iter().filter(|arm| arm.guard.is_some()).count();
+                (expr.arms.len().saturating_sub(1) + guards) as u32
+            }
+            Expr::Binary(expr) if matches!(expr.op, BinOp::And(_) | BinOp::Or(_)) => 1,
+            _ => 0,
+        };
+        visit::visit_expr(self, node);
+    }
+
+    // Nested items are scored on their own by `FunctionCollector`
+    fn visit_item(&mut self, _node: &'ast syn::Item) {}
+}
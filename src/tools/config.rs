@@ -1,9 +1,27 @@
+use super::advanced::ParallelOptions;
+use crate::error::AgentError;
+use crate::client::{parse_keep_alive, KeepAlive};
+use crate::thinking::{ThinkingSettings, ThinkingTags};
 use super::core::{ExportFormat, ToolExecutor, ToolResult};
+use super::enhanced_websearch::{
+    default_content_language_check, default_content_spam_phrases, default_max_concurrent_scrapes,
+    default_min_content_quality_score, default_min_content_word_diversity, default_min_content_words,
+    default_scrape_timeout_seconds, default_total_search_deadline_secs, parse_domain, parse_engine_id, parse_language_code,
+    parse_region_code, search_locale_override, RateLimit, SEARCH_ENGINE_IDS,
+};
+use super::errors::RetryConfig;
+use super::model_config::ModelProfile;
+use super::http::{configure_web_client, default_http_proxy, default_https_proxy};
+use super::permissions::{CommandPolicy, CommandPolicyMode, NonInteractivePermission};
+use super::plugins::{check_spec, ExternalToolSpec};
+use super::search::ErrorStrategy;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::sync::Once;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -21,6 +39,499 @@ pub struct AppConfig {
     pub system_prompt: Option<String>,
     pub enable_command_generation: bool,
     pub enable_proactive_tool_mode: bool,
+    /// Off answers every request as plain conversation (`--no-tools` for one run)
+    #[serde(default = "default_true")]
+    pub enable_tools: bool,
+    #[serde(default)]
+    pub context_token_budget: Option<u32>,
+    #[serde(default)]
+    pub command_policy: CommandPolicyMode,
+    #[serde(default)]
+    pub command_allowlist: Vec<String>,
+    #[serde(default)]
+    pub command_denylist: Vec<String>,
+    #[serde(default = "default_command_timeout")]
+    pub command_timeout: u64,
+    #[serde(default = "default_true")]
+    pub confirm_file_writes: bool,
+    #[serde(default)]
+    pub non_interactive_permission: NonInteractivePermission,
+    #[serde(default = "default_max_backups_per_file")]
+    pub max_backups_per_file: usize,
+    #[serde(default = "default_ollama_retry_attempts")]
+    pub ollama_retry_attempts: u32,
+    #[serde(default = "default_ollama_retry_delay_ms")]
+    pub ollama_retry_delay_ms: u64,
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+    #[serde(default)]
+    pub default_model: Option<String>,
+    #[serde(default = "default_parallel_tool_timeout")]
+    pub parallel_tool_timeout: u64,
+    #[serde(default)]
+    pub parallel_tool_retries: u32,
+    #[serde(default = "default_parallel_retry_backoff_ms")]
+    pub parallel_retry_backoff_ms: u64,
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+    #[serde(default)]
+    pub http_headers: HashMap<String, String>,
+    #[serde(default)]
+    pub search_rate_limits: HashMap<String, RateLimit>,
+    /// Web search engines to query; empty means all of them
+    #[serde(default)]
+    pub search_engines: Vec<String>,
+    /// Web search keeps only results on these domains; empty keeps every domain
+    #[serde(default)]
+    pub search_allowed_domains: Vec<String>,
+    /// Web search drops results on these domains, even allowed ones
+    #[serde(default)]
+    pub search_blocked_domains: Vec<String>,
+    #[serde(default)]
+    pub model_profiles: HashMap<String, ModelProfile>,
+    #[serde(default)]
+    pub search_language: Option<String>,
+    #[serde(default)]
+    pub search_region: Option<String>,
+    #[serde(default = "default_max_concurrent_scrapes")]
+    pub max_concurrent_scrapes: usize,
+    #[serde(default = "default_scrape_timeout_seconds")]
+    pub scrape_timeout_seconds: u64,
+    #[serde(default = "default_total_search_deadline_secs")]
+    pub total_search_deadline_secs: u64,
+    /// Extracted search result pages scoring below this (0.0-1.0) are dropped
+    #[serde(default = "default_min_content_quality_score")]
+    pub min_content_quality_score: f64,
+    #[serde(default = "default_min_content_words")]
+    pub min_content_words: usize,
+    #[serde(default = "default_min_content_word_diversity")]
+    pub min_content_word_diversity: f64,
+    #[serde(default = "default_content_spam_phrases")]
+    pub content_spam_phrases: Vec<String>,
+    /// Drop extracted pages detected to be in another language than `search_language`
+    #[serde(default = "default_content_language_check")]
+    pub content_language_check: bool,
+    /// Upper bound on requests in one web performance test
+    #[serde(default = "default_perf_test_max_requests")]
+    pub perf_test_max_requests: usize,
+    /// Tool output longer than this is cut down before it goes into the next prompt
+    #[serde(default = "default_max_tool_result_chars")]
+    pub max_tool_result_chars: usize,
+    /// How oversized tool output is cut down: keep the head, keep the tail, or summarize it
+    #[serde(default)]
+    pub tool_result_strategy: ToolResultStrategy,
+    /// Worker threads for file search walks and scoring (0 = one per CPU)
+    #[serde(default)]
+    pub file_search_threads: usize,
+    /// Window between the two CPU counter samples behind the monitor's CPU usage
+    #[serde(default = "default_cpu_sample_interval_ms")]
+    pub cpu_sample_interval_ms: u64,
+    /// Reuse earlier answers to identical `ask` prompts (`--cache`/`--no-cache` override)
+    #[serde(default)]
+    pub response_cache: bool,
+    #[serde(default = "default_response_cache_ttl_hours")]
+    pub response_cache_ttl_hours: u64,
+    /// Print reasoning models' thinking dimmed instead of a placeholder (`--show-thinking` for one run)
+    #[serde(default)]
+    pub show_thinking: bool,
+    /// Markers around a reasoning model's thinking; an empty tag turns detection off
+    #[serde(default = "default_thinking_open_tag")]
+    pub thinking_open_tag: String,
+    #[serde(default = "default_thinking_close_tag")]
+    pub thinking_close_tag: String,
+    /// Store responses in history with their thinking blocks
+    #[serde(default)]
+    pub keep_thinking_in_history: bool,
+    /// Download a requested model that isn't installed instead of failing (`--pull-if-missing` for one run)
+    #[serde(default)]
+    pub pull_if_missing: bool,
+    /// User scripts registered as tools for the model, keyed by tool name
+    #[serde(default)]
+    pub external_tools: HashMap<String, ExternalToolSpec>,
+    /// Free-form settings for scripts and custom commands; the only place keys
+    /// outside this schema survive a save
+    #[serde(default)]
+    pub custom: HashMap<String, serde_json::Value>,
+}
+
+/// Keys accepted by `config set`; `<name>` entries take any name after the dot
+pub const CONFIG_KEYS: &[&str] = &[
+    "auto_approve_safe",
+    "max_file_size",
+    "default_timeout",
+    "git_default_remote",
+    "theme",
+    "editor",
+    "log_level",
+    "backup_enabled",
+    "system_prompt",
+    "enable_command_generation",
+    "enable_proactive_tool_mode",
+    "enable_tools",
+    "context_token_budget",
+    "command_policy",
+    "command_allowlist",
+    "command_denylist",
+    "command_timeout",
+    "confirm_file_writes",
+    "non_interactive_permission",
+    "max_backups_per_file",
+    "ollama_retry_attempts",
+    "ollama_retry_delay_ms",
+    "keep_alive",
+    "default_model",
+    "parallel_tool_timeout",
+    "parallel_tool_retries",
+    "parallel_retry_backoff_ms",
+    "http_proxy",
+    "https_proxy",
+    "http_headers.<name>",
+    "search_language",
+    "search_region",
+    "search_rate_limits.<engine>",
+    "search_engines",
+    "search_allowed_domains",
+    "search_blocked_domains",
+    "max_concurrent_scrapes",
+    "scrape_timeout_seconds",
+    "total_search_deadline_secs",
+    "min_content_quality_score",
+    "min_content_words",
+    "min_content_word_diversity",
+    "content_spam_phrases",
+    "content_language_check",
+    "perf_test_max_requests",
+    "max_tool_result_chars",
+    "tool_result_strategy",
+    "file_search_threads",
+    "cpu_sample_interval_ms",
+    "response_cache",
+    "response_cache_ttl_hours",
+    "show_thinking",
+    "thinking_open_tag",
+    "thinking_close_tag",
+    "keep_thinking_in_history",
+    "pull_if_missing",
+    "external_tools.<name>",
+    "api_keys.<name>",
+    "custom.<name>",
+];
+
+/// Stands in for API keys and header values in redacted output
+const REDACTED_SECRET: &str = "********";
+
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+impl AppConfig {
+    /// Values that deserialize but that `config set` would have rejected
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            problems.push(format!("log_level must be one of: {}", LOG_LEVELS.join(", ")));
+        }
+        if self.max_backups_per_file == 0 {
+            problems.push("max_backups_per_file must be a positive number".to_string());
+        }
+        if self.max_concurrent_scrapes == 0 {
+            problems.push("max_concurrent_scrapes must be a positive number".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_content_quality_score) {
+            problems.push("min_content_quality_score must be between 0 and 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_content_word_diversity) {
+            problems.push("min_content_word_diversity must be between 0 and 1".to_string());
+        }
+        if self.perf_test_max_requests == 0 {
+            problems.push("perf_test_max_requests must be a positive number".to_string());
+        }
+        if self.max_tool_result_chars == 0 {
+            problems.push("max_tool_result_chars must be a positive number".to_string());
+        }
+        if self.cpu_sample_interval_ms == 0 {
+            problems.push("cpu_sample_interval_ms must be a positive number".to_string());
+        }
+        if self.response_cache_ttl_hours == 0 {
+            problems.push("response_cache_ttl_hours must be a positive number".to_string());
+        }
+        if let Some(Err(e)) = self.keep_alive.as_deref().map(parse_keep_alive) {
+            problems.push(format!("keep_alive: {}", e));
+        }
+        if let Err(e) = configure_web_client(
+            reqwest::Client::builder(),
+            self.http_proxy.as_deref(),
+            self.https_proxy.as_deref(),
+            &self.http_headers,
+        ) {
+            problems.push(e.to_string());
+        }
+        if let Some(Err(e)) = self.search_language.as_deref().map(parse_language_code) {
+            problems.push(format!("search_language: {}", e));
+        }
+        if let Some(Err(e)) = self.search_region.as_deref().map(parse_region_code) {
+            problems.push(format!("search_region: {}", e));
+        }
+
+        let mut tools: Vec<_> = self.external_tools.iter().collect();
+        tools.sort_unstable_by_key(|(name, _)| name.as_str());
+        for (name, spec) in tools {
+            if let Err(e) = check_spec(name, spec) {
+                problems.push(format!("external_tools.{}: {}", name, e));
+            }
+        }
+
+        for engine in &self.search_engines {
+            if let Err(e) = parse_engine_id(engine) {
+                problems.push(format!("search_engines: {}", e));
+            }
+        }
+        for (key, domains) in [
+            ("search_allowed_domains", &self.search_allowed_domains),
+            ("search_blocked_domains", &self.search_blocked_domains),
+        ] {
+            for domain in domains {
+                if let Err(e) = parse_domain(domain) {
+                    problems.push(format!("{}: {}", key, e));
+                }
+            }
+        }
+
+        let mut engines: Vec<_> = self.search_rate_limits.iter().collect();
+        engines.sort_unstable_by_key(|(engine, _)| engine.as_str());
+        for (engine, limit) in engines {
+            if !SEARCH_ENGINE_IDS.contains(&engine.as_str()) {
+                problems.push(format!("search_rate_limits: unknown search engine '{}'", engine));
+            } else if limit.capacity < 1 || limit.refill_per_second <= 0.0 {
+                problems.push(format!(
+                    "search_rate_limits.{} needs a capacity of 1 or more and refill_per_second above 0",
+                    engine
+                ));
+            }
+        }
+
+        problems
+    }
+
+    /// Copy with API keys and header values masked, for display
+    pub fn redacted(&self) -> AppConfig {
+        let mask = |map: &HashMap<String, String>| {
+            map.keys().map(|name| (name.clone(), REDACTED_SECRET.to_string())).collect()
+        };
+        AppConfig {
+            api_keys: mask(&self.api_keys),
+            http_headers: mask(&self.http_headers),
+            ..self.clone()
+        }
+    }
+}
+
+/// Top-level keys in a raw config file that the schema doesn't know about
+fn unknown_config_keys(raw: &serde_json::Map<String, serde_json::Value>) -> Vec<String> {
+    let known = serde_json::to_value(AppConfig::default()).unwrap_or_default();
+    let mut unknown: Vec<String> = raw
+        .keys()
+        .filter(|key| known.get(key.as_str()).is_none())
+        .cloned()
+        .collect();
+    unknown.sort_unstable();
+    unknown
+}
+
+/// Problems in a raw config document: unknown keys, then type errors or the
+/// constraints `config set` enforces
+fn config_problems(
+    raw: &serde_json::Map<String, serde_json::Value>,
+    parsed: Result<AppConfig, String>,
+) -> Vec<String> {
+    let mut problems: Vec<String> = unknown_config_keys(raw)
+        .into_iter()
+        .map(|key| format!("unknown key '{}' (move it under \"custom\" to keep it)", key))
+        .collect();
+    match parsed {
+        Ok(config) => problems.extend(config.problems()),
+        Err(e) => problems.push(e),
+    }
+    problems
+}
+
+/// `config validate` and `config import` error listing every problem
+fn problems_error(problems: &[String], source: &Path) -> String {
+    let mut error = format!("{} problem(s) in {}:", problems.len(), source.display());
+    for problem in problems {
+        error.push_str(&format!("\n  - {}", problem));
+    }
+    if problems.iter().any(|problem| problem.starts_with("unknown key")) {
+        error.push_str(&format!("\nValid keys: {}", CONFIG_KEYS.join(", ")));
+    }
+    error
+}
+
+/// File format for `config export`/`import`, picked by extension (JSON by default)
+#[derive(Debug, Clone, Copy)]
+enum ConfigFileFormat {
+    Json,
+    Toml,
+}
+
+impl ConfigFileFormat {
+    fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()).as_deref() {
+            Some("toml") => Ok(Self::Toml),
+            Some("yaml" | "yml") => Err("YAML config files are not supported; use a .json or .toml path".to_string()),
+            _ => Ok(Self::Json),
+        }
+    }
+}
+
+/// Put back secrets that a `config export --redact` file only has as `********`,
+/// taking them from the current config; masked secrets it doesn't have are dropped.
+/// Returns how many were kept
+fn restore_masked_secrets(imported: &mut AppConfig, current: &AppConfig) -> usize {
+    let mut kept = 0;
+    for (secrets, current) in [
+        (&mut imported.api_keys, &current.api_keys),
+        (&mut imported.http_headers, &current.http_headers),
+    ] {
+        secrets.retain(|name, value| {
+            if value != REDACTED_SECRET {
+                return true;
+            }
+            match current.get(name) {
+                Some(existing) => {
+                    *value = existing.clone();
+                    kept += 1;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+    kept
+}
+
+fn domains_label(domains: &[String]) -> String {
+    if domains.is_empty() {
+        "none".to_string()
+    } else {
+        domains.join(", ")
+    }
+}
+
+fn engines_label(engines: &[String]) -> String {
+    if engines.is_empty() {
+        "all".to_string()
+    } else {
+        engines.join(", ")
+    }
+}
+
+fn unknown_key_error(key: &str) -> String {
+    format!(
+        "Unknown configuration key: {}. Valid keys: {}",
+        key,
+        CONFIG_KEYS.join(", ")
+    )
+}
+
+/// Content quality thresholds for pages extracted from web search results
+#[derive(Debug, Clone)]
+pub struct ContentQualitySettings {
+    pub min_score: f64,
+    pub min_words: usize,
+    pub min_word_diversity: f64,
+    pub spam_phrases: Vec<String>,
+    pub language_check: bool,
+}
+
+/// How tool output over `max_tool_result_chars` is shortened before the model sees it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolResultStrategy {
+    /// Keep the beginning
+    #[default]
+    Head,
+    /// Keep the end, where logs and build output usually have the interesting part
+    Tail,
+    /// Ask the model for a summary, falling back to the head if that fails
+    Summarize,
+}
+
+impl std::str::FromStr for ToolResultStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "head" => Ok(ToolResultStrategy::Head),
+            "tail" => Ok(ToolResultStrategy::Tail),
+            "summarize" => Ok(ToolResultStrategy::Summarize),
+            _ => Err(format!(
+                "Unknown tool result strategy '{}': expected head, tail or summarize",
+                s
+            )),
+        }
+    }
+}
+
+/// Proxy and header settings for web search and scraping clients
+#[derive(Debug, Clone, Default)]
+pub struct WebRequestSettings {
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    pub headers: HashMap<String, String>,
+}
+
+fn default_ollama_retry_attempts() -> u32 {
+    3
+}
+
+fn default_ollama_retry_delay_ms() -> u64 {
+    500
+}
+
+fn default_parallel_tool_timeout() -> u64 {
+    300
+}
+
+fn default_parallel_retry_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_cpu_sample_interval_ms() -> u64 {
+    200
+}
+
+fn default_perf_test_max_requests() -> usize {
+    200
+}
+
+fn default_max_tool_result_chars() -> usize {
+    800
+}
+
+fn default_response_cache_ttl_hours() -> u64 {
+    24
+}
+
+fn default_thinking_open_tag() -> String {
+    ThinkingTags::default().open
+}
+
+fn default_thinking_close_tag() -> String {
+    ThinkingTags::default().close
+}
+
+fn default_max_backups_per_file() -> usize {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_command_timeout() -> u64 {
+    600
 }
 
 impl Default for AppConfig {
@@ -40,6 +551,54 @@ impl Default for AppConfig {
             system_prompt: None,
             enable_command_generation: true,
             enable_proactive_tool_mode: true,
+            enable_tools: true,
+            context_token_budget: None,
+            command_policy: CommandPolicyMode::Strict,
+            command_allowlist: Vec::new(),
+            command_denylist: Vec::new(),
+            command_timeout: default_command_timeout(),
+            confirm_file_writes: true,
+            non_interactive_permission: NonInteractivePermission::Deny,
+            max_backups_per_file: default_max_backups_per_file(),
+            ollama_retry_attempts: default_ollama_retry_attempts(),
+            ollama_retry_delay_ms: default_ollama_retry_delay_ms(),
+            keep_alive: None,
+            default_model: None,
+            parallel_tool_timeout: default_parallel_tool_timeout(),
+            parallel_tool_retries: 0,
+            parallel_retry_backoff_ms: default_parallel_retry_backoff_ms(),
+            http_proxy: None,
+            https_proxy: None,
+            http_headers: HashMap::new(),
+            search_rate_limits: HashMap::new(),
+            search_engines: Vec::new(),
+            search_allowed_domains: Vec::new(),
+            search_blocked_domains: Vec::new(),
+            model_profiles: HashMap::new(),
+            search_language: None,
+            search_region: None,
+            max_concurrent_scrapes: default_max_concurrent_scrapes(),
+            scrape_timeout_seconds: default_scrape_timeout_seconds(),
+            total_search_deadline_secs: default_total_search_deadline_secs(),
+            min_content_quality_score: default_min_content_quality_score(),
+            min_content_words: default_min_content_words(),
+            min_content_word_diversity: default_min_content_word_diversity(),
+            content_spam_phrases: default_content_spam_phrases(),
+            content_language_check: default_content_language_check(),
+            perf_test_max_requests: default_perf_test_max_requests(),
+            max_tool_result_chars: default_max_tool_result_chars(),
+            tool_result_strategy: ToolResultStrategy::Head,
+            file_search_threads: 0,
+            cpu_sample_interval_ms: default_cpu_sample_interval_ms(),
+            response_cache: false,
+            response_cache_ttl_hours: default_response_cache_ttl_hours(),
+            show_thinking: false,
+            thinking_open_tag: default_thinking_open_tag(),
+            thinking_close_tag: default_thinking_close_tag(),
+            keep_thinking_in_history: false,
+            pull_if_missing: false,
+            external_tools: HashMap::new(),
+            custom: HashMap::new(),
         }
     }
 }
@@ -59,15 +618,21 @@ impl ToolExecutor {
         key: &str,
         value: serde_json::Value,
     ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        // Never echo secrets back to the terminal
+        let shown_value = if key.starts_with("api_keys.") || key.starts_with("http_headers.") {
+            serde_json::Value::String("********".to_string())
+        } else {
+            value.clone()
+        };
         println!(
             "{} Setting configuration: {} = {:?}",
             "⚙️".cyan(),
             key.yellow(),
-            value
+            shown_value
         );
 
         let _config_path = self.get_config_path()?;
-        let mut config = self.load_config().await.unwrap_or_default();
+        let mut config = self.load_config().await?;
 
         match key {
             "auto_approve_safe" => {
@@ -122,116 +687,922 @@ impl ToolExecutor {
                     });
                 }
             }
-            "theme" => {
-                if let Some(val) = value.as_str() {
-                    config.theme = val.to_string();
-                } else {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("theme must be a string".to_string()),
-                        metadata: None,
+            "theme" => {
+                if let Some(val) = value.as_str() {
+                    config.theme = val.to_string();
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("theme must be a string".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "editor" => {
+                if let Some(val) = value.as_str() {
+                    config.editor = val.to_string();
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("editor must be a string".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "log_level" => {
+                if let Some(val) = value.as_str() {
+                    if LOG_LEVELS.contains(&val) {
+                        config.log_level = val.to_string();
+                    } else {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(format!("log_level must be one of: {}", LOG_LEVELS.join(", "))),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("log_level must be a string".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "backup_enabled" => {
+                if let Some(val) = value.as_bool() {
+                    config.backup_enabled = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("backup_enabled must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "system_prompt" => {
+                if let Some(val) = value.as_str() {
+                    config.system_prompt = Some(val.to_string());
+                } else if value.is_null() {
+                    config.system_prompt = None;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("system_prompt must be a string or null".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "enable_command_generation" => {
+                if let Some(val) = value.as_bool() {
+                    config.enable_command_generation = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("enable_command_generation must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "enable_proactive_tool_mode" => {
+                if let Some(val) = value.as_bool() {
+                    config.enable_proactive_tool_mode = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("enable_proactive_tool_mode must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "enable_tools" => {
+                if let Some(val) = value.as_bool() {
+                    config.enable_tools = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("enable_tools must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "context_token_budget" => {
+                if let Some(val) = value.as_u64() {
+                    config.context_token_budget = Some(val as u32);
+                } else if value.is_null() {
+                    config.context_token_budget = None;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("context_token_budget must be a number or null".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "command_policy" => {
+                match value.as_str().map(str::parse::<CommandPolicyMode>) {
+                    Some(Ok(mode)) => config.command_policy = mode,
+                    Some(Err(e)) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("command_policy must be a string".to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "non_interactive_permission" => {
+                match value.as_str().map(str::parse::<NonInteractivePermission>) {
+                    Some(Ok(default)) => config.non_interactive_permission = default,
+                    Some(Err(e)) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("non_interactive_permission must be a string".to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "confirm_file_writes" => {
+                if let Some(val) = value.as_bool() {
+                    config.confirm_file_writes = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("confirm_file_writes must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "max_backups_per_file" => {
+                if let Some(val) = value.as_u64().filter(|v| *v > 0) {
+                    config.max_backups_per_file = val as usize;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("max_backups_per_file must be a positive number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "ollama_retry_attempts" => {
+                if let Some(val) = value.as_u64().and_then(|v| u32::try_from(v).ok()) {
+                    config.ollama_retry_attempts = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("ollama_retry_attempts must be a number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "ollama_retry_delay_ms" => {
+                if let Some(val) = value.as_u64() {
+                    config.ollama_retry_delay_ms = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("ollama_retry_delay_ms must be a number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "keep_alive" => {
+                // An empty value clears the setting so Ollama's default applies again
+                let keep_alive = match &value {
+                    serde_json::Value::Null => None,
+                    serde_json::Value::String(text) if text.trim().is_empty() => None,
+                    serde_json::Value::String(text) => Some(text.clone()),
+                    serde_json::Value::Number(number) => Some(number.to_string()),
+                    _ => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("keep_alive must be a duration such as 5m or a number of seconds".to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                };
+                if let Some(Err(e)) = keep_alive.as_deref().map(parse_keep_alive) {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+                config.keep_alive = keep_alive.map(|k| k.trim().to_string());
+            }
+            "default_model" => {
+                // An empty value clears the setting so the model picker is shown again
+                match &value {
+                    serde_json::Value::Null => config.default_model = None,
+                    serde_json::Value::String(name) if name.trim().is_empty() => config.default_model = None,
+                    serde_json::Value::String(name) => config.default_model = Some(name.trim().to_string()),
+                    _ => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("default_model must be a model name".to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "parallel_tool_timeout" => {
+                if let Some(val) = value.as_u64() {
+                    config.parallel_tool_timeout = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("parallel_tool_timeout must be a number of seconds (0 disables it)".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "parallel_tool_retries" => {
+                if let Some(val) = value.as_u64().and_then(|v| u32::try_from(v).ok()) {
+                    config.parallel_tool_retries = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("parallel_tool_retries must be a number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "parallel_retry_backoff_ms" => {
+                if let Some(val) = value.as_u64() {
+                    config.parallel_retry_backoff_ms = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("parallel_retry_backoff_ms must be a number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "max_concurrent_scrapes" => {
+                if let Some(val) = value.as_u64().filter(|v| *v > 0) {
+                    config.max_concurrent_scrapes = val as usize;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("max_concurrent_scrapes must be a positive number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "perf_test_max_requests" => {
+                if let Some(val) = value.as_u64().filter(|v| *v > 0) {
+                    config.perf_test_max_requests = val as usize;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("perf_test_max_requests must be a positive number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "max_tool_result_chars" => {
+                if let Some(val) = value.as_u64().filter(|v| *v > 0) {
+                    config.max_tool_result_chars = val as usize;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("max_tool_result_chars must be a positive number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "tool_result_strategy" => {
+                match value.as_str().map(str::parse::<ToolResultStrategy>) {
+                    Some(Ok(strategy)) => config.tool_result_strategy = strategy,
+                    Some(Err(e)) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("tool_result_strategy must be a string".to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "cpu_sample_interval_ms" => {
+                if let Some(val) = value.as_u64().filter(|v| *v > 0) {
+                    config.cpu_sample_interval_ms = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("cpu_sample_interval_ms must be a positive number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "response_cache" => {
+                if let Some(val) = value.as_bool() {
+                    config.response_cache = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("response_cache must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "response_cache_ttl_hours" => {
+                if let Some(val) = value.as_u64().filter(|v| *v > 0) {
+                    config.response_cache_ttl_hours = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("response_cache_ttl_hours must be a positive number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "show_thinking" => {
+                if let Some(val) = value.as_bool() {
+                    config.show_thinking = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("show_thinking must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "thinking_open_tag" => {
+                if let Some(val) = value.as_str() {
+                    config.thinking_open_tag = val.to_string();
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("thinking_open_tag must be a string".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "thinking_close_tag" => {
+                if let Some(val) = value.as_str() {
+                    config.thinking_close_tag = val.to_string();
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("thinking_close_tag must be a string".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "keep_thinking_in_history" => {
+                if let Some(val) = value.as_bool() {
+                    config.keep_thinking_in_history = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("keep_thinking_in_history must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "pull_if_missing" => {
+                if let Some(val) = value.as_bool() {
+                    config.pull_if_missing = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("pull_if_missing must be a boolean".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "scrape_timeout_seconds" => {
+                if let Some(val) = value.as_u64() {
+                    config.scrape_timeout_seconds = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("scrape_timeout_seconds must be a number of seconds (0 disables it)".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "total_search_deadline_secs" => {
+                if let Some(val) = value.as_u64() {
+                    config.total_search_deadline_secs = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("total_search_deadline_secs must be a number of seconds (0 waits for every engine)".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "min_content_quality_score" | "min_content_word_diversity" => {
+                match value.as_f64().filter(|v| (0.0..=1.0).contains(v)) {
+                    Some(val) if key == "min_content_quality_score" => config.min_content_quality_score = val,
+                    Some(val) => config.min_content_word_diversity = val,
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(format!("{} must be a number between 0 and 1", key)),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "min_content_words" => {
+                if let Some(val) = value.as_u64() {
+                    config.min_content_words = val as usize;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("min_content_words must be a number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "content_spam_phrases" => {
+                // Accept a JSON array or a comma-separated string; an empty value disables the check
+                let phrases: Option<Vec<String>> = if let Some(list) = value.as_array() {
+                    list.iter().map(|v| v.as_str().map(str::to_string)).collect()
+                } else {
+                    value.as_str().map(|list| {
+                        list.split(',')
+                            .map(|phrase| phrase.trim().to_string())
+                            .filter(|phrase| !phrase.is_empty())
+                            .collect()
+                    })
+                };
+
+                match phrases {
+                    Some(phrases) => config.content_spam_phrases = phrases,
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("content_spam_phrases must be a list of strings".to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "content_language_check" => {
+                if let Some(val) = value.as_bool() {
+                    config.content_language_check = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("content_language_check must be true or false".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "file_search_threads" => {
+                if let Some(val) = value.as_u64() {
+                    config.file_search_threads = val as usize;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("file_search_threads must be a number of threads (0 uses one per CPU)".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "command_timeout" => {
+                if let Some(val) = value.as_u64() {
+                    config.command_timeout = val;
+                } else {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("command_timeout must be a number".to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+            }
+            "command_allowlist" | "command_denylist" => {
+                // Accept a JSON array or a comma-separated string
+                let entries: Option<Vec<String>> = if let Some(list) = value.as_array() {
+                    list.iter().map(|v| v.as_str().map(str::to_string)).collect()
+                } else {
+                    value.as_str().map(|list| {
+                        list.split(',')
+                            .map(|entry| entry.trim().to_string())
+                            .filter(|entry| !entry.is_empty())
+                            .collect()
+                    })
+                };
+
+                match entries {
+                    Some(entries) if key == "command_allowlist" => config.command_allowlist = entries,
+                    Some(entries) => config.command_denylist = entries,
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(format!("{} must be a list of strings", key)),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "http_proxy" | "https_proxy" => {
+                // An empty value clears the setting so the environment variables apply again
+                let proxy = match &value {
+                    serde_json::Value::Null => None,
+                    serde_json::Value::String(proxy) if proxy.trim().is_empty() => None,
+                    serde_json::Value::String(proxy) => Some(proxy.trim().to_string()),
+                    _ => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(format!("{} must be a URL string", key)),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                };
+
+                let (http, https) = if key == "http_proxy" {
+                    (proxy.as_deref(), None)
+                } else {
+                    (None, proxy.as_deref())
+                };
+                if let Err(e) = configure_web_client(reqwest::Client::builder(), http, https, &HashMap::new()) {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e.to_string()),
+                        metadata: None,
+            web_search_result: None,
+                    });
+                }
+
+                if key == "http_proxy" {
+                    config.http_proxy = proxy;
+                } else {
+                    config.https_proxy = proxy;
+                }
+            }
+            key if key.starts_with("http_headers.") => {
+                // e.g. http_headers.Authorization; an empty value removes the header
+                let name = key.trim_start_matches("http_headers.");
+                let header_value = value.as_str().map(str::to_string);
+
+                if let Some(header_value) = header_value.as_deref().filter(|v| !v.is_empty()) {
+                    let headers = HashMap::from([(name.to_string(), header_value.to_string())]);
+                    if let Err(e) = configure_web_client(reqwest::Client::builder(), None, None, &headers) {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e.to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+
+                match header_value {
+                    Some(header_value) if header_value.is_empty() => {
+                        config.http_headers.remove(name);
+                    }
+                    Some(header_value) if !name.is_empty() => {
+                        config.http_headers.insert(name.to_string(), header_value);
+                    }
+                    _ => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("http_headers.<name> must be a string".to_string()),
+                            metadata: None,
+            web_search_result: None,
+                        });
+                    }
+                }
+            }
+            "search_language" | "search_region" => {
+                // An empty value goes back to the default (English, no region)
+                let code = match &value {
+                    serde_json::Value::Null => Ok(None),
+                    serde_json::Value::String(code) if code.trim().is_empty() => Ok(None),
+                    serde_json::Value::String(code) if key == "search_language" => parse_language_code(code).map(Some),
+                    serde_json::Value::String(code) => parse_region_code(code).map(Some),
+                    _ => Err(format!("{} must be a string", key)),
+                };
+                match code {
+                    Ok(code) if key == "search_language" => config.search_language = code,
+                    Ok(code) => config.search_region = code,
+                    Err(e) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e),
+                            metadata: None,
             web_search_result: None,
-                    });
+                        });
+                    }
                 }
             }
-            "editor" => {
-                if let Some(val) = value.as_str() {
-                    config.editor = val.to_string();
+            "search_engines" | "search.engines" => {
+                // A JSON array or a comma-separated string of engine ids; empty enables every engine
+                let names: Option<Vec<String>> = if let Some(list) = value.as_array() {
+                    list.iter().map(|v| v.as_str().map(str::to_string)).collect()
+                } else if value.is_null() {
+                    Some(Vec::new())
                 } else {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("editor must be a string".to_string()),
-                        metadata: None,
+                    value.as_str().map(|list| {
+                        list.split(',')
+                            .map(|name| name.trim().to_string())
+                            .filter(|name| !name.is_empty())
+                            .collect()
+                    })
+                };
+                let engines = match names {
+                    Some(names) => names.iter().map(|name| parse_engine_id(name)).collect::<Result<Vec<_>, _>>(),
+                    None => Err("search_engines must be a list of engine names".to_string()),
+                };
+
+                match engines {
+                    Ok(mut engines) => {
+                        let mut seen = std::collections::HashSet::new();
+                        engines.retain(|engine| seen.insert(engine.clone()));
+                        config.search_engines = engines;
+                    }
+                    Err(e) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e),
+                            metadata: None,
             web_search_result: None,
-                    });
+                        });
+                    }
                 }
             }
-            "log_level" => {
-                if let Some(val) = value.as_str() {
-                    if ["debug", "info", "warn", "error"].contains(&val) {
-                        config.log_level = val.to_string();
-                    } else {
+            "search_allowed_domains" | "search_blocked_domains" => {
+                // A JSON array or a comma-separated string of domains; empty clears the list
+                let entries: Option<Vec<String>> = if let Some(list) = value.as_array() {
+                    list.iter().map(|v| v.as_str().map(str::to_string)).collect()
+                } else if value.is_null() {
+                    Some(Vec::new())
+                } else {
+                    value.as_str().map(|list| {
+                        list.split(',')
+                            .map(|domain| domain.trim().to_string())
+                            .filter(|domain| !domain.is_empty())
+                            .collect()
+                    })
+                };
+                let domains = match entries {
+                    Some(entries) => entries.iter().map(|entry| parse_domain(entry)).collect::<Result<Vec<_>, _>>(),
+                    None => Err(format!("{} must be a list of domains", key)),
+                };
+
+                match domains {
+                    Ok(mut domains) => {
+                        let mut seen = std::collections::HashSet::new();
+                        domains.retain(|domain| seen.insert(domain.clone()));
+                        if key == "search_allowed_domains" {
+                            config.search_allowed_domains = domains;
+                        } else {
+                            config.search_blocked_domains = domains;
+                        }
+                    }
+                    Err(e) => {
                         return Ok(ToolResult {
                             success: false,
                             output: String::new(),
-                            error: Some(
-                                "log_level must be one of: debug, info, warn, error".to_string(),
-                            ),
+                            error: Some(e),
                             metadata: None,
             web_search_result: None,
                         });
                     }
-                } else {
+                }
+            }
+            key if key.starts_with("search_rate_limits.") => {
+                // e.g. search_rate_limits.duckduckgo = {"capacity": 2, "refill_per_second": 0.5};
+                // null or an empty value restores the engine's default
+                let engine = key.trim_start_matches("search_rate_limits.");
+                if !SEARCH_ENGINE_IDS.contains(&engine) {
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
-                        error: Some("log_level must be a string".to_string()),
+                        error: Some(format!(
+                            "Unknown search engine '{}' (expected one of: {})",
+                            engine,
+                            SEARCH_ENGINE_IDS.join(", ")
+                        )),
                         metadata: None,
             web_search_result: None,
                     });
                 }
-            }
-            "backup_enabled" => {
-                if let Some(val) = value.as_bool() {
-                    config.backup_enabled = val;
+
+                if value.is_null() || value.as_str() == Some("") {
+                    config.search_rate_limits.remove(engine);
                 } else {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("backup_enabled must be a boolean".to_string()),
-                        metadata: None,
+                    match serde_json::from_value::<RateLimit>(value.clone()) {
+                        Ok(limit) if limit.capacity >= 1 && limit.refill_per_second > 0.0 => {
+                            config.search_rate_limits.insert(engine.to_string(), limit);
+                        }
+                        _ => {
+                            return Ok(ToolResult {
+                                success: false,
+                                output: String::new(),
+                                error: Some(
+                                    "search_rate_limits.<engine> must be {\"capacity\": <1 or more>, \"refill_per_second\": <above 0>}"
+                                        .to_string(),
+                                ),
+                                metadata: None,
             web_search_result: None,
-                    });
+                            });
+                        }
+                    }
                 }
             }
-            "system_prompt" => {
-                if let Some(val) = value.as_str() {
-                    config.system_prompt = Some(val.to_string());
-                } else if value.is_null() {
-                    config.system_prompt = None;
-                } else {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("system_prompt must be a string or null".to_string()),
-                        metadata: None,
+            key if key.starts_with("api_keys.") => {
+                // e.g. api_keys.brave_api_key; an empty value removes the key
+                let name = key.trim_start_matches("api_keys.");
+                let secret = value
+                    .as_str()
+                    .map(str::to_string)
+                    .or_else(|| value.is_number().then(|| value.to_string()));
+
+                match secret {
+                    Some(secret) if secret.is_empty() => {
+                        config.api_keys.remove(name);
+                    }
+                    Some(secret) if !name.is_empty() => {
+                        config.api_keys.insert(name.to_string(), secret);
+                    }
+                    _ => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("api_keys.<name> must be a string".to_string()),
+                            metadata: None,
             web_search_result: None,
-                    });
+                        });
+                    }
                 }
             }
-            "enable_command_generation" => {
-                if let Some(val) = value.as_bool() {
-                    config.enable_command_generation = val;
+            key if key.starts_with("external_tools.") => {
+                // e.g. external_tools.lint = {"description": ..., "executable": ..., "args": [...], "input_schema": {...}};
+                // null or an empty value removes the tool
+                let name = key.trim_start_matches("external_tools.");
+                if value.is_null() || value.as_str() == Some("") {
+                    config.external_tools.remove(name);
                 } else {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("enable_command_generation must be a boolean".to_string()),
-                        metadata: None,
+                    let spec = serde_json::from_value::<ExternalToolSpec>(value.clone())
+                        .map_err(|e| e.to_string())
+                        .and_then(|spec| check_spec(name, &spec).map(|_| spec));
+                    match spec {
+                        Ok(spec) => {
+                            config.external_tools.insert(name.to_string(), spec);
+                        }
+                        Err(e) => {
+                            return Ok(ToolResult {
+                                success: false,
+                                output: String::new(),
+                                error: Some(format!(
+                                    "external_tools.<name> must be {{\"description\", \"executable\", \"args\", \"input_schema\"}}: {}",
+                                    e
+                                )),
+                                metadata: None,
             web_search_result: None,
-                    });
+                            });
+                        }
+                    }
                 }
             }
-            "enable_proactive_tool_mode" => {
-                if let Some(val) = value.as_bool() {
-                    config.enable_proactive_tool_mode = val;
-                } else {
+            key if key.starts_with("custom.") => {
+                // Any JSON value; null removes the entry
+                let name = key.trim_start_matches("custom.");
+                if name.is_empty() {
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
-                        error: Some("enable_proactive_tool_mode must be a boolean".to_string()),
+                        error: Some("custom.<name> needs a name".to_string()),
                         metadata: None,
             web_search_result: None,
                     });
                 }
+                if value.is_null() {
+                    config.custom.remove(name);
+                } else {
+                    config.custom.insert(name.to_string(), value.clone());
+                }
             }
             _ => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("Unknown configuration key: {}", key)),
+                    error: Some(unknown_key_error(key)),
                     metadata: None,
             web_search_result: None,
                 });
@@ -242,9 +1613,9 @@ impl ToolExecutor {
 
         Ok(ToolResult {
             success: true,
-            output: format!("Configuration updated: {} = {:?}", key, value),
+            output: format!("Configuration updated: {} = {:?}", key, shown_value),
             error: None,
-            metadata: Some(serde_json::to_value(&config)?),
+            metadata: Some(serde_json::to_value(config.redacted())?),
             web_search_result: None,
         })
     }
@@ -255,7 +1626,7 @@ impl ToolExecutor {
     ) -> Result<ToolResult, Box<dyn std::error::Error>> {
         println!("{} Getting configuration", "📋".cyan());
 
-        let config = self.load_config().await.unwrap_or_default();
+        let config = self.load_config().await?;
 
         let output = match key {
             Some("auto_approve_safe") => format!("auto_approve_safe: {}", config.auto_approve_safe),
@@ -271,11 +1642,114 @@ impl ToolExecutor {
             Some("system_prompt") => format!("system_prompt: {}", config.system_prompt.as_deref().unwrap_or("None")),
             Some("enable_command_generation") => format!("enable_command_generation: {}", config.enable_command_generation),
             Some("enable_proactive_tool_mode") => format!("enable_proactive_tool_mode: {}", config.enable_proactive_tool_mode),
+            Some("enable_tools") => format!("enable_tools: {}", config.enable_tools),
+            Some("command_policy") => format!("command_policy: {:?}", config.command_policy),
+            Some("confirm_file_writes") => format!("confirm_file_writes: {}", config.confirm_file_writes),
+            Some("non_interactive_permission") => {
+                format!("non_interactive_permission: {:?}", config.non_interactive_permission)
+            }
+            Some("max_backups_per_file") => format!("max_backups_per_file: {}", config.max_backups_per_file),
+            Some("command_timeout") => format!("command_timeout: {}", config.command_timeout),
+            Some("ollama_retry_attempts") => format!("ollama_retry_attempts: {}", config.ollama_retry_attempts),
+            Some("ollama_retry_delay_ms") => format!("ollama_retry_delay_ms: {}", config.ollama_retry_delay_ms),
+            Some("keep_alive") => format!("keep_alive: {}", config.keep_alive.as_deref().unwrap_or("ollama default")),
+            Some("default_model") => format!("default_model: {}", config.default_model.as_deref().unwrap_or("None")),
+            Some("parallel_tool_timeout") => format!("parallel_tool_timeout: {}", config.parallel_tool_timeout),
+            Some("parallel_tool_retries") => format!("parallel_tool_retries: {}", config.parallel_tool_retries),
+            Some("parallel_retry_backoff_ms") => format!("parallel_retry_backoff_ms: {}", config.parallel_retry_backoff_ms),
+            Some("command_allowlist") => format!("command_allowlist: {}", config.command_allowlist.join(", ")),
+            Some("command_denylist") => format!("command_denylist: {}", config.command_denylist.join(", ")),
+            Some("context_token_budget") => format!(
+                "context_token_budget: {}",
+                config.context_token_budget.map_or("model default".to_string(), |b| b.to_string())
+            ),
+            Some("http_proxy") => format!("http_proxy: {}", config.http_proxy.as_deref().unwrap_or("from environment")),
+            Some("https_proxy") => format!("https_proxy: {}", config.https_proxy.as_deref().unwrap_or("from environment")),
+            Some("http_headers") => {
+                let mut names: Vec<_> = config.http_headers.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                format!("http_headers: {}", if names.is_empty() { "none".to_string() } else { names.join(", ") })
+            }
+            Some("search_language") => format!("search_language: {}", config.search_language.as_deref().unwrap_or("en")),
+            Some("search_region") => format!("search_region: {}", config.search_region.as_deref().unwrap_or("none")),
+            Some("search_engines" | "search.engines") => format!("search_engines: {}", engines_label(&config.search_engines)),
+            Some("search_allowed_domains") => format!("search_allowed_domains: {}", domains_label(&config.search_allowed_domains)),
+            Some("search_blocked_domains") => format!("search_blocked_domains: {}", domains_label(&config.search_blocked_domains)),
+            Some("max_concurrent_scrapes") => format!("max_concurrent_scrapes: {}", config.max_concurrent_scrapes),
+            Some("scrape_timeout_seconds") => format!("scrape_timeout_seconds: {}", config.scrape_timeout_seconds),
+            Some("total_search_deadline_secs") => format!("total_search_deadline_secs: {}", config.total_search_deadline_secs),
+            Some("min_content_quality_score") => format!("min_content_quality_score: {}", config.min_content_quality_score),
+            Some("min_content_words") => format!("min_content_words: {}", config.min_content_words),
+            Some("min_content_word_diversity") => format!("min_content_word_diversity: {}", config.min_content_word_diversity),
+            Some("content_spam_phrases") => format!("content_spam_phrases: {}", config.content_spam_phrases.join(", ")),
+            Some("content_language_check") => format!("content_language_check: {}", config.content_language_check),
+            Some("perf_test_max_requests") => format!("perf_test_max_requests: {}", config.perf_test_max_requests),
+            Some("max_tool_result_chars") => format!("max_tool_result_chars: {}", config.max_tool_result_chars),
+            Some("tool_result_strategy") => format!("tool_result_strategy: {:?}", config.tool_result_strategy),
+            Some("file_search_threads") => format!("file_search_threads: {}", config.file_search_threads),
+            Some("cpu_sample_interval_ms") => format!("cpu_sample_interval_ms: {}", config.cpu_sample_interval_ms),
+            Some("response_cache") => format!("response_cache: {}", config.response_cache),
+            Some("response_cache_ttl_hours") => format!("response_cache_ttl_hours: {}", config.response_cache_ttl_hours),
+            Some("show_thinking") => format!("show_thinking: {}", config.show_thinking),
+            Some("thinking_open_tag") => format!("thinking_open_tag: {}", config.thinking_open_tag),
+            Some("thinking_close_tag") => format!("thinking_close_tag: {}", config.thinking_close_tag),
+            Some("keep_thinking_in_history") => format!("keep_thinking_in_history: {}", config.keep_thinking_in_history),
+            Some("pull_if_missing") => format!("pull_if_missing: {}", config.pull_if_missing),
+            Some("search_rate_limits") => {
+                let mut limits: Vec<_> = config
+                    .search_rate_limits
+                    .iter()
+                    .map(|(engine, limit)| format!("{} ({} burst, {}/s)", engine, limit.capacity, limit.refill_per_second))
+                    .collect();
+                limits.sort_unstable();
+                format!("search_rate_limits: {}", if limits.is_empty() { "defaults".to_string() } else { limits.join(", ") })
+            }
+            Some(key) if key.starts_with("search_rate_limits.") => format!(
+                "{}: {}",
+                key,
+                config.search_rate_limits.get(key.trim_start_matches("search_rate_limits.")).map_or(
+                    "default".to_string(),
+                    |limit| format!("{} burst, {}/s", limit.capacity, limit.refill_per_second)
+                )
+            ),
+            Some("api_keys") => {
+                let mut names: Vec<_> = config.api_keys.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                format!("api_keys: {}", if names.is_empty() { "none".to_string() } else { names.join(", ") })
+            }
+            Some("external_tools") => {
+                let mut names: Vec<_> = config.external_tools.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                format!("external_tools: {}", if names.is_empty() { "none".to_string() } else { names.join(", ") })
+            }
+            Some(key) if key.starts_with("external_tools.") => format!(
+                "{}: {}",
+                key,
+                config.external_tools.get(key.trim_start_matches("external_tools.")).map_or(
+                    "not set".to_string(),
+                    |spec| format!("{} {} - {}", spec.executable, spec.args.join(" "), spec.description)
+                )
+            ),
+            Some("custom") => {
+                let mut names: Vec<_> = config.custom.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                format!("custom: {}", if names.is_empty() { "none".to_string() } else { names.join(", ") })
+            }
+            Some(key) if key.starts_with("custom.") => format!(
+                "{}: {}",
+                key,
+                config.custom.get(key.trim_start_matches("custom.")).map_or("not set".to_string(), |v| v.to_string())
+            ),
+            Some(key) if key.starts_with("api_keys.") => format!(
+                "{}: {}",
+                key,
+                if config.api_keys.contains_key(key.trim_start_matches("api_keys.")) { "configured" } else { "not set" }
+            ),
             Some(unknown_key) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("Unknown configuration key: {}", unknown_key)),
+                    error: Some(unknown_key_error(unknown_key)),
                     metadata: None,
             web_search_result: None,
                 });
@@ -294,9 +1768,56 @@ impl ToolExecutor {
                     system_prompt: {}\n\
                     enable_command_generation: {}\n\
                     enable_proactive_tool_mode: {}\n\
+                    enable_tools: {}\n\
+                    context_token_budget: {}\n\
+                    command_policy: {:?}\n\
+                    command_allowlist: {}\n\
+                    command_denylist: {}\n\
+                    command_timeout: {} seconds\n\
+                    confirm_file_writes: {}\n\
+                    non_interactive_permission: {:?}\n\
+                    max_backups_per_file: {}\n\
+                    ollama_retry_attempts: {}\n\
+                    ollama_retry_delay_ms: {} ms\n\
+                    keep_alive: {}\n\
+                    default_model: {}\n\
+                    parallel_tool_timeout: {} seconds\n\
+                    parallel_tool_retries: {}\n\
+                    parallel_retry_backoff_ms: {} ms\n\
+                    http_proxy: {}\n\
+                    https_proxy: {}\n\
+                    http_headers: {} configured\n\
+                    search_language: {}\n\
+                    search_region: {}\n\
+                    search_rate_limits: {} overridden\n\
+                    search_engines: {}\n\
+                    search_allowed_domains: {}\n\
+                    search_blocked_domains: {}\n\
+                    max_concurrent_scrapes: {}\n\
+                    scrape_timeout_seconds: {} seconds\n\
+                    total_search_deadline_secs: {} seconds\n\
+                    min_content_quality_score: {}\n\
+                    min_content_words: {}\n\
+                    min_content_word_diversity: {}\n\
+                    content_spam_phrases: {}\n\
+                    content_language_check: {}\n\
+                    perf_test_max_requests: {}\n\
+                    max_tool_result_chars: {}\n\
+                    tool_result_strategy: {:?}\n\
+                    file_search_threads: {}\n\
+                    cpu_sample_interval_ms: {} ms\n\
+                    response_cache: {}\n\
+                    response_cache_ttl_hours: {} hours\n\
+                    show_thinking: {}\n\
+                    thinking_open_tag: {}\n\
+                    thinking_close_tag: {}\n\
+                    keep_thinking_in_history: {}\n\
+                    pull_if_missing: {}\n\
                     database_connections: {} configured\n\
                     api_keys: {} configured\n\
-                    custom_commands: {} configured",
+                    custom_commands: {} configured\n\
+                    external_tools: {} configured\n\
+                    custom: {} configured",
                     config.auto_approve_safe,
                     config.max_file_size,
                     config.default_timeout,
@@ -308,9 +1829,56 @@ impl ToolExecutor {
                     config.system_prompt.as_deref().unwrap_or("None"),
                     config.enable_command_generation,
                     config.enable_proactive_tool_mode,
+                    config.enable_tools,
+                    config.context_token_budget.map_or("model default".to_string(), |b| b.to_string()),
+                    config.command_policy,
+                    config.command_allowlist.join(", "),
+                    config.command_denylist.join(", "),
+                    config.command_timeout,
+                    config.confirm_file_writes,
+                    config.non_interactive_permission,
+                    config.max_backups_per_file,
+                    config.ollama_retry_attempts,
+                    config.ollama_retry_delay_ms,
+                    config.keep_alive.as_deref().unwrap_or("ollama default"),
+                    config.default_model.as_deref().unwrap_or("None"),
+                    config.parallel_tool_timeout,
+                    config.parallel_tool_retries,
+                    config.parallel_retry_backoff_ms,
+                    config.http_proxy.as_deref().unwrap_or("from environment"),
+                    config.https_proxy.as_deref().unwrap_or("from environment"),
+                    config.http_headers.len(),
+                    config.search_language.as_deref().unwrap_or("en"),
+                    config.search_region.as_deref().unwrap_or("none"),
+                    config.search_rate_limits.len(),
+                    engines_label(&config.search_engines),
+                    domains_label(&config.search_allowed_domains),
+                    domains_label(&config.search_blocked_domains),
+                    config.max_concurrent_scrapes,
+                    config.scrape_timeout_seconds,
+                    config.total_search_deadline_secs,
+                    config.min_content_quality_score,
+                    config.min_content_words,
+                    config.min_content_word_diversity,
+                    config.content_spam_phrases.join(", "),
+                    config.content_language_check,
+                    config.perf_test_max_requests,
+                    config.max_tool_result_chars,
+                    config.tool_result_strategy,
+                    config.file_search_threads,
+                    config.cpu_sample_interval_ms,
+                    config.response_cache,
+                    config.response_cache_ttl_hours,
+                    config.show_thinking,
+                    config.thinking_open_tag,
+                    config.thinking_close_tag,
+                    config.keep_thinking_in_history,
+                    config.pull_if_missing,
                     config.database_connections.len(),
                     config.api_keys.len(),
-                    config.custom_commands.len()
+                    config.custom_commands.len(),
+                    config.external_tools.len(),
+                    config.custom.len()
                 )
             }
         };
@@ -319,7 +1887,172 @@ impl ToolExecutor {
             success: true,
             output,
             error: None,
-            metadata: Some(serde_json::to_value(&config)?),
+            metadata: Some(serde_json::to_value(config.redacted())?),
+            web_search_result: None,
+        })
+    }
+
+    /// Check the config file against the schema: JSON syntax, unknown keys,
+    /// value types and the same constraints `config set` enforces
+    pub async fn validate_config(&self) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let config_path = self.get_config_path()?;
+        println!("{} Validating configuration: {}", "🔎".cyan(), config_path.display());
+
+        if !config_path.exists() {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("No configuration file at {}; defaults are in use", config_path.display()),
+                error: None,
+                metadata: Some(serde_json::json!({ "path": config_path, "problems": [] })),
+                web_search_result: None,
+            });
+        }
+
+        let content = fs::read_to_string(&config_path)?;
+        let problems = match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(serde_json::Value::Object(raw)) => {
+                config_problems(&raw, serde_json::from_str::<AppConfig>(&content).map_err(|e| e.to_string()))
+            }
+            Ok(_) => vec!["the configuration must be a JSON object".to_string()],
+            Err(e) => vec![format!("invalid JSON: {}", e)],
+        };
+
+        let metadata = serde_json::json!({ "path": config_path, "problems": problems });
+        if problems.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("✅ {} is valid", config_path.display()),
+                error: None,
+                metadata: Some(metadata),
+                web_search_result: None,
+            });
+        }
+
+        Ok(ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(problems_error(&problems, &config_path)),
+            metadata: Some(metadata),
+            web_search_result: None,
+        })
+    }
+
+    /// Write the full typed configuration, defaults included, to a JSON or TOML file
+    pub async fn export_config(&self, path: &str, redact: bool) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Exporting configuration to: {}", "📤".cyan(), path.yellow());
+
+        let format = match ConfigFileFormat::from_path(Path::new(path)) {
+            Ok(format) => format,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e),
+                    metadata: None,
+                    web_search_result: None,
+                })
+            }
+        };
+
+        let config = self.read_config()?;
+        let secrets = config.api_keys.len() + config.http_headers.len();
+        let config = if redact { config.redacted() } else { config };
+        let content = match format {
+            ConfigFileFormat::Json => serde_json::to_string_pretty(&config)?,
+            ConfigFileFormat::Toml => toml::to_string_pretty(&config)?,
+        };
+        fs::write(path, content)?;
+
+        let mut output = format!("✅ Configuration exported to: {}", path);
+        if secrets > 0 && !redact {
+            output.push_str(&format!(
+                "\n{} Includes {} API key(s) and HTTP header value(s); pass --redact to mask them before sharing",
+                "⚠".yellow(),
+                secrets
+            ));
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            metadata: Some(serde_json::json!({
+                "path": path,
+                "format": format!("{:?}", format).to_lowercase(),
+                "redacted": redact,
+            })),
+            web_search_result: None,
+        })
+    }
+
+    /// Replace the configuration with a JSON or TOML file after checking it the way
+    /// `config validate` does; the previous file is kept as `config.json.bak`
+    pub async fn import_config(&self, path: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Importing configuration from: {}", "📥".cyan(), path.yellow());
+
+        let source = Path::new(path);
+        let failure = |error: String, problems: &[String]| ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            metadata: Some(serde_json::json!({ "path": path, "problems": problems })),
+            web_search_result: None,
+        };
+
+        let format = match ConfigFileFormat::from_path(source) {
+            Ok(format) => format,
+            Err(e) => return Ok(failure(e, &[])),
+        };
+        if !source.exists() {
+            return Ok(failure(format!("File not found: {}", path), &[]));
+        }
+
+        let content = fs::read_to_string(source)?;
+        let raw = match format {
+            ConfigFileFormat::Json => serde_json::from_str::<serde_json::Value>(&content)
+                .map_err(|e| format!("invalid JSON in {}: {}", path, e)),
+            ConfigFileFormat::Toml => toml::from_str::<serde_json::Value>(&content)
+                .map_err(|e| format!("invalid TOML in {}: {}", path, e)),
+        };
+        let raw = match raw {
+            Ok(raw) => raw,
+            Err(e) => return Ok(failure(e, &[])),
+        };
+        let Some(raw_map) = raw.as_object() else {
+            return Ok(failure(format!("{} must contain a mapping of settings", path), &[]));
+        };
+
+        let parsed = serde_json::from_value::<AppConfig>(raw.clone()).map_err(|e| e.to_string());
+        let problems = config_problems(raw_map, parsed.clone());
+        if !problems.is_empty() {
+            return Ok(failure(problems_error(&problems, source), &problems));
+        }
+        let mut config = parsed?;
+
+        let current = self.read_config().unwrap_or_default();
+        let kept_secrets = restore_masked_secrets(&mut config, &current);
+
+        let config_path = self.get_config_path()?;
+        if config_path.exists() {
+            fs::copy(&config_path, config_path.with_extension("json.bak"))?;
+        }
+        self.save_config(&config).await?;
+
+        let mut output = format!("✅ Configuration imported from {} into {}", path, config_path.display());
+        if kept_secrets > 0 {
+            output.push_str(&format!(
+                "\nKept {} masked secret(s) from the previous configuration",
+                kept_secrets
+            ));
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            metadata: Some(serde_json::json!({
+                "path": path,
+                "config_path": config_path,
+                "kept_secrets": kept_secrets,
+            })),
             web_search_result: None,
         })
     }
@@ -625,8 +2358,7 @@ impl ToolExecutor {
     }
 
     fn get_data_dir(&self) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
-        let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-        let data_dir = home_dir.join(".ollama_agent");
+        let data_dir = crate::paths::config_dir().ok_or("Could not find home directory")?;
 
         if !data_dir.exists() {
             fs::create_dir_all(&data_dir)?;
@@ -635,19 +2367,50 @@ impl ToolExecutor {
         Ok(data_dir)
     }
 
-    async fn load_config(&self) -> Result<AppConfig, Box<dyn std::error::Error>> {
+    pub(super) async fn load_config(&self) -> Result<AppConfig, Box<dyn std::error::Error>> {
+        self.read_config()
+    }
+
+    // Synchronous variant for callers outside an async context (e.g. file writes)
+    pub(super) fn read_config(&self) -> Result<AppConfig, Box<dyn std::error::Error>> {
         let config_path = self.get_config_path()?;
 
         if config_path.exists() {
-            let content = fs::read_to_string(config_path)?;
-            let config: AppConfig = serde_json::from_str(&content)?;
+            let content = fs::read_to_string(&config_path)?;
+            let config: AppConfig = serde_json::from_str(&content).map_err(|e| {
+                AgentError::Config(format!(
+                    "Invalid configuration in {}: {} (run `config validate` for details)",
+                    config_path.display(),
+                    e
+                ))
+            })?;
+
+            // Warn once per process; unknown keys are dropped on the next save
+            static WARNED: Once = Once::new();
+            WARNED.call_once(|| {
+                let unknown = serde_json::from_str::<serde_json::Value>(&content)
+                    .ok()
+                    .and_then(|raw| raw.as_object().map(unknown_config_keys))
+                    .unwrap_or_default();
+                for key in unknown {
+                    eprintln!(
+                        "{} Ignoring unknown configuration key '{}' in {} (move it under \"custom\" to keep it)",
+                        "⚠️".yellow(),
+                        key,
+                        config_path.display()
+                    );
+                }
+                for problem in config.problems() {
+                    eprintln!("{} Configuration: {}", "⚠️".yellow(), problem);
+                }
+            });
             Ok(config)
         } else {
             Ok(AppConfig::default())
         }
     }
 
-    async fn save_config(&self, config: &AppConfig) -> Result<(), Box<dyn std::error::Error>> {
+    pub(super) async fn save_config(&self, config: &AppConfig) -> Result<(), Box<dyn std::error::Error>> {
         let config_path = self.get_config_path()?;
         let content = serde_json::to_string_pretty(config)?;
         fs::write(config_path, content)?;
@@ -714,6 +2477,212 @@ impl ToolExecutor {
         Ok(config.enable_proactive_tool_mode)
     }
 
+    pub async fn is_tools_enabled(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        let config = self.load_config().await.unwrap_or_default();
+        Ok(config.enable_tools)
+    }
+
+    pub async fn get_context_token_budget(&self) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+        let config = self.load_config().await.unwrap_or_default();
+        Ok(config.context_token_budget)
+    }
+
+    // Persisted policy wins; without a config file fall back to the executor's ToolConfig
+    pub async fn get_command_policy(&self) -> CommandPolicy {
+        let has_config_file = self.get_config_path().map(|p| p.exists()).unwrap_or(false);
+        if !has_config_file {
+            return self.config.command_policy.clone();
+        }
+
+        match self.load_config().await {
+            Ok(config) => CommandPolicy {
+                mode: config.command_policy,
+                allow: config.command_allowlist,
+                deny: config.command_denylist,
+            },
+            Err(_) => self.config.command_policy.clone(),
+        }
+    }
+
+    pub fn is_file_write_confirmation_enabled(&self) -> bool {
+        self.read_config()
+            .map(|config| config.confirm_file_writes)
+            .unwrap_or(true)
+    }
+
+    /// Permission prompt settings: (auto_approve_safe, non_interactive_permission)
+    pub fn get_permission_settings(&self) -> (bool, NonInteractivePermission) {
+        let config = self.read_config().unwrap_or_default();
+        (config.auto_approve_safe, config.non_interactive_permission)
+    }
+
+    /// Level for the log file when neither `--log-level` nor OLLAMA_AGENT_LOG is set
+    pub fn get_log_level(&self) -> String {
+        self.read_config().unwrap_or_default().log_level
+    }
+
+    // (backup_enabled, max_backups_per_file)
+    pub fn get_backup_settings(&self) -> (bool, usize) {
+        let config = self.read_config().unwrap_or_default();
+        (config.backup_enabled, config.max_backups_per_file)
+    }
+
+    pub fn get_ollama_retry_config(&self) -> RetryConfig {
+        let config = self.read_config().unwrap_or_default();
+        RetryConfig {
+            max_retries: config.ollama_retry_attempts,
+            base_delay: std::time::Duration::from_millis(config.ollama_retry_delay_ms),
+            ..RetryConfig::default()
+        }
+    }
+
+    /// Proxies and headers for web clients; proxies not set in the config come from the environment
+    pub fn get_web_request_settings(&self) -> WebRequestSettings {
+        let config = self.read_config().unwrap_or_default();
+        WebRequestSettings {
+            http_proxy: config.http_proxy.or_else(default_http_proxy),
+            https_proxy: config.https_proxy.or_else(default_https_proxy),
+            headers: config.http_headers,
+        }
+    }
+
+    /// Web search language and region: `--lang`/`--region`, else the config file, else English
+    pub fn get_search_locale(&self) -> (String, Option<String>) {
+        let config = self.read_config().unwrap_or_default();
+        let (language, region) = search_locale_override();
+        (
+            language.or(config.search_language).unwrap_or_else(|| "en".to_string()),
+            region.or(config.search_region),
+        )
+    }
+
+    /// Thresholds deciding which extracted search result pages are kept
+    pub fn get_content_quality_settings(&self) -> ContentQualitySettings {
+        let config = self.read_config().unwrap_or_default();
+        ContentQualitySettings {
+            min_score: config.min_content_quality_score,
+            min_words: config.min_content_words,
+            min_word_diversity: config.min_content_word_diversity,
+            spam_phrases: config.content_spam_phrases,
+            language_check: config.content_language_check,
+        }
+    }
+
+    /// Web search engines to query (empty = all)
+    pub fn get_search_engines(&self) -> Vec<String> {
+        self.read_config().unwrap_or_default().search_engines
+    }
+
+    /// Default allowed and blocked domains for web search
+    pub fn get_search_domain_filters(&self) -> (Vec<String>, Vec<String>) {
+        let config = self.read_config().unwrap_or_default();
+        (config.search_allowed_domains, config.search_blocked_domains)
+    }
+
+    /// Per-engine rate limit overrides for web search
+    pub fn get_search_rate_limits(&self) -> HashMap<String, RateLimit> {
+        self.read_config().unwrap_or_default().search_rate_limits
+    }
+
+    /// Concurrent page fetches and per-page timeout for search content extraction
+    pub fn get_scrape_limits(&self) -> (usize, u64) {
+        let config = self.read_config().unwrap_or_default();
+        (config.max_concurrent_scrapes.max(1), config.scrape_timeout_seconds)
+    }
+
+    /// Overall deadline for a search's engine fan-out (0 = wait for every engine)
+    pub fn get_total_search_deadline_secs(&self) -> u64 {
+        self.read_config().unwrap_or_default().total_search_deadline_secs
+    }
+
+    /// Most requests a web performance test may send
+    pub fn get_perf_test_max_requests(&self) -> usize {
+        self.read_config().unwrap_or_default().perf_test_max_requests.max(1)
+    }
+
+    /// Prompt limit for one tool's output and how longer output is shortened
+    pub fn get_tool_result_limit(&self) -> (usize, ToolResultStrategy) {
+        let config = self.read_config().unwrap_or_default();
+        (config.max_tool_result_chars.max(1), config.tool_result_strategy)
+    }
+
+    /// Worker threads for file search (0 = one per CPU)
+    pub fn get_file_search_threads(&self) -> usize {
+        self.read_config().unwrap_or_default().file_search_threads
+    }
+
+    /// Sampling window for CPU usage readings
+    pub fn get_cpu_sample_interval(&self) -> Duration {
+        let config = self.read_config().unwrap_or_default();
+        Duration::from_millis(config.cpu_sample_interval_ms.max(1))
+    }
+
+    /// Whether `ask` responses are cached, and for how many hours
+    pub fn get_response_cache_settings(&self) -> (bool, u64) {
+        let config = self.read_config().unwrap_or_default();
+        (config.response_cache, config.response_cache_ttl_hours.max(1))
+    }
+
+    /// How reasoning models' thinking blocks are shown and stored
+    pub fn get_thinking_settings(&self) -> ThinkingSettings {
+        let config = self.read_config().unwrap_or_default();
+        ThinkingSettings {
+            tags: ThinkingTags {
+                open: config.thinking_open_tag,
+                close: config.thinking_close_tag,
+            },
+            show: config.show_thinking,
+            keep_in_history: config.keep_thinking_in_history,
+        }
+    }
+
+    /// Whether a missing model is pulled instead of failing
+    pub fn get_pull_if_missing(&self) -> bool {
+        self.read_config().unwrap_or_default().pull_if_missing
+    }
+
+    /// External tools from the config file, for the tool registry
+    pub fn get_external_tools(&self) -> HashMap<String, ExternalToolSpec> {
+        self.read_config().unwrap_or_default().external_tools
+    }
+
+    /// API key from the environment variable, else `api_keys.<name>` in the config file
+    pub fn get_api_key(&self, name: &str, env_var: &str) -> Option<String> {
+        std::env::var(env_var)
+            .ok()
+            .or_else(|| self.read_config().ok()?.api_keys.get(name).cloned())
+            .filter(|key| !key.trim().is_empty())
+    }
+
+    /// Configured keep-alive for generate requests; unparsable values are ignored
+    pub fn get_keep_alive(&self) -> Option<KeepAlive> {
+        let config = self.read_config().unwrap_or_default();
+        config.keep_alive.and_then(|value| parse_keep_alive(&value).ok())
+    }
+
+    /// Model to use when a command is run without `--model`
+    pub fn get_default_model(&self) -> Option<String> {
+        self.read_config().unwrap_or_default().default_model
+    }
+
+    /// Per-tool timeout and retry settings for parallel tool execution
+    pub fn get_parallel_options(&self) -> ParallelOptions {
+        let config = self.read_config().unwrap_or_default();
+        ParallelOptions {
+            timeout: (config.parallel_tool_timeout > 0)
+                .then(|| Duration::from_secs(config.parallel_tool_timeout)),
+            error_strategy: ErrorStrategy::RetryWithBackoff {
+                max_retries: config.parallel_tool_retries,
+                backoff_ms: config.parallel_retry_backoff_ms,
+            },
+        }
+    }
+
+    pub async fn get_command_timeout(&self) -> u64 {
+        let config = self.load_config().await.unwrap_or_default();
+        config.command_timeout
+    }
+
     fn conversation_to_html(&self, conversation: &[ConversationEntry]) -> String {
         let mut output = vec![
             "<!DOCTYPE html>".to_string(),
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc as StdArc;
+use std::time::Instant;
 
 // Tool definition system
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +38,9 @@ pub enum AvailableTool {
     WebScrape {
         url: String,
     },
+    GithubIssue {
+        reference: String,
+    },
     FileSearch {
         pattern: String,
         directory: Option<String>,
@@ -52,9 +56,22 @@ pub enum AvailableTool {
         path: String,
         operation: EditOperation,
     },
+    FileMove {
+        from: String,
+        to: String,
+    },
+    FileCopy {
+        from: String,
+        to: String,
+    },
+    FileDelete {
+        path: String,
+        recursive: bool,
+    },
     ContentSearch {
         pattern: String,
         directory: Option<String>,
+        options: ContentSearchOptions,
     },
     CreateProject {
         name: String,
@@ -112,6 +129,12 @@ pub enum AvailableTool {
         cached: bool,
         repository_path: Option<String>,
     },
+    GitBlame {
+        file: String,
+        start: Option<usize>,
+        end: Option<usize>,
+        repository_path: Option<String>,
+    },
 
     // API Operations
     HttpRequest {
@@ -161,6 +184,29 @@ pub enum AvailableTool {
         package: Option<String>,
         requirements_file: Option<String>,
     },
+    PoetryOperation {
+        operation: PoetryOperation,
+        package: Option<String>,
+        dev: bool,
+    },
+    YarnOperation {
+        operation: YarnOperation,
+        package: Option<String>,
+        dev: bool,
+    },
+    PnpmOperation {
+        operation: PnpmOperation,
+        package: Option<String>,
+        dev: bool,
+    },
+    PackageInstall {
+        package: Option<String>,
+        dev: bool,
+    },
+    RunTests {
+        filter: Option<String>,
+        path: Option<String>,
+    },
 
     // System Operations
     ProcessList {
@@ -169,9 +215,13 @@ pub enum AvailableTool {
     SystemInfo,
     DiskUsage {
         path: Option<String>,
+        /// List this many of the largest directories instead of the total
+        top: Option<usize>,
+        depth: Option<usize>,
     },
     MemoryUsage,
     NetworkInfo,
+    Sensors,
     SystemPackageManager {
         operation: PackageManagerOperation,
         package: Option<String>,
@@ -205,6 +255,12 @@ pub enum AvailableTool {
         follow: bool,
         tail: Option<u32>,
     },
+    DockerStats {
+        container: Option<String>,
+    },
+    DockerPrune {
+        target: DockerPruneTarget,
+    },
 
     // Text Processing
     JsonFormat {
@@ -267,6 +323,11 @@ pub enum AvailableTool {
     CancelScheduledTask {
         name: String,
     },
+    /// A user-registered script (see `plugins`), called with the model's inputs
+    ExternalTool {
+        name: String,
+        input: serde_json::Value,
+    },
     ParallelExecution {
         tools: Vec<AvailableTool>,
     },
@@ -287,6 +348,150 @@ pub enum AvailableTool {
     },
 }
 
+impl AvailableTool {
+    /// Variant name, used as the `tool` field in structured logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            AvailableTool::WebSearch { .. } => "WebSearch",
+            AvailableTool::WebScrape { .. } => "WebScrape",
+            AvailableTool::GithubIssue { .. } => "GithubIssue",
+            AvailableTool::FileSearch { .. } => "FileSearch",
+            AvailableTool::FileRead { .. } => "FileRead",
+            AvailableTool::FileWrite { .. } => "FileWrite",
+            AvailableTool::FileEdit { .. } => "FileEdit",
+            AvailableTool::FileMove { .. } => "FileMove",
+            AvailableTool::FileCopy { .. } => "FileCopy",
+            AvailableTool::FileDelete { .. } => "FileDelete",
+            AvailableTool::ContentSearch { .. } => "ContentSearch",
+            AvailableTool::CreateProject { .. } => "CreateProject",
+            AvailableTool::ExecuteCommand { .. } => "ExecuteCommand",
+            AvailableTool::GenerateCommand { .. } => "GenerateCommand",
+            AvailableTool::ListDirectory { .. } => "ListDirectory",
+            AvailableTool::FileWatch { .. } => "FileWatch",
+            AvailableTool::GitStatus { .. } => "GitStatus",
+            AvailableTool::GitAdd { .. } => "GitAdd",
+            AvailableTool::GitCommit { .. } => "GitCommit",
+            AvailableTool::GitPush { .. } => "GitPush",
+            AvailableTool::GitPull { .. } => "GitPull",
+            AvailableTool::GitBranch { .. } => "GitBranch",
+            AvailableTool::GitLog { .. } => "GitLog",
+            AvailableTool::GitDiff { .. } => "GitDiff",
+            AvailableTool::GitBlame { .. } => "GitBlame",
+            AvailableTool::HttpRequest { .. } => "HttpRequest",
+            AvailableTool::RestApiCall { .. } => "RestApiCall",
+            AvailableTool::GraphQLQuery { .. } => "GraphQLQuery",
+            AvailableTool::SqlQuery { .. } => "SqlQuery",
+            AvailableTool::SqliteQuery { .. } => "SqliteQuery",
+            AvailableTool::CargoOperation { .. } => "CargoOperation",
+            AvailableTool::NpmOperation { .. } => "NpmOperation",
+            AvailableTool::PipOperation { .. } => "PipOperation",
+            AvailableTool::PoetryOperation { .. } => "PoetryOperation",
+            AvailableTool::YarnOperation { .. } => "YarnOperation",
+            AvailableTool::PnpmOperation { .. } => "PnpmOperation",
+            AvailableTool::PackageInstall { .. } => "PackageInstall",
+            AvailableTool::RunTests { .. } => "RunTests",
+            AvailableTool::ProcessList { .. } => "ProcessList",
+            AvailableTool::SystemInfo => "SystemInfo",
+            AvailableTool::DiskUsage { .. } => "DiskUsage",
+            AvailableTool::MemoryUsage => "MemoryUsage",
+            AvailableTool::NetworkInfo => "NetworkInfo",
+            AvailableTool::Sensors => "Sensors",
+            AvailableTool::SystemPackageManager { .. } => "SystemPackageManager",
+            AvailableTool::ServiceManager { .. } => "ServiceManager",
+            AvailableTool::EnvironmentInfo => "EnvironmentInfo",
+            AvailableTool::NetworkScan { .. } => "NetworkScan",
+            AvailableTool::DockerList { .. } => "DockerList",
+            AvailableTool::DockerRun { .. } => "DockerRun",
+            AvailableTool::DockerStop { .. } => "DockerStop",
+            AvailableTool::DockerLogs { .. } => "DockerLogs",
+            AvailableTool::DockerStats { .. } => "DockerStats",
+            AvailableTool::DockerPrune { .. } => "DockerPrune",
+            AvailableTool::JsonFormat { .. } => "JsonFormat",
+            AvailableTool::JsonQuery { .. } => "JsonQuery",
+            AvailableTool::CsvParse { .. } => "CsvParse",
+            AvailableTool::RegexMatch { .. } => "RegexMatch",
+            AvailableTool::TextTransform { .. } => "TextTransform",
+            AvailableTool::SetModelParameter { .. } => "SetModelParameter",
+            AvailableTool::GetModelParameter { .. } => "GetModelParameter",
+            AvailableTool::SwitchModel { .. } => "SwitchModel",
+            AvailableTool::SetConfig { .. } => "SetConfig",
+            AvailableTool::GetConfig { .. } => "GetConfig",
+            AvailableTool::ExportConversation { .. } => "ExportConversation",
+            AvailableTool::ImportConversation { .. } => "ImportConversation",
+            AvailableTool::ClearHistory => "ClearHistory",
+            AvailableTool::ScheduleTask { .. } => "ScheduleTask",
+            AvailableTool::ListScheduledTasks => "ListScheduledTasks",
+            AvailableTool::CancelScheduledTask { .. } => "CancelScheduledTask",
+            AvailableTool::ExternalTool { .. } => "ExternalTool",
+            AvailableTool::ParallelExecution { .. } => "ParallelExecution",
+            AvailableTool::SmartSuggestion { .. } => "SmartSuggestion",
+            AvailableTool::PerformanceMonitor { .. } => "PerformanceMonitor",
+            AvailableTool::CodeAnalysis { .. } => "CodeAnalysis",
+            AvailableTool::SecurityScan { .. } => "SecurityScan",
+        }
+    }
+}
+
+/// How `content_search` matches and how much surrounding text it returns
+#[derive(Debug, Clone)]
+pub struct ContentSearchOptions {
+    /// Lines shown above and below each match
+    pub context_lines: usize,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Files listed, highest match count first
+    pub max_files: usize,
+    /// Color matched text; off for output handed to the model
+    pub highlight: bool,
+    /// Search non-UTF-8 text files with invalid bytes replaced
+    pub lossy: bool,
+}
+
+impl Default for ContentSearchOptions {
+    fn default() -> Self {
+        Self {
+            context_lines: 2,
+            case_sensitive: true,
+            whole_word: false,
+            max_files: 20,
+            highlight: false,
+            lossy: false,
+        }
+    }
+}
+
+/// How `file_read` handles binary and non-UTF-8 files
+#[derive(Debug, Clone, Default)]
+pub struct FileReadOptions {
+    /// Replace invalid UTF-8 sequences instead of skipping the file
+    pub lossy: bool,
+    /// Show a hex dump instead of text
+    pub hex: bool,
+    /// Only read the first N bytes; binary files are hex dumped
+    pub head: Option<usize>,
+}
+
+/// Load pattern for `web_performance_test`
+#[derive(Debug, Clone)]
+pub struct PerfTestOptions {
+    /// Total requests, capped by the `perf_test_max_requests` setting
+    pub requests: usize,
+    /// Requests in flight at once; 1 runs them one after another
+    pub concurrency: usize,
+    /// Per-request limit; slower requests count as failed
+    pub timeout: std::time::Duration,
+}
+
+impl Default for PerfTestOptions {
+    fn default() -> Self {
+        Self {
+            requests: 10,
+            concurrency: 1,
+            timeout: std::time::Duration::from_secs(10),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum EditOperation {
     Replace {
@@ -381,6 +586,38 @@ pub enum PipOperation {
     Show,
 }
 
+#[derive(Debug, Clone)]
+pub enum PoetryOperation {
+    Install,
+    Remove,
+    Run { script: String },
+}
+
+#[derive(Debug, Clone)]
+pub enum YarnOperation {
+    Install,
+    Remove,
+    Run { script: String },
+}
+
+#[derive(Debug, Clone)]
+pub enum PnpmOperation {
+    Install,
+    Remove,
+    Run { script: String },
+}
+
+/// Language-level package manager inferred from a project's lockfiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectPackageManager {
+    Cargo,
+    Npm,
+    Yarn,
+    Pnpm,
+    Pip,
+    Poetry,
+}
+
 #[derive(Debug, Clone)]
 pub enum DockerResourceType {
     Containers,
@@ -389,6 +626,44 @@ pub enum DockerResourceType {
     Networks,
 }
 
+/// What `docker ... prune -f` removes
+#[derive(Debug, Clone, Copy)]
+pub enum DockerPruneTarget {
+    Containers,
+    Images,
+    Volumes,
+    System,
+}
+
+impl DockerPruneTarget {
+    pub fn docker_args(&self) -> &'static [&'static str] {
+        match self {
+            DockerPruneTarget::Containers => &["container", "prune", "-f"],
+            DockerPruneTarget::Images => &["image", "prune", "-f"],
+            DockerPruneTarget::Volumes => &["volume", "prune", "-f"],
+            DockerPruneTarget::System => &["system", "prune", "-f"],
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            DockerPruneTarget::Containers => "all stopped containers",
+            DockerPruneTarget::Images => "dangling images",
+            DockerPruneTarget::Volumes => "unused anonymous volumes",
+            DockerPruneTarget::System => {
+                "stopped containers, unused networks, dangling images and build cache"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ComposeAction {
+    Up,
+    Down,
+    Ps,
+}
+
 #[derive(Debug, Clone)]
 pub enum TextOperation {
     ToUpperCase,
@@ -493,6 +768,13 @@ pub struct WebSearchConfig {
     pub user_location: Option<UserLocation>,
     pub include_citations: bool,
     pub search_context_size: SearchContextSize,
+    #[serde(default = "super::http::default_http_proxy")]
+    pub http_proxy: Option<String>,
+    #[serde(default = "super::http::default_https_proxy")]
+    pub https_proxy: Option<String>,
+    /// Extra headers sent with every request (e.g. `Authorization` for private pages)
+    #[serde(default, skip_serializing)]
+    pub default_headers: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -552,6 +834,7 @@ pub struct ToolConfig {
     pub git_default_remote: String,
     pub database_connections: HashMap<String, String>,
     pub api_keys: HashMap<String, String>,
+    pub command_policy: super::permissions::CommandPolicy,
 }
 
 impl Default for ToolConfig {
@@ -563,6 +846,7 @@ impl Default for ToolConfig {
             git_default_remote: "origin".to_string(),
             database_connections: HashMap::new(),
             api_keys: HashMap::new(),
+            command_policy: super::permissions::CommandPolicy::default(),
         }
     }
 }
@@ -576,6 +860,9 @@ impl Default for WebSearchConfig {
             user_location: None,
             include_citations: true,
             search_context_size: SearchContextSize::Medium,
+            http_proxy: super::http::default_http_proxy(),
+            https_proxy: super::http::default_https_proxy(),
+            default_headers: HashMap::new(),
         }
     }
 }
@@ -606,19 +893,74 @@ impl ToolExecutor {
     pub async fn execute_tool(
         &self,
         tool: AvailableTool,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let name = tool.name();
+        let started = Instant::now();
+
+        let skipped = if super::dry_run::dry_run() {
+            super::dry_run::describe(&tool).and_then(super::dry_run::intercept)
+        } else {
+            None
+        };
+        let dry_run = skipped.is_some();
+        let result = match skipped {
+            Some(result) => Ok(result),
+            None => self.dispatch_tool(tool).await,
+        };
+
+        let duration_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(result) if result.success => tracing::info!(
+                target: "tool_call",
+                tool = name,
+                duration_ms,
+                success = true,
+                dry_run,
+                "tool call"
+            ),
+            Ok(result) => tracing::warn!(
+                target: "tool_call",
+                tool = name,
+                duration_ms,
+                success = false,
+                dry_run,
+                error = result.error.as_deref(),
+                "tool call failed"
+            ),
+            Err(e) => tracing::warn!(
+                target: "tool_call",
+                tool = name,
+                duration_ms,
+                success = false,
+                dry_run,
+                error = %e,
+                "tool call failed"
+            ),
+        }
+
+        result
+    }
+
+    async fn dispatch_tool(
+        &self,
+        tool: AvailableTool,
     ) -> Result<ToolResult, Box<dyn std::error::Error>> {
         match tool {
             // Existing tools
             AvailableTool::WebSearch { query, .. } => self.web_search(&query).await,
             AvailableTool::WebScrape { url } => self.web_scrape(&url).await,
+            AvailableTool::GithubIssue { reference } => self.github_issue(&reference).await,
             AvailableTool::FileSearch { pattern, directory } => {
                 self.file_search(&pattern, directory.as_deref())
             }
             AvailableTool::FileRead { path } => self.file_read(&path),
             AvailableTool::FileWrite { path, content } => self.file_write(&path, &content),
             AvailableTool::FileEdit { path, operation } => self.file_edit(&path, operation),
-            AvailableTool::ContentSearch { pattern, directory } => {
-                self.content_search(&pattern, directory.as_deref())
+            AvailableTool::FileMove { from, to } => self.file_move(&from, &to),
+            AvailableTool::FileCopy { from, to } => self.file_copy(&from, &to),
+            AvailableTool::FileDelete { path, recursive } => self.file_delete(&path, recursive),
+            AvailableTool::ContentSearch { pattern, directory, options } => {
+                self.content_search(&pattern, directory.as_deref(), &options)
             }
             AvailableTool::CreateProject {
                 name,
@@ -697,6 +1039,15 @@ impl ToolExecutor {
                 self.git_diff(file.as_deref(), cached, repository_path.as_deref())
                     .await
             }
+            AvailableTool::GitBlame {
+                file,
+                start,
+                end,
+                repository_path,
+            } => {
+                self.git_blame(&file, start, end, repository_path.as_deref())
+                    .await
+            }
 
             // API operations
             AvailableTool::HttpRequest {
@@ -758,13 +1109,38 @@ impl ToolExecutor {
                 self.pip_operation(operation, package.as_deref(), requirements_file.as_deref())
                     .await
             }
+            AvailableTool::PoetryOperation {
+                operation,
+                package,
+                dev,
+            } => self.poetry_operation(operation, package.as_deref(), dev).await,
+            AvailableTool::YarnOperation {
+                operation,
+                package,
+                dev,
+            } => self.yarn_operation(operation, package.as_deref(), dev).await,
+            AvailableTool::PnpmOperation {
+                operation,
+                package,
+                dev,
+            } => self.pnpm_operation(operation, package.as_deref(), dev).await,
+            AvailableTool::PackageInstall { package, dev } => {
+                self.package_install(package.as_deref(), dev).await
+            }
+            AvailableTool::RunTests { filter, path } => {
+                self.run_tests(filter.as_deref(), path.as_deref()).await
+            }
 
             // System operations
             AvailableTool::ProcessList { filter } => self.process_list(filter.as_deref()).await,
             AvailableTool::SystemInfo => self.system_info().await,
-            AvailableTool::DiskUsage { path } => self.disk_usage(path.as_deref()).await,
+            AvailableTool::DiskUsage { path, top: Some(top), depth } => {
+                self.disk_breakdown(path.as_deref(), top, depth.unwrap_or(1)).await
+            }
+            AvailableTool::DiskUsage { path, .. } => self.disk_usage(path.as_deref()).await,
             AvailableTool::MemoryUsage => self.memory_usage().await,
             AvailableTool::NetworkInfo => self.network_info().await,
+            AvailableTool::Sensors => self.sensors().await,
 
             // Docker operations
             AvailableTool::DockerList { resource_type } => self.docker_list(resource_type).await,
@@ -784,6 +1160,8 @@ impl ToolExecutor {
                 follow,
                 tail,
             } => self.docker_logs(&container, follow, tail).await,
+            AvailableTool::DockerStats { container } => self.docker_stats(container.as_deref()).await,
+            AvailableTool::DockerPrune { target } => self.docker_prune(target).await,
 
             // Text processing
             AvailableTool::JsonFormat { input } => self.json_format(&input),
@@ -827,6 +1205,7 @@ impl ToolExecutor {
             }
             AvailableTool::ListScheduledTasks => self.list_scheduled_tasks().await,
             AvailableTool::CancelScheduledTask { name } => self.cancel_scheduled_task(&name).await,
+            AvailableTool::ExternalTool { name, input } => self.run_external_tool(&name, &input).await,
 
             // Enhanced system operations
             AvailableTool::SystemPackageManager { operation, package } => {
@@ -915,151 +1294,26 @@ impl ToolExecutor {
             });
         }
         
-        println!(
-            "{} Executing {} tools in parallel",
-            "⚡".cyan(),
-            limited_tools.len()
-        );
-        
         // Increment depth counter
         self.execution_depth.fetch_add(1, Ordering::Relaxed);
-        
-        // Create futures for each tool
-        let futures: Vec<_> = limited_tools
-            .iter()
-            .enumerate()
-            .map(|(index, tool)| {
-                let tool_clone = tool.clone();
-                let executor_clone = Self {
-                    web_client: self.web_client.clone(),
-                    config: self.config.clone(),
-                    execution_depth: self.execution_depth.clone(),
-                };
-                
-                async move {
-                    let start_time = std::time::Instant::now();
-                    let result = executor_clone.execute_tool(tool_clone.clone()).await;
-                    let duration = start_time.elapsed();
-                    
-                    match result {
-                        Ok(tool_result) => {
-                            println!(
-                                "{} Tool {} completed in {:.2}s",
-                                "✓".green(),
-                                index + 1,
-                                duration.as_secs_f64()
-                            );
-                            (index, Ok(tool_result))
-                        }
-                        Err(e) => {
-                            println!(
-                                "{} Tool {} failed in {:.2}s: {}",
-                                "✗".red(),
-                                index + 1,
-                                duration.as_secs_f64(),
-                                e
-                            );
-                            (index, Err(e))
-                        }
-                    }
-                }
-            })
-            .collect();
-        
-        // Execute all tools concurrently
-        let results = futures::future::join_all(futures).await;
-        
+
+        let result = self
+            .parallel_execution(limited_tools, &self.get_parallel_options())
+            .await;
+
         // Decrement depth counter
         self.execution_depth.fetch_sub(1, Ordering::Relaxed);
-        
-        // Process results
-        let mut successful_results = Vec::new();
-        let mut failed_results = Vec::new();
-        let mut output_parts = Vec::new();
-        
-        for (index, result) in results {
-            match result {
-                Ok(tool_result) => {
-                    successful_results.push((index, tool_result.clone()));
-                    if tool_result.success {
-                        output_parts.push(format!(
-                            "Tool {}: SUCCESS\n{}",
-                            index + 1,
-                            tool_result.output
-                        ));
-                    } else {
-                        output_parts.push(format!(
-                            "Tool {}: FAILED\n{}",
-                            index + 1,
-                            tool_result.error.unwrap_or("Unknown error".to_string())
-                        ));
-                    }
-                }
-                Err(e) => {
-                    failed_results.push((index, e.to_string()));
-                    output_parts.push(format!(
-                        "Tool {}: ERROR\n{}",
-                        index + 1,
-                        e
-                    ));
-                }
-            }
+
+        let mut result = result?;
+        if let Some(serde_json::Value::Object(metadata)) = result.metadata.as_mut() {
+            metadata.insert("execution_depth".to_string(), serde_json::json!(current_depth));
+            metadata.insert("max_depth_limit".to_string(), serde_json::json!(MAX_DEPTH));
+            metadata.insert("max_parallel_limit".to_string(), serde_json::json!(MAX_PARALLEL_TOOLS));
+            metadata.insert(
+                "tools_limited".to_string(),
+                serde_json::json!(tools.len() > MAX_PARALLEL_TOOLS),
+            );
         }
-        
-        let overall_success = failed_results.is_empty() && 
-            successful_results.iter().all(|(_, result)| result.success);
-        
-        let summary = if overall_success {
-            format!(
-                "{} All {} tools executed successfully",
-                "✓".green(),
-                limited_tools.len()
-            )
-        } else {
-            format!(
-                "{} {}/{} tools completed successfully",
-                if successful_results.len() > failed_results.len() { "⚠️".yellow() } else { "✗".red() },
-                successful_results.iter().filter(|(_, r)| r.success).count(),
-                limited_tools.len()
-            )
-        };
-        
-        Ok(ToolResult {
-            success: overall_success,
-            output: format!(
-                "{}\n\n{}\n\n--- Detailed Results ---\n{}",
-                summary,
-                format!(
-                    "Execution Summary:\n- Total tools: {}\n- Successful: {}\n- Failed: {}",
-                    limited_tools.len(),
-                    successful_results.iter().filter(|(_, r)| r.success).count(),
-                    failed_results.len() + successful_results.iter().filter(|(_, r)| !r.success).count()
-                ),
-                output_parts.join("\n\n---\n\n")
-            ),
-            error: if failed_results.is_empty() {
-                None
-            } else {
-                Some(format!(
-                    "Failed tools: {}",
-                    failed_results
-                        .iter()
-                        .map(|(i, e)| format!("Tool {}: {}", i + 1, e))
-                        .collect::<Vec<_>>()
-                        .join("; ")
-                ))
-            },
-            metadata: Some(serde_json::json!({
-                "parallel_execution": true,
-                "total_tools": limited_tools.len(),
-                "successful_tools": successful_results.iter().filter(|(_, r)| r.success).count(),
-                "failed_tools": failed_results.len() + successful_results.iter().filter(|(_, r)| !r.success).count(),
-                "execution_depth": current_depth,
-                "max_depth_limit": MAX_DEPTH,
-                "max_parallel_limit": MAX_PARALLEL_TOOLS,
-                "tools_limited": tools.len() > MAX_PARALLEL_TOOLS
-            })),
-            web_search_result: None,
-        })
+        Ok(result)
     }
 }
@@ -0,0 +1,95 @@
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use similar::{ChangeTag, TextDiff};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set by the global `--yes` flag to skip write confirmations
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// Unified diff between the current and proposed content; a missing file diffs as all additions
+pub fn unified_diff(path: &str, old: Option<&str>, new: &str) -> String {
+    let old_content = old.unwrap_or("");
+    let old_header = if old.is_some() {
+        format!("a/{}", path)
+    } else {
+        "/dev/null".to_string()
+    };
+
+    TextDiff::from_lines(old_content, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&old_header, &format!("b/{}", path))
+        .to_string()
+}
+
+pub fn print_diff(diff: &str) {
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line.dimmed());
+        }
+    }
+}
+
+/// Count added and removed lines
+pub fn diff_stats(old: Option<&str>, new: &str) -> (usize, usize) {
+    let diff = TextDiff::from_lines(old.unwrap_or(""), new);
+    diff.iter_all_changes()
+        .fold((0, 0), |(added, removed), change| match change.tag() {
+            ChangeTag::Insert => (added + 1, removed),
+            ChangeTag::Delete => (added, removed + 1),
+            ChangeTag::Equal => (added, removed),
+        })
+}
+
+/// Show the pending change and ask before writing when running interactively
+pub fn confirm_file_change(
+    path: &str,
+    old: Option<&str>,
+    new: &str,
+    require_confirmation: bool,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    if old == Some(new) {
+        println!("{} No changes to {}", "ℹ️".blue(), path.yellow());
+        return Ok(true);
+    }
+
+    let (added, removed) = diff_stats(old, new);
+    println!();
+    println!(
+        "{} {} ({}, {})",
+        "📝 Pending changes:".cyan().bold(),
+        path.yellow(),
+        format!("+{}", added).green(),
+        format!("-{}", removed).red()
+    );
+    print_diff(&unified_diff(path, old, new));
+    println!();
+
+    let interactive = std::io::IsTerminal::is_terminal(&std::io::stdin());
+    if !require_confirmation || assume_yes() || !interactive {
+        return Ok(true);
+    }
+
+    let approved = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("{} Apply these changes?", "✏️".yellow()))
+        .default(false)
+        .interact()?;
+
+    Ok(approved)
+}
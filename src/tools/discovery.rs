@@ -318,6 +318,17 @@ impl ToolDiscovery {
             println!();
         }
         
+        // User-registered tools from `external_tools` in the config
+        let external_tools = super::plugins::external_tools();
+        if !external_tools.is_empty() {
+            println!("{}", "🔌 External Tools:".cyan().bold());
+            for (name, spec) in &external_tools {
+                println!("  {} {} - {}", "•".cyan(), name, spec.description);
+                println!("    Runs: {} {}", spec.executable.blue(), spec.args.join(" ").dimmed());
+            }
+            println!();
+        }
+        
         // Missing tools
         if !results.missing_tools.is_empty() {
             println!("{}", "❌ Missing Tools:".red().bold());
@@ -1,8 +1,89 @@
-use super::core::{DockerResourceType, ToolExecutor, ToolResult};
+use super::core::{ComposeAction, DockerPruneTarget, DockerResourceType, ToolExecutor, ToolResult};
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Command;
 
+/// One line of `docker stats --no-stream --format '{{json .}}'`
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerStats {
+    name: String,
+    #[serde(rename = "CPUPerc")]
+    cpu_perc: String,
+    mem_usage: String,
+    mem_perc: String,
+    #[serde(rename = "NetIO")]
+    net_io: String,
+    #[serde(rename = "BlockIO")]
+    block_io: String,
+    #[serde(rename = "PIDs", default)]
+    pids: String,
+}
+
+fn format_stats_table(stats: &[ContainerStats]) -> String {
+    let headers = ["NAME", "CPU %", "MEM USAGE / LIMIT", "MEM %", "NET I/O", "BLOCK I/O", "PIDS"];
+    let rows: Vec<[&str; 7]> = stats
+        .iter()
+        .map(|s| {
+            [
+                s.name.as_str(),
+                s.cpu_perc.as_str(),
+                s.mem_usage.as_str(),
+                s.mem_perc.as_str(),
+                s.net_io.as_str(),
+                s.block_io.as_str(),
+                s.pids.as_str(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |cells: &[&str; 7]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut lines = vec![format_row(&headers)];
+    lines.extend(rows.iter().map(format_row));
+    lines.join("\n")
+}
+
+/// The size from docker's "Total reclaimed space: 1.2GB" line
+fn parse_reclaimed_space(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("Total reclaimed space:")
+            .map(|size| size.trim().to_string())
+    })
+}
+
+/// Split a container command the way a shell would, so quoted arguments stay whole
+fn split_command(command: &str) -> Result<Vec<String>, String> {
+    shell_words::split(command).map_err(|e| format!("Invalid command '{}': {}", command, e))
+}
+
+fn invalid_command(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        metadata: None,
+        web_search_result: None,
+    }
+}
+
 impl ToolExecutor {
     pub async fn docker_list(
         &self,
@@ -86,7 +167,10 @@ impl ToolExecutor {
         
         // Add command if provided
         if let Some(ref run_command) = command {
-            cmd.args(run_command.split_whitespace());
+            match split_command(run_command) {
+                Ok(args) => cmd.args(args),
+                Err(e) => return Ok(invalid_command(e)),
+            };
         }
 
         let output = cmd.output()?;
@@ -196,4 +280,235 @@ impl ToolExecutor {
             web_search_result: None,
         })
     }
-}
\ No newline at end of file
+
+    // Prefer the `docker compose` plugin (v2), falling back to standalone `docker-compose` (v1)
+    fn detect_compose_command(&self) -> Option<Vec<&'static str>> {
+        let available = |program: &str, args: &[&str]| {
+            Command::new(program)
+                .args(args)
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false)
+        };
+
+        if available("docker", &["compose", "version"]) {
+            Some(vec!["docker", "compose"])
+        } else if available("docker-compose", &["version"]) {
+            Some(vec!["docker-compose"])
+        } else {
+            None
+        }
+    }
+
+    pub async fn docker_compose(
+        &self,
+        file: Option<&str>,
+        action: ComposeAction,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Docker Compose: {:?}", "🐳".cyan(), action);
+
+        let Some(compose) = self.detect_compose_command() else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    "Neither `docker compose` (v2) nor `docker-compose` (v1) is installed".to_string(),
+                ),
+                metadata: None,
+                web_search_result: None,
+            });
+        };
+
+        let mut cmd = tokio::process::Command::new(compose[0]);
+        cmd.args(&compose[1..]);
+        
+        if let Some(compose_file) = file {
+            cmd.args(["-f", compose_file]);
+        }
+        
+        match action {
+            ComposeAction::Up => {
+                cmd.args(["up", "-d"]);
+            }
+            ComposeAction::Down => {
+                cmd.arg("down");
+            }
+            ComposeAction::Ps => {
+                cmd.arg("ps");
+            }
+        }
+
+        // Stream output so image builds during `up` don't look frozen
+        let timeout_secs = self.get_command_timeout().await;
+        let mut result = self.stream_process(cmd, timeout_secs).await?;
+        if !result.success && result.error.is_none() {
+            result.error = Some("Docker compose command failed".to_string());
+        }
+        result.metadata = Some(serde_json::json!({
+            "file": file,
+            "action": format!("{:?}", action),
+            "compose_command": compose.join(" ")
+        }));
+        Ok(result)
+    }
+
+    pub async fn docker_build(
+        &self,
+        path: &str,
+        tag: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Building Docker image from: {}", "🐳".cyan(), path.yellow());
+
+        let mut cmd = tokio::process::Command::new("docker");
+        cmd.arg("build");
+        
+        if let Some(image_tag) = tag {
+            cmd.args(["-t", image_tag]);
+        }
+        
+        cmd.arg(path);
+
+        let timeout_secs = self.get_command_timeout().await;
+        let mut result = self.stream_process(cmd, timeout_secs).await?;
+        if !result.success && result.error.is_none() {
+            result.error = Some("Docker build command failed".to_string());
+        }
+        result.metadata = Some(serde_json::json!({
+            "path": path,
+            "tag": tag
+        }));
+        Ok(result)
+    }
+
+    pub async fn docker_exec(
+        &self,
+        container: &str,
+        command: &str,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!(
+            "{} Executing in container {}: {}",
+            "🐳".cyan(),
+            container.yellow(),
+            command.yellow()
+        );
+
+        let mut cmd = Command::new("docker");
+        cmd.args(["exec", container]);
+        match split_command(command) {
+            Ok(args) => cmd.args(args),
+            Err(e) => return Ok(invalid_command(e)),
+        };
+
+        let output = cmd.output()?;
+        let success = output.status.success();
+        
+        let output_text = if success {
+            String::from_utf8_lossy(&output.stdout)
+        } else {
+            String::from_utf8_lossy(&output.stderr)
+        };
+
+        Ok(ToolResult {
+            success,
+            output: output_text.to_string(),
+            error: if success { None } else { Some("Docker exec command failed".to_string()) },
+            metadata: Some(serde_json::json!({
+                "container": container,
+                "command": command
+            })),
+            web_search_result: None,
+        })
+    }
+
+    pub async fn docker_stats(
+        &self,
+        container: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!(
+            "{} Docker stats for: {}",
+            "🐳".cyan(),
+            container.unwrap_or("all running containers").yellow()
+        );
+
+        let mut cmd = Command::new("docker");
+        cmd.args(["stats", "--no-stream", "--format", "{{json .}}"]);
+        if let Some(container) = container {
+            cmd.arg(container);
+        }
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::from_utf8_lossy(&output.stderr).to_string(),
+                error: Some("Docker stats command failed".to_string()),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stats: Vec<ContainerStats> = stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+
+        Ok(ToolResult {
+            success: true,
+            output: if stats.is_empty() {
+                "No running containers".to_string()
+            } else {
+                format_stats_table(&stats)
+            },
+            error: None,
+            metadata: Some(serde_json::json!({
+                "container": container,
+                "stats": stats
+            })),
+            web_search_result: None,
+        })
+    }
+
+    pub async fn docker_prune(
+        &self,
+        target: DockerPruneTarget,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Pruning Docker {:?}", "🐳".cyan(), target);
+
+        let output = Command::new("docker").args(target.docker_args()).output()?;
+        let success = output.status.success();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let reclaimed = parse_reclaimed_space(&stdout);
+        let output_text = if success {
+            // Restate docker's total as a summary line of our own
+            let mut text: Vec<&str> = stdout
+                .lines()
+                .filter(|line| !line.trim().starts_with("Total reclaimed space:"))
+                .collect();
+            while text.last().is_some_and(|line| line.trim().is_empty()) {
+                text.pop();
+            }
+            let summary = format!("🧹 Reclaimed {}", reclaimed.as_deref().unwrap_or("0B"));
+            if text.is_empty() {
+                summary
+            } else {
+                format!("{}\n\n{}", text.join("\n"), summary)
+            }
+        } else {
+            String::from_utf8_lossy(&output.stderr).to_string()
+        };
+
+        Ok(ToolResult {
+            success,
+            output: output_text,
+            error: if success { None } else { Some("Docker prune command failed".to_string()) },
+            metadata: Some(serde_json::json!({
+                "target": format!("{:?}", target),
+                "reclaimed_space": reclaimed
+            })),
+            web_search_result: None,
+        })
+    }
+}
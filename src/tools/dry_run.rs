@@ -0,0 +1,312 @@
+use colored::Colorize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::core::{
+    AvailableTool, CargoOperation, EditOperation, GitBranchOperation, HttpMethod, NpmOperation,
+    PackageManagerOperation, PipOperation, PnpmOperation, PoetryOperation, RestOperation,
+    ServiceOperation, ToolResult, YarnOperation,
+};
+
+// Set by the global `--dry-run` flag to log side-effecting tools instead of running them
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::Relaxed);
+}
+
+pub fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Under `--dry-run`, log `action` and return a successful result in place of running it
+pub fn intercept(action: String) -> Option<ToolResult> {
+    if !dry_run() {
+        return None;
+    }
+
+    println!("{} {} {}", "🧪".cyan(), "[dry-run] would".yellow().bold(), action);
+    Some(ToolResult {
+        success: true,
+        output: format!("[dry-run] Skipped: {}", action),
+        error: None,
+        metadata: Some(serde_json::json!({
+            "dry_run": true,
+            "action": action
+        })),
+        web_search_result: None,
+    })
+}
+
+fn git(repository_path: &Option<String>, args: &str) -> String {
+    match repository_path {
+        Some(path) => format!("run `git -C {} {}`", path, args),
+        None => format!("run `git {}`", args),
+    }
+}
+
+fn with_package(command: String, package: &Option<String>) -> String {
+    match package {
+        Some(package) => format!("run `{} {}`", command, package),
+        None => format!("run `{}`", command),
+    }
+}
+
+/// What a side-effecting tool would do, or `None` for tools that only read and
+/// can run normally during a dry run
+pub fn describe(tool: &AvailableTool) -> Option<String> {
+    let action = match tool {
+        AvailableTool::FileWrite { path, content } => {
+            format!("write {} ({} bytes)", path, content.len())
+        }
+        AvailableTool::FileMove { from, to } => format!("move {} to {}", from, to),
+        AvailableTool::FileCopy { from, to } => format!("copy {} to {}", from, to),
+        AvailableTool::FileDelete { path, recursive } => {
+            if *recursive {
+                format!("delete {} and everything in it", path)
+            } else {
+                format!("delete {}", path)
+            }
+        }
+        AvailableTool::FileEdit { path, operation } => match operation {
+            EditOperation::Replace { old, new } => format!(
+                "edit {}: replace {} bytes with {} bytes",
+                path,
+                old.len(),
+                new.len()
+            ),
+            EditOperation::Insert { line, content } => {
+                format!("edit {}: insert {} bytes at line {}", path, content.len(), line)
+            }
+            EditOperation::Append { content } => {
+                format!("edit {}: append {} bytes", path, content.len())
+            }
+            EditOperation::Delete {
+                line_start,
+                line_end,
+            } => format!(
+                "edit {}: delete lines {}-{}",
+                path,
+                line_start,
+                line_end.unwrap_or(*line_start)
+            ),
+        },
+        AvailableTool::CreateProject {
+            name,
+            project_type,
+            path,
+        } => format!(
+            "create {} project '{}' in {}",
+            project_type,
+            name,
+            path.as_deref().unwrap_or(".")
+        ),
+        AvailableTool::ExecuteCommand { command } => format!("run `{}`", command),
+
+        AvailableTool::GitAdd {
+            files,
+            repository_path,
+        } => git(repository_path, &format!("add {}", files.join(" "))),
+        AvailableTool::GitCommit {
+            message,
+            repository_path,
+        } => git(repository_path, &format!("commit -m {:?}", message)),
+        AvailableTool::GitPush {
+            remote,
+            branch,
+            repository_path,
+        } => git(
+            repository_path,
+            format!(
+                "push {} {}",
+                remote.as_deref().unwrap_or("origin"),
+                branch.as_deref().unwrap_or("")
+            )
+            .trim_end(),
+        ),
+        AvailableTool::GitPull {
+            remote,
+            branch,
+            repository_path,
+        } => git(
+            repository_path,
+            format!(
+                "pull {} {}",
+                remote.as_deref().unwrap_or("origin"),
+                branch.as_deref().unwrap_or("")
+            )
+            .trim_end(),
+        ),
+        AvailableTool::GitBranch {
+            operation,
+            repository_path,
+        } => match operation {
+            GitBranchOperation::List => return None,
+            GitBranchOperation::Create { name } => git(repository_path, &format!("branch {}", name)),
+            GitBranchOperation::Switch { name } => {
+                git(repository_path, &format!("checkout {}", name))
+            }
+            GitBranchOperation::Delete { name } => {
+                git(repository_path, &format!("branch -d {}", name))
+            }
+            GitBranchOperation::Merge { from } => git(repository_path, &format!("merge {}", from)),
+        },
+
+        AvailableTool::HttpRequest {
+            method,
+            url,
+            body,
+            ..
+        } if !matches!(method, HttpMethod::GET | HttpMethod::HEAD | HttpMethod::OPTIONS) => format!(
+            "send {:?} {} ({} byte body)",
+            method,
+            url,
+            body.as_ref().map_or(0, String::len)
+        ),
+        AvailableTool::RestApiCall {
+            endpoint,
+            operation,
+            ..
+        } if !matches!(operation, RestOperation::Get) => {
+            format!("call {} ({:?})", endpoint, operation)
+        }
+        AvailableTool::GraphQLQuery { endpoint, query, .. }
+            if query.trim_start().starts_with("mutation") =>
+        {
+            format!("send GraphQL mutation to {}", endpoint)
+        }
+        AvailableTool::SqlQuery { query, .. } | AvailableTool::SqliteQuery { query, .. }
+            if !is_read_only_sql(query) =>
+        {
+            format!("run SQL `{}`", query)
+        }
+
+        AvailableTool::CargoOperation {
+            operation, package, ..
+        } if !matches!(operation, CargoOperation::Check) => {
+            with_package(format!("cargo {:?}", operation).to_lowercase(), package)
+        }
+        AvailableTool::NpmOperation {
+            operation, package, ..
+        } => match operation {
+            NpmOperation::List | NpmOperation::Audit => return None,
+            NpmOperation::Run { script } => format!("run `npm run {}`", script),
+            _ => with_package(format!("npm {:?}", operation).to_lowercase(), package),
+        },
+        AvailableTool::PipOperation {
+            operation, package, ..
+        } => match operation {
+            PipOperation::Install | PipOperation::Uninstall => {
+                with_package(format!("pip {:?}", operation).to_lowercase(), package)
+            }
+            _ => return None,
+        },
+        AvailableTool::PoetryOperation {
+            operation, package, ..
+        } => match operation {
+            PoetryOperation::Run { script } => format!("run `poetry run {}`", script),
+            _ => with_package(format!("poetry {:?}", operation).to_lowercase(), package),
+        },
+        AvailableTool::YarnOperation {
+            operation, package, ..
+        } => match operation {
+            YarnOperation::Run { script } => format!("run `yarn run {}`", script),
+            _ => with_package(format!("yarn {:?}", operation).to_lowercase(), package),
+        },
+        AvailableTool::PnpmOperation {
+            operation, package, ..
+        } => match operation {
+            PnpmOperation::Run { script } => format!("run `pnpm run {}`", script),
+            _ => with_package(format!("pnpm {:?}", operation).to_lowercase(), package),
+        },
+        AvailableTool::PackageInstall { package, .. } => {
+            with_package("<detected package manager> install".to_string(), package)
+        }
+        AvailableTool::RunTests { filter, .. } => match filter {
+            Some(filter) => format!("run the project's tests matching `{}`", filter),
+            None => "run the project's tests".to_string(),
+        },
+        AvailableTool::SystemPackageManager { operation, package } => match operation {
+            PackageManagerOperation::Install
+            | PackageManagerOperation::Remove
+            | PackageManagerOperation::Update => with_package(
+                format!("<system package manager> {:?}", operation).to_lowercase(),
+                package,
+            ),
+            _ => return None,
+        },
+        AvailableTool::ServiceManager {
+            operation,
+            service_name,
+        } => match operation {
+            ServiceOperation::Status | ServiceOperation::List => return None,
+            _ => format!("{:?} service '{}'", operation, service_name).to_lowercase(),
+        },
+
+        AvailableTool::DockerRun {
+            image,
+            command,
+            ports,
+            volumes,
+            environment,
+        } => format!(
+            "run `{}`",
+            docker_run_command(image, command, ports, volumes, environment)
+        ),
+        AvailableTool::DockerStop { container } => format!("run `docker stop {}`", container),
+        AvailableTool::DockerPrune { target } => {
+            format!("run `docker {}`", target.docker_args().join(" "))
+        }
+
+        AvailableTool::SetConfig { key, value } => format!("set config {} = {}", key, value),
+        AvailableTool::ExportConversation { format, path } => {
+            format!("export the conversation as {:?} to {}", format, path)
+        }
+        AvailableTool::ImportConversation { path } => {
+            format!("import a conversation from {}", path)
+        }
+        AvailableTool::ClearHistory => "clear the conversation history".to_string(),
+        AvailableTool::ScheduleTask {
+            command, schedule, ..
+        } => format!("schedule `{}` ({})", command, schedule),
+        AvailableTool::CancelScheduledTask { name } => {
+            format!("cancel scheduled task '{}'", name)
+        }
+        AvailableTool::ExternalTool { name, input } => {
+            format!("run external tool '{}' with {}", name, input)
+        }
+        _ => return None,
+    };
+
+    Some(action)
+}
+
+/// The `docker run` invocation `docker_run` builds for these arguments
+pub fn docker_run_command(
+    image: &str,
+    command: &Option<String>,
+    ports: &Option<Vec<String>>,
+    volumes: &Option<Vec<String>>,
+    environment: &Option<HashMap<String, String>>,
+) -> String {
+    let mut parts = vec!["docker run -d".to_string()];
+    parts.extend(ports.iter().flatten().map(|port| format!("-p {}", port)));
+    parts.extend(volumes.iter().flatten().map(|volume| format!("-v {}", volume)));
+    if let Some(environment) = environment {
+        let mut keys: Vec<&String> = environment.keys().collect();
+        keys.sort();
+        parts.extend(keys.into_iter().map(|key| format!("-e {}={}", key, environment[key])));
+    }
+    parts.push(image.to_string());
+    parts.extend(command.clone());
+    parts.join(" ")
+}
+
+fn is_read_only_sql(query: &str) -> bool {
+    let first = query
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(first.as_str(), "select" | "with" | "explain" | "pragma" | "show" | "describe")
+}
@@ -1,15 +1,27 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use futures::StreamExt;
 use colored::Colorize;
 use reqwest::Client;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
 use tokio::time::timeout;
 use url::Url;
 
+use super::core::SearchResultItem;
+use super::errors::{send_with_retry, RetryConfig};
+use super::http::{
+    configure_web_client, default_http_proxy, default_https_proxy, read_page_body,
+    PAGE_BYTES_PER_CONTENT_CHAR,
+};
+use super::web_search::SEARCH_METRICS;
+
 /// Enhanced configuration for intelligent web search
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnhancedWebSearchConfig {
@@ -26,6 +38,19 @@ pub struct EnhancedWebSearchConfig {
     pub retry_delay_ms: u64,
     pub follow_redirects: bool,
     pub respect_robots_txt: bool,
+    /// Result language as an ISO 639-1 code (e.g. "de"); also picks the Wikipedia edition
+    #[serde(default = "default_search_language")]
+    pub language: String,
+    /// Country for localized ranking as an ISO 3166 code (e.g. "de", "ch")
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default = "default_http_proxy")]
+    pub http_proxy: Option<String>,
+    #[serde(default = "default_https_proxy")]
+    pub https_proxy: Option<String>,
+    /// Extra headers sent with every request (e.g. `Authorization` for private pages)
+    #[serde(default, skip_serializing)]
+    pub default_headers: HashMap<String, String>,
     
     // Content and intelligence settings
     pub enable_content_extraction: bool,
@@ -38,11 +63,59 @@ pub struct EnhancedWebSearchConfig {
     pub cache_duration_hours: u64,
     pub concurrent_engines: usize,
     pub adaptive_timeouts: bool,
+    /// Result pages fetched for content extraction at the same time
+    #[serde(default = "default_max_concurrent_scrapes")]
+    pub max_concurrent_scrapes: usize,
+    /// Time allowed for fetching and extracting one page, separate from
+    /// `timeout_seconds` for search requests; 0 disables it
+    #[serde(default = "default_scrape_timeout_seconds")]
+    pub scrape_timeout_seconds: u64,
+    /// Overall deadline for querying the engines; stragglers are dropped and the
+    /// results gathered so far are used. 0 waits for every engine
+    #[serde(default = "default_total_search_deadline_secs")]
+    pub total_search_deadline_secs: u64,
     
     // Quality filters
+    /// Extracted page content scoring below this (0.0-1.0) is dropped; see [`ContentQualityScorer`]
     pub min_content_quality_score: f64,
     pub min_relevance_threshold: f64,
+    /// Extracted content with fewer words is rejected outright
+    #[serde(default = "default_min_content_words")]
+    pub min_content_words: usize,
+    /// Share of distinct words below which content counts as repetitive boilerplate
+    #[serde(default = "default_min_content_word_diversity")]
+    pub min_content_word_diversity: f64,
+    /// Phrases that mark ads and cookie banners; three or more hits reject the content
+    #[serde(default = "default_content_spam_phrases")]
+    pub content_spam_phrases: Vec<String>,
+    /// Reject content detected to be in another language than `language`
+    #[serde(default = "default_content_language_check")]
+    pub content_language_check: bool,
     pub exclude_low_authority_domains: bool,
+    
+    /// Per-engine token bucket overrides keyed by engine id (see [`SEARCH_ENGINE_IDS`])
+    #[serde(default)]
+    pub rate_limits: HashMap<String, RateLimit>,
+    /// Engine ids to query; empty queries every available engine
+    #[serde(default)]
+    pub enabled_engines: Vec<String>,
+    /// Keep only results on these domains (and their subdomains); empty keeps every domain
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Drop results on these domains, even when they are also allowed
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+    
+    // Optional self-hosted engines
+    pub searx_base_url: Option<String>,
+    
+    // Optional API-backed engines; scrapers become a fallback when these are set
+    #[serde(default, skip_serializing)]
+    pub google_api_key: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub google_cse_id: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub brave_api_key: Option<String>,
 }
 
 impl Default for EnhancedWebSearchConfig {
@@ -58,6 +131,11 @@ impl Default for EnhancedWebSearchConfig {
             retry_delay_ms: 800,
             follow_redirects: true,
             respect_robots_txt: false,
+            language: default_search_language(),
+            region: None,
+            http_proxy: default_http_proxy(),
+            https_proxy: default_https_proxy(),
+            default_headers: HashMap::new(),
             enable_content_extraction: true,
             enable_semantic_ranking: true,
             enable_query_expansion: true,
@@ -66,9 +144,149 @@ impl Default for EnhancedWebSearchConfig {
             cache_duration_hours: 6,
             concurrent_engines: 6,
             adaptive_timeouts: true,
-            min_content_quality_score: 0.3,
+            max_concurrent_scrapes: default_max_concurrent_scrapes(),
+            scrape_timeout_seconds: default_scrape_timeout_seconds(),
+            total_search_deadline_secs: default_total_search_deadline_secs(),
+            min_content_quality_score: default_min_content_quality_score(),
             min_relevance_threshold: 0.2,
+            min_content_words: default_min_content_words(),
+            min_content_word_diversity: default_min_content_word_diversity(),
+            content_spam_phrases: default_content_spam_phrases(),
+            content_language_check: default_content_language_check(),
             exclude_low_authority_domains: true,
+            rate_limits: HashMap::new(),
+            enabled_engines: Vec::new(),
+            allowed_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+            searx_base_url: None,
+            google_api_key: None,
+            google_cse_id: None,
+            brave_api_key: None,
+        }
+    }
+}
+
+fn default_search_language() -> String {
+    "en".to_string()
+}
+
+pub fn default_max_concurrent_scrapes() -> usize {
+    3
+}
+
+pub fn default_scrape_timeout_seconds() -> u64 {
+    15
+}
+
+pub fn default_total_search_deadline_secs() -> u64 {
+    25
+}
+
+pub fn default_min_content_quality_score() -> f64 {
+    0.3
+}
+
+pub fn default_min_content_words() -> usize {
+    15
+}
+
+pub fn default_min_content_word_diversity() -> f64 {
+    0.3
+}
+
+pub fn default_content_spam_phrases() -> Vec<String> {
+    [
+        "click here", "buy now", "limited time", "act now", "subscribe",
+        "advertisement", "sponsored", "affiliate", "cookie consent",
+    ]
+    .iter()
+    .map(|phrase| phrase.to_string())
+    .collect()
+}
+
+pub fn default_content_language_check() -> bool {
+    true
+}
+
+lazy_static::lazy_static! {
+    /// `--lang` / `--region` from the command line; they take precedence over the config file
+    static ref LOCALE_OVERRIDE: std::sync::RwLock<(Option<String>, Option<String>)> =
+        std::sync::RwLock::new((None, None));
+}
+
+pub fn set_search_locale_override(language: Option<String>, region: Option<String>) {
+    *LOCALE_OVERRIDE.write().unwrap() = (language, region);
+}
+
+pub fn search_locale_override() -> (Option<String>, Option<String>) {
+    LOCALE_OVERRIDE.read().unwrap().clone()
+}
+
+/// Validate a language code such as "de" or "DE" and return it lowercased
+pub fn parse_language_code(value: &str) -> std::result::Result<String, String> {
+    let code = value.trim().to_lowercase();
+    if (2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(code)
+    } else {
+        Err(format!("'{}' is not a language code (expected e.g. en, de, fr)", value))
+    }
+}
+
+/// Validate a two-letter region code such as "ch" or "US" and return it lowercased
+pub fn parse_region_code(value: &str) -> std::result::Result<String, String> {
+    let code = value.trim().to_lowercase();
+    if code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(code)
+    } else {
+        Err(format!("'{}' is not a region code (expected e.g. us, de, ch)", value))
+    }
+}
+
+impl EnhancedWebSearchConfig {
+    /// Whether `enabled_engines` lets this engine id run
+    fn engine_enabled(&self, id: &str) -> bool {
+        self.enabled_engines.is_empty() || self.enabled_engines.iter().any(|enabled| enabled == id)
+    }
+    
+    /// Region to localize for: the configured one, else the language's home country
+    fn effective_region(&self) -> Option<String> {
+        self.region.clone().or_else(|| {
+            let region = match self.language.as_str() {
+                "en" => return None,
+                "ja" => "jp",
+                "ko" => "kr",
+                "zh" => "cn",
+                "sv" => "se",
+                "da" => "dk",
+                "cs" => "cz",
+                "el" => "gr",
+                "uk" => "ua",
+                "he" => "il",
+                other => other,
+            };
+            Some(region.to_string())
+        })
+    }
+    
+    /// DuckDuckGo `kl` parameter, e.g. "de-de" or "ch-fr"; "wt-wt" means no region
+    fn duckduckgo_locale(&self) -> String {
+        match self.effective_region().as_deref() {
+            Some("gb") => format!("uk-{}", self.language),
+            Some(region) => format!("{}-{}", region, self.language),
+            None => "wt-wt".to_string(),
+        }
+    }
+    
+    /// `Accept-Language` for scraped engines, keeping English as a fallback
+    fn accept_language(&self) -> String {
+        let primary = match &self.region {
+            Some(region) => format!("{}-{},{};q=0.9", self.language, region.to_uppercase(), self.language),
+            None => self.language.clone(),
+        };
+        if self.language == "en" {
+            primary
+        } else {
+            format!("{},en;q=0.5", primary)
         }
     }
 }
@@ -98,6 +316,9 @@ pub struct EnhancedSearchResult {
     
     // Source and scoring
     pub source: String,
+    /// Every engine that returned this result, filled in when duplicates are merged
+    #[serde(default)]
+    pub sources: Vec<String>,
     pub relevance_score: f64,
     pub authority_score: f64,
     pub quality_score: f64,
@@ -120,6 +341,20 @@ pub struct EnhancedSearchResult {
     pub processing_time: Option<Duration>,
 }
 
+impl EnhancedSearchResult {
+    /// The plain result shape `WebSearchResult` and its citations use
+    pub fn to_result_item(&self) -> SearchResultItem {
+        SearchResultItem {
+            title: self.title.clone(),
+            url: self.url.clone(),
+            snippet: self.snippet.clone(),
+            content: self.content.clone(),
+            relevance_score: self.relevance_score,
+            source_domain: extract_domain(&self.url).unwrap_or_default(),
+        }
+    }
+}
+
 /// Social media and engagement metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SocialMetrics {
@@ -139,6 +374,181 @@ pub struct TechnicalMetrics {
     pub structured_data: bool,
 }
 
+/// How an engine obtains results, which decides when it is queried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineKind {
+    /// General web search through an official API (needs credentials)
+    WebApi,
+    /// General web search by scraping result pages; used as a fallback when a web API is configured
+    Scraper,
+    /// Site-specific or self-hosted engines that always run
+    Standard,
+}
+
+/// Token bucket settings for one search engine
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Requests that may go out back to back before throttling starts
+    pub capacity: u32,
+    /// Tokens regained per second, i.e. the sustained request rate
+    pub refill_per_second: f64,
+}
+
+/// Config keys for `rate_limits`, one per engine
+pub const SEARCH_ENGINE_IDS: &[&str] = &[
+    "google", "brave", "searxng", "duckduckgo", "bing", "wikipedia", "stack_overflow", "arxiv", "reddit",
+];
+
+/// `rate_limits` key for an engine name, e.g. "Stack Overflow" -> "stack_overflow"
+pub fn engine_id(name: &str) -> String {
+    name.to_lowercase().replace(' ', "_")
+}
+
+/// Canonical engine id for user input such as "stackoverflow" or "Stack Overflow"
+pub fn parse_engine_id(value: &str) -> std::result::Result<String, String> {
+    let squash = |id: &str| id.to_lowercase().replace(['_', ' ', '-'], "");
+    SEARCH_ENGINE_IDS.iter()
+        .find(|id| squash(id) == squash(value))
+        .map(|id| id.to_string())
+        .ok_or_else(|| format!("unknown search engine '{}' (expected one of: {})", value, SEARCH_ENGINE_IDS.join(", ")))
+}
+
+/// Domain for the allow/block lists from input such as `https://www.Example.com/docs`
+/// or `*.example.com`: lowercase host, without `www.` or a leading wildcard
+pub fn parse_domain(value: &str) -> std::result::Result<String, String> {
+    let trimmed = value.trim().to_lowercase();
+    let without_scheme = trimmed.split_once("://").map_or(trimmed.as_str(), |(_, rest)| rest);
+    let host = without_scheme.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.trim_start_matches("*.").trim_start_matches("www.").trim_end_matches('.');
+    let valid = !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if valid {
+        Ok(host.to_string())
+    } else {
+        Err(format!("'{}' is not a domain name (expected something like example.com)", value))
+    }
+}
+
+/// Whether `url`'s host is `domain` or one of its subdomains
+fn url_in_domain(url: &str, domain: &str) -> bool {
+    let Some(host) = Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_lowercase)) else {
+        return false;
+    };
+    let host = host.trim_start_matches("www.");
+    host == domain || host.strip_suffix(domain).is_some_and(|rest| rest.ends_with('.'))
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Per-host token buckets. A single instance is shared by every search in the
+/// process so repeated searches can't burst past an engine's rate.
+#[derive(Default)]
+pub struct HostRateLimiter {
+    buckets: std::sync::Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl HostRateLimiter {
+    /// Take a token for `host` and return how long the caller must wait before
+    /// sending. The token is reserved immediately, so concurrent callers queue up
+    /// one refill interval apart instead of all waking at once.
+    pub fn reserve(&self, host: &str, limit: RateLimit) -> Duration {
+        if limit.refill_per_second <= 0.0 {
+            return Duration::ZERO;
+        }
+        let capacity = f64::from(limit.capacity.max(1));
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(host.to_string()).or_insert(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.refill_per_second).min(capacity);
+        bucket.last_refill = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / limit.refill_per_second)
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref SEARCH_RATE_LIMITER: Arc<HostRateLimiter> = Arc::new(HostRateLimiter::default());
+}
+
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// HTTP layer behind the search engines, so their parsing can run against saved responses
+#[async_trait]
+pub trait Fetcher: Send + Sync {
+    /// GET `url` and return the body of a successful response
+    async fn get(&self, url: &str, headers: &[(&str, &str)], config: &EnhancedWebSearchConfig) -> Result<String>;
+}
+
+/// Live fetcher using the search client, with retries and adaptive timeouts
+pub struct HttpFetcher {
+    client: Arc<Client>,
+}
+
+impl HttpFetcher {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Fetcher for HttpFetcher {
+    async fn get(&self, url: &str, headers: &[(&str, &str)], config: &EnhancedWebSearchConfig) -> Result<String> {
+        let response = fetch_with_headers(&self.client, url, headers, config).await?;
+        Ok(response.text().await?)
+    }
+}
+
+/// Answers requests with canned bodies: the first fixture whose key occurs in the URL wins
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct FixtureFetcher {
+    fixtures: Vec<(String, String)>,
+}
+
+#[cfg(test)]
+impl FixtureFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `body` for every URL containing `key` (e.g. a host name)
+    pub fn with_fixture(mut self, key: &str, body: &str) -> Self {
+        self.fixtures.push((key.to_string(), body.to_string()));
+        self
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Fetcher for FixtureFetcher {
+    async fn get(&self, url: &str, _headers: &[(&str, &str)], _config: &EnhancedWebSearchConfig) -> Result<String> {
+        self.fixtures.iter()
+            .find(|(key, _)| url.contains(key.as_str()))
+            .map(|(_, body)| body.clone())
+            .ok_or_else(|| anyhow!("No fixture for {}", url))
+    }
+}
+
 /// Trait for search engine implementations
 #[async_trait]
 pub trait SearchEngine: Send + Sync {
@@ -146,17 +556,21 @@ pub trait SearchEngine: Send + Sync {
     fn get_name(&self) -> &str;
     fn get_priority(&self) -> u8; // 1-10, higher is better
     fn supports_intent(&self, intent: &QueryIntent) -> bool;
-    fn get_rate_limit_delay(&self) -> Duration;
+    /// Token bucket used when `rate_limits` has no override for this engine
+    fn default_rate_limit(&self) -> RateLimit;
+    /// Host whose bucket this engine's requests draw from
+    fn rate_limit_host(&self) -> String;
+    fn kind(&self) -> EngineKind { EngineKind::Standard }
 }
 
 /// DuckDuckGo search engine implementation
 pub struct DuckDuckGoEngine {
-    client: Arc<Client>,
+    fetcher: Arc<dyn Fetcher>,
 }
 
 impl DuckDuckGoEngine {
-    pub fn new(client: Arc<Client>) -> Self {
-        Self { client }
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        Self { fetcher }
     }
     
     fn get_intent_specific_selectors(&self, intent: &QueryIntent) -> Vec<&'static str> {
@@ -185,10 +599,14 @@ impl DuckDuckGoEngine {
 impl SearchEngine for DuckDuckGoEngine {
     async fn search(&self, query: &str, intent: &QueryIntent, config: &EnhancedWebSearchConfig) -> Result<Vec<EnhancedSearchResult>> {
         let enhanced_query = enhance_query_for_intent(query, intent);
-        let url = format!("https://html.duckduckgo.com/html/?q={}", urlencoding::encode(&enhanced_query));
+        let url = format!(
+            "https://html.duckduckgo.com/html/?q={}&kl={}",
+            urlencoding::encode(&enhanced_query),
+            config.duckduckgo_locale()
+        );
         
-        let response = fetch_with_intelligent_retry(&self.client, &url, config).await?;
-        let html = response.text().await?;
+        let accept_language = config.accept_language();
+        let html = self.fetcher.get(&url, &[("Accept-Language", &accept_language)], config).await?;
         let document = Html::parse_document(&html);
         
         let mut results = Vec::new();
@@ -210,6 +628,7 @@ impl SearchEngine for DuckDuckGoEngine {
                                     snippet,
                                     content: None,
                                     source: "DuckDuckGo".to_string(),
+                                    sources: Vec::new(),
                                     relevance_score: calculate_semantic_relevance(&title, query, intent),
                                     authority_score: calculate_context_aware_authority(&extract_domain(&clean_url).unwrap_or_default(), intent, query),
                                     quality_score: 0.0, // Will be calculated later
@@ -217,7 +636,7 @@ impl SearchEngine for DuckDuckGoEngine {
                                     final_score: 0.0, // Will be calculated later
                                     query_intent: intent.clone(),
                                     content_type: infer_content_type(&clean_url, &title),
-                                    language: Some("en".to_string()),
+                                    language: Some(config.language.clone()),
                                     word_count: None,
                                     reading_time: None,
                                     freshness_score: calculate_freshness_score(&clean_url),
@@ -250,17 +669,19 @@ impl SearchEngine for DuckDuckGoEngine {
     fn get_name(&self) -> &str { "DuckDuckGo" }
     fn get_priority(&self) -> u8 { 8 }
     fn supports_intent(&self, _intent: &QueryIntent) -> bool { true }
-    fn get_rate_limit_delay(&self) -> Duration { Duration::from_millis(500) }
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 2, refill_per_second: 0.5 } }
+    fn rate_limit_host(&self) -> String { "html.duckduckgo.com".to_string() }
+    fn kind(&self) -> EngineKind { EngineKind::Scraper }
 }
 
 /// Bing search engine implementation
 pub struct BingEngine {
-    client: Arc<Client>,
+    fetcher: Arc<dyn Fetcher>,
 }
 
 impl BingEngine {
-    pub fn new(client: Arc<Client>) -> Self {
-        Self { client }
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        Self { fetcher }
     }
 }
 
@@ -268,10 +689,17 @@ impl BingEngine {
 impl SearchEngine for BingEngine {
     async fn search(&self, query: &str, intent: &QueryIntent, config: &EnhancedWebSearchConfig) -> Result<Vec<EnhancedSearchResult>> {
         let enhanced_query = enhance_query_for_intent(query, intent);
-        let url = format!("https://www.bing.com/search?q={}", urlencoding::encode(&enhanced_query));
+        let mut url = format!(
+            "https://www.bing.com/search?q={}&setlang={}",
+            urlencoding::encode(&enhanced_query),
+            config.language
+        );
+        if let Some(region) = &config.region {
+            url.push_str(&format!("&cc={}", region));
+        }
         
-        let response = fetch_with_intelligent_retry(&self.client, &url, config).await?;
-        let html = response.text().await?;
+        let accept_language = config.accept_language();
+        let html = self.fetcher.get(&url, &[("Accept-Language", &accept_language)], config).await?;
         let document = Html::parse_document(&html);
         
         let mut results = Vec::new();
@@ -298,6 +726,7 @@ impl SearchEngine for BingEngine {
                                 snippet,
                                 content: None,
                                 source: "Bing".to_string(),
+                                sources: Vec::new(),
                                 relevance_score: calculate_semantic_relevance(&title, query, intent),
                                 authority_score: calculate_context_aware_authority(&extract_domain(href).unwrap_or_default(), intent, query),
                                 quality_score: 0.0,
@@ -305,7 +734,7 @@ impl SearchEngine for BingEngine {
                                 final_score: 0.0,
                                 query_intent: intent.clone(),
                                 content_type: infer_content_type(href, &title),
-                                language: Some("en".to_string()),
+                                language: Some(config.language.clone()),
                                 word_count: None,
                                 reading_time: None,
                                 freshness_score: calculate_freshness_score(href),
@@ -337,17 +766,19 @@ impl SearchEngine for BingEngine {
     fn get_name(&self) -> &str { "Bing" }
     fn get_priority(&self) -> u8 { 7 }
     fn supports_intent(&self, _intent: &QueryIntent) -> bool { true }
-    fn get_rate_limit_delay(&self) -> Duration { Duration::from_millis(600) }
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 2, refill_per_second: 0.5 } }
+    fn rate_limit_host(&self) -> String { "www.bing.com".to_string() }
+    fn kind(&self) -> EngineKind { EngineKind::Scraper }
 }
 
 /// Wikipedia specialized engine
 pub struct WikipediaEngine {
-    client: Arc<Client>,
+    fetcher: Arc<dyn Fetcher>,
 }
 
 impl WikipediaEngine {
-    pub fn new(client: Arc<Client>) -> Self {
-        Self { client }
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        Self { fetcher }
     }
     
     /// Determine if Wikipedia is appropriate for this query
@@ -407,22 +838,23 @@ impl SearchEngine for WikipediaEngine {
         }
         
         let search_url = format!(
-            "https://en.wikipedia.org/w/api.php?action=query&format=json&list=search&srsearch={}&srlimit={}",
+            "https://{}.wikipedia.org/w/api.php?action=query&format=json&list=search&srsearch={}&srlimit={}",
+            config.language,
             urlencoding::encode(query),
             config.max_results_per_engine
         );
         
-        let response = fetch_with_intelligent_retry(&self.client, &search_url, config).await?;
-        let json: serde_json::Value = response.json().await?;
+        let body = self.fetcher.get(&search_url, &[], config).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)?;
         
         let mut results = Vec::new();
         
         if let Some(search_results) = json["query"]["search"].as_array() {
             for item in search_results.iter().take(config.max_results_per_engine) {
                 if let (Some(title), Some(_pageid)) = (item["title"].as_str(), item["pageid"].as_u64()) {
-                    let url = format!("https://en.wikipedia.org/wiki/{}", urlencoding::encode(title));
+                    let url = format!("https://{}.wikipedia.org/wiki/{}", config.language, urlencoding::encode(title));
                     let snippet = item["snippet"].as_str()
-                        .map(|s| clean_html_entities(s))
+                        .map(strip_html_tags)
                         .filter(|s| !s.is_empty());
                     
                     results.push(EnhancedSearchResult {
@@ -431,6 +863,7 @@ impl SearchEngine for WikipediaEngine {
                         snippet,
                         content: None,
                         source: "Wikipedia".to_string(),
+                        sources: Vec::new(),
                         relevance_score: calculate_semantic_relevance(title, query, intent),
                         authority_score: calculate_context_aware_authority("wikipedia.org", intent, query),
                         quality_score: 0.9,
@@ -438,7 +871,7 @@ impl SearchEngine for WikipediaEngine {
                         final_score: 0.0,
                         query_intent: intent.clone(),
                         content_type: Some("encyclopedia".to_string()),
-                        language: Some("en".to_string()),
+                        language: Some(config.language.clone()),
                         word_count: None,
                         reading_time: None,
                         freshness_score: 0.8, // Wikipedia is well-maintained
@@ -472,83 +905,80 @@ impl SearchEngine for WikipediaEngine {
         // Wikipedia support is now determined dynamically in the search method
         true
     }
-    fn get_rate_limit_delay(&self) -> Duration { Duration::from_millis(200) }
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 5, refill_per_second: 2.0 } }
+    fn rate_limit_host(&self) -> String { "wikipedia.org".to_string() }
 }
 
-/// Stack Overflow specialized engine for technical queries
+/// Stack Overflow questions through the Stack Exchange API
 pub struct StackOverflowEngine {
-    client: Arc<Client>,
+    fetcher: Arc<dyn Fetcher>,
 }
 
 impl StackOverflowEngine {
-    pub fn new(client: Arc<Client>) -> Self {
-        Self { client }
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        Self { fetcher }
     }
 }
 
 #[async_trait]
 impl SearchEngine for StackOverflowEngine {
     async fn search(&self, query: &str, intent: &QueryIntent, config: &EnhancedWebSearchConfig) -> Result<Vec<EnhancedSearchResult>> {
-        // Stack Overflow is excellent for technical queries
-        if !matches!(intent, QueryIntent::Technical | QueryIntent::Tutorial) {
-            return Ok(Vec::new());
-        }
-        
-        let url = format!("https://stackoverflow.com/search?q={}", urlencoding::encode(query));
+        // The API ANDs every word, so the generic expansion terms would only filter out matches
+        let query = strip_query_expansion(query, intent);
+        let url = format!(
+            "https://api.stackexchange.com/2.3/search/advanced?order=desc&sort=relevance&q={}&site=stackoverflow&pagesize={}&filter=withbody",
+            urlencoding::encode(query),
+            config.max_results_per_engine
+        );
         
-        let response = fetch_with_intelligent_retry(&self.client, &url, config).await?;
-        let html = response.text().await?;
-        let document = Html::parse_document(&html);
+        let body = self.fetcher.get(&url, &[], config).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)?;
+        if let Some(message) = json["error_message"].as_str() {
+            return Err(anyhow!("Stack Exchange API error: {}", message));
+        }
         
         let mut results = Vec::new();
-        let question_selector = Selector::parse(".question-summary .question-hyperlink")
-            .map_err(|e| anyhow!("Invalid selector: {}", e))?;
-        
-        for (i, element) in document.select(&question_selector).enumerate() {
-            if i >= config.max_results_per_engine { break; }
+        for (rank, item) in json["items"].as_array().into_iter().flatten().enumerate() {
+            let (Some(title), Some(link)) = (item["title"].as_str(), item["link"].as_str()) else {
+                continue;
+            };
+            let score = item["score"].as_i64().unwrap_or(0);
+            let answers = item["answer_count"].as_u64().unwrap_or(0);
+            let accepted = item["accepted_answer_id"].is_u64();
             
-            if let Some(href) = element.value().attr("href") {
-                let title = extract_clean_text(&element.text().collect::<String>());
-                let full_url = if href.starts_with("/") {
-                    format!("https://stackoverflow.com{}", href)
-                } else {
-                    href.to_string()
-                };
-                
-                if is_quality_result(&title, &full_url, config) {
-                    let snippet = extract_intelligent_snippet(&document, element, intent);
-                    
-                    results.push(EnhancedSearchResult {
-                        title: title.clone(),
-                        url: full_url.clone(),
-                        snippet,
-                        content: None,
-                        source: "Stack Overflow".to_string(),
-                        relevance_score: calculate_semantic_relevance(&title, query, intent),
-                        authority_score: calculate_context_aware_authority("stackoverflow.com", intent, query),
-                        quality_score: 0.0,
-                        diversity_score: 0.0,
-                        final_score: 0.0,
-                        query_intent: intent.clone(),
-                        content_type: Some("qa_forum".to_string()),
-                        language: Some("en".to_string()),
-                        word_count: None,
-                        reading_time: None,
-                        freshness_score: calculate_freshness_score(&full_url),
-                        social_signals: None,
-                        technical_metrics: Some(TechnicalMetrics {
-                            https_enabled: full_url.starts_with("https://"),
-                            mobile_friendly: Some(true),
-                            load_speed_estimate: Some(0.7),
-                            accessibility_score: Some(0.8),
-                            structured_data: true,
-                        }),
-                        timestamp: chrono::Utc::now(),
-                        extraction_time: None,
-                        processing_time: None,
-                    });
+            let mut summary = format!("Score {} · {} answer{}", score, answers, if answers == 1 { "" } else { "s" });
+            if accepted {
+                summary.push_str(" (accepted)");
+            }
+            if let Some(tags) = item["tags"].as_array() {
+                let tags: Vec<&str> = tags.iter().filter_map(|tag| tag.as_str()).collect();
+                if !tags.is_empty() {
+                    summary.push_str(&format!(" · [{}]", tags.join(", ")));
                 }
             }
+            let body = item["body"].as_str().map(strip_html_tags).unwrap_or_default();
+            let snippet = if body.is_empty() {
+                summary
+            } else {
+                format!("{} — {}", summary, limit_content_length(body, 240))
+            };
+            
+            let mut result = web_api_result(title, link, Some(&snippet), "Stack Overflow", rank, query, intent);
+            // Highly voted, answered questions are the ones worth reading
+            let votes = (score.max(0) as f64 + 1.0).log10() / 3.0;
+            result.quality_score = (0.5 + votes + if accepted { 0.2 } else { 0.0 }).min(1.0);
+            result.content_type = Some("qa_forum".to_string());
+            result.language = Some("en".to_string());
+            result.freshness_score = item["last_activity_date"].as_i64()
+                .map(freshness_from_timestamp)
+                .unwrap_or_else(|| calculate_freshness_score(link));
+            result.social_signals = Some(SocialMetrics {
+                estimated_shares: item["view_count"].as_u64(),
+                backlink_count: None,
+                domain_authority: Some(0.9),
+                trust_signals: if accepted { vec!["accepted_answer".to_string()] } else { Vec::new() },
+            });
+            results.push(result);
         }
         
         Ok(results)
@@ -559,17 +989,119 @@ impl SearchEngine for StackOverflowEngine {
     fn supports_intent(&self, intent: &QueryIntent) -> bool {
         matches!(intent, QueryIntent::Technical | QueryIntent::Tutorial)
     }
-    fn get_rate_limit_delay(&self) -> Duration { Duration::from_millis(800) }
+    // Anonymous clients get 300 requests a day per IP
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 3, refill_per_second: 0.5 } }
+    fn rate_limit_host(&self) -> String { "api.stackexchange.com".to_string() }
+}
+
+/// ArXiv papers from the export API's Atom feed
+pub struct ArxivEngine {
+    fetcher: Arc<dyn Fetcher>,
+}
+
+impl ArxivEngine {
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        Self { fetcher }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for ArxivEngine {
+    async fn search(&self, query: &str, intent: &QueryIntent, config: &EnhancedWebSearchConfig) -> Result<Vec<EnhancedSearchResult>> {
+        let terms: Vec<String> = strip_query_expansion(query, intent)
+            .split_whitespace()
+            .map(|term| format!("all:{}", urlencoding::encode(term)))
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = format!(
+            "https://export.arxiv.org/api/query?search_query={}&start=0&max_results={}&sortBy=relevance",
+            terms.join("+AND+"),
+            config.max_results_per_engine
+        );
+        
+        let xml = self.fetcher.get(&url, &[], config).await?;
+        parse_arxiv_feed(&xml, query, intent)
+    }
+    
+    fn get_name(&self) -> &str { "arXiv" }
+    fn get_priority(&self) -> u8 { 8 }
+    fn supports_intent(&self, intent: &QueryIntent) -> bool {
+        matches!(intent, QueryIntent::Academic)
+    }
+    // arXiv asks API clients to wait three seconds between requests
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 1, refill_per_second: 0.34 } }
+    fn rate_limit_host(&self) -> String { "export.arxiv.org".to_string() }
+}
+
+fn parse_arxiv_feed(xml: &str, query: &str, intent: &QueryIntent) -> Result<Vec<EnhancedSearchResult>> {
+    let document = roxmltree::Document::parse(xml).map_err(|e| anyhow!("Invalid arXiv feed: {}", e))?;
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|child| child.has_tag_name(name))
+            .and_then(|child| child.text())
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+    };
+    
+    let mut results = Vec::new();
+    for (rank, entry) in document.root_element().children().filter(|node| node.has_tag_name("entry")).enumerate() {
+        let (Some(title), Some(id)) = (child_text(entry, "title"), child_text(entry, "id")) else {
+            continue;
+        };
+        // The feed reports query errors as a single entry titled "Error"
+        if title == "Error" && id.contains("api/errors") {
+            return Err(anyhow!("arXiv API error: {}", child_text(entry, "summary").unwrap_or_default()));
+        }
+        let url = id.replace("http://", "https://");
+        
+        let authors: Vec<String> = entry.children()
+            .filter(|child| child.has_tag_name("author"))
+            .filter_map(|author| child_text(author, "name"))
+            .collect();
+        let published = child_text(entry, "published").unwrap_or_default();
+        let mut byline = match authors.len() {
+            0 => String::new(),
+            1 | 2 => authors.join(", "),
+            _ => format!("{} et al.", authors[0]),
+        };
+        if let Some(date) = published.get(..10) {
+            byline = if byline.is_empty() { date.to_string() } else { format!("{} ({})", byline, date) };
+        }
+        let abstract_text = child_text(entry, "summary").unwrap_or_default();
+        let snippet = format!("{} — {}", byline, limit_content_length(abstract_text, 300));
+        
+        let mut result = web_api_result(&title, &url, Some(&snippet), "arXiv", rank, query, intent);
+        result.content_type = Some("research_paper".to_string());
+        result.quality_score = 0.8;
+        result.freshness_score = chrono::DateTime::parse_from_rfc3339(&published)
+            .map(|date| freshness_from_timestamp(date.timestamp()))
+            .unwrap_or(0.6);
+        results.push(result);
+    }
+    
+    Ok(results)
+}
+
+/// Freshness from a creation or last-activity time in Unix seconds
+fn freshness_from_timestamp(timestamp: i64) -> f64 {
+    let age_days = (chrono::Utc::now().timestamp() - timestamp) / 86_400;
+    match age_days {
+        d if d < 365 => 1.0,
+        d if d < 3 * 365 => 0.8,
+        d if d < 6 * 365 => 0.6,
+        _ => 0.4,
+    }
 }
 
 /// Reddit specialized engine for discussions and current topics
 pub struct RedditEngine {
-    client: Arc<Client>,
+    fetcher: Arc<dyn Fetcher>,
 }
 
 impl RedditEngine {
-    pub fn new(client: Arc<Client>) -> Self {
-        Self { client }
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        Self { fetcher }
     }
 }
 
@@ -591,8 +1123,7 @@ impl SearchEngine for RedditEngine {
         
         let url = format!("https://www.reddit.com/search/?q={}&type=link&sort=relevance", urlencoding::encode(query));
         
-        let response = fetch_with_intelligent_retry(&self.client, &url, config).await?;
-        let html = response.text().await?;
+        let html = self.fetcher.get(&url, &[], config).await?;
         let document = Html::parse_document(&html);
         
         let mut results = Vec::new();
@@ -625,6 +1156,7 @@ impl SearchEngine for RedditEngine {
                                 snippet,
                                 content: None,
                                 source: "Reddit".to_string(),
+                                sources: Vec::new(),
                                 relevance_score: calculate_semantic_relevance(&title, query, intent),
                                 authority_score: calculate_context_aware_authority("reddit.com", intent, query),
                                 quality_score: 0.0,
@@ -671,7 +1203,387 @@ impl SearchEngine for RedditEngine {
     fn supports_intent(&self, intent: &QueryIntent) -> bool {
         matches!(intent, QueryIntent::News | QueryIntent::General | QueryIntent::Comparison)
     }
-    fn get_rate_limit_delay(&self) -> Duration { Duration::from_millis(1000) }
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 2, refill_per_second: 0.2 } }
+    fn rate_limit_host(&self) -> String { "www.reddit.com".to_string() }
+}
+
+/// A cached search, persisted to disk between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSearch {
+    pub query: String,
+    pub intent: QueryIntent,
+    pub results: Vec<EnhancedSearchResult>,
+    pub cached_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CachedSearch {
+    fn is_expired(&self, cache_duration_hours: u64) -> bool {
+        let max_age = chrono::Duration::hours(cache_duration_hours as i64);
+        chrono::Utc::now() - self.cached_at >= max_age
+    }
+}
+
+/// SearXNG metasearch engine backed by a (self-hosted) instance's JSON API
+pub struct SearxEngine {
+    fetcher: Arc<dyn Fetcher>,
+    base_url: String,
+}
+
+impl SearxEngine {
+    pub fn new(fetcher: Arc<dyn Fetcher>, base_url: &str) -> Self {
+        Self {
+            fetcher,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for SearxEngine {
+    async fn search(&self, query: &str, intent: &QueryIntent, config: &EnhancedWebSearchConfig) -> Result<Vec<EnhancedSearchResult>> {
+        let language = match &config.region {
+            Some(region) => format!("{}-{}", config.language, region.to_uppercase()),
+            None => config.language.clone(),
+        };
+        let url = format!(
+            "{}/search?q={}&format=json&language={}",
+            self.base_url,
+            urlencoding::encode(query),
+            language
+        );
+        
+        let body = self.fetcher.get(&url, &[], config).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)?;
+        
+        let mut results = Vec::new();
+        
+        if let Some(items) = json["results"].as_array() {
+            for item in items.iter().take(config.max_results_per_engine) {
+                let (title, result_url) = match (item["title"].as_str(), item["url"].as_str()) {
+                    (Some(title), Some(url)) => (extract_clean_text(title), url.to_string()),
+                    _ => continue,
+                };
+                
+                if !is_quality_result(&title, &result_url, config) {
+                    continue;
+                }
+                
+                let snippet = item["content"].as_str()
+                    .map(|s| extract_clean_text(&clean_html_entities(s)))
+                    .filter(|s| !s.is_empty());
+                
+                // SearXNG aggregates several upstream engines; prefer its own score when present
+                let semantic_relevance = calculate_semantic_relevance(&title, query, intent);
+                let relevance_score = item["score"].as_f64()
+                    .map(|score| (score / 10.0).clamp(0.0, 1.0))
+                    .map_or(semantic_relevance, |score| score.max(semantic_relevance));
+                
+                results.push(EnhancedSearchResult {
+                    title: title.clone(),
+                    url: result_url.clone(),
+                    snippet,
+                    content: None,
+                    source: "SearXNG".to_string(),
+                    sources: Vec::new(),
+                    relevance_score,
+                    authority_score: calculate_context_aware_authority(&extract_domain(&result_url).unwrap_or_default(), intent, query),
+                    quality_score: 0.0,
+                    diversity_score: 0.0,
+                    final_score: 0.0,
+                    query_intent: intent.clone(),
+                    content_type: infer_content_type(&result_url, &title),
+                    language: Some(config.language.clone()),
+                    word_count: None,
+                    reading_time: None,
+                    freshness_score: calculate_freshness_score(&result_url),
+                    social_signals: None,
+                    technical_metrics: Some(TechnicalMetrics {
+                        https_enabled: result_url.starts_with("https://"),
+                        mobile_friendly: None,
+                        load_speed_estimate: None,
+                        accessibility_score: None,
+                        structured_data: false,
+                    }),
+                    timestamp: chrono::Utc::now(),
+                    extraction_time: None,
+                    processing_time: None,
+                });
+            }
+        }
+        
+        Ok(results)
+    }
+    
+    fn get_name(&self) -> &str { "SearXNG" }
+    fn get_priority(&self) -> u8 { 10 }
+    fn supports_intent(&self, _intent: &QueryIntent) -> bool { true }
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 5, refill_per_second: 2.0 } }
+    fn rate_limit_host(&self) -> String { host_of(&self.base_url) }
+}
+
+/// Authority bonus for results ranked by a first-party search API
+const WEB_API_AUTHORITY_BOOST: f64 = 0.15;
+
+/// Build a result from a web search API item; `rank` is its 0-based position
+fn web_api_result(
+    title: &str,
+    url: &str,
+    snippet: Option<&str>,
+    source: &str,
+    rank: usize,
+    query: &str,
+    intent: &QueryIntent,
+) -> EnhancedSearchResult {
+    let title = strip_html_tags(title);
+    let snippet = snippet.map(strip_html_tags).filter(|s| !s.is_empty());
+    
+    // The API's own ranking is a strong relevance signal
+    let rank_relevance = (1.0 - rank as f64 * 0.05).max(0.3);
+    let relevance_score = calculate_semantic_relevance(&title, query, intent).max(rank_relevance);
+    let domain = extract_domain(url).unwrap_or_default();
+    let authority_score =
+        (calculate_context_aware_authority(&domain, intent, query) + WEB_API_AUTHORITY_BOOST).min(1.0);
+    
+    EnhancedSearchResult {
+        content_type: infer_content_type(url, &title),
+        title,
+        url: url.to_string(),
+        snippet,
+        content: None,
+        source: source.to_string(),
+        sources: Vec::new(),
+        relevance_score,
+        authority_score,
+        quality_score: 0.0,
+        diversity_score: 0.0,
+        final_score: 0.0,
+        query_intent: intent.clone(),
+        language: None,
+        word_count: None,
+        reading_time: None,
+        freshness_score: calculate_freshness_score(url),
+        social_signals: None,
+        technical_metrics: Some(TechnicalMetrics {
+            https_enabled: url.starts_with("https://"),
+            mobile_friendly: None,
+            load_speed_estimate: None,
+            accessibility_score: None,
+            structured_data: false,
+        }),
+        timestamp: chrono::Utc::now(),
+        extraction_time: None,
+        processing_time: None,
+    }
+}
+
+/// Google Programmable Search (Custom Search JSON API)
+pub struct GoogleCseEngine {
+    fetcher: Arc<dyn Fetcher>,
+    api_key: String,
+    cx: String,
+}
+
+impl GoogleCseEngine {
+    pub fn new(fetcher: Arc<dyn Fetcher>, api_key: &str, cx: &str) -> Self {
+        Self {
+            fetcher,
+            api_key: api_key.to_string(),
+            cx: cx.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for GoogleCseEngine {
+    async fn search(&self, query: &str, intent: &QueryIntent, config: &EnhancedWebSearchConfig) -> Result<Vec<EnhancedSearchResult>> {
+        // The API returns at most 10 results per request
+        let mut url = format!(
+            "https://www.googleapis.com/customsearch/v1?key={}&cx={}&q={}&num={}&hl={}&lr=lang_{}",
+            urlencoding::encode(&self.api_key),
+            urlencoding::encode(&self.cx),
+            urlencoding::encode(query),
+            config.max_results_per_engine.clamp(1, 10),
+            config.language,
+            config.language
+        );
+        if let Some(region) = &config.region {
+            url.push_str(&format!("&gl={}", region));
+        }
+        
+        let body = self.fetcher.get(&url, &[], config).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)?;
+        
+        let results = json["items"].as_array().map(|items| {
+            items.iter()
+                .filter_map(|item| Some((item["title"].as_str()?, item["link"].as_str()?, item["snippet"].as_str())))
+                .enumerate()
+                .map(|(rank, (title, link, snippet))| web_api_result(title, link, snippet, "Google", rank, query, intent))
+                .filter(|result| is_quality_result(&result.title, &result.url, config))
+                .collect()
+        });
+        
+        Ok(results.unwrap_or_default())
+    }
+    
+    fn get_name(&self) -> &str { "Google" }
+    fn get_priority(&self) -> u8 { 10 }
+    fn supports_intent(&self, _intent: &QueryIntent) -> bool { true }
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 5, refill_per_second: 1.0 } }
+    fn rate_limit_host(&self) -> String { "www.googleapis.com".to_string() }
+    fn kind(&self) -> EngineKind { EngineKind::WebApi }
+}
+
+/// Brave Search API
+pub struct BraveEngine {
+    fetcher: Arc<dyn Fetcher>,
+    subscription_token: String,
+}
+
+impl BraveEngine {
+    pub fn new(fetcher: Arc<dyn Fetcher>, subscription_token: &str) -> Self {
+        Self {
+            fetcher,
+            subscription_token: subscription_token.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for BraveEngine {
+    async fn search(&self, query: &str, intent: &QueryIntent, config: &EnhancedWebSearchConfig) -> Result<Vec<EnhancedSearchResult>> {
+        let mut url = format!(
+            "https://api.search.brave.com/res/v1/web/search?q={}&count={}&search_lang={}",
+            urlencoding::encode(query),
+            config.max_results_per_engine.clamp(1, 20),
+            // Brave uses its own codes for a few languages
+            match config.language.as_str() {
+                "ja" => "jp",
+                "zh" => "zh-hans",
+                language => language,
+            }
+        );
+        if let Some(region) = &config.region {
+            url.push_str(&format!("&country={}", region));
+        }
+        let headers = [
+            ("Accept", "application/json"),
+            ("X-Subscription-Token", self.subscription_token.as_str()),
+        ];
+        
+        let body = self.fetcher.get(&url, &headers, config).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)?;
+        
+        let results = json["web"]["results"].as_array().map(|items| {
+            items.iter()
+                .filter_map(|item| Some((item["title"].as_str()?, item["url"].as_str()?, item["description"].as_str())))
+                .enumerate()
+                .map(|(rank, (title, url, description))| web_api_result(title, url, description, "Brave", rank, query, intent))
+                .filter(|result| is_quality_result(&result.title, &result.url, config))
+                .collect()
+        });
+        
+        Ok(results.unwrap_or_default())
+    }
+    
+    fn get_name(&self) -> &str { "Brave" }
+    fn get_priority(&self) -> u8 { 10 }
+    fn supports_intent(&self, _intent: &QueryIntent) -> bool { true }
+    fn default_rate_limit(&self) -> RateLimit { RateLimit { capacity: 1, refill_per_second: 1.0 } }
+    fn rate_limit_host(&self) -> String { "api.search.brave.com".to_string() }
+    fn kind(&self) -> EngineKind { EngineKind::WebApi }
+}
+
+/// Parsed robots.txt rules that apply to our user agent
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    allow: Vec<String>,
+    disallow: Vec<String>,
+}
+
+impl RobotsRules {
+    /// Parse robots.txt, keeping the group for our user agent (falling back to `*`)
+    pub fn parse(content: &str, user_agent: &str) -> Self {
+        let user_agent = user_agent.to_lowercase();
+        let mut specific = RobotsRules::default();
+        let mut wildcard = RobotsRules::default();
+        let mut has_specific = false;
+        
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((field, value)) = line.split_once(':') else { continue };
+            let field = field.trim().to_lowercase();
+            let value = value.trim();
+            
+            match field.as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts a new group
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    if value.is_empty() {
+                        continue;
+                    }
+                    for agent in &group_agents {
+                        let target = if agent == "*" {
+                            &mut wildcard
+                        } else if user_agent.contains(agent.as_str()) {
+                            has_specific = true;
+                            &mut specific
+                        } else {
+                            continue;
+                        };
+                        if field == "allow" {
+                            target.allow.push(value.to_string());
+                        } else {
+                            target.disallow.push(value.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        if has_specific { specific } else { wildcard }
+    }
+    
+    /// Longest matching rule wins; ties go to Allow
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let longest = |rules: &[String]| {
+            rules.iter()
+                .filter(|rule| robots_pattern_matches(rule, path))
+                .map(|rule| rule.len())
+                .max()
+        };
+        
+        match (longest(&self.allow), longest(&self.disallow)) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(allow), Some(disallow)) => allow >= disallow,
+        }
+    }
+}
+
+/// Match a robots.txt path pattern, supporting `*` wildcards and a trailing `$` anchor
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(stripped) => (stripped, true),
+        None => (pattern, false),
+    };
+    
+    let escaped: Vec<String> = pattern.split('*').map(regex::escape).collect();
+    let expression = format!("^{}{}", escaped.join(".*"), if anchored { "$" } else { "" });
+    
+    regex::Regex::new(&expression)
+        .map(|re| re.is_match(path))
+        .unwrap_or(false)
 }
 
 /// Main enhanced web search orchestrator
@@ -679,31 +1591,59 @@ pub struct EnhancedWebSearchEngine {
     engines: Vec<Box<dyn SearchEngine>>,
     client: Arc<Client>,
     config: Arc<EnhancedWebSearchConfig>,
-    cache: Arc<tokio::sync::RwLock<HashMap<String, (Vec<EnhancedSearchResult>, Instant)>>>,
+    cache: Arc<tokio::sync::RwLock<HashMap<String, CachedSearch>>>,
+    cache_path: Option<PathBuf>,
+    cache_loaded: OnceCell<()>,
+    robots_cache: Arc<tokio::sync::RwLock<HashMap<String, RobotsRules>>>,
+    rate_limiter: Arc<HostRateLimiter>,
 }
 
 impl EnhancedWebSearchEngine {
-    pub fn new(config: EnhancedWebSearchConfig) -> Self {
-        let client = Arc::new(
-            Client::builder()
-                .timeout(Duration::from_secs(config.timeout_seconds))
-                .user_agent(&config.user_agent)
-                .build()
-                .expect("Failed to create HTTP client")
-        );
-        
+    pub fn new(config: EnhancedWebSearchConfig) -> Result<Self> {
+        let client = build_search_client(&config)?;
+        let fetcher = Arc::new(HttpFetcher::new(client.clone()));
+        Ok(Self::from_parts(config, client, fetcher))
+    }
+    
+    /// Run the engines against `fetcher` instead of the network, e.g. a [`FixtureFetcher`]
+    #[cfg(test)]
+    pub fn with_fetcher(config: EnhancedWebSearchConfig, fetcher: Arc<dyn Fetcher>) -> Result<Self> {
+        let client = build_search_client(&config)?;
+        Ok(Self::from_parts(config, client, fetcher))
+    }
+    
+    /// `client` stays in use for robots.txt and page scraping
+    fn from_parts(config: EnhancedWebSearchConfig, client: Arc<Client>, fetcher: Arc<dyn Fetcher>) -> Self {
         let mut engines: Vec<Box<dyn SearchEngine>> = Vec::new();
-        engines.push(Box::new(DuckDuckGoEngine::new(client.clone())));
-        engines.push(Box::new(BingEngine::new(client.clone())));
-        engines.push(Box::new(WikipediaEngine::new(client.clone())));
-        engines.push(Box::new(StackOverflowEngine::new(client.clone())));
-        engines.push(Box::new(RedditEngine::new(client.clone())));
+        // Configured API engines and SearXNG go first so they are never dropped by `concurrent_engines`
+        if let (Some(api_key), Some(cx)) = (&config.google_api_key, &config.google_cse_id) {
+            engines.push(Box::new(GoogleCseEngine::new(fetcher.clone(), api_key, cx)));
+        }
+        if let Some(token) = &config.brave_api_key {
+            engines.push(Box::new(BraveEngine::new(fetcher.clone(), token)));
+        }
+        if let Some(base_url) = &config.searx_base_url {
+            engines.push(Box::new(SearxEngine::new(fetcher.clone(), base_url)));
+        }
+        engines.push(Box::new(DuckDuckGoEngine::new(fetcher.clone())));
+        engines.push(Box::new(BingEngine::new(fetcher.clone())));
+        engines.push(Box::new(WikipediaEngine::new(fetcher.clone())));
+        engines.push(Box::new(StackOverflowEngine::new(fetcher.clone())));
+        engines.push(Box::new(ArxivEngine::new(fetcher.clone())));
+        engines.push(Box::new(RedditEngine::new(fetcher.clone())));
+        engines.retain(|engine| config.engine_enabled(&engine_id(engine.get_name())));
+        
+        let cache_path = Some(crate::paths::data_dir().join("websearch-cache.json"));
         
         Self {
             engines,
             client,
             config: Arc::new(config),
             cache: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            cache_path,
+            cache_loaded: OnceCell::new(),
+            robots_cache: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            rate_limiter: SEARCH_RATE_LIMITER.clone(),
         }
     }
     
@@ -715,9 +1655,17 @@ impl EnhancedWebSearchEngine {
         let intent = classify_query_intent(query);
         println!("{} Classified query intent: {:?}", "🧠".cyan(), intent);
         
-        // Step 2: Check cache if enabled
+        // Step 2: Check cache if enabled; results are cached after domain filtering
+        let cache_key = format!(
+            "{}:{:?}:{}:{}{}{}",
+            query,
+            intent,
+            self.config.language,
+            self.config.region.as_deref().unwrap_or(""),
+            self.engine_set_key(),
+            self.domain_filter_key()
+        );
         if self.config.cache_results {
-            let cache_key = format!("{}:{:?}", query, intent);
             if let Some(cached_results) = self.get_cached_results(&cache_key).await {
                 println!("{} Using cached results", "💾".green());
                 return Ok(cached_results);
@@ -734,7 +1682,8 @@ impl EnhancedWebSearchEngine {
         println!("{} Enhanced query: {}", "✨".yellow(), enhanced_query);
         
         // Step 4: Select and execute search engines based on intent
-        let suitable_engines: Vec<_> = self.engines.iter()
+        let suitable_engines: Vec<&dyn SearchEngine> = self.engines.iter()
+            .map(|engine| engine.as_ref())
             .filter(|engine| engine.supports_intent(&intent))
             .collect();
         
@@ -742,66 +1691,218 @@ impl EnhancedWebSearchEngine {
             return Err(anyhow!("No suitable search engines for intent: {:?}", intent));
         }
         
-        // Step 5: Execute searches concurrently with intelligent load balancing
-        let search_futures: Vec<_> = suitable_engines.into_iter()
+        // Step 5: Execute searches concurrently. With a web search API configured the
+        // scrapers only run if the API engines come back empty (missing quota, rate limits).
+        let web_api_sources: HashSet<&str> = suitable_engines.iter()
+            .filter(|engine| engine.kind() == EngineKind::WebApi)
+            .map(|engine| engine.get_name())
+            .collect();
+        let (first_round, fallback): (Vec<_>, Vec<_>) = suitable_engines.into_iter()
+            .partition(|engine| web_api_sources.is_empty() || engine.kind() != EngineKind::Scraper);
+        
+        // Step 6: Aggregate results. The fallback round shares the deadline, so one
+        // slow engine can't hold up the whole search.
+        let deadline = (self.config.total_search_deadline_secs > 0).then(|| {
+            tokio::time::Instant::now() + Duration::from_secs(self.config.total_search_deadline_secs)
+        });
+        let mut all_results = self.run_engines(first_round, &enhanced_query, &intent, deadline).await;
+        
+        let web_api_found = all_results.iter().any(|result| web_api_sources.contains(result.source.as_str()));
+        let time_left = deadline.is_none_or(|deadline| tokio::time::Instant::now() < deadline);
+        if !web_api_sources.is_empty() && !web_api_found && !fallback.is_empty() && time_left {
+            println!("{} Search APIs returned nothing, falling back to scrapers", "↪".yellow());
+            all_results.extend(self.run_engines(fallback, &enhanced_query, &intent, deadline).await);
+        }
+        
+        if all_results.is_empty() {
+            return Err(anyhow!("No search results found from any engine"));
+        }
+        self.apply_domain_filters(&mut all_results)?;
+        
+        // Step 7: Intelligent processing pipeline
+        let processed_results = self.intelligent_processing_pipeline(all_results, &enhanced_query, &intent).await;
+        
+        // Step 8: Cache results if enabled
+        if self.config.cache_results {
+            self.cache_results(&cache_key, query, &intent, &processed_results).await;
+            if let Err(e) = self.flush_cache_to_disk().await {
+                println!("{} Failed to persist search cache: {}", "⚠".yellow(), e);
+            }
+        }
+        
+        let total_time = start_time.elapsed();
+        println!("{} Search completed in {:?} with {} final results", 
+            "🎯".green(), total_time, processed_results.len());
+        
+        Ok(processed_results)
+    }
+    
+    /// Query a single engine by id, bypassing intent routing and the result cache
+    pub async fn search_single_engine(&self, id: &str, query: &str) -> Result<Vec<EnhancedSearchResult>> {
+        let engine = self.engines.iter()
+            .map(|engine| engine.as_ref())
+            .find(|engine| engine_id(engine.get_name()) == id)
+            .ok_or_else(|| {
+                let active: Vec<_> = self.engines.iter().map(|engine| engine_id(engine.get_name())).collect();
+                anyhow!("Search engine '{}' is not enabled (active: {})", id, active.join(", "))
+            })?;
+        
+        // A site-specific engine skips intents it doesn't serve, so fall back to one it does
+        let classified = classify_query_intent(query);
+        let intent = [QueryIntent::Technical, QueryIntent::General]
+            .into_iter()
+            .chain(std::iter::once(classified.clone()))
+            .rev()
+            .find(|intent| engine.supports_intent(intent))
+            .unwrap_or(classified);
+        println!("{} Classified query intent: {:?}", "🧠".cyan(), intent);
+        
+        let mut results = self.run_engines(vec![engine], query, &intent, None).await;
+        if results.is_empty() {
+            return Err(anyhow!("{} returned no results", engine.get_name()));
+        }
+        self.apply_domain_filters(&mut results)?;
+        Ok(self.intelligent_processing_pipeline(results, query, &intent).await)
+    }
+    
+    /// Drop results outside `allowed_domains` or inside `blocked_domains` before any
+    /// page is scraped; blocked wins when a domain is on both lists
+    fn apply_domain_filters(&self, results: &mut Vec<EnhancedSearchResult>) -> Result<()> {
+        let (allowed, blocked) = (&self.config.allowed_domains, &self.config.blocked_domains);
+        if allowed.is_empty() && blocked.is_empty() {
+            return Ok(());
+        }
+        
+        let before = results.len();
+        results.retain(|result| {
+            !blocked.iter().any(|domain| url_in_domain(&result.url, domain))
+                && (allowed.is_empty() || allowed.iter().any(|domain| url_in_domain(&result.url, domain)))
+        });
+        if results.len() < before {
+            println!("{} Domain filters removed {} of {} results", "🚫".yellow(), before - results.len(), before);
+        }
+        if results.is_empty() {
+            return Err(anyhow!("No search results left after the domain filters"));
+        }
+        Ok(())
+    }
+    
+    /// Cache key part so searches restricted to some engines don't share results with others
+    fn engine_set_key(&self) -> String {
+        if self.config.enabled_engines.is_empty() {
+            return String::new();
+        }
+        let mut engines = self.config.enabled_engines.clone();
+        engines.sort();
+        engines.dedup();
+        format!(":engines={}", engines.join(","))
+    }
+
+    /// Cache key suffix so filtered and unfiltered searches don't share results
+    fn domain_filter_key(&self) -> String {
+        if self.config.allowed_domains.is_empty() && self.config.blocked_domains.is_empty() {
+            return String::new();
+        }
+        format!(":+{}:-{}", self.config.allowed_domains.join(","), self.config.blocked_domains.join(","))
+    }
+    
+    /// Query engines concurrently (up to `concurrent_engines`) and collect their results.
+    /// Engines still running at `deadline` are dropped and whatever has arrived is returned.
+    async fn run_engines(
+        &self,
+        engines: Vec<&dyn SearchEngine>,
+        query: &str,
+        intent: &QueryIntent,
+        deadline: Option<tokio::time::Instant>,
+    ) -> Vec<EnhancedSearchResult> {
+        let mut pending: Vec<&str> = Vec::new();
+        let mut search_futures: futures::stream::FuturesUnordered<_> = engines
+            .into_iter()
             .take(self.config.concurrent_engines)
             .map(|engine| {
-                let query = enhanced_query.clone();
-                let intent = intent.clone();
                 let config = self.config.clone();
+                let limit = config
+                    .rate_limits
+                    .get(&engine_id(engine.get_name()))
+                    .copied()
+                    .unwrap_or_else(|| engine.default_rate_limit());
+                let wait = self.rate_limiter.reserve(&engine.rate_limit_host(), limit);
+                pending.push(engine.get_name());
                 async move {
+                    if !wait.is_zero() {
+                        if wait >= Duration::from_secs(1) {
+                            println!(
+                                "{} Throttling {} for {:.1}s",
+                                "⏳".yellow(),
+                                engine.get_name(),
+                                wait.as_secs_f64()
+                            );
+                        }
+                        tokio::time::sleep(wait).await;
+                    }
                     let engine_start = Instant::now();
-                    tokio::time::sleep(engine.get_rate_limit_delay()).await;
-                    
-                    match engine.search(&query, &intent, &config).await {
+
+                    let results = match engine.search(query, intent, &config).await {
                         Ok(mut results) => {
                             let engine_time = engine_start.elapsed();
+                            SEARCH_METRICS.record_response_time(engine_time.as_millis() as u64);
                             for result in &mut results {
                                 result.processing_time = Some(engine_time);
+                                result
+                                    .language
+                                    .get_or_insert_with(|| config.language.clone());
                             }
-                            println!("{} {} found {} results in {:?}", 
-                                "✓".green(), engine.get_name(), results.len(), engine_time);
-                            Ok((engine.get_name(), results))
+                            println!(
+                                "{} {} found {} results in {:?}",
+                                "✓".green(),
+                                engine.get_name(),
+                                results.len(),
+                                engine_time
+                            );
+                            results
                         }
                         Err(e) => {
                             println!("{} {} failed: {}", "✗".red(), engine.get_name(), e);
-                            Err(e)
+                            Vec::new()
                         }
-                    }
+                    };
+                    (engine.get_name(), results)
                 }
             })
             .collect();
-        
-        let search_results = futures::future::join_all(search_futures).await;
-        
-        // Step 6: Aggregate and process results
+
         let mut all_results = Vec::new();
-        for result in search_results {
-            if let Ok((_engine_name, mut results)) = result {
-                if !results.is_empty() {
-                    all_results.append(&mut results);
-                }
-            }
-        }
-        
-        if all_results.is_empty() {
-            return Err(anyhow!("No search results found from any engine"));
+        loop {
+            let next = search_futures.next();
+            let finished = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, next).await {
+                    Ok(finished) => finished,
+                    Err(_) => break,
+                },
+                None => next.await,
+            };
+            let Some((name, results)) = finished else {
+                break;
+            };
+            pending.retain(|pending_name| *pending_name != name);
+            all_results.extend(results);
         }
-        
-        // Step 7: Intelligent processing pipeline
-        let processed_results = self.intelligent_processing_pipeline(all_results, &enhanced_query, &intent).await;
-        
-        // Step 8: Cache results if enabled
-        if self.config.cache_results {
-            let cache_key = format!("{}:{:?}", query, intent);
-            self.cache_results(&cache_key, &processed_results).await;
+
+        if !pending.is_empty() {
+            println!(
+                "{} Search deadline of {}s reached; continuing without {}",
+                "⏱".yellow(),
+                self.config.total_search_deadline_secs,
+                pending.join(", ")
+            );
+            tracing::warn!(
+                engines = %pending.join(", "),
+                deadline_secs = self.config.total_search_deadline_secs,
+                "search engines dropped at the deadline"
+            );
         }
-        
-        let total_time = start_time.elapsed();
-        println!("{} Search completed in {:?} with {} final results", 
-            "🎯".green(), total_time, processed_results.len());
-        
-        Ok(processed_results)
+
+        all_results
     }
     
     /// Intelligent processing pipeline for results
@@ -818,8 +1919,7 @@ impl EnhancedWebSearchEngine {
         // Step 2: Enhanced scoring with multiple factors
         self.calculate_enhanced_scores(&mut results, query, intent).await;
         
-        // Step 3: Quality filtering
-        results.retain(|r| r.quality_score >= self.config.min_content_quality_score);
+        // Step 3: Relevance filtering; page content is gated on quality once extracted
         results.retain(|r| r.relevance_score >= self.config.min_relevance_threshold);
         
         // Step 4: Diversification if enabled
@@ -832,7 +1932,10 @@ impl EnhancedWebSearchEngine {
         
         // Step 6: Content extraction for top results
         let top_results: Vec<_> = results.into_iter().take(self.config.max_total_results).collect();
-        let final_results = self.extract_content_intelligently(top_results).await;
+        let mut final_results = self.extract_content_intelligently(top_results).await;
+        
+        // Step 7: Re-rank now that page content has been scored
+        final_results.sort_by(|a, b| b.final_score.partial_cmp(&a.final_score).unwrap_or(std::cmp::Ordering::Equal));
         
         final_results
     }
@@ -867,12 +1970,17 @@ impl EnhancedWebSearchEngine {
                                 (authority * 0.25) + 
                                 (quality * 0.20) + 
                                 (intent_boost * 0.15) + 
-                                (freshness * 0.05);
+                                (freshness * 0.05) +
+                                consensus_boost(result);
         }
     }
     
     /// Extract content intelligently with adaptive strategies
     async fn extract_content_intelligently(&self, mut results: Vec<EnhancedSearchResult>) -> Vec<EnhancedSearchResult> {
+        // Bound open connections, and give up on slow pages instead of waiting for them
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(self.config.max_concurrent_scrapes.max(1)));
+        let scrape_timeout = (self.config.scrape_timeout_seconds > 0)
+            .then(|| Duration::from_secs(self.config.scrape_timeout_seconds));
         let content_futures: Vec<_> = results.iter()
             .take(self.config.max_scrape_urls)
             .map(|result| {
@@ -880,20 +1988,39 @@ impl EnhancedWebSearchEngine {
                 let config = self.config.clone();
                 let url = result.url.clone();
                 let intent = result.query_intent.clone();
+                let semaphore = semaphore.clone();
                 
                 async move {
-                    extract_content_with_adaptive_strategy(&client, &url, &intent, &config).await
+                    let _permit = semaphore.acquire().await?;
+                    let scrape = async {
+                        if config.respect_robots_txt {
+                            self.ensure_allowed_by_robots(&url).await?;
+                        }
+                        extract_content_with_adaptive_strategy(&client, &url, &intent, &config).await
+                    };
+                    match scrape_timeout {
+                        Some(limit) => timeout(limit, scrape)
+                            .await
+                            .map_err(|_| anyhow!("timed out after {}s", limit.as_secs()))?,
+                        None => scrape.await,
+                    }
                 }
             })
             .collect();
         
         let content_results = futures::future::join_all(content_futures).await;
+        let scorer = ContentQualityScorer::from_config(&self.config);
         
         for (index, content_result) in content_results.into_iter().enumerate() {
             if index < results.len() {
                 match content_result {
                     Ok(content) => {
-                        if is_high_quality_content(&content, &self.config) {
+                        let quality = scorer.evaluate(&content);
+                        if quality.is_accepted() {
+                            // Fold the content's score into the result's so ranking reflects the page itself
+                            let blended = (results[index].quality_score + quality.score) / 2.0;
+                            results[index].final_score += (blended - results[index].quality_score) * 0.20;
+                            results[index].quality_score = blended;
                             results[index].content = Some(content);
                             results[index].word_count = results[index].content.as_ref()
                                 .map(|c| c.split_whitespace().count());
@@ -902,7 +2029,8 @@ impl EnhancedWebSearchEngine {
                             
                             println!("{} Extracted content from: {}", "📄".cyan(), results[index].url);
                         } else {
-                            println!("{} Low quality content from: {}", "⚠".yellow(), results[index].url);
+                            println!("{} Low quality content from: {} ({})", "⚠".yellow(), results[index].url,
+                                quality.rejection.unwrap_or_default());
                         }
                     }
                     Err(e) => {
@@ -915,28 +2043,133 @@ impl EnhancedWebSearchEngine {
         results
     }
     
+    /// Fail if the site's robots.txt disallows fetching this URL for our user agent
+    async fn ensure_allowed_by_robots(&self, url: &str) -> Result<()> {
+        let parsed = Url::parse(url)?;
+        let host = parsed.host_str().ok_or_else(|| anyhow!("URL has no host: {}", url))?;
+        let origin = match parsed.port() {
+            Some(port) => format!("{}://{}:{}", parsed.scheme(), host, port),
+            None => format!("{}://{}", parsed.scheme(), host),
+        };
+        
+        let cached = self.robots_cache.read().await.get(&origin).cloned();
+        let rules = match cached {
+            Some(rules) => rules,
+            None => {
+                let rules = self.fetch_robots_rules(&origin).await;
+                self.robots_cache.write().await.insert(origin.clone(), rules.clone());
+                rules
+            }
+        };
+        
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        
+        if rules.is_allowed(&path) {
+            Ok(())
+        } else {
+            Err(anyhow!("Disallowed by robots.txt: {}", url))
+        }
+    }
+    
+    /// Fetch and parse robots.txt; a missing or unreachable file allows everything
+    async fn fetch_robots_rules(&self, origin: &str) -> RobotsRules {
+        let robots_url = format!("{}/robots.txt", origin);
+        let request = self.client.get(&robots_url).send();
+        
+        match timeout(Duration::from_secs(self.config.timeout_seconds), request).await {
+            Ok(Ok(response)) if response.status().is_success() => match response.text().await {
+                Ok(body) => RobotsRules::parse(&body, &self.config.user_agent),
+                Err(_) => RobotsRules::default(),
+            },
+            _ => RobotsRules::default(),
+        }
+    }
+    
     /// Get cached results if available and not expired
     async fn get_cached_results(&self, cache_key: &str) -> Option<Vec<EnhancedSearchResult>> {
+        self.ensure_cache_loaded().await;
+        
         let cache = self.cache.read().await;
-        if let Some((results, timestamp)) = cache.get(cache_key) {
-            let age = timestamp.elapsed();
-            if age < Duration::from_secs(self.config.cache_duration_hours * 3600) {
-                return Some(results.clone());
+        if let Some(entry) = cache.get(cache_key) {
+            if !entry.is_expired(self.config.cache_duration_hours) {
+                return Some(entry.results.clone());
             }
         }
         None
     }
     
     /// Cache search results
-    async fn cache_results(&self, cache_key: &str, results: &[EnhancedSearchResult]) {
+    async fn cache_results(&self, cache_key: &str, query: &str, intent: &QueryIntent, results: &[EnhancedSearchResult]) {
+        self.ensure_cache_loaded().await;
+        
         let mut cache = self.cache.write().await;
-        cache.insert(cache_key.to_string(), (results.to_vec(), Instant::now()));
+        cache.insert(cache_key.to_string(), CachedSearch {
+            query: query.to_string(),
+            intent: intent.clone(),
+            results: results.to_vec(),
+            cached_at: chrono::Utc::now(),
+        });
         
         // Clean up old entries (simple LRU-style cleanup)
         if cache.len() > 100 {
-            let cutoff = Instant::now() - Duration::from_secs(self.config.cache_duration_hours * 3600);
-            cache.retain(|_, (_, timestamp)| *timestamp > cutoff);
+            let cache_duration_hours = self.config.cache_duration_hours;
+            cache.retain(|_, entry| !entry.is_expired(cache_duration_hours));
+        }
+    }
+    
+    /// Load the on-disk cache once, the first time the cache is touched
+    async fn ensure_cache_loaded(&self) {
+        self.cache_loaded.get_or_init(|| async {
+            if let Err(e) = self.load_cache_from_disk().await {
+                println!("{} Failed to load search cache: {}", "⚠".yellow(), e);
+            }
+        }).await;
+    }
+    
+    /// Load persisted cache entries, dropping any that have expired
+    pub async fn load_cache_from_disk(&self) -> Result<usize> {
+        let path = match &self.cache_path {
+            Some(path) if path.exists() => path,
+            _ => return Ok(0),
+        };
+        
+        let content = fs::read_to_string(path)?;
+        let mut entries: HashMap<String, CachedSearch> = serde_json::from_str(&content)?;
+        let cache_duration_hours = self.config.cache_duration_hours;
+        entries.retain(|_, entry| !entry.is_expired(cache_duration_hours));
+        
+        let loaded = entries.len();
+        let mut cache = self.cache.write().await;
+        for (key, entry) in entries {
+            cache.entry(key).or_insert(entry);
         }
+        
+        Ok(loaded)
+    }
+    
+    /// Write the current (unexpired) cache to disk
+    pub async fn flush_cache_to_disk(&self) -> Result<()> {
+        let path = self.cache_path.as_ref()
+            .ok_or_else(|| anyhow!("Could not find home directory"))?;
+        
+        let cache_duration_hours = self.config.cache_duration_hours;
+        let json = {
+            let cache = self.cache.read().await;
+            let live: HashMap<&String, &CachedSearch> = cache.iter()
+                .filter(|(_, entry)| !entry.is_expired(cache_duration_hours))
+                .collect();
+            serde_json::to_string(&live)?
+        };
+        
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, json)?;
+        
+        Ok(())
     }
 }
 
@@ -1034,15 +2267,19 @@ fn enhance_query_for_intent(query: &str, intent: &QueryIntent) -> String {
     }
 }
 
+fn query_expansions(intent: &QueryIntent) -> &'static [&'static str] {
+    match intent {
+        QueryIntent::Technical => &["documentation", "examples", "guide"],
+        QueryIntent::Academic => &["research", "study", "analysis"],
+        QueryIntent::Tutorial => &["tutorial", "how-to", "step-by-step"],
+        QueryIntent::Factual => &["explanation", "definition", "overview"],
+        _ => &[]
+    }
+}
+
 /// Expand query with related terms
 fn expand_query(query: &str, intent: &QueryIntent) -> String {
-    let expansions = match intent {
-        QueryIntent::Technical => vec!["documentation", "examples", "guide"],
-        QueryIntent::Academic => vec!["research", "study", "analysis"],
-        QueryIntent::Tutorial => vec!["tutorial", "how-to", "step-by-step"],
-        QueryIntent::Factual => vec!["explanation", "definition", "overview"],
-        _ => vec![]
-    };
+    let expansions = query_expansions(intent);
     
     if expansions.is_empty() {
         query.to_string()
@@ -1051,41 +2288,63 @@ fn expand_query(query: &str, intent: &QueryIntent) -> String {
     }
 }
 
-/// Fetch with intelligent retry and adaptive timeouts
-async fn fetch_with_intelligent_retry(
-    client: &Client, 
-    url: &str, 
+/// Undo [`expand_query`] for APIs that require every term to match
+fn strip_query_expansion<'a>(query: &'a str, intent: &QueryIntent) -> &'a str {
+    let expansions = query_expansions(intent);
+    if expansions.is_empty() {
+        return query;
+    }
+    query.strip_suffix(&format!(" {}", expansions.join(" "))).unwrap_or(query)
+}
+
+/// HTTP client shared by the search engines and page scraping
+fn build_search_client(config: &EnhancedWebSearchConfig) -> Result<Arc<Client>> {
+    let builder = Client::builder()
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .user_agent(&config.user_agent);
+    let builder = configure_web_client(
+        builder,
+        config.http_proxy.as_deref(),
+        config.https_proxy.as_deref(),
+        &config.default_headers,
+    )?;
+    Ok(Arc::new(
+        builder.build().map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?
+    ))
+}
+
+/// Fetch with intelligent retry and adaptive timeouts, adding request headers (e.g. API tokens)
+async fn fetch_with_headers(
+    client: &Client,
+    url: &str,
+    headers: &[(&str, &str)],
     config: &EnhancedWebSearchConfig
 ) -> Result<reqwest::Response> {
-    let mut last_error = None;
-    let base_delay = Duration::from_millis(config.retry_delay_ms);
-    
-    for attempt in 0..=config.retry_attempts {
+    let retry = RetryConfig {
+        max_retries: config.retry_attempts as u32,
+        base_delay: Duration::from_millis(config.retry_delay_ms),
+        jitter: false,
+        ..RetryConfig::default()
+    };
+
+    let response = send_with_retry(&retry, None, |attempt| {
         let timeout_duration = if config.adaptive_timeouts {
             Duration::from_secs(config.timeout_seconds + (attempt as u64 * 2))
         } else {
             Duration::from_secs(config.timeout_seconds)
         };
-        
-        match timeout(timeout_duration, client.get(url).send()).await {
-            Ok(Ok(response)) => {
-                if response.status().is_success() {
-                    return Ok(response);
-                } else {
-                    last_error = Some(anyhow!("HTTP error: {}", response.status()));
-                }
-            }
-            Ok(Err(e)) => last_error = Some(anyhow!("Request error: {}", e)),
-            Err(_) => last_error = Some(anyhow!("Request timeout after {:?}", timeout_duration)),
-        }
-        
-        if attempt < config.retry_attempts {
-            let delay = base_delay * (2_u32.pow(attempt as u32)); // Exponential backoff
-            tokio::time::sleep(delay).await;
-        }
+        headers.iter()
+            .fold(client.get(url), |request, (name, value)| request.header(*name, *value))
+            .timeout(timeout_duration)
+    })
+    .await
+    .map_err(|e| anyhow!("Request error: {}", e))?;
+
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(anyhow!("HTTP error: {}", response.status()))
     }
-    
-    Err(last_error.unwrap_or_else(|| anyhow!("Unknown fetch error")))
 }
 
 /// Calculate semantic relevance with intent awareness
@@ -1135,6 +2394,7 @@ fn calculate_context_aware_authority(domain: &str, intent: &QueryIntent, query:
         d if d.contains("wikipedia.org") => 0.85, // Reduced from 0.95
         d if d.contains(".gov") => 0.90,
         d if d.contains(".edu") => 0.85,
+        d if d.contains("arxiv.org") => 0.85,
         d if d.contains("stackoverflow.com") => 0.80,
         d if d.contains("github.com") => 0.75,
         d if d.contains("mozilla.org") || d.contains("w3.org") => 0.75,
@@ -1180,7 +2440,9 @@ fn calculate_context_aware_authority(domain: &str, intent: &QueryIntent, query:
             }
         },
         QueryIntent::Academic => {
-            if domain_lower.contains(".edu") || domain_lower.contains("wikipedia") {
+            if domain_lower.contains("arxiv.org") {
+                1.2 // Preprints are primary sources for research queries
+            } else if domain_lower.contains(".edu") || domain_lower.contains("wikipedia") {
                 1.1 // Moderate boost for academic content
             } else {
                 1.0
@@ -1339,31 +2601,73 @@ fn infer_content_type(url: &str, title: &str) -> Option<String> {
 
 /// Advanced deduplication with fuzzy matching
 fn advanced_deduplication(results: Vec<EnhancedSearchResult>) -> Vec<EnhancedSearchResult> {
-    let mut unique_results = Vec::new();
-    let mut seen_urls = HashSet::new();
-    let mut seen_titles = HashSet::new();
+    let mut unique_results: Vec<EnhancedSearchResult> = Vec::new();
+    // Normalized URL and title of each unique result, by index
+    let mut seen: Vec<(String, String)> = Vec::new();
     
-    for result in results {
+    for mut result in results {
+        if result.sources.is_empty() {
+            result.sources.push(result.source.clone());
+        }
         let normalized_url = normalize_url(&result.url);
         let normalized_title = normalize_title(&result.title);
         
         // Check for near-duplicates
-        let is_duplicate = seen_urls.iter().any(|existing_url: &String| {
+        let duplicate_of = seen.iter().position(|(existing_url, existing_title)| {
             url_similarity(existing_url, &normalized_url) > 0.8
-        }) || seen_titles.iter().any(|existing_title: &String| {
-            title_similarity(existing_title, &normalized_title) > 0.9
+                || title_similarity(existing_title, &normalized_title) > 0.9
         });
         
-        if !is_duplicate {
-            seen_urls.insert(normalized_url);
-            seen_titles.insert(normalized_title);
-            unique_results.push(result);
+        match duplicate_of {
+            Some(index) => merge_duplicate(&mut unique_results[index], result),
+            None => {
+                seen.push((normalized_url, normalized_title));
+                unique_results.push(result);
+            }
         }
     }
     
     unique_results
 }
 
+/// Fold a duplicate into the result already kept: the richer of the two survives, and
+/// the engines behind both are recorded so agreement can boost the ranking
+fn merge_duplicate(kept: &mut EnhancedSearchResult, duplicate: EnhancedSearchResult) {
+    let mut sources = std::mem::take(&mut kept.sources);
+    for source in &duplicate.sources {
+        if !sources.contains(source) {
+            sources.push(source.clone());
+        }
+    }
+    let relevance_score = kept.relevance_score.max(duplicate.relevance_score);
+    let freshness_score = kept.freshness_score.max(duplicate.freshness_score);
+    
+    if result_richness(&duplicate) > result_richness(kept) {
+        *kept = duplicate;
+    }
+    kept.sources = sources;
+    kept.relevance_score = relevance_score;
+    kept.freshness_score = freshness_score;
+}
+
+/// Extracted content first, then snippet length
+fn result_richness(result: &EnhancedSearchResult) -> (usize, usize) {
+    (
+        result.content.as_ref().map_or(0, |content| content.len()),
+        result.snippet.as_ref().map_or(0, |snippet| snippet.len()),
+    )
+}
+
+/// Final score added for each engine beyond the first that returned a result
+const CONSENSUS_BOOST_PER_ENGINE: f64 = 0.05;
+const MAX_CONSENSUS_BOOST: f64 = 0.15;
+
+/// Independent engines agreeing on a result is a strong relevance signal
+fn consensus_boost(result: &EnhancedSearchResult) -> f64 {
+    let extra_engines = result.sources.len().saturating_sub(1);
+    (extra_engines as f64 * CONSENSUS_BOOST_PER_ENGINE).min(MAX_CONSENSUS_BOOST)
+}
+
 /// Diversify results to ensure variety in sources and perspectives
 fn diversify_results(mut results: Vec<EnhancedSearchResult>, config: &EnhancedWebSearchConfig) -> Vec<EnhancedSearchResult> {
     // Group by domain
@@ -1398,8 +2702,10 @@ async fn extract_content_with_adaptive_strategy(
     intent: &QueryIntent, 
     config: &EnhancedWebSearchConfig
 ) -> Result<String> {
-    let response = fetch_with_intelligent_retry(client, url, config).await?;
-    let html = response.text().await?;
+    let response = fetch_with_headers(client, url, &[], config).await?;
+    // Content past the first few hundred KB of markup is never reached, so don't download it
+    let max_bytes = config.max_content_length.saturating_mul(PAGE_BYTES_PER_CONTENT_CHAR);
+    let html = read_page_body(response, max_bytes).await?;
     let document = Html::parse_document(&html);
     
     // Intent-specific content selectors
@@ -1451,35 +2757,239 @@ async fn extract_content_with_adaptive_strategy(
     Err(anyhow!("No quality content found"))
 }
 
-/// Check if content meets quality standards
-fn is_high_quality_content(content: &str, _config: &EnhancedWebSearchConfig) -> bool {
-    if content.len() < 50 {
-        return false;
+/// Text handed to each [`ContentHeuristic`], tokenized once
+pub struct ContentSample<'a> {
+    pub text: &'a str,
+    pub lowercase: String,
+    pub words: Vec<&'a str>,
+}
+
+impl<'a> ContentSample<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            lowercase: text.to_lowercase(),
+            words: text.split_whitespace().collect(),
+        }
     }
-    
-    let words: Vec<&str> = content.split_whitespace().collect();
-    if words.len() < 15 {
-        return false;
+}
+
+/// One signal in content quality scoring
+pub trait ContentHeuristic: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Score in 0.0-1.0, or the reason the content should be rejected outright
+    fn check(&self, sample: &ContentSample) -> std::result::Result<f64, String>;
+}
+
+/// Outcome of scoring one page's content
+#[derive(Debug, Clone)]
+pub struct ContentQuality {
+    /// Mean of the heuristic scores
+    pub score: f64,
+    /// First heuristic that rejected the content, with its reason
+    pub rejection: Option<String>,
+}
+
+impl ContentQuality {
+    pub fn is_accepted(&self) -> bool {
+        self.rejection.is_none()
     }
-    
-    // Check word diversity
-    let unique_words: HashSet<&str> = words.iter().cloned().collect();
-    let diversity = unique_words.len() as f64 / words.len() as f64;
-    
-    if diversity < 0.3 {
-        return false;
+}
+
+/// Scores extracted page content with a list of heuristics and gates it on
+/// `min_content_quality_score`
+pub struct ContentQualityScorer {
+    heuristics: Vec<Box<dyn ContentHeuristic>>,
+    min_score: f64,
+}
+
+impl ContentQualityScorer {
+    /// The built-in heuristics, tuned by the config's content quality settings
+    pub fn from_config(config: &EnhancedWebSearchConfig) -> Self {
+        let mut scorer = Self {
+            heuristics: Vec::new(),
+            min_score: config.min_content_quality_score,
+        };
+        scorer = scorer
+            .with_heuristic(Box::new(LengthHeuristic { min_words: config.min_content_words }))
+            .with_heuristic(Box::new(DiversityHeuristic { min_diversity: config.min_content_word_diversity }))
+            .with_heuristic(Box::new(SpamPhraseHeuristic {
+                phrases: config.content_spam_phrases.iter().map(|p| p.to_lowercase()).collect(),
+            }));
+        if config.content_language_check {
+            scorer = scorer.with_heuristic(Box::new(LanguageHeuristic { language: config.language.clone() }));
+        }
+        scorer
     }
-    
-    // Check for spam patterns
-    let spam_patterns = [
-        "click here", "buy now", "limited time", "act now", "subscribe",
-        "advertisement", "sponsored", "affiliate", "cookie consent"
+
+    pub fn with_heuristic(mut self, heuristic: Box<dyn ContentHeuristic>) -> Self {
+        self.heuristics.push(heuristic);
+        self
+    }
+
+    pub fn evaluate(&self, content: &str) -> ContentQuality {
+        let sample = ContentSample::new(content);
+        let mut total = 0.0;
+        for heuristic in &self.heuristics {
+            match heuristic.check(&sample) {
+                Ok(score) => total += score.clamp(0.0, 1.0),
+                Err(reason) => {
+                    return ContentQuality {
+                        score: 0.0,
+                        rejection: Some(format!("{}: {}", heuristic.name(), reason)),
+                    }
+                }
+            }
+        }
+
+        let score = if self.heuristics.is_empty() { 1.0 } else { total / self.heuristics.len() as f64 };
+        let rejection = (score < self.min_score)
+            .then(|| format!("score {:.2} is below min_content_quality_score {:.2}", score, self.min_score));
+        ContentQuality { score, rejection }
+    }
+}
+
+/// Rejects content under `min_words`; longer pages score higher up to ~300 words
+struct LengthHeuristic {
+    min_words: usize,
+}
+
+impl ContentHeuristic for LengthHeuristic {
+    fn name(&self) -> &'static str {
+        "length"
+    }
+
+    fn check(&self, sample: &ContentSample) -> std::result::Result<f64, String> {
+        let words = sample.words.len();
+        if words < self.min_words {
+            return Err(format!("{} words, fewer than {}", words, self.min_words));
+        }
+        Ok(0.5 + 0.5 * (words as f64 / 300.0).min(1.0))
+    }
+}
+
+/// Rejects repetitive text (menus, tag clouds) by its share of distinct words
+struct DiversityHeuristic {
+    min_diversity: f64,
+}
+
+impl ContentHeuristic for DiversityHeuristic {
+    fn name(&self) -> &'static str {
+        "diversity"
+    }
+
+    fn check(&self, sample: &ContentSample) -> std::result::Result<f64, String> {
+        if sample.words.is_empty() {
+            return Err("no words".to_string());
+        }
+        let unique: HashSet<&str> = sample.words.iter().copied().collect();
+        let diversity = unique.len() as f64 / sample.words.len() as f64;
+        if diversity < self.min_diversity {
+            return Err(format!("{:.2} distinct word share, below {:.2}", diversity, self.min_diversity));
+        }
+        Ok(if self.min_diversity > 0.0 { (diversity / (2.0 * self.min_diversity)).min(1.0) } else { 1.0 })
+    }
+}
+
+/// Penalizes ad and banner phrases; three hits reject the content
+struct SpamPhraseHeuristic {
+    phrases: Vec<String>,
+}
+
+const SPAM_PHRASE_HITS_TO_REJECT: usize = 3;
+
+impl ContentHeuristic for SpamPhraseHeuristic {
+    fn name(&self) -> &'static str {
+        "spam"
+    }
+
+    fn check(&self, sample: &ContentSample) -> std::result::Result<f64, String> {
+        let hits: Vec<&str> = self.phrases.iter()
+            .filter(|phrase| !phrase.is_empty() && sample.lowercase.contains(phrase.as_str()))
+            .map(String::as_str)
+            .collect();
+        if hits.len() >= SPAM_PHRASE_HITS_TO_REJECT {
+            return Err(format!("contains {}", hits.join(", ")));
+        }
+        Ok(1.0 - hits.len() as f64 / SPAM_PHRASE_HITS_TO_REJECT as f64)
+    }
+}
+
+/// Rejects content confidently detected as another language than the search language
+struct LanguageHeuristic {
+    language: String,
+}
+
+impl ContentHeuristic for LanguageHeuristic {
+    fn name(&self) -> &'static str {
+        "language"
+    }
+
+    fn check(&self, sample: &ContentSample) -> std::result::Result<f64, String> {
+        // Only languages we can recognize are enforced; anything else passes
+        if !LANGUAGE_STOPWORDS.iter().any(|(code, _)| *code == self.language) {
+            return Ok(1.0);
+        }
+        match detect_language(sample) {
+            Some(detected) if detected != self.language => {
+                Err(format!("looks like '{}', expected '{}'", detected, self.language))
+            }
+            _ => Ok(1.0),
+        }
+    }
+}
+
+/// Frequent function words of the Latin-script languages the language check recognizes
+const LANGUAGE_STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "of", "to", "that", "it", "for", "with", "are", "this", "was"]),
+    ("de", &["der", "die", "und", "ist", "das", "nicht", "mit", "ein", "den", "zu", "auf", "sich"]),
+    ("fr", &["le", "les", "et", "est", "des", "une", "pour", "dans", "que", "pas", "qui", "sur"]),
+    ("es", &["el", "los", "y", "es", "que", "por", "una", "para", "con", "del", "las", "como"]),
+    ("it", &["il", "di", "che", "è", "per", "una", "sono", "della", "con", "non", "gli", "anche"]),
+    ("pt", &["o", "os", "que", "do", "da", "não", "uma", "para", "com", "por", "são", "mais"]),
+    ("nl", &["de", "het", "een", "en", "van", "is", "niet", "dat", "op", "voor", "zijn", "met"]),
+];
+
+/// Best guess at the language of a text, or `None` when no language clearly wins.
+/// Text that is mostly in a non-Latin script is reported by script ("cyrillic", "cjk", ...)
+fn detect_language(sample: &ContentSample) -> Option<&'static str> {
+    let letters: Vec<char> = sample.text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let non_latin = [
+        ("cyrillic", '\u{0400}'..='\u{04FF}'),
+        ("greek", '\u{0370}'..='\u{03FF}'),
+        ("arabic", '\u{0600}'..='\u{06FF}'),
+        ("hebrew", '\u{0590}'..='\u{05FF}'),
+        ("cjk", '\u{3040}'..='\u{9FFF}'),
+        ("hangul", '\u{AC00}'..='\u{D7AF}'),
     ];
-    let spam_count = spam_patterns.iter()
-        .filter(|&pattern| content.to_lowercase().contains(pattern))
-        .count();
-    
-    spam_count < 3
+    for (script, range) in non_latin {
+        let count = letters.iter().filter(|c| range.contains(*c)).count();
+        if count * 2 > letters.len() {
+            return Some(script);
+        }
+    }
+
+    let words: Vec<String> = sample.lowercase
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect();
+    let mut hits: Vec<(&'static str, usize)> = LANGUAGE_STOPWORDS.iter()
+        .map(|(code, stopwords)| (*code, words.iter().filter(|w| stopwords.contains(&w.as_str())).count()))
+        .collect();
+    hits.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    // Require stopwords to be common and one language to clearly lead
+    let (best, best_hits) = hits[0];
+    let runner_up = hits[1].1;
+    if best_hits * 20 >= words.len() && best_hits * 2 >= runner_up * 3 {
+        Some(best)
+    } else {
+        None
+    }
 }
 
 /// Check if result meets quality standards
@@ -1556,7 +3066,7 @@ fn extract_clean_text(text: &str) -> String {
         .join(" ")
 }
 
-/// Extract intelligent snippet based on intent
+/// Extract intelligent snippet based on intent, from the result block around the title link
 fn extract_intelligent_snippet(_document: &Html, element: scraper::ElementRef, intent: &QueryIntent) -> Option<String> {
     // Intent-specific snippet selectors
     let snippet_selectors = match intent {
@@ -1564,16 +3074,20 @@ fn extract_intelligent_snippet(_document: &Html, element: scraper::ElementRef, i
         QueryIntent::Academic => vec![".abstract", ".summary", ".excerpt", ".description"],
         _ => vec![".b_caption p", ".result__snippet", ".st", ".s", ".description", ".summary"]
     };
+    let headings = Selector::parse("h2, h3").ok()?;
     
-    if let Some(parent) = element.parent() {
-        if let Some(parent_element) = scraper::ElementRef::wrap(parent) {
-            for selector_str in &snippet_selectors {
-                if let Ok(selector) = Selector::parse(selector_str) {
-                    if let Some(snippet_elem) = parent_element.select(&selector).next() {
-                        let snippet = extract_clean_text(&snippet_elem.text().collect::<String>());
-                        if snippet.len() > 15 && snippet.len() < 400 {
-                            return Some(snippet);
-                        }
+    // The link sits in a heading, with the snippet a sibling of it or of its parent.
+    // An ancestor holding several headings is the result list, so stop before it.
+    for ancestor in element.ancestors().take(3).filter_map(scraper::ElementRef::wrap) {
+        if ancestor.select(&headings).count() > 1 {
+            break;
+        }
+        for selector_str in &snippet_selectors {
+            if let Ok(selector) = Selector::parse(selector_str) {
+                if let Some(snippet_elem) = ancestor.select(&selector).next() {
+                    let snippet = extract_clean_text(&snippet_elem.text().collect::<String>());
+                    if snippet.len() > 15 && snippet.len() < 400 {
+                        return Some(snippet);
                     }
                 }
             }
@@ -1582,12 +3096,12 @@ fn extract_intelligent_snippet(_document: &Html, element: scraper::ElementRef, i
     None
 }
 
-/// Clean DuckDuckGo redirect URLs
+/// Clean DuckDuckGo redirect URLs, dropping the `&rut=` tracking parameter after the target
 fn clean_duckduckgo_url(href: &str) -> Option<String> {
-    if href.starts_with("/l/?uddg=") {
-        urlencoding::decode(&href[9..]).ok().map(|s| s.to_string())
-    } else if href.starts_with("//duckduckgo.com/l/?uddg=") {
-        urlencoding::decode(&href[25..]).ok().map(|s| s.to_string())
+    let redirect = href.strip_prefix("/l/?uddg=").or_else(|| href.strip_prefix("//duckduckgo.com/l/?uddg="));
+    if let Some(target) = redirect {
+        let target = target.split('&').next().unwrap_or(target);
+        urlencoding::decode(target).ok().map(|s| s.to_string())
     } else if href.starts_with("http") {
         Some(href.to_string())
     } else {
@@ -1596,6 +3110,15 @@ fn clean_duckduckgo_url(href: &str) -> Option<String> {
 }
 
 /// Clean HTML entities from text
+/// Plain text of an HTML fragment such as `a <strong>b</strong>`, whitespace collapsed
+fn strip_html_tags(text: &str) -> String {
+    let fragment = Html::parse_fragment(text);
+    fragment.root_element().text().collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn clean_html_entities(text: &str) -> String {
     text.replace("&amp;", "&")
         .replace("&lt;", "<")
@@ -1685,7 +3208,11 @@ pub fn format_enhanced_search_results(results: &[EnhancedSearchResult], query: &
         
         result_text.push(format!("{}. 🔗 **{}**", index + 1, result.title));
         result_text.push(format!("   URL: {}", result.url));
-        result_text.push(format!("   Source: {} | Intent: {:?}", result.source, result.query_intent));
+        if result.sources.len() > 1 {
+            result_text.push(format!("   Sources: {} | Intent: {:?}", result.sources.join(", "), result.query_intent));
+        } else {
+            result_text.push(format!("   Source: {} | Intent: {:?}", result.source, result.query_intent));
+        }
         result_text.push(format!("   Scores: Relevance {:.2} | Authority {:.2} | Quality {:.2} | Final {:.2}", 
             result.relevance_score, result.authority_score, result.quality_score, result.final_score));
         
@@ -1714,4 +3241,244 @@ pub fn format_enhanced_search_results(results: &[EnhancedSearchResult], query: &
     }
     
     output.join("\n\n")
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUCKDUCKGO_HTML: &str = r##"<html><body><div class="results">
+        <div class="result results_links web-result"><div class="links_main result__body">
+            <h2 class="result__title"><a class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fdoc.rust-lang.org%2Fbook%2Fch04-01-what-is-ownership.html&amp;rut=abc">What is Ownership? - The Rust Programming Language</a></h2>
+            <a class="result__snippet" href="#">Ownership is a set of rules that govern how a Rust program manages memory.</a>
+        </div></div>
+        <div class="result results_links web-result"><div class="links_main result__body">
+            <h2 class="result__title"><a class="result__a" href="https://example.com/login">Login</a></h2>
+        </div></div>
+        <div class="result results_links web-result"><div class="links_main result__body">
+            <h2 class="result__title"><a class="result__a" href="https://blog.example.org/rust-ownership">Understanding ownership in Rust</a></h2>
+        </div></div>
+    </div></body></html>"##;
+
+    const BING_HTML: &str = r##"<html><body><ol id="b_results">
+        <li class="b_algo"><h2><a href="https://www.rust-lang.org/learn">Learn Rust - Rust Programming Language</a></h2>
+            <div class="b_caption"><p>A language empowering everyone to build reliable and efficient software.</p></div></li>
+        <li class="b_algo"><h2><a href="https://doc.rust-lang.org/std/">std - Rust standard library</a></h2></li>
+    </ol></body></html>"##;
+
+    const WIKIPEDIA_JSON: &str = r##"{"query": {"search": [
+        {"title": "Rust (programming language)", "pageid": 29414838,
+         "snippet": "<span class=\"searchmatch\">Rust</span> is a general-purpose programming language emphasizing performance &amp; safety"}
+    ]}}"##;
+
+    const STACK_EXCHANGE_JSON: &str = r##"{"items": [
+        {"title": "Why can&#39;t I borrow as mutable twice?", "link": "https://stackoverflow.com/questions/123/why-cant-i-borrow",
+         "score": 42, "answer_count": 3, "accepted_answer_id": 456, "tags": ["rust", "borrow-checker"],
+         "view_count": 9000, "last_activity_date": 1700000000,
+         "body": "<p>The compiler rejects <code>let a = &amp;mut x;</code> twice.</p>"},
+        {"link": "https://stackoverflow.com/questions/789/no-title"}
+    ], "has_more": false}"##;
+
+    const ARXIV_XML: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+    <feed xmlns="http://www.w3.org/2005/Atom">
+      <entry>
+        <id>http://arxiv.org/abs/2101.00001v1</id>
+        <published>2021-01-01T00:00:00Z</published>
+        <title>Ownership Types
+          for Safe Systems Programming</title>
+        <summary>We study ownership types and their use in systems languages.</summary>
+        <author><name>Ada Lovelace</name></author>
+        <author><name>Alan Turing</name></author>
+        <author><name>Grace Hopper</name></author>
+      </entry>
+    </feed>"##;
+
+    const REDDIT_HTML: &str = r##"<html><body>
+        <a data-testid="post-title" href="/r/rust/comments/abc/rust_vs_go_after_a_year/">Rust vs Go after a year in production</a>
+    </body></html>"##;
+
+    const SEARX_JSON: &str = r##"{"results": [
+        {"title": "Tokio tutorial", "url": "https://tokio.rs/tokio/tutorial", "content": "Tokio is an asynchronous runtime for the Rust programming language.", "score": 12.0}
+    ]}"##;
+
+    const GOOGLE_JSON: &str = r##"{"items": [
+        {"title": "The <b>Rust</b> Book", "link": "https://doc.rust-lang.org/book/", "snippet": "An introductory book about <b>Rust</b>."}
+    ]}"##;
+
+    const BRAVE_JSON: &str = r##"{"web": {"results": [
+        {"title": "Rust by Example", "url": "https://doc.rust-lang.org/rust-by-example/", "description": "A collection of runnable <strong>examples</strong>."}
+    ]}}"##;
+
+    fn fetcher(key: &str, body: &str) -> Arc<dyn Fetcher> {
+        Arc::new(FixtureFetcher::new().with_fixture(key, body))
+    }
+
+    async fn run(engine: &dyn SearchEngine, query: &str, intent: QueryIntent) -> Vec<EnhancedSearchResult> {
+        engine
+            .search(query, &intent, &EnhancedWebSearchConfig::default())
+            .await
+            .expect("search against fixture failed")
+    }
+
+    #[tokio::test]
+    async fn duckduckgo_decodes_redirects_and_skips_navigation_links() {
+        let engine = DuckDuckGoEngine::new(fetcher("html.duckduckgo.com", DUCKDUCKGO_HTML));
+        let results = run(&engine, "rust ownership", QueryIntent::General).await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "What is Ownership? - The Rust Programming Language");
+        assert_eq!(results[0].url, "https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html");
+        assert_eq!(
+            results[0].snippet.as_deref(),
+            Some("Ownership is a set of rules that govern how a Rust program manages memory.")
+        );
+        assert_eq!(results[0].source, "DuckDuckGo");
+        assert_eq!(results[1].url, "https://blog.example.org/rust-ownership");
+        // A result without a snippet must not borrow its neighbour's
+        assert_eq!(results[1].snippet, None);
+    }
+
+    #[tokio::test]
+    async fn bing_reads_title_url_and_caption() {
+        let engine = BingEngine::new(fetcher("www.bing.com", BING_HTML));
+        let results = run(&engine, "learn rust", QueryIntent::General).await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Learn Rust - Rust Programming Language");
+        assert_eq!(results[0].url, "https://www.rust-lang.org/learn");
+        assert_eq!(
+            results[0].snippet.as_deref(),
+            Some("A language empowering everyone to build reliable and efficient software.")
+        );
+        assert_eq!(results[0].source, "Bing");
+        assert_eq!(results[1].snippet, None);
+    }
+
+    #[tokio::test]
+    async fn wikipedia_builds_article_urls_and_strips_search_markup() {
+        let engine = WikipediaEngine::new(fetcher("wikipedia.org/w/api.php", WIKIPEDIA_JSON));
+        let results = run(&engine, "rust programming language", QueryIntent::General).await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust (programming language)");
+        assert_eq!(results[0].url, "https://en.wikipedia.org/wiki/Rust%20%28programming%20language%29");
+        assert_eq!(
+            results[0].snippet.as_deref(),
+            Some("Rust is a general-purpose programming language emphasizing performance & safety")
+        );
+        assert_eq!(results[0].source, "Wikipedia");
+    }
+
+    #[tokio::test]
+    async fn stack_exchange_summarizes_votes_answers_and_tags() {
+        let engine = StackOverflowEngine::new(fetcher("api.stackexchange.com", STACK_EXCHANGE_JSON));
+        let results = run(&engine, "borrow mutable twice", QueryIntent::Technical).await;
+
+        // Items without a title are skipped
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Why can't I borrow as mutable twice?");
+        assert_eq!(results[0].url, "https://stackoverflow.com/questions/123/why-cant-i-borrow");
+        assert_eq!(
+            results[0].snippet.as_deref(),
+            Some("Score 42 · 3 answers (accepted) · [rust, borrow-checker] — The compiler rejects let a = &mut x; twice.")
+        );
+        assert_eq!(results[0].source, "Stack Overflow");
+        assert_eq!(results[0].content_type.as_deref(), Some("qa_forum"));
+    }
+
+    #[tokio::test]
+    async fn stack_exchange_reports_api_errors() {
+        let engine = StackOverflowEngine::new(fetcher(
+            "api.stackexchange.com",
+            r##"{"error_id": 502, "error_message": "too many requests from this IP"}"##,
+        ));
+        let error = engine
+            .search("borrow", &QueryIntent::Technical, &EnhancedWebSearchConfig::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("too many requests"));
+    }
+
+    #[tokio::test]
+    async fn arxiv_reads_atom_entries() {
+        let engine = ArxivEngine::new(fetcher("export.arxiv.org", ARXIV_XML));
+        let results = run(&engine, "ownership types", QueryIntent::Academic).await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Ownership Types for Safe Systems Programming");
+        assert_eq!(results[0].url, "https://arxiv.org/abs/2101.00001v1");
+        assert_eq!(
+            results[0].snippet.as_deref(),
+            Some("Ada Lovelace et al. (2021-01-01) — We study ownership types and their use in systems languages.")
+        );
+        assert_eq!(results[0].source, "arXiv");
+    }
+
+    #[tokio::test]
+    async fn reddit_makes_post_links_absolute() {
+        let engine = RedditEngine::new(fetcher("www.reddit.com/search", REDDIT_HTML));
+        let results = run(&engine, "rust vs go discussion", QueryIntent::Comparison).await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust vs Go after a year in production");
+        assert_eq!(results[0].url, "https://www.reddit.com/r/rust/comments/abc/rust_vs_go_after_a_year/");
+        assert_eq!(results[0].snippet, None);
+        assert_eq!(results[0].source, "Reddit");
+    }
+
+    #[tokio::test]
+    async fn searx_reads_json_results() {
+        let engine = SearxEngine::new(fetcher("searx.example.org/search", SEARX_JSON), "https://searx.example.org/");
+        let results = run(&engine, "tokio tutorial", QueryIntent::Tutorial).await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Tokio tutorial");
+        assert_eq!(results[0].url, "https://tokio.rs/tokio/tutorial");
+        assert_eq!(results[0].snippet.as_deref(), Some("Tokio is an asynchronous runtime for the Rust programming language."));
+        assert_eq!(results[0].source, "SearXNG");
+    }
+
+    #[tokio::test]
+    async fn web_apis_strip_highlighting_from_titles_and_snippets() {
+        let google = GoogleCseEngine::new(fetcher("www.googleapis.com/customsearch", GOOGLE_JSON), "key", "cx");
+        let results = run(&google, "rust book", QueryIntent::General).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "The Rust Book");
+        assert_eq!(results[0].url, "https://doc.rust-lang.org/book/");
+        assert_eq!(results[0].snippet.as_deref(), Some("An introductory book about Rust."));
+        assert_eq!(results[0].source, "Google");
+
+        let brave = BraveEngine::new(fetcher("api.search.brave.com", BRAVE_JSON), "token");
+        let results = run(&brave, "rust examples", QueryIntent::General).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust by Example");
+        assert_eq!(results[0].url, "https://doc.rust-lang.org/rust-by-example/");
+        assert_eq!(results[0].snippet.as_deref(), Some("A collection of runnable examples."));
+        assert_eq!(results[0].source, "Brave");
+    }
+
+    #[tokio::test]
+    async fn missing_fixture_is_an_error() {
+        let engine = BingEngine::new(fetcher("html.duckduckgo.com", DUCKDUCKGO_HTML));
+        let error = engine
+            .search("rust", &QueryIntent::General, &EnhancedWebSearchConfig::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("No fixture for https://www.bing.com/search"));
+    }
+
+    #[tokio::test]
+    async fn single_engine_search_applies_domain_filters() {
+        let config = EnhancedWebSearchConfig {
+            cache_results: false,
+            max_scrape_urls: 0,
+            blocked_domains: vec!["example.org".to_string()],
+            ..EnhancedWebSearchConfig::default()
+        };
+        let engine = EnhancedWebSearchEngine::with_fetcher(config, fetcher("html.duckduckgo.com", DUCKDUCKGO_HTML))
+            .expect("engine");
+        let results = engine.search_single_engine("duckduckgo", "rust ownership").await.expect("search");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html");
+    }
+}
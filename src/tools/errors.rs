@@ -1,3 +1,4 @@
+use colored::Colorize;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -282,6 +283,64 @@ impl Default for RetryConfig {
     }
 }
 
+impl RetryConfig {
+    /// Backoff before retry `retry` (1-based), capped at `max_delay`
+    pub fn delay_for(&self, retry: u32) -> std::time::Duration {
+        let factor = self.backoff_multiplier.powi(retry.saturating_sub(1) as i32);
+        let mut millis = (self.base_delay.as_millis() as f64 * factor).min(self.max_delay.as_millis() as f64);
+        if self.jitter {
+            use rand::Rng;
+            millis *= rand::thread_rng().gen_range(0.8..1.2);
+        }
+        std::time::Duration::from_millis(millis as u64)
+    }
+}
+
+/// 5xx are transient; a 4xx is the caller's fault and won't change on retry
+pub fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+}
+
+/// Send an HTTP request with exponential backoff, retrying connection errors,
+/// timeouts and retryable statuses. `build` is called once per attempt (0-based)
+/// so callers can vary per-attempt settings such as timeouts. The final response
+/// is returned whatever its status. With a `label`, retries are reported on stderr.
+pub async fn send_with_retry<F>(
+    config: &RetryConfig,
+    label: Option<&str>,
+    build: F,
+) -> Result<reqwest::Response, reqwest::Error>
+where
+    F: Fn(u32) -> reqwest::RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        let reason = match build(attempt).send().await {
+            Ok(response) if attempt < config.max_retries && is_retryable_status(response.status()) => {
+                response.status().to_string()
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if attempt < config.max_retries && (e.is_connect() || e.is_timeout()) => {
+                if e.is_timeout() { "timed out".to_string() } else { "connection failed".to_string() }
+            }
+            Err(e) => return Err(e),
+        };
+
+        attempt += 1;
+        if let Some(label) = label {
+            eprintln!(
+                "{} {} {}, retrying ({}/{})…",
+                "⚠️".yellow(),
+                label,
+                reason,
+                attempt,
+                config.max_retries
+            );
+        }
+        tokio::time::sleep(config.delay_for(attempt)).await;
+    }
+}
+
 pub struct RetryExecutor {
     config: RetryConfig,
 }
@@ -1,26 +1,165 @@
 use colored::Colorize;
-use regex::Regex;
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
 use std::fs;
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
-use walkdir::WalkDir;
 
-use super::core::{EditOperation, ToolExecutor, ToolResult};
-use super::search::{enhanced_file_search, ErrorStrategy, SearchQuery, ToolChain};
-use super::web_search::{WebSearchEngine, format_search_results, get_fallback_resources};
-use super::core::WebSearchConfig;
+use super::core::{ContentSearchOptions, EditOperation, FileReadOptions, PerfTestOptions, ToolExecutor, ToolResult};
+use super::diff::confirm_file_change;
+use super::permissions::CommandPolicy;
+use super::search::{
+    enhanced_file_search, parallel_file_walk, resolve_search_threads, ErrorStrategy, SearchQuery,
+    ToolChain,
+};
+use super::web_search::{WebSearchEngine, citations_for, format_search_results, get_fallback_resources};
+use super::core::{SearchMetadata, WebSearchConfig, WebSearchResult};
 use super::enhanced_websearch::{EnhancedWebSearchEngine, EnhancedWebSearchConfig, format_enhanced_search_results};
 
+// Keeps one noisy file from crowding the rest out of the results
+const MAX_MATCHED_LINES_PER_FILE: usize = 25;
+const MAX_LINE_CHARS: usize = 300;
+const FILE_SEARCH_MAX_RESULTS: usize = 50;
+const HEX_DUMP_WIDTH: usize = 16;
+
+/// Why a file could not be treated as text
+enum NotText {
+    InvalidUtf8,
+}
+
+fn file_operation_failed(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        metadata: None,
+        web_search_result: None,
+    }
+}
+
+/// Deleting the working directory or the repository's history is never what was meant
+fn refuse_dangerous_delete(path: &Path) -> Result<(), String> {
+    let current_dir = std::env::current_dir().map_err(|_| "Cannot determine current directory")?;
+    if path == current_dir {
+        return Err("Refusing to delete the working directory itself".to_string());
+    }
+    if path.components().any(|component| component.as_os_str() == ".git") {
+        return Err("Refusing to delete the .git directory or anything in it".to_string());
+    }
+    Ok(())
+}
+
+/// Copy `from` into a new directory `to`, returning the number of files copied
+/// and of symlinks skipped (they could point outside the working directory)
+fn copy_dir_recursive(from: &Path, to: &Path) -> std::io::Result<(usize, usize)> {
+    fs::create_dir(to)?;
+    let (mut files, mut skipped) = (0, 0);
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            skipped += 1;
+        } else if file_type.is_dir() {
+            let (nested_files, nested_skipped) = copy_dir_recursive(&entry.path(), &target)?;
+            files += nested_files;
+            skipped += nested_skipped;
+        } else {
+            fs::copy(entry.path(), &target)?;
+            files += 1;
+        }
+    }
+    Ok((files, skipped))
+}
+
+/// Text of a file [`crate::workspace::is_binary`] has already let through
+fn decode_text(bytes: Vec<u8>, lossy: bool) -> Result<String, NotText> {
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(error) if lossy => Ok(String::from_utf8_lossy(error.as_bytes()).into_owned()),
+        Err(_) => Err(NotText::InvalidUtf8),
+    }
+}
+
+/// `xxd`-style dump: offset, hex bytes, then printable ASCII
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    for (row, chunk) in bytes.chunks(HEX_DUMP_WIDTH).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+            .collect();
+        output.push_str(&format!(
+            "{:08x}  {:<width$}  |{}|\n",
+            row * HEX_DUMP_WIDTH,
+            hex.join(" "),
+            ascii,
+            width = HEX_DUMP_WIDTH * 3 - 1
+        ));
+    }
+    output.trim_end().to_string()
+}
+
+/// Kill a child started by [`ToolExecutor::stream_process`] along with anything it spawned
+async fn kill_process_group(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: only sends a signal; the child leads its own process group
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+    let _ = child.kill().await;
+}
+
+fn truncate_line(line: &str) -> String {
+    let line = line.trim_end();
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+fn highlight_matches(regex: &Regex, text: &str) -> String {
+    let mut highlighted = String::with_capacity(text.len());
+    let mut last = 0;
+    for found in regex.find_iter(text) {
+        highlighted.push_str(&text[last..found.start()]);
+        highlighted.push_str(&found.as_str().red().bold().to_string());
+        last = found.end();
+    }
+    highlighted.push_str(&text[last..]);
+    highlighted
+}
+
 impl ToolExecutor {
     // Enhanced web search implementation using the new intelligent system
     pub async fn web_search(&self, query: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
-        let config = EnhancedWebSearchConfig::default();
-        let search_engine = EnhancedWebSearchEngine::new(config);
+        let search_engine = match EnhancedWebSearchEngine::new(self.enhanced_search_config()) {
+            Ok(engine) => engine,
+            Err(e) => return Ok(self.web_client_error(e)),
+        };
         
+        let started = std::time::Instant::now();
         match search_engine.intelligent_search(query).await {
             Ok(results) => {
                 let search_success = !results.is_empty();
                 let final_output = format_enhanced_search_results(&results, query);
+                let engines_used: std::collections::HashSet<&String> = results.iter().map(|r| &r.source).collect();
+                // The sources the summary cites, see `crate::citations`
+                let items: Vec<_> = results.iter().map(|r| r.to_result_item()).collect();
+                let search_result = WebSearchResult {
+                    query_used: query.to_string(),
+                    citations: citations_for(&items),
+                    results: items,
+                    search_metadata: SearchMetadata {
+                        total_searches_performed: engines_used.len(),
+                        search_queries_used: vec![query.to_string()],
+                        timestamp: std::time::SystemTime::now(),
+                        processing_time_ms: started.elapsed().as_millis() as u64,
+                    },
+                };
                 
                 // Count results with content
                 let content_count = results.iter().filter(|r| r.content.is_some()).count();
@@ -39,12 +178,12 @@ impl ToolExecutor {
                         "query_intent": format!("{:?}", results.first().map(|r| &r.query_intent).unwrap_or(&super::enhanced_websearch::QueryIntent::General)),
                         "total_results": results.len(),
                         "results_with_content": content_count,
-                        "search_engines_used": results.iter().map(|r| &r.source).collect::<std::collections::HashSet<_>>().into_iter().collect::<Vec<_>>(),
+                        "search_engines_used": engines_used.into_iter().collect::<Vec<_>>(),
                         "average_relevance_score": avg_relevance,
                         "average_authority_score": if !results.is_empty() { results.iter().map(|r| r.authority_score).sum::<f64>() / results.len() as f64 } else { 0.0 },
                         "average_quality_score": if !results.is_empty() { results.iter().map(|r| r.quality_score).sum::<f64>() / results.len() as f64 } else { 0.0 }
                     })),
-                    web_search_result: None,
+                    web_search_result: Some(search_result),
                 })
             }
             Err(e) => {
@@ -71,10 +210,66 @@ impl ToolExecutor {
         }
     }
 
+    /// Enhanced search settings from the config file and environment (API keys, proxies, locale)
+    pub fn enhanced_search_config(&self) -> EnhancedWebSearchConfig {
+        let web = self.get_web_request_settings();
+        let (language, region) = self.get_search_locale();
+        let (max_concurrent_scrapes, scrape_timeout_seconds) = self.get_scrape_limits();
+        let quality = self.get_content_quality_settings();
+        let (allowed_domains, blocked_domains) = self.get_search_domain_filters();
+        EnhancedWebSearchConfig {
+            language,
+            region,
+            max_concurrent_scrapes,
+            scrape_timeout_seconds,
+            total_search_deadline_secs: self.get_total_search_deadline_secs(),
+            min_content_quality_score: quality.min_score,
+            min_content_words: quality.min_words,
+            min_content_word_diversity: quality.min_word_diversity,
+            content_spam_phrases: quality.spam_phrases,
+            content_language_check: quality.language_check,
+            google_api_key: self.get_api_key("google_api_key", "GOOGLE_API_KEY"),
+            google_cse_id: self.get_api_key("google_cse_id", "GOOGLE_CSE_ID"),
+            brave_api_key: self.get_api_key("brave_api_key", "BRAVE_API_KEY"),
+            http_proxy: web.http_proxy,
+            https_proxy: web.https_proxy,
+            default_headers: web.headers,
+            rate_limits: self.get_search_rate_limits(),
+            enabled_engines: self.get_search_engines(),
+            allowed_domains,
+            blocked_domains,
+            ..EnhancedWebSearchConfig::default()
+        }
+    }
+
+    /// Legacy web search settings with the configured proxies and headers
+    fn web_search_config(&self) -> WebSearchConfig {
+        let web = self.get_web_request_settings();
+        WebSearchConfig {
+            http_proxy: web.http_proxy,
+            https_proxy: web.https_proxy,
+            default_headers: web.headers,
+            ..WebSearchConfig::default()
+        }
+    }
+
+    /// Failure result when the HTTP client can't be built (e.g. malformed proxy URL)
+    fn web_client_error(&self, error: anyhow::Error) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: format!("{} Web request configuration error: {}", "✗".red(), error),
+            error: Some(error.to_string()),
+            metadata: None,
+            web_search_result: None,
+        }
+    }
+
     // Web scraping implementation using the new robust system
     pub async fn web_scrape(&self, url: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
-        let config = WebSearchConfig::default();
-        let search_engine = WebSearchEngine::new(config);
+        let search_engine = match WebSearchEngine::new(self.web_search_config()) {
+            Ok(engine) => engine,
+            Err(e) => return Ok(self.web_client_error(e)),
+        };
         
         match search_engine.extract_page_content(url).await {
             Ok(content) => {
@@ -125,54 +320,50 @@ impl ToolExecutor {
             search_dir.blue()
         );
 
-        // Use synchronous fuzzy matching implementation
-        let mut found_files = Vec::new();
-        let search_path = std::path::Path::new(search_dir);
-
+        let started = std::time::Instant::now();
+        let threads = self.get_file_search_threads();
         let pattern_lower = pattern.to_lowercase();
 
-        for entry in WalkDir::new(search_path).follow_links(false) {
-            let entry = entry?;
-            if entry.file_type().is_file() {
-                let path = entry.path();
-
+        // Score the filename and the full path (for directory matches), keeping the better one
+        let (mut found_files, stopped_early) = parallel_file_walk(
+            Path::new(search_dir),
+            threads,
+            Some(FILE_SEARCH_MAX_RESULTS),
+            |path| {
                 // Skip ignored files/directories
                 if self.should_ignore_path(path) {
-                    continue;
-                }
-
-                // Check filename for fuzzy match
-                if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                    if let Some(score) =
-                        self.fuzzy_match_sync(&pattern_lower, &filename.to_lowercase())
-                    {
-                        found_files.push((path.to_path_buf(), score));
-                    }
+                    return None;
                 }
+                let filename = path.file_name().and_then(|n| n.to_str())?.to_lowercase();
+                let filename_score = self.fuzzy_match_sync(&pattern_lower, &filename);
+                let path_score =
+                    self.fuzzy_match_sync(&pattern_lower, &path.to_string_lossy().to_lowercase());
+                let score = match (filename_score, path_score) {
+                    (Some(a), Some(b)) => a.max(b),
+                    (score, None) | (None, score) => score?,
+                };
+                Some(((path.to_path_buf(), score), filename.contains(&pattern_lower)))
+            },
+        );
 
-                // Also check full path for better directory matching
-                let full_path = path.to_string_lossy();
-                if let Some(score) =
-                    self.fuzzy_match_sync(&pattern_lower, &full_path.to_lowercase())
-                {
-                    // Update score if this is better than filename match
-                    if let Some(existing) = found_files.iter_mut().find(|(p, _)| p == path) {
-                        if score > existing.1 {
-                            existing.1 = score;
-                        }
-                    } else {
-                        found_files.push((path.to_path_buf(), score));
-                    }
-                }
+        println!(
+            "{} Searched in {:.2}s with {} thread(s){}",
+            "⏱".cyan(),
+            started.elapsed().as_secs_f64(),
+            resolve_search_threads(threads),
+            if stopped_early {
+                format!(", stopped after {} strong matches", FILE_SEARCH_MAX_RESULTS)
+            } else {
+                String::new()
             }
-        }
+        );
 
         // Sort by score (descending)
         found_files.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
         // Format output
         let mut output = Vec::new();
-        for (path, score) in found_files.iter().take(50) {
+        for (path, score) in found_files.iter().take(FILE_SEARCH_MAX_RESULTS) {
             output.push(format!("{} (score: {:.2})", path.display(), score));
         }
 
@@ -207,6 +398,7 @@ impl ToolExecutor {
             search_content,
             search_filenames: true,
             max_results,
+            threads: self.get_file_search_threads(),
             ..Default::default()
         };
 
@@ -329,6 +521,14 @@ impl ToolExecutor {
     }
 
     pub fn file_read(&self, path: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        self.file_read_with_options(path, &FileReadOptions::default())
+    }
+
+    pub fn file_read_with_options(
+        &self,
+        path: &str,
+        options: &FileReadOptions,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
         println!("{} Reading file: {}", "📖".cyan(), path.yellow());
 
         // Validate path to prevent directory traversal
@@ -345,7 +545,64 @@ impl ToolExecutor {
             }
         };
 
-        match fs::read_to_string(&validated_path) {
+        // With --head only that much is read, however large the file is
+        let read = fs::File::open(&validated_path).and_then(|file| {
+            let size = file.metadata()?.len() as usize;
+            let limit = options.head.map_or(u64::MAX, |head| head as u64);
+            let mut bytes = Vec::new();
+            file.take(limit).read_to_end(&mut bytes)?;
+            Ok((size, bytes))
+        });
+        let (size, mut bytes) = match read {
+            Ok(read) => read,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                    metadata: None,
+                    web_search_result: None,
+                });
+            }
+        };
+        let binary = crate::workspace::is_binary(&validated_path).unwrap_or(false);
+
+        let binary_result = |output: String, kind: &str| ToolResult {
+            success: true,
+            output,
+            error: None,
+            metadata: Some(serde_json::json!({
+                "operation": "file_read",
+                "path": path,
+                "size": size,
+                "content": kind
+            })),
+            web_search_result: None,
+        };
+
+        if options.hex || (options.head.is_some() && binary) {
+            return Ok(binary_result(hex_dump(&bytes), "hex"));
+        }
+        if binary {
+            return Ok(binary_result(
+                format!(
+                    "Binary file, {} bytes, skipped (use --hex or --head N to inspect it)",
+                    size
+                ),
+                "binary",
+            ));
+        }
+
+        // --head can cut a multi-byte character in half; drop the partial tail
+        if options.head.is_some_and(|head| head < size) {
+            if let Err(error) = std::str::from_utf8(&bytes) {
+                if error.error_len().is_none() {
+                    bytes.truncate(error.valid_up_to());
+                }
+            }
+        }
+
+        match decode_text(bytes, options.lossy) {
             Ok(content) => Ok(ToolResult {
                 success: true,
                 output: content,
@@ -353,13 +610,13 @@ impl ToolExecutor {
                 metadata: None,
                 web_search_result: None,
             }),
-            Err(e) => Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(e.to_string()),
-                metadata: None,
-                web_search_result: None,
-            }),
+            Err(NotText::InvalidUtf8) => Ok(binary_result(
+                format!(
+                    "Not valid UTF-8, {} bytes, skipped (use --lossy to read it anyway, or --hex)",
+                    size
+                ),
+                "non-utf8",
+            )),
         }
     }
 
@@ -384,6 +641,32 @@ impl ToolExecutor {
             }
         };
 
+        let existing_content = fs::read_to_string(&validated_path).ok();
+        if !confirm_file_change(
+            path,
+            existing_content.as_deref(),
+            content,
+            self.is_file_write_confirmation_enabled(),
+        )? {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Write cancelled by user".to_string()),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
+        if let Err(e) = self.backup_before_write(&validated_path) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to back up {} before writing: {}", path, e)),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
         if let Some(parent) = validated_path.parent() {
             fs::create_dir_all(parent)?;
         }
@@ -406,6 +689,133 @@ impl ToolExecutor {
         }
     }
 
+    /// Move or rename a file or directory. Both paths go through `validate_path`,
+    /// an existing destination directory receives the source by name, and an
+    /// existing destination file is never replaced.
+    pub fn file_move(&self, from: &str, to: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Moving {} to {}", "🚚".cyan(), from.yellow(), to.yellow());
+
+        let destination = match self.transfer_destination(from, to) {
+            Ok(destination) => destination,
+            Err(error) => return Ok(file_operation_failed(error)),
+        };
+        match fs::rename(from, &destination) {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!("Moved {} to {}", from, destination.display()),
+                error: None,
+                metadata: None,
+                web_search_result: None,
+            }),
+            Err(e) => Ok(file_operation_failed(format!("Could not move {}: {}", from, e))),
+        }
+    }
+
+    /// Copy a file, or a directory with everything in it, under the same rules as `file_move`
+    pub fn file_copy(&self, from: &str, to: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Copying {} to {}", "📄".cyan(), from.yellow(), to.yellow());
+
+        let destination = match self.transfer_destination(from, to) {
+            Ok(destination) => destination,
+            Err(error) => return Ok(file_operation_failed(error)),
+        };
+        let copied = if Path::new(from).is_dir() {
+            copy_dir_recursive(Path::new(from), &destination)
+        } else {
+            fs::copy(from, &destination).map(|_| (1, 0))
+        };
+        match copied {
+            Ok((files, skipped)) => Ok(ToolResult {
+                success: true,
+                output: if skipped > 0 {
+                    format!(
+                        "Copied {} to {} ({} file(s), {} symlink(s) skipped)",
+                        from,
+                        destination.display(),
+                        files,
+                        skipped
+                    )
+                } else {
+                    format!("Copied {} to {} ({} file(s))", from, destination.display(), files)
+                },
+                error: None,
+                metadata: None,
+                web_search_result: None,
+            }),
+            Err(e) => Ok(file_operation_failed(format!("Could not copy {}: {}", from, e))),
+        }
+    }
+
+    /// Delete a file, or a directory when `recursive` is set. The working
+    /// directory itself and `.git` are refused. Files are backed up first, so
+    /// `tool file restore` can bring them back; directories are not.
+    pub fn file_delete(&self, path: &str, recursive: bool) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Deleting: {}", "🗑".cyan(), path.yellow());
+
+        let is_dir = match self.check_file_delete(path, recursive) {
+            Ok(is_dir) => is_dir,
+            Err(error) => return Ok(file_operation_failed(error)),
+        };
+        // The link itself is removed, not what it points to
+        let target = Path::new(path);
+        let removed = if is_dir {
+            fs::remove_dir_all(target)
+        } else {
+            if let Err(e) = self.backup_before_write(target) {
+                return Ok(file_operation_failed(format!("Failed to back up {} before deleting: {}", path, e)));
+            }
+            fs::remove_file(target)
+        };
+        match removed {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!("Deleted {}", path),
+                error: None,
+                metadata: None,
+                web_search_result: None,
+            }),
+            Err(e) => Ok(file_operation_failed(format!("Could not delete {}: {}", path, e))),
+        }
+    }
+
+    /// Why `file_delete` would refuse `path`, checked before asking for approval.
+    /// Returns whether it is a directory.
+    pub fn check_file_delete(&self, path: &str, recursive: bool) -> Result<bool, String> {
+        let validated_path = self.validate_path(path)?;
+        let metadata = fs::symlink_metadata(path).map_err(|_| format!("{} does not exist", path))?;
+        refuse_dangerous_delete(&validated_path)?;
+        if metadata.is_dir() && !recursive {
+            return Err(format!(
+                "{} is a directory; pass --recursive to delete it and everything in it",
+                path
+            ));
+        }
+        Ok(metadata.is_dir())
+    }
+
+    /// Validate both sides of a move or copy and work out the final destination path
+    fn transfer_destination(&self, from: &str, to: &str) -> Result<std::path::PathBuf, String> {
+        let source = self.validate_path(from)?;
+        if fs::symlink_metadata(from).is_err() {
+            return Err(format!("{} does not exist", from));
+        }
+        self.validate_path(to)?;
+
+        let mut destination = std::path::PathBuf::from(to);
+        if destination.is_dir() {
+            let name = Path::new(from).file_name().ok_or_else(|| format!("{} has no file name", from))?;
+            destination.push(name);
+        }
+        if fs::symlink_metadata(&destination).is_ok() {
+            return Err(format!("{} already exists", destination.display()));
+        }
+        // Copying a directory into itself would never finish
+        if source.is_dir() && self.validate_path(&destination.to_string_lossy()).is_ok_and(|d| d.starts_with(&source)) {
+            return Err(format!("Cannot move or copy {} into itself", from));
+        }
+        Ok(destination)
+    }
+
     pub fn file_edit(
         &self,
         path: &str,
@@ -500,6 +910,31 @@ impl ToolExecutor {
             }
         };
 
+        if !confirm_file_change(
+            path,
+            Some(&current_content),
+            &new_content,
+            self.is_file_write_confirmation_enabled(),
+        )? {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Edit cancelled by user".to_string()),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
+        if let Err(e) = self.backup_before_write(Path::new(path)) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to back up {} before editing: {}", path, e)),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
         match std::fs::write(path, &new_content) {
             Ok(_) => {
                 let operation_desc = match operation {
@@ -542,6 +977,7 @@ impl ToolExecutor {
         &self,
         pattern: &str,
         directory: Option<&str>,
+        options: &ContentSearchOptions,
     ) -> Result<ToolResult, Box<dyn std::error::Error>> {
         let search_dir = directory.unwrap_or(".");
         println!(
@@ -551,110 +987,161 @@ impl ToolExecutor {
             search_dir.blue()
         );
 
-        let regex = Regex::new(pattern)?;
-        let mut results = Vec::new();
+        let expression = if options.whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern.to_string()
+        };
+        let regex = RegexBuilder::new(&expression)
+            .case_insensitive(!options.case_sensitive)
+            .build()?;
+
+        // (path, match count, matching line indexes, content)
+        let mut files: Vec<(String, usize, Vec<usize>, String)> = Vec::new();
+        let mut skipped_files = 0;
+        for entry in WalkBuilder::new(search_dir).follow_links(true).build().flatten() {
+            if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+                continue;
+            }
+            if crate::workspace::is_binary(entry.path()).unwrap_or(true) {
+                skipped_files += 1;
+                continue;
+            }
+            let Ok(bytes) = fs::read(entry.path()) else {
+                continue;
+            };
+            let Ok(content) = decode_text(bytes, options.lossy) else {
+                skipped_files += 1;
+                continue;
+            };
 
-        for entry in WalkDir::new(search_dir).follow_links(true) {
-            let entry = entry?;
-            if entry.file_type().is_file() {
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    for (line_num, line) in content.lines().enumerate() {
-                        if regex.is_match(line) {
-                            results.push(format!(
-                                "{}:{}: {}",
-                                entry.path().display(),
-                                line_num + 1,
-                                line.trim()
-                            ));
-                        }
-                    }
+            let mut count = 0;
+            let mut matched_lines = Vec::new();
+            for (index, line) in content.lines().enumerate() {
+                let line_matches = regex.find_iter(line).count();
+                if line_matches > 0 {
+                    count += line_matches;
+                    matched_lines.push(index);
                 }
             }
+            if count > 0 {
+                files.push((entry.path().display().to_string(), count, matched_lines, content));
+            }
         }
 
-        Ok(ToolResult {
-            success: true,
-            output: if results.is_empty() {
-                "No content found matching the pattern".to_string()
-            } else {
-                results.join("\n")
-            },
-            error: None,
-            metadata: None,
-            web_search_result: None,
-        })
-    }
-
-    fn validate_command(&self, command: &str) -> Result<(), String> {
-        // Check for dangerous patterns that could lead to command injection
-        let dangerous_patterns = [
-            "|",
-            "&&",
-            "||",
-            ";",
-            "`",
-            "$(",
-            "&",
-            ">",
-            "<",
-            ">>",
-            "<<",
-            "rm -rf /",
-            "rm -rf /*",
-            ":(){ :|:& };:",
-            "curl",
-            "wget",
-            "nc",
-            "netcat",
-        ];
-
-        // Check for SQL injection patterns
-        let sql_patterns = [
-            "DROP TABLE",
-            "DELETE FROM",
-            "UPDATE",
-            "INSERT INTO",
-            "CREATE TABLE",
-            "ALTER TABLE",
-        ];
+        let skipped_note = match skipped_files {
+            0 => String::new(),
+            count => format!(
+                " ({} binary or non-UTF-8 file(s) skipped{})",
+                count,
+                if options.lossy { "" } else { "; --lossy searches the non-UTF-8 ones" }
+            ),
+        };
 
-        // Check for path traversal patterns
-        let path_patterns = [
-            "../", "..\\", "/etc/", "/var/", "/usr/", "/home/", "C:\\", "~/",
-        ];
+        if files.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("No content found matching the pattern{}", skipped_note),
+                error: None,
+                metadata: Some(serde_json::json!({
+                    "operation": "content_search",
+                    "pattern": pattern,
+                    "total_matches": 0,
+                    "files_matched": 0,
+                    "skipped_binary_files": skipped_files,
+                    "files": []
+                })),
+                web_search_result: None,
+            });
+        }
 
-        let command_lower = command.to_lowercase();
+        files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let total_matches: usize = files.iter().map(|file| file.1).sum();
 
-        // Check dangerous command patterns
-        for pattern in &dangerous_patterns {
-            if command_lower.contains(pattern) {
-                return Err(format!(
-                    "Command contains potentially dangerous pattern: {}",
-                    pattern
-                ));
-            }
+        let mut output = format!("Found {} match(es) in {} file(s)", total_matches, files.len());
+        if files.len() > options.max_files {
+            output.push_str(&format!(", showing the top {}", options.max_files));
         }
-
-        // Check SQL injection patterns
-        for pattern in &sql_patterns {
-            if command_lower.contains(&pattern.to_lowercase()) {
-                return Err(format!(
-                    "Command contains SQL injection pattern: {}",
-                    pattern
-                ));
+        output.push_str(&skipped_note);
+        output.push('\n');
+
+        let mut file_metadata = Vec::new();
+        for (path, count, matched_lines, content) in files.iter().take(options.max_files) {
+            let lines: Vec<&str> = content.lines().collect();
+            let shown = &matched_lines[..matched_lines.len().min(MAX_MATCHED_LINES_PER_FILE)];
+
+            let header = format!("{} ({} match(es))", path, count);
+            output.push('\n');
+            if options.highlight {
+                output.push_str(&header.bold().blue().to_string());
+            } else {
+                output.push_str(&header);
+            }
+            output.push('\n');
+
+            // Merge the context windows of nearby matches into grep-style blocks
+            let mut blocks: Vec<(usize, usize)> = Vec::new();
+            for &index in shown {
+                let first = index.saturating_sub(options.context_lines);
+                let last = (index + options.context_lines).min(lines.len() - 1);
+                match blocks.last_mut() {
+                    Some(block) if first <= block.1 + 1 => block.1 = last,
+                    _ => blocks.push((first, last)),
+                }
             }
-        }
 
-        // Check path traversal patterns
-        for pattern in &path_patterns {
-            if command_lower.contains(pattern) {
-                return Err(format!(
-                    "Command contains path traversal pattern: {}",
-                    pattern
+            let width = blocks.last().map_or(1, |block| (block.1 + 1).to_string().len());
+            for (block_index, (first, last)) in blocks.iter().enumerate() {
+                if block_index > 0 {
+                    output.push_str("  --\n");
+                }
+                for (index, line) in lines.iter().enumerate().take(*last + 1).skip(*first) {
+                    let is_match = shown.binary_search(&index).is_ok();
+                    let text = truncate_line(line);
+                    let number = format!("{:>width$}{}", index + 1, if is_match { ':' } else { '-' });
+                    let line = match (options.highlight, is_match) {
+                        (false, _) => format!("  {} {}", number, text),
+                        (true, true) => format!("  {} {}", number.green(), highlight_matches(&regex, &text)),
+                        (true, false) => format!("  {} {}", number.dimmed(), text.dimmed()),
+                    };
+                    output.push_str(&line);
+                    output.push('\n');
+                }
+            }
+            if matched_lines.len() > shown.len() {
+                output.push_str(&format!(
+                    "  ... {} more matching line(s) in this file\n",
+                    matched_lines.len() - shown.len()
                 ));
             }
+
+            file_metadata.push(serde_json::json!({
+                "path": path,
+                "matches": count,
+                "lines": matched_lines.iter().map(|index| index + 1).collect::<Vec<_>>()
+            }));
         }
 
+        Ok(ToolResult {
+            success: true,
+            output: output.trim_end().to_string(),
+            error: None,
+            metadata: Some(serde_json::json!({
+                "operation": "content_search",
+                "pattern": pattern,
+                "total_matches": total_matches,
+                "files_matched": files.len(),
+                "skipped_binary_files": skipped_files,
+                "files": file_metadata
+            })),
+            web_search_result: None,
+        })
+    }
+
+    pub(super) fn validate_command(&self, command: &str, policy: &CommandPolicy) -> Result<(), String> {
+        // Pattern rules (operators, network tools, SQL, paths) come from the configured policy
+        policy.validate(command)?;
+
         // Check for excessively long commands (potential buffer overflow)
         if command.len() > 1000 {
             return Err("Command is too long (potential buffer overflow)".to_string());
@@ -710,7 +1197,10 @@ impl ToolExecutor {
             Ok(p) => p,
             Err(_) => {
                 // If canonicalization fails, check if parent exists and create safe path
-                let parent = path_obj.parent();
+                // (a bare file name has an empty parent: the current directory)
+                let parent = path_obj
+                    .parent()
+                    .map(|parent| if parent.as_os_str().is_empty() { Path::new(".") } else { parent });
                 if let Some(parent) = parent {
                     if let Ok(parent_canonical) = parent.canonicalize() {
                         parent_canonical.join(path_obj.file_name().unwrap_or_default())
@@ -742,7 +1232,8 @@ impl ToolExecutor {
         println!("{} Executing command: {}", "⚡".cyan(), command.yellow());
 
         // Security validation: Check for dangerous patterns
-        if let Err(validation_error) = self.validate_command(command) {
+        let policy = self.get_command_policy().await;
+        if let Err(validation_error) = self.validate_command(command, &policy) {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -755,75 +1246,167 @@ impl ToolExecutor {
         // Check if we're in a TTY environment
         let is_tty = std::io::IsTerminal::is_terminal(&std::io::stdin());
 
-        let mut child = if cfg!(target_os = "windows") {
-            let mut cmd = Command::new("cmd");
+        if !is_tty {
+            // For non-TTY, stream output live while capturing it
+            let timeout_secs = self.get_command_timeout().await;
+            return self.execute_command_streaming(command, timeout_secs).await;
+        }
+
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = tokio::process::Command::new("cmd");
             cmd.args(["/C", command]);
+            cmd
+        } else {
+            let mut cmd = tokio::process::Command::new("sh");
+            cmd.args(["-c", command]);
+            cmd
+        };
 
-            if is_tty {
-                // For TTY environments, inherit stdio to allow interaction
-                cmd.stdin(std::process::Stdio::inherit())
-                    .stdout(std::process::Stdio::inherit())
-                    .stderr(std::process::Stdio::inherit())
-            } else {
-                // For non-TTY environments, use piped stdio
-                cmd.stdin(std::process::Stdio::null())
-                    .stdout(std::process::Stdio::piped())
-                    .stderr(std::process::Stdio::piped())
-            };
+        // For TTY environments, inherit stdio to allow interaction. Killed if the
+        // tool is abandoned, e.g. when a parallel run times out.
+        let mut child = cmd
+            .stdin(std::process::Stdio::inherit())
+            .stdout(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let status = child.wait().await?;
+        let output_msg = format!(
+            "Command completed with exit code: {}",
+            status.code().unwrap_or(-1)
+        );
 
-            cmd.spawn()?
+        Ok(ToolResult {
+            success: status.success(),
+            output: output_msg,
+            error: None,
+            metadata: None,
+            web_search_result: None,
+        })
+    }
+
+    async fn execute_command_streaming(
+        &self,
+        command: &str,
+        timeout_secs: u64,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let cmd = if cfg!(target_os = "windows") {
+            let mut cmd = tokio::process::Command::new("cmd");
+            cmd.args(["/C", command]);
+            cmd
         } else {
-            let mut cmd = Command::new("sh");
+            let mut cmd = tokio::process::Command::new("sh");
             cmd.args(["-c", command]);
+            cmd
+        };
 
-            if is_tty {
-                // For TTY environments, inherit stdio to allow interaction
-                cmd.stdin(std::process::Stdio::inherit())
-                    .stdout(std::process::Stdio::inherit())
-                    .stderr(std::process::Stdio::inherit())
-            } else {
-                // For non-TTY environments, use piped stdio
-                cmd.stdin(std::process::Stdio::null())
-                    .stdout(std::process::Stdio::piped())
-                    .stderr(std::process::Stdio::piped())
-            };
+        self.stream_process(cmd, timeout_secs).await
+    }
+
+    /// Run a prepared command, printing stdout/stderr lines as they arrive and capturing them
+    pub(crate) async fn stream_process(
+        &self,
+        mut cmd: tokio::process::Command,
+        timeout_secs: u64,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        use tokio::io::{AsyncBufReadExt, BufReader};
+        use tokio::sync::mpsc;
+
+        // Its own process group, so a timeout also reaches whatever the command started
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        // Forward both pipes into one channel so lines keep their arrival order
+        let (tx, mut rx) = mpsc::unbounded_channel::<(bool, String)>();
+        if let Some(stdout) = child.stdout.take() {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if tx.send((false, line)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if tx.send((true, line)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        let mut output = String::new();
+        let deadline = tokio::time::sleep(std::time::Duration::from_secs(timeout_secs));
+        tokio::pin!(deadline);
+
+        let timed_out = loop {
+            tokio::select! {
+                line = rx.recv() => match line {
+                    Some((is_stderr, line)) => {
+                        if is_stderr {
+                            eprintln!("{}", line);
+                        } else {
+                            println!("{}", line);
+                        }
+                        output.push_str(&line);
+                        output.push('\n');
+                    }
+                    // Both pipes closed
+                    None => break false,
+                },
+                _ = &mut deadline => break true,
+            }
+        };
+
+        // The pipes can close before the process exits, so waiting shares the deadline
+        let status = if timed_out {
+            None
+        } else {
+            tokio::select! {
+                status = child.wait() => Some(status?),
+                _ = &mut deadline => None,
+            }
+        };
 
-            cmd.spawn()?
+        let Some(status) = status else {
+            kill_process_group(&mut child).await;
+            return Ok(ToolResult {
+                success: false,
+                output,
+                error: Some(format!("Command timed out after {}s and was killed", timeout_secs)),
+                metadata: Some(serde_json::json!({ "timed_out": true })),
+                web_search_result: None,
+            });
         };
 
-        let (output_msg, success) = if is_tty {
-            // For TTY environments, just wait for completion
-            let status = child.wait()?;
-            let msg = format!(
+        let output_msg = if output.is_empty() {
+            format!(
                 "Command completed with exit code: {}",
                 status.code().unwrap_or(-1)
-            );
-            (msg, status.success())
+            )
         } else {
-            // For non-TTY, capture output
-            let output = child.wait_with_output()?;
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
-
-            let msg = if !stdout.is_empty() {
-                stdout.to_string()
-            } else if !stderr.is_empty() {
-                stderr.to_string()
-            } else {
-                format!(
-                    "Command completed with exit code: {}",
-                    output.status.code().unwrap_or(-1)
-                )
-            };
-
-            (msg, output.status.success())
+            output
         };
 
         Ok(ToolResult {
-            success,
+            success: status.success(),
             output: output_msg,
             error: None,
-            metadata: None,
+            metadata: Some(serde_json::json!({ "exit_code": status.code() })),
             web_search_result: None,
         })
     }
@@ -1130,8 +1713,10 @@ edition = "2021"
         query: &str,
         include_specialized: bool,
     ) -> Result<ToolResult, Box<dyn std::error::Error>> {
-        let config = WebSearchConfig::default();
-        let search_engine = WebSearchEngine::new(config);
+        let search_engine = match WebSearchEngine::new(self.web_search_config()) {
+            Ok(engine) => engine,
+            Err(e) => return Ok(self.web_client_error(e)),
+        };
         
         match search_engine.enhanced_search(query, include_specialized).await {
             Ok(search_result) => {
@@ -1190,66 +1775,85 @@ edition = "2021"
     pub async fn web_performance_test(
         &self,
         url: &str,
-        test_count: usize,
+        options: &PerfTestOptions,
     ) -> Result<ToolResult, Box<dyn std::error::Error>> {
-        println!("{} Running performance test for: {}", "⚡".cyan(), url.yellow());
+        use futures::StreamExt;
+
+        let max_requests = self.get_perf_test_max_requests();
+        let test_count = options.requests.clamp(1, max_requests);
+        let concurrency = options.concurrency.clamp(1, test_count);
+        if options.requests > max_requests {
+            println!(
+                "{} Capping {} requests to {} (perf_test_max_requests)",
+                "⚠️".yellow(),
+                options.requests,
+                max_requests
+            );
+        }
+        println!(
+            "{} Running performance test for: {} ({} requests, {} at a time, {}s timeout)",
+            "⚡".cyan(),
+            url.yellow(),
+            test_count,
+            concurrency,
+            options.timeout.as_secs_f64()
+        );
 
-        let mut results = Vec::new();
         let start_time = std::time::Instant::now();
-
-        for i in 0..test_count {
+        let timeout = options.timeout;
+        let requests = futures::stream::iter(0..test_count).map(|i| async move {
+            // Sequential runs keep a small gap between requests, as a courtesy to the host
+            if concurrency == 1 && i > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
             let request_start = std::time::Instant::now();
-            
-            match self.web_client.get(url).send().await {
-                Ok(response) => {
-                    let response_time = request_start.elapsed();
-                    let status = response.status().as_u16();
-                    let content_length = response.content_length().unwrap_or(0);
-                    
-                    results.push((i + 1, true, status, response_time.as_millis() as u64, content_length));
-                }
+            let outcome = self.web_client.get(url).timeout(timeout).send().await;
+            let response_time = request_start.elapsed().as_millis() as u64;
+            match outcome {
+                Ok(response) => (i + 1, true, response.status().as_u16(), response_time, response.content_length().unwrap_or(0)),
                 Err(e) => {
-                    let response_time = request_start.elapsed();
-                    results.push((i + 1, false, 0, response_time.as_millis() as u64, 0));
-                    println!("{} Request {} failed: {}", "❌".red(), i + 1, e);
+                    if e.is_timeout() {
+                        println!("{} Request {} timed out after {:.1}s", "❌".red(), i + 1, timeout.as_secs_f64());
+                    } else {
+                        println!("{} Request {} failed: {}", "❌".red(), i + 1, e);
+                    }
+                    (i + 1, false, 0, response_time, 0)
                 }
             }
-
-            // Small delay between requests
-            if i < test_count - 1 {
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            }
-        }
+        });
+        let results: Vec<(usize, bool, u16, u64, u64)> = requests.buffer_unordered(concurrency).collect().await;
 
         let total_time = start_time.elapsed();
-        let successful_requests = results.iter().filter(|(_, success, _, _, _)| *success).count();
-        
+        let mut times: Vec<u64> = results.iter()
+            .filter(|(_, success, _, _, _)| *success)
+            .map(|(_, _, _, time, _)| *time)
+            .collect();
+        times.sort_unstable();
+        let successful_requests = times.len();
+
         let avg_response_time = if successful_requests > 0 {
-            results.iter()
-                .filter(|(_, success, _, _, _)| *success)
-                .map(|(_, _, _, time, _)| *time)
-                .sum::<u64>() as f64 / successful_requests as f64
+            times.iter().sum::<u64>() as f64 / successful_requests as f64
         } else {
             0.0
         };
-
-        let min_response_time = results.iter()
-            .filter(|(_, success, _, _, _)| *success)
-            .map(|(_, _, _, time, _)| *time)
-            .min()
-            .unwrap_or(0);
-
-        let max_response_time = results.iter()
-            .filter(|(_, success, _, _, _)| *success)
-            .map(|(_, _, _, time, _)| *time)
-            .max()
-            .unwrap_or(0);
+        let min_response_time = times.first().copied().unwrap_or(0);
+        let max_response_time = times.last().copied().unwrap_or(0);
+        // Nearest-rank percentile over the successful requests
+        let percentile = |p: f64| -> u64 {
+            if times.is_empty() {
+                return 0;
+            }
+            let rank = ((p / 100.0) * times.len() as f64).ceil() as usize;
+            times[rank.clamp(1, times.len()) - 1]
+        };
+        let (p50, p90, p99) = (percentile(50.0), percentile(90.0), percentile(99.0));
 
         let output = format!(
             "⚡ Web Performance Test Results for: {}\n\
             ═══════════════════════════════════════════════════════\n\
             📊 Test Summary:\n\
             • Total Requests: {}\n\
+            • Concurrency: {}\n\
             • Successful: {} ({:.1}%)\n\
             • Failed: {} ({:.1}%)\n\
             • Total Time: {:.2}s\n\
@@ -1257,10 +1861,14 @@ edition = "2021"
             ⏱️ Response Time Statistics:\n\
             • Average: {:.1}ms\n\
             • Minimum: {}ms\n\
+            • p50: {}ms\n\
+            • p90: {}ms\n\
+            • p99: {}ms\n\
             • Maximum: {}ms\n\n\
             📈 Performance Rating: {}",
             url,
             test_count,
+            concurrency,
             successful_requests,
             (successful_requests as f64 / test_count as f64) * 100.0,
             test_count - successful_requests,
@@ -1269,6 +1877,9 @@ edition = "2021"
             test_count as f64 / total_time.as_secs_f64(),
             avg_response_time,
             min_response_time,
+            p50,
+            p90,
+            p99,
             max_response_time,
             if avg_response_time < 200.0 { "🟢 Excellent" }
             else if avg_response_time < 500.0 { "🟡 Good" }
@@ -1279,15 +1890,21 @@ edition = "2021"
         Ok(ToolResult {
             success: successful_requests > 0,
             output,
-            error: None,
+            error: (successful_requests == 0).then(|| format!("All {} requests to {} failed", test_count, url)),
             metadata: Some(serde_json::json!({
                 "url": url,
                 "test_count": test_count,
+                "requested_count": options.requests,
+                "concurrency": concurrency,
+                "timeout_seconds": timeout.as_secs_f64(),
                 "successful_requests": successful_requests,
                 "failed_requests": test_count - successful_requests,
                 "success_rate": (successful_requests as f64 / test_count as f64) * 100.0,
                 "avg_response_time_ms": avg_response_time,
                 "min_response_time_ms": min_response_time,
+                "p50_response_time_ms": p50,
+                "p90_response_time_ms": p90,
+                "p99_response_time_ms": p99,
                 "max_response_time_ms": max_response_time,
                 "total_time_seconds": total_time.as_secs_f64(),
                 "requests_per_second": test_count as f64 / total_time.as_secs_f64()
@@ -1,7 +1,171 @@
 use super::core::{GitBranchOperation, ToolExecutor, ToolResult};
 use colored::Colorize;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 
+pub const CONVENTIONAL_COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+// Directory names too generic to be useful as a scope
+const GENERIC_DIRS: &[&str] = &["src", "lib", "app", "pkg", "crates", "packages"];
+
+/// Derive candidate Conventional Commit scopes from `git diff --stat`, most-changed first
+pub fn scopes_from_diff_stat(stat: &str) -> Vec<String> {
+    let mut weights: HashMap<String, usize> = HashMap::new();
+
+    for line in stat.lines() {
+        let Some((path, changes)) = line.split_once('|') else { continue };
+        let path = path.trim();
+        // Renames are shown as `old => new`; the new side is what matters
+        let path = path.rsplit("=> ").next().unwrap_or(path).trim_end_matches('}');
+        let changed: usize = changes
+            .split_whitespace()
+            .next()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(1);
+
+        let path = Path::new(path);
+        let scope = path
+            .parent()
+            .and_then(|parent| {
+                parent
+                    .components()
+                    .rev()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .find(|dir| !GENERIC_DIRS.contains(&dir.as_str()))
+            })
+            .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().to_string()));
+
+        if let Some(scope) = scope {
+            *weights.entry(scope.to_lowercase()).or_insert(0) += changed;
+        }
+    }
+
+    let mut scopes: Vec<(String, usize)> = weights.into_iter().collect();
+    scopes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scopes.into_iter().map(|(scope, _)| scope).collect()
+}
+
+/// Check a message's subject line against `type(scope): summary`
+pub fn is_conventional_commit(message: &str) -> bool {
+    let pattern = format!(
+        r"^({})(\([a-z0-9._/-]+\))?!?: \S.*$",
+        CONVENTIONAL_COMMIT_TYPES.join("|")
+    );
+    let subject = message.lines().next().unwrap_or("").trim();
+    Regex::new(&pattern)
+        .map(|re| re.is_match(subject))
+        .unwrap_or(false)
+}
+
+/// One line of `git blame --porcelain` output
+#[derive(Debug, Clone)]
+pub struct BlameLine {
+    pub line: usize,
+    pub commit: String,
+    pub author: String,
+    /// Author date as `YYYY-MM-DD` in the author's timezone
+    pub date: String,
+    pub content: String,
+}
+
+/// Parse `git blame --porcelain`. Each line starts with a header of
+/// `<sha> <orig line> <final line> [<group size>]`; the commit's author fields
+/// only follow the first time that commit appears, so they are remembered by sha.
+pub fn parse_blame_porcelain(porcelain: &str) -> Vec<BlameLine> {
+    let mut commits: HashMap<String, (String, String)> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current: Option<(String, usize)> = None;
+    let mut author = String::new();
+    let mut author_time: Option<i64> = None;
+    let mut author_tz = String::new();
+
+    for raw in porcelain.lines() {
+        if let Some(content) = raw.strip_prefix('\t') {
+            let Some((commit, line)) = current.take() else {
+                continue;
+            };
+            let (author, date) = commits
+                .entry(commit.clone())
+                .or_insert_with(|| (author.clone(), format_blame_date(author_time, &author_tz)))
+                .clone();
+            lines.push(BlameLine { line, commit, author, date, content: content.to_string() });
+            continue;
+        }
+
+        if let Some(name) = raw.strip_prefix("author ") {
+            author = name.to_string();
+        } else if let Some(time) = raw.strip_prefix("author-time ") {
+            author_time = time.trim().parse().ok();
+        } else if let Some(tz) = raw.strip_prefix("author-tz ") {
+            author_tz = tz.trim().to_string();
+        } else {
+            let mut fields = raw.split_whitespace();
+            if let (Some(sha), Some(_), Some(final_line)) = (fields.next(), fields.next(), fields.next()) {
+                if sha.len() == 40 && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                    if let Ok(line) = final_line.parse() {
+                        current = Some((sha.to_string(), line));
+                        author.clear();
+                        author_time = None;
+                        author_tz.clear();
+                    }
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+fn format_blame_date(time: Option<i64>, tz: &str) -> String {
+    let Some(utc) = time.and_then(|time| chrono::DateTime::from_timestamp(time, 0)) else {
+        return "-".to_string();
+    };
+    // `+0200` / `-0530`
+    let offset = (tz.len() == 5)
+        .then(|| {
+            let sign = if tz.starts_with('-') { -1 } else { 1 };
+            let hours: i32 = tz[1..3].parse().ok()?;
+            let minutes: i32 = tz[3..5].parse().ok()?;
+            chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        })
+        .flatten();
+    match offset {
+        Some(offset) => utc.with_timezone(&offset).format("%Y-%m-%d").to_string(),
+        None => utc.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// `line | commit | author | date | content`, with uncommitted lines marked as such
+pub fn format_blame(lines: &[BlameLine]) -> String {
+    let line_width = lines.iter().map(|l| l.line.to_string().len()).max().unwrap_or(1);
+    let author_width = lines.iter().map(|l| l.author.chars().count().min(20)).max().unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|l| {
+            let commit = if l.commit.bytes().all(|b| b == b'0') {
+                "uncommitted".to_string()
+            } else {
+                l.commit[..8].to_string()
+            };
+            let author: String = l.author.chars().take(20).collect();
+            format!(
+                "{:>line_width$} | {:<11} | {:<author_width$} | {} | {}",
+                l.line,
+                commit,
+                author,
+                l.date,
+                l.content,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl ToolExecutor {
     pub async fn git_status(
         &self,
@@ -347,4 +511,258 @@ impl ToolExecutor {
             web_search_result: None,
         })
     }
-}
\ No newline at end of file
+
+    pub async fn git_list_branches(
+        &self,
+        pattern: Option<&str>,
+        repository_path: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Listing git branches", "🌿".cyan());
+
+        let mut cmd = Command::new("git");
+        
+        if let Some(repo_path) = repository_path {
+            cmd.args(["-C", repo_path]);
+        }
+        
+        cmd.args(["branch", "--list"]);
+        
+        if let Some(pattern) = pattern {
+            cmd.arg(pattern);
+        }
+
+        let output = cmd.output()?;
+        let success = output.status.success();
+        
+        let mut current_branch = None;
+        let output_text = if success {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            stdout
+                .lines()
+                .map(|line| {
+                    if let Some(name) = line.strip_prefix("* ") {
+                        current_branch = Some(name.to_string());
+                        format!("* {} (current)", name)
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            String::from_utf8_lossy(&output.stderr).to_string()
+        };
+
+        Ok(ToolResult {
+            success,
+            output: output_text,
+            error: if success { None } else { Some("Git branch command failed".to_string()) },
+            metadata: Some(serde_json::json!({
+                "repository_path": repository_path,
+                "pattern": pattern,
+                "current_branch": current_branch,
+                "command": "branch"
+            })),
+            web_search_result: None,
+        })
+    }
+
+    pub async fn git_checkout(
+        &self,
+        target: &str,
+        repository_path: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Checking out: {}", "🔀".cyan(), target.yellow());
+
+        let mut cmd = Command::new("git");
+        
+        if let Some(repo_path) = repository_path {
+            cmd.args(["-C", repo_path]);
+        }
+        
+        cmd.args(["checkout", target]);
+
+        let output = cmd.output()?;
+        let success = output.status.success();
+        
+        // git reports checkout progress on stderr even when it succeeds
+        let output_text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        Ok(ToolResult {
+            success,
+            output: output_text,
+            error: if success { None } else { Some("Git checkout command failed".to_string()) },
+            metadata: Some(serde_json::json!({
+                "repository_path": repository_path,
+                "target": target,
+                "command": "checkout"
+            })),
+            web_search_result: None,
+        })
+    }
+
+    pub async fn git_stash(
+        &self,
+        pop: bool,
+        repository_path: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!(
+            "{} {}",
+            "📦".cyan(),
+            if pop { "Popping git stash" } else { "Stashing changes" }
+        );
+
+        let mut cmd = Command::new("git");
+        
+        if let Some(repo_path) = repository_path {
+            cmd.args(["-C", repo_path]);
+        }
+        
+        if pop {
+            cmd.args(["stash", "pop"]);
+        } else {
+            cmd.args(["stash", "push"]);
+        }
+
+        let output = cmd.output()?;
+        let success = output.status.success();
+        
+        let output_text = if success {
+            String::from_utf8_lossy(&output.stdout)
+        } else {
+            String::from_utf8_lossy(&output.stderr)
+        };
+
+        Ok(ToolResult {
+            success,
+            output: output_text.to_string(),
+            error: if success { None } else { Some("Git stash command failed".to_string()) },
+            metadata: Some(serde_json::json!({
+                "repository_path": repository_path,
+                "pop": pop,
+                "command": "stash"
+            })),
+            web_search_result: None,
+        })
+    }
+
+    /// `git blame` for a file, optionally limited to lines `start..=end`
+    pub async fn git_blame(
+        &self,
+        file: &str,
+        start: Option<usize>,
+        end: Option<usize>,
+        repository_path: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Getting git blame for {}", "🔎".cyan(), file);
+
+        if start == Some(0) || matches!((start, end), (Some(start), Some(end)) if end < start) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Line range must start at 1 and end at or after its start".to_string()),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
+        let mut cmd = Command::new("git");
+        
+        if let Some(repo_path) = repository_path {
+            cmd.args(["-C", repo_path]);
+        }
+        
+        cmd.args(["blame", "--porcelain"]);
+        
+        if start.is_some() || end.is_some() {
+            let range = match end {
+                Some(end) => format!("{},{}", start.unwrap_or(1), end),
+                None => format!("{},", start.unwrap_or(1)),
+            };
+            cmd.args(["-L", &range]);
+        }
+        
+        cmd.args(["--", file]);
+
+        let output = cmd.output()?;
+        let success = output.status.success();
+
+        if !success {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Ok(ToolResult {
+                success,
+                output: stderr.to_string(),
+                error: Some(format!("Git blame command failed: {}", stderr.trim())),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
+        let lines = parse_blame_porcelain(&String::from_utf8_lossy(&output.stdout));
+        let mut authors: Vec<&str> = Vec::new();
+        for line in &lines {
+            if !authors.contains(&line.author.as_str()) {
+                authors.push(&line.author);
+            }
+        }
+
+        Ok(ToolResult {
+            success,
+            output: format_blame(&lines),
+            error: None,
+            metadata: Some(serde_json::json!({
+                "repository_path": repository_path,
+                "file": file,
+                "start": start,
+                "end": end,
+                "line_count": lines.len(),
+                "authors": authors,
+                "command": "blame"
+            })),
+            web_search_result: None,
+        })
+    }
+
+    pub async fn git_diff_stat(
+        &self,
+        cached: bool,
+        repository_path: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let mut cmd = Command::new("git");
+        
+        if let Some(repo_path) = repository_path {
+            cmd.args(["-C", repo_path]);
+        }
+        
+        cmd.args(["diff", "--stat"]);
+        
+        if cached {
+            cmd.arg("--cached");
+        }
+
+        let output = cmd.output()?;
+        let success = output.status.success();
+        
+        let output_text = if success {
+            String::from_utf8_lossy(&output.stdout)
+        } else {
+            String::from_utf8_lossy(&output.stderr)
+        };
+
+        Ok(ToolResult {
+            success,
+            output: output_text.to_string(),
+            error: if success { None } else { Some("Git diff --stat command failed".to_string()) },
+            metadata: Some(serde_json::json!({
+                "repository_path": repository_path,
+                "cached": cached,
+                "command": "diff --stat"
+            })),
+            web_search_result: None,
+        })
+    }
+}
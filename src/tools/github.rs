@@ -0,0 +1,316 @@
+use super::core::{ToolExecutor, ToolResult};
+use colored::Colorize;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+
+// Repeated lookups within a chat or agent loop reuse the first fetch
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+lazy_static::lazy_static! {
+    static ref ISSUE_CACHE: Mutex<HashMap<String, (Instant, ToolResult)>> = Mutex::new(HashMap::new());
+}
+
+/// An issue or pull request: `owner/repo#123` or a github.com URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+impl std::fmt::Display for IssueRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}#{}", self.owner, self.repo, self.number)
+    }
+}
+
+pub fn parse_issue_ref(reference: &str) -> Option<IssueRef> {
+    let reference = reference.trim();
+
+    // https://github.com/owner/repo/issues/123 or .../pull/123/files#discussion_r1; URLs
+    // come first since their fragment would otherwise read as the issue number
+    let url = reference
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.");
+    if let Some(path) = url.strip_prefix("github.com/") {
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let parts: Vec<&str> = path.split('/').collect();
+        return match parts.as_slice() {
+            [owner, repo, "issues" | "pull", number, ..] => Some(IssueRef {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                number: number.parse().ok()?,
+            }),
+            _ => None,
+        };
+    }
+
+    let (repo_path, number) = reference.split_once('#')?;
+    let (owner, repo) = repo_path.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some(IssueRef {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        number: number.parse().ok()?,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Label {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    title: String,
+    body: Option<String>,
+    state: String,
+    html_url: String,
+    user: User,
+    created_at: String,
+    #[serde(default)]
+    labels: Vec<Label>,
+    comments: u64,
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Branch {
+    label: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequest {
+    head: Branch,
+    base: Branch,
+    #[serde(default)]
+    merged: bool,
+    additions: Option<u64>,
+    deletions: Option<u64>,
+    changed_files: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Comment {
+    user: User,
+    created_at: String,
+    body: Option<String>,
+}
+
+impl ToolExecutor {
+    /// Fetch an issue or pull request with its comments as context for the model
+    pub async fn github_issue(&self, reference: &str) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let Some(issue_ref) = parse_issue_ref(reference) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "'{}' is not a GitHub issue reference; use owner/repo#123 or an issue or pull request URL",
+                    reference
+                )),
+                metadata: None,
+                web_search_result: None,
+            });
+        };
+
+        let cache_key = issue_ref.to_string();
+        if let Some((fetched_at, result)) = ISSUE_CACHE.lock().unwrap().get(&cache_key) {
+            if fetched_at.elapsed() < CACHE_TTL {
+                println!("{} Using cached {}", "🐙".cyan(), cache_key);
+                return Ok(result.clone());
+            }
+        }
+
+        println!("{} Fetching {} from GitHub", "🐙".cyan(), cache_key.yellow());
+        let result = match self.fetch_issue(&issue_ref).await {
+            Ok(result) => result,
+            Err(message) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(message),
+                metadata: Some(serde_json::json!({ "reference": cache_key })),
+                web_search_result: None,
+            },
+        };
+
+        if result.success {
+            ISSUE_CACHE
+                .lock()
+                .unwrap()
+                .insert(cache_key, (Instant::now(), result.clone()));
+        }
+        Ok(result)
+    }
+
+    async fn fetch_issue(&self, issue_ref: &IssueRef) -> Result<ToolResult, String> {
+        let api_url = std::env::var("GITHUB_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+        let base = format!(
+            "{}/repos/{}/{}",
+            api_url.trim_end_matches('/'),
+            issue_ref.owner,
+            issue_ref.repo
+        );
+        let token = self.get_api_key("github_token", "GITHUB_TOKEN");
+
+        let issue: Issue = self
+            .github_get(&format!("{}/issues/{}", base, issue_ref.number), token.as_deref(), issue_ref)
+            .await?;
+        let pull: Option<PullRequest> = match issue.pull_request {
+            Some(_) => Some(
+                self.github_get(&format!("{}/pulls/{}", base, issue_ref.number), token.as_deref(), issue_ref)
+                    .await?,
+            ),
+            None => None,
+        };
+        let comments: Vec<Comment> = if issue.comments > 0 {
+            self.github_get(
+                &format!("{}/issues/{}/comments?per_page=100", base, issue_ref.number),
+                token.as_deref(),
+                issue_ref,
+            )
+            .await?
+        } else {
+            Vec::new()
+        };
+
+        let kind = if pull.is_some() { "Pull request" } else { "Issue" };
+        let state = match &pull {
+            Some(pull) if pull.merged => "merged",
+            _ => issue.state.as_str(),
+        };
+
+        let mut output = format!("{} {}: {} [{}]\n", kind, issue_ref, issue.title, state);
+        output.push_str(&format!(
+            "Opened by {} on {}\n{}\n",
+            issue.user.login, issue.created_at, issue.html_url
+        ));
+        if !issue.labels.is_empty() {
+            let labels: Vec<&str> = issue.labels.iter().map(|label| label.name.as_str()).collect();
+            output.push_str(&format!("Labels: {}\n", labels.join(", ")));
+        }
+        if let Some(pull) = &pull {
+            output.push_str(&format!("Branch: {} → {}\n", pull.head.label, pull.base.label));
+            if let (Some(additions), Some(deletions), Some(files)) =
+                (pull.additions, pull.deletions, pull.changed_files)
+            {
+                output.push_str(&format!("Changes: +{} -{} in {} file(s)\n", additions, deletions, files));
+            }
+        }
+
+        let body = issue.body.as_deref().unwrap_or("").trim();
+        output.push('\n');
+        output.push_str(if body.is_empty() { "(no description)" } else { body });
+        output.push('\n');
+
+        if !comments.is_empty() {
+            output.push_str(&format!("\nComments ({}):\n", comments.len()));
+            for comment in &comments {
+                output.push_str(&format!(
+                    "\n{} on {}:\n{}\n",
+                    comment.user.login,
+                    comment.created_at,
+                    comment.body.as_deref().unwrap_or("").trim()
+                ));
+            }
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            metadata: Some(serde_json::json!({
+                "operation": "github_issue",
+                "reference": issue_ref.to_string(),
+                "kind": kind.to_lowercase(),
+                "state": state,
+                "url": issue.html_url,
+                "comments": comments.len()
+            })),
+            web_search_result: None,
+        })
+    }
+
+    async fn github_get<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        token: Option<&str>,
+        issue_ref: &IssueRef,
+    ) -> Result<T, String> {
+        let mut request = self
+            .web_client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .header("User-Agent", "ollama-cli-assistant")
+            .timeout(Duration::from_secs(30));
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("GitHub request failed: {}", e))?;
+        let status = response.status();
+        if status.is_success() {
+            return response
+                .json()
+                .await
+                .map_err(|e| format!("Unexpected response from GitHub: {}", e));
+        }
+
+        let rate_limited = response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            == Some("0");
+        let reset = response
+            .headers()
+            .get("x-ratelimit-reset")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok())
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|at| at.with_timezone(&chrono::Local).format("%H:%M").to_string());
+
+        Err(match status {
+            StatusCode::UNAUTHORIZED => format!(
+                "GitHub rejected the token for {} (401). Check api_keys.github_token or GITHUB_TOKEN",
+                issue_ref
+            ),
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS if rate_limited || token.is_none() => {
+                let mut message = "GitHub API rate limit exceeded".to_string();
+                if let Some(reset) = reset {
+                    message.push_str(&format!(" (resets at {})", reset));
+                }
+                if token.is_none() {
+                    message.push_str("; set api_keys.github_token or GITHUB_TOKEN for a higher limit");
+                }
+                message
+            }
+            StatusCode::FORBIDDEN => format!(
+                "GitHub denied access to {} (403); the token may lack permission for this repository",
+                issue_ref
+            ),
+            // GitHub answers 404 rather than 401 for private repositories without access
+            StatusCode::NOT_FOUND if token.is_none() => format!(
+                "{} not found. If the repository is private, set api_keys.github_token or GITHUB_TOKEN",
+                issue_ref
+            ),
+            StatusCode::NOT_FOUND => format!("{} not found, or the token can't access it", issue_ref),
+            _ => format!("GitHub returned HTTP {} for {}", status, issue_ref),
+        })
+    }
+}
@@ -1,8 +1,12 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
 use std::collections::VecDeque;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use colored::Colorize;
+use crate::render::{escape_html, markdown_to_html};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -11,6 +15,10 @@ pub struct HistoryEntry {
     pub assistant_response: String,
     pub tools_used: Vec<String>,
     pub session_id: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,27 +44,6 @@ impl ConversationHistory {
         }
     }
 
-    pub fn get_recent(&self, count: usize) -> Vec<&HistoryEntry> {
-        self.entries.iter().rev().take(count).collect()
-    }
-
-    pub fn search(&self, query: &str, limit: usize) -> Vec<&HistoryEntry> {
-        let query_lower = query.to_lowercase();
-        self.entries
-            .iter()
-            .filter(|entry| {
-                entry.user_input.to_lowercase().contains(&query_lower)
-                    || entry.assistant_response.to_lowercase().contains(&query_lower)
-            })
-            .rev()
-            .take(limit)
-            .collect()
-    }
-
-    pub fn clear(&mut self) {
-        self.entries.clear();
-    }
-
     pub fn export_to_markdown(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let mut content = String::new();
         content.push_str("# Conversation History\n\n");
@@ -105,72 +92,581 @@ impl ConversationHistory {
         fs::write(path, content)?;
         Ok(())
     }
+
+    /// A single self-contained page: one collapsible section per session with
+    /// a table of contents, messages as chat bubbles and highlighted code
+    pub fn export_to_html(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        // Sessions in order of their first message
+        let mut sessions: Vec<(&str, Vec<&HistoryEntry>)> = Vec::new();
+        for entry in &self.entries {
+            match sessions.iter_mut().find(|(id, _)| *id == entry.session_id) {
+                Some((_, entries)) => entries.push(entry),
+                None => sessions.push((&entry.session_id, vec![entry])),
+            }
+        }
+
+        let mut content = String::new();
+        content.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        content.push_str("<title>Conversation History</title>\n");
+        content.push_str(HTML_STYLE);
+        content.push_str("</head>\n<body>\n<h1>Conversation History</h1>\n");
+
+        content.push_str("<nav class=\"toc\">\n<h2>Sessions</h2>\n<ol>\n");
+        for (i, (id, entries)) in sessions.iter().enumerate() {
+            content.push_str(&format!(
+                "<li><a href=\"#session-{}\">{}</a> <span class=\"meta\">{} · {} message(s)</span></li>\n",
+                i + 1,
+                escape_html(&session_title(id, entries)),
+                escape_html(&entries[0].timestamp),
+                entries.len()
+            ));
+        }
+        content.push_str("</ol>\n</nav>\n");
+
+        for (i, (id, entries)) in sessions.iter().enumerate() {
+            content.push_str(&format!(
+                "<details class=\"session\" id=\"session-{}\" open>\n<summary>{} <span class=\"meta\">{}</span></summary>\n",
+                i + 1,
+                escape_html(&session_title(id, entries)),
+                escape_html(&entries[0].timestamp)
+            ));
+            if !entries[0].tags.is_empty() {
+                let tags: Vec<String> = entries[0]
+                    .tags
+                    .iter()
+                    .map(|tag| format!("<span class=\"tag\">#{}</span>", escape_html(tag)))
+                    .collect();
+                content.push_str(&format!("<div class=\"tags\">{}</div>\n", tags.join(" ")));
+            }
+
+            for entry in entries {
+                content.push_str(&format!(
+                    "<div class=\"bubble user\"><div class=\"meta\">You · {}</div>{}</div>\n",
+                    escape_html(&entry.timestamp),
+                    markdown_to_html(&entry.user_input)
+                ));
+                content.push_str(&format!(
+                    "<div class=\"bubble assistant\"><div class=\"meta\">{}</div>{}",
+                    escape_html(if entry.model.is_empty() { "Assistant" } else { &entry.model }),
+                    markdown_to_html(&entry.assistant_response)
+                ));
+                if !entry.tools_used.is_empty() {
+                    content.push_str(&format!(
+                        "<div class=\"tools\">Tools: {}</div>",
+                        escape_html(&entry.tools_used.join(", "))
+                    ));
+                }
+                content.push_str("</div>\n");
+            }
+            content.push_str("</details>\n");
+        }
+
+        content.push_str("</body>\n</html>\n");
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// Short heading for a session in the HTML export: its model and id prefix
+fn session_title(id: &str, entries: &[&HistoryEntry]) -> String {
+    let short_id = id.get(..8).unwrap_or(id);
+    match entries.iter().map(|entry| entry.model.as_str()).find(|model| !model.is_empty()) {
+        Some(model) => format!("{} [{}]", model, short_id),
+        None => format!("Session [{}]", short_id),
+    }
+}
+
+const HTML_STYLE: &str = "<style>
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; max-width: 900px; margin: 2em auto; padding: 0 1em; background: #f4f5f7; color: #1f2328; }
+h1 { font-size: 1.6em; }
+.meta { color: #6a737d; font-size: 0.8em; font-weight: normal; }
+.toc { background: #fff; border: 1px solid #d0d7de; border-radius: 8px; padding: 0.5em 1.5em; margin-bottom: 1.5em; }
+.toc h2 { font-size: 1.1em; }
+.toc a { color: #0969da; text-decoration: none; }
+.session { background: #fff; border: 1px solid #d0d7de; border-radius: 8px; padding: 0.5em 1em 1em; margin-bottom: 1.5em; }
+.session summary { cursor: pointer; font-weight: 600; padding: 0.5em 0; }
+.tags { margin-bottom: 0.5em; }
+.tag { background: #ddf4ff; color: #0969da; border-radius: 1em; padding: 0.1em 0.6em; font-size: 0.8em; }
+.bubble { border-radius: 12px; padding: 0.6em 1em; margin: 0.8em 0; max-width: 85%; overflow-wrap: anywhere; }
+.bubble.user { background: #0969da; color: #fff; margin-left: auto; }
+.bubble.user .meta { color: #cfe3ff; }
+.bubble.user code { background: rgba(255, 255, 255, 0.2); }
+.bubble.assistant { background: #eef1f4; margin-right: auto; }
+.bubble p { margin: 0.4em 0; }
+.tools { color: #9a6700; font-size: 0.85em; font-style: italic; margin-top: 0.5em; }
+code { font-family: ui-monospace, 'SF Mono', Menlo, Consolas, monospace; background: rgba(175, 184, 193, 0.3); border-radius: 4px; padding: 0.1em 0.3em; font-size: 0.9em; }
+pre.code { background: #2b303b; color: #c0c5ce; border-radius: 6px; padding: 0.8em 1em; overflow-x: auto; }
+pre.code code { background: none; padding: 0; color: inherit; }
+blockquote { border-left: 3px solid #d0d7de; margin: 0.4em 0; padding-left: 0.8em; color: #57606a; }
+</style>
+";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        id TEXT PRIMARY KEY,
+        model TEXT NOT NULL DEFAULT '',
+        started_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS messages (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        timestamp TEXT NOT NULL,
+        model TEXT NOT NULL DEFAULT '',
+        user_input TEXT NOT NULL,
+        assistant_response TEXT NOT NULL,
+        tools_used TEXT NOT NULL DEFAULT '[]'
+    );
+    CREATE INDEX IF NOT EXISTS idx_messages_timestamp ON messages(timestamp);
+    CREATE INDEX IF NOT EXISTS idx_messages_model ON messages(model);
+    CREATE INDEX IF NOT EXISTS idx_messages_session ON messages(session_id);
+    CREATE TABLE IF NOT EXISTS tags (
+        session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        tag TEXT NOT NULL COLLATE NOCASE,
+        PRIMARY KEY (session_id, tag)
+    );
+    CREATE INDEX IF NOT EXISTS idx_tags_tag ON tags(tag);
+    CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+        user_input, assistant_response, content='messages', content_rowid='id'
+    );
+    CREATE TRIGGER IF NOT EXISTS messages_ai AFTER INSERT ON messages BEGIN
+        INSERT INTO messages_fts(rowid, user_input, assistant_response)
+        VALUES (new.id, new.user_input, new.assistant_response);
+    END;
+    CREATE TRIGGER IF NOT EXISTS messages_ad AFTER DELETE ON messages BEGIN
+        INSERT INTO messages_fts(messages_fts, rowid, user_input, assistant_response)
+        VALUES ('delete', old.id, old.user_input, old.assistant_response);
+    END;
+";
+
+const MESSAGE_COLUMNS: &str = "m.timestamp, m.user_input, m.assistant_response, m.tools_used, m.session_id, m.model,
+    (SELECT group_concat(t.tag, ',') FROM tags t WHERE t.session_id = m.session_id)";
+
+/// Matches messages whose conversation carries the tag bound to `?1`, or all messages when it is NULL.
+const TAG_FILTER: &str =
+    "(?1 IS NULL OR EXISTS (SELECT 1 FROM tags t WHERE t.session_id = m.session_id AND t.tag = ?1))";
+
+/// Matches messages with `?3 <= timestamp < ?4`, either bound NULL for an open end. Stored
+/// timestamps are RFC 3339 in UTC, so string comparison follows time and uses the index.
+const TIME_FILTER: &str = "(?3 IS NULL OR m.timestamp >= ?3) AND (?4 IS NULL OR m.timestamp < ?4)";
+
+/// One page of `history show`, newest first
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    /// 1-based page number
+    pub page: usize,
+    pub pages: usize,
+    /// Entries matching the filters across all pages
+    pub total: usize,
+    /// Entries on earlier pages, so numbering carries on from one page to the next
+    pub offset: usize,
+}
+
+/// Timestamp window for listing and searching history; either end may be open.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeRange {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn new(
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if let (Some(since), Some(until)) = (since, until) {
+            if since >= until {
+                return Err("--since must be earlier than --until".into());
+            }
+        }
+        Ok(Self { since, until })
+    }
+
+    fn bounds(&self) -> (Option<String>, Option<String>) {
+        (
+            self.since.map(|since| since.to_rfc3339()),
+            self.until.map(|until| until.to_rfc3339()),
+        )
+    }
+}
+
+/// Parse a `--since` value: the start of a local `YYYY-MM-DD` day, an RFC 3339
+/// timestamp, or an age such as `30m`, `2h`, `7d` or `2w` before now.
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_time_bound(value, false)
+}
+
+/// Parse an `--until` value like [`parse_since`]; a plain date includes that whole day.
+pub fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_time_bound(value, true)
+}
+
+fn parse_time_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let date = if end_of_day { date.succ_opt().unwrap_or(date) } else { date };
+        return date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+            .map(|start| start.with_timezone(&Utc))
+            .ok_or_else(|| format!("'{}' is not a valid local date", value));
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let age = value
+        .char_indices()
+        .last()
+        .and_then(|(split, unit)| {
+            let amount: i64 = value[..split].parse().ok().filter(|amount| *amount >= 0)?;
+            match unit {
+                's' => Duration::try_seconds(amount),
+                'm' => Duration::try_minutes(amount),
+                'h' => Duration::try_hours(amount),
+                'd' => Duration::try_days(amount),
+                'w' => Duration::try_weeks(amount),
+                _ => None,
+            }
+        })
+        .ok_or_else(|| {
+            format!(
+                "'{}' is not a date (YYYY-MM-DD), an RFC 3339 timestamp or an age like 30m, 2h, 7d, 2w",
+                value
+            )
+        })?;
+    Utc::now()
+        .checked_sub_signed(age)
+        .ok_or_else(|| format!("'{}' reaches too far back", value))
 }
 
+/// Conversation history backed by SQLite at `history.db` in the data directory.
 pub struct HistoryManager {
-    history: ConversationHistory,
-    file_path: PathBuf,
+    conn: Connection,
+    db_path: PathBuf,
 }
 
 impl HistoryManager {
-    pub fn new() -> Self {
-        let file_path = dirs::config_dir()
+    /// Open the history database, falling back to an in-memory one that is not saved
+    /// when the file can't be opened. Fails only if SQLite can't run at all.
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let db_path = crate::paths::data_dir().join("history.db");
+
+        let conn = match Self::open_database(&db_path) {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!(
+                    "{} Failed to open history database {}: {} (history will not be saved)",
+                    "⚠️".yellow(),
+                    db_path.display(),
+                    e
+                );
+                let conn = Connection::open_in_memory()?;
+                conn.execute_batch(SCHEMA)?;
+                conn
+            }
+        };
+
+        let mut manager = Self { conn, db_path };
+        if let Err(e) = manager.migrate_legacy_history() {
+            eprintln!("{} Failed to import legacy history: {}", "⚠️".yellow(), e);
+        }
+        Ok(manager)
+    }
+
+    fn open_database(path: &Path) -> Result<Connection, Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(conn)
+    }
+
+    /// Import the pre-SQLite `history.json` once, then rename it so it is not imported again.
+    fn migrate_legacy_history(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let legacy_path = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("ollama-cli-assistant")
             .join("history.json");
-        
-        let history = Self::load_from_file(&file_path).unwrap_or_else(|_| {
-            ConversationHistory::new(100) // Default max entries
-        });
+        if !legacy_path.exists() {
+            return Ok(());
+        }
 
-        Self { history, file_path }
+        let content = fs::read_to_string(&legacy_path)?;
+        let history: ConversationHistory = serde_json::from_str(&content)?;
+
+        let tx = self.conn.transaction()?;
+        for entry in &history.entries {
+            Self::insert_entry(&tx, entry)?;
+        }
+        tx.commit()?;
+
+        fs::rename(&legacy_path, legacy_path.with_extension("json.migrated"))?;
+        println!(
+            "{} Imported {} history entries into {}",
+            "📦".cyan(),
+            history.entries.len(),
+            self.db_path.display()
+        );
+        Ok(())
     }
 
-    pub fn add_entry(&mut self, entry: HistoryEntry) {
-        self.history.add_entry(entry);
-        let _ = self.save_to_file(); // Ignore errors for now
+    fn insert_entry(conn: &Connection, entry: &HistoryEntry) -> rusqlite::Result<()> {
+        Self::ensure_session(conn, &entry.session_id, &entry.model, &entry.timestamp)?;
+        conn.execute(
+            "INSERT INTO messages (session_id, timestamp, model, user_input, assistant_response, tools_used)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.session_id,
+                entry.timestamp,
+                entry.model,
+                entry.user_input,
+                entry.assistant_response,
+                serde_json::to_string(&entry.tools_used).unwrap_or_else(|_| "[]".to_string()),
+            ],
+        )?;
+        Ok(())
     }
 
-    pub fn get_recent(&self, count: usize) -> Vec<&HistoryEntry> {
-        self.history.get_recent(count)
+    /// Create the session row if needed, filling in the model if it was tagged before its first message.
+    fn ensure_session(conn: &Connection, session_id: &str, model: &str, started_at: &str) -> rusqlite::Result<()> {
+        conn.execute(
+            "INSERT INTO sessions (id, model, started_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(id) DO UPDATE SET model = excluded.model WHERE sessions.model = ''",
+            params![session_id, model, started_at],
+        )?;
+        Ok(())
     }
 
-    pub fn search(&self, query: &str, limit: usize) -> Vec<&HistoryEntry> {
-        self.history.search(query, limit)
+    fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+        let tools_used: String = row.get(3)?;
+        let tags: Option<String> = row.get(6)?;
+        Ok(HistoryEntry {
+            timestamp: row.get(0)?,
+            user_input: row.get(1)?,
+            assistant_response: row.get(2)?,
+            tools_used: serde_json::from_str(&tools_used).unwrap_or_default(),
+            session_id: row.get(4)?,
+            model: row.get(5)?,
+            tags: tags
+                .map(|tags| tags.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
     }
 
-    pub fn clear(&mut self) {
-        self.history.clear();
-        let _ = self.save_to_file();
+    fn query_entries(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> rusqlite::Result<Vec<HistoryEntry>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, Self::row_to_entry)?;
+        rows.collect()
     }
 
-    pub fn export(&self, path: &str, format: &str) -> Result<(), Box<dyn std::error::Error>> {
-        match format.to_lowercase().as_str() {
-            "markdown" | "md" => self.history.export_to_markdown(path),
-            "json" => self.history.export_to_json(path),
-            "text" | "txt" => self.history.export_to_text(path),
-            _ => Err(format!("Unsupported format: {}", format).into()),
+    pub fn add_entry(&mut self, entry: HistoryEntry) {
+        if let Err(e) = Self::insert_entry(&self.conn, &entry) {
+            eprintln!("{} Failed to save history entry: {}", "⚠️".yellow(), e);
         }
     }
 
-    fn save_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // Create directory if it doesn't exist
-        if let Some(parent) = self.file_path.parent() {
-            fs::create_dir_all(parent)?;
+    /// Delete the most recent message of a session if it answered `user_input`, e.g. when
+    /// that answer is regenerated.
+    pub fn remove_last_entry(&mut self, session_id: &str, user_input: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let removed = self.conn.execute(
+            "DELETE FROM messages WHERE id = (SELECT MAX(id) FROM messages WHERE session_id = ?1)
+             AND user_input = ?2",
+            params![session_id, user_input],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Most recent entries within `range`, optionally limited to conversations carrying `tag`.
+    pub fn get_recent_filtered(&self, count: usize, tag: Option<&str>, range: TimeRange) -> Vec<HistoryEntry> {
+        let sql = format!(
+            "SELECT {} FROM messages m WHERE {} AND {} ORDER BY m.timestamp DESC, m.id DESC LIMIT ?2",
+            MESSAGE_COLUMNS, TAG_FILTER, TIME_FILTER
+        );
+        let (since, until) = range.bounds();
+        self.query_entries(&sql, params![tag, count as i64, since, until])
+            .unwrap_or_else(|e| {
+                eprintln!("{} Failed to read history: {}", "⚠️".yellow(), e);
+                Vec::new()
+            })
+    }
+
+    /// Page `page` (1-based) of the entries `get_recent_filtered` would list, `page_size` per page.
+    pub fn get_page(
+        &self,
+        page: usize,
+        page_size: usize,
+        tag: Option<&str>,
+        range: TimeRange,
+    ) -> Result<HistoryPage, Box<dyn std::error::Error>> {
+        let (since, until) = range.bounds();
+        let page_size = page_size.max(1);
+        // ?2 is unused here but bound so the filters keep their parameter numbers
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM messages m WHERE {} AND {}", TAG_FILTER, TIME_FILTER),
+            params![tag, page_size as i64, since, until],
+            |row| row.get(0),
+        )?;
+        let total = total as usize;
+        let pages = total.div_ceil(page_size).max(1);
+        if page > pages {
+            return Err(format!(
+                "Page {} is past the end of history ({} page{})",
+                page,
+                pages,
+                if pages == 1 { "" } else { "s" }
+            )
+            .into());
         }
-        
-        let json = serde_json::to_string_pretty(&self.history)?;
-        fs::write(&self.file_path, json)?;
+
+        let offset = (page - 1) * page_size;
+        let sql = format!(
+            "SELECT {} FROM messages m WHERE {} AND {} ORDER BY m.timestamp DESC, m.id DESC LIMIT ?2 OFFSET ?5",
+            MESSAGE_COLUMNS, TAG_FILTER, TIME_FILTER
+        );
+        let entries = self.query_entries(&sql, params![tag, page_size as i64, since, until, offset as i64])?;
+        Ok(HistoryPage { entries, page, pages, total, offset })
+    }
+
+    /// Full-text search over user input and responses within `range`, newest first.
+    pub fn search_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        tag: Option<&str>,
+        range: TimeRange,
+    ) -> Vec<HistoryEntry> {
+        let Some(fts_query) = Self::fts_query(query) else {
+            return self.get_recent_filtered(limit, tag, range);
+        };
+
+        let sql = format!(
+            "SELECT {} FROM messages_fts f JOIN messages m ON m.id = f.rowid
+             WHERE messages_fts MATCH ?5 AND {} AND {}
+             ORDER BY m.timestamp DESC, m.id DESC LIMIT ?2",
+            MESSAGE_COLUMNS, TAG_FILTER, TIME_FILTER
+        );
+        let (since, until) = range.bounds();
+        self.query_entries(&sql, params![tag, limit as i64, since, until, fts_query])
+            .unwrap_or_else(|e| {
+                eprintln!("{} History search failed: {}", "⚠️".yellow(), e);
+                Vec::new()
+            })
+    }
+
+    pub fn add_tag(&mut self, session_id: &str, model: &str, tag: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let tag = Self::normalize_tag(tag)?;
+        Self::ensure_session(&self.conn, session_id, model, &chrono::Utc::now().to_rfc3339())?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO tags (session_id, tag) VALUES (?1, ?2)",
+            params![session_id, tag],
+        )?;
         Ok(())
     }
 
-    fn load_from_file(path: &PathBuf) -> Result<ConversationHistory, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(path)?;
-        let history: ConversationHistory = serde_json::from_str(&content)?;
-        Ok(history)
+    /// Returns false when the conversation did not carry the tag.
+    pub fn remove_tag(&mut self, session_id: &str, tag: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let tag = Self::normalize_tag(tag)?;
+        let removed = self.conn.execute(
+            "DELETE FROM tags WHERE session_id = ?1 AND tag = ?2",
+            params![session_id, tag],
+        )?;
+        Ok(removed > 0)
+    }
+
+    pub fn session_tags(&self, session_id: &str) -> Vec<String> {
+        self.conn
+            .prepare("SELECT tag FROM tags WHERE session_id = ?1 ORDER BY tag")
+            .and_then(|mut stmt| {
+                stmt.query_map(params![session_id], |row| row.get(0))?
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 
-    pub fn show_entries(&self, entries: &[&HistoryEntry], detailed: bool) {
+    /// Resolve a session id prefix, or the most recent conversation when `prefix` is `None`.
+    pub fn resolve_session(&self, prefix: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+        let mut ids: Vec<String> = match prefix {
+            Some(prefix) => {
+                let mut stmt = self.conn.prepare(
+                    "SELECT id FROM sessions WHERE substr(id, 1, length(?1)) = ?1 LIMIT 2",
+                )?;
+                let rows = stmt.query_map(params![prefix], |row| row.get(0))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            }
+            None => {
+                let mut stmt = self.conn.prepare(
+                    "SELECT session_id FROM messages ORDER BY timestamp DESC, id DESC LIMIT 1",
+                )?;
+                let rows = stmt.query_map([], |row| row.get(0))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            }
+        };
+
+        match ids.len() {
+            0 => Err(match prefix {
+                Some(prefix) => format!("No conversation matches '{}'", prefix).into(),
+                None => "No conversations in history".into(),
+            }),
+            1 => Ok(ids.remove(0)),
+            _ => Err(format!("Multiple conversations match '{}', please be more specific", prefix.unwrap_or_default()).into()),
+        }
+    }
+
+    fn normalize_tag(tag: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let tag = tag.trim();
+        if tag.is_empty() || tag.contains(',') {
+            return Err("Tags must be non-empty and cannot contain commas".into());
+        }
+        Ok(tag.to_string())
+    }
+
+    /// Quote each term so user input is matched literally rather than parsed as FTS5 syntax.
+    fn fts_query(query: &str) -> Option<String> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+            .collect();
+        if terms.is_empty() {
+            None
+        } else {
+            Some(terms.join(" "))
+        }
+    }
+
+    pub fn clear(&mut self) {
+        if let Err(e) = self.conn.execute_batch("DELETE FROM tags; DELETE FROM messages; DELETE FROM sessions;") {
+            eprintln!("{} Failed to clear history: {}", "⚠️".yellow(), e);
+        }
+    }
+
+    pub fn export(&self, path: &str, format: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let sql = format!(
+            "SELECT {} FROM messages m ORDER BY m.timestamp ASC, m.id ASC",
+            MESSAGE_COLUMNS
+        );
+        let entries = self.query_entries(&sql, [])?;
+        let mut history = ConversationHistory::new(entries.len());
+        for entry in entries {
+            history.add_entry(entry);
+        }
+
+        match format.to_lowercase().as_str() {
+            "markdown" | "md" => history.export_to_markdown(path),
+            "json" => history.export_to_json(path),
+            "text" | "txt" => history.export_to_text(path),
+            "html" | "htm" => history.export_to_html(path),
+            _ => Err(format!("Unsupported format: {}", format).into()),
+        }
+    }
+
+    /// Print entries as `history show` does; takes owned entries or references to them.
+    pub fn show_entries<E: Borrow<HistoryEntry>>(&self, entries: &[E], detailed: bool) {
         if entries.is_empty() {
             println!("{} No history entries found", "ℹ️".blue());
             return;
@@ -178,21 +674,71 @@ impl HistoryManager {
 
         println!("{} {} entries found", "📜".cyan(), entries.len());
         println!();
+        print!("{}", Self::format_entries(entries, detailed, 1));
+    }
+
+    /// Print one page with a footer pointing at the next, through the pager if asked
+    pub fn show_page(&self, page: &HistoryPage, detailed: bool, pager: bool) {
+        if page.entries.is_empty() {
+            println!("{} No history entries found", "ℹ️".blue());
+            return;
+        }
+
+        let mut text = format!(
+            "{} {} entries found, showing {}-{}\n\n",
+            "📜".cyan(),
+            page.total,
+            page.offset + 1,
+            page.offset + page.entries.len()
+        );
+        text.push_str(&Self::format_entries(&page.entries, detailed, page.offset + 1));
+        if page.pages > 1 {
+            let more = if page.page < page.pages {
+                format!(", use --page {} for more", page.page + 1)
+            } else {
+                String::new()
+            };
+            text.push_str(&format!("{}\n", format!("page {}/{}{}", page.page, page.pages, more).dimmed()));
+        }
 
-        for (i, entry) in entries.iter().enumerate() {
-            println!("{} {} {}", "●".blue(), (i + 1).to_string().yellow(), entry.timestamp.dimmed());
+        if pager {
+            crate::render::page_output(&text);
+        } else {
+            print!("{}", text);
+        }
+    }
+
+    /// Entries as printed by `history show`, numbered from `first_number`
+    fn format_entries<E: Borrow<HistoryEntry>>(entries: &[E], detailed: bool, first_number: usize) -> String {
+        let mut text = String::new();
+        for (i, entry) in entries.iter().map(Borrow::borrow).enumerate() {
+            let session = entry.session_id.get(..8).unwrap_or(&entry.session_id);
+            let tags = entry
+                .tags
+                .iter()
+                .map(|tag| format!("#{}", tag))
+                .collect::<Vec<_>>()
+                .join(" ");
+            text.push_str(&format!(
+                "{} {} {} {} {}\n",
+                "●".blue(),
+                (first_number + i).to_string().yellow(),
+                entry.timestamp.dimmed(),
+                format!("[{}]", session).dimmed(),
+                tags.magenta()
+            ));
             
             if detailed {
-                println!("   {} {}", "User:".blue(), entry.user_input);
+                text.push_str(&format!("   {} {}\n", "User:".blue(), entry.user_input));
                 let response_preview = if entry.assistant_response.len() > 150 {
                     format!("{}...", &entry.assistant_response[..150])
                 } else {
                     entry.assistant_response.clone()
                 };
-                println!("   {} {}", "Assistant:".green(), response_preview);
+                text.push_str(&format!("   {} {}\n", "Assistant:".green(), response_preview));
                 
                 if !entry.tools_used.is_empty() {
-                    println!("   {} {}", "Tools:".yellow(), entry.tools_used.join(", "));
+                    text.push_str(&format!("   {} {}\n", "Tools:".yellow(), entry.tools_used.join(", ")));
                 }
             } else {
                 let input_preview = if entry.user_input.len() > 80 {
@@ -200,10 +746,11 @@ impl HistoryManager {
                 } else {
                     entry.user_input.clone()
                 };
-                println!("   {}", input_preview);
+                text.push_str(&format!("   {}\n", input_preview));
             }
             
-            println!();
+            text.push('\n');
         }
+        text
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,127 @@
+use anyhow::{anyhow, bail, Result};
+use futures::StreamExt;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, COOKIE, PROXY_AUTHORIZATION,
+};
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use std::collections::HashMap;
+use url::Url;
+
+/// First non-empty value among the given environment variables
+fn first_env(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+/// Proxy for plain HTTP requests from `HTTP_PROXY`/`ALL_PROXY` (either case)
+pub fn default_http_proxy() -> Option<String> {
+    first_env(&["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"])
+}
+
+/// Proxy for HTTPS requests from `HTTPS_PROXY`/`ALL_PROXY` (either case)
+pub fn default_https_proxy() -> Option<String> {
+    first_env(&["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"])
+}
+
+/// Parse a proxy setting, accepting `host:port` as shorthand for `http://host:port`
+fn parse_proxy_url(setting: &str, value: &str) -> Result<Url> {
+    let candidate = if value.contains("://") {
+        value.to_string()
+    } else {
+        format!("http://{}", value)
+    };
+
+    let url = Url::parse(&candidate).map_err(|e| anyhow!("Invalid {} '{}': {}", setting, value, e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!(
+            "Invalid {} '{}': unsupported scheme '{}' (use http:// or https://)",
+            setting,
+            value,
+            url.scheme()
+        );
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        bail!("Invalid {} '{}': missing host", setting, value);
+    }
+    Ok(url)
+}
+
+/// Apply proxies and extra default headers to a web client. Malformed proxy URLs
+/// or headers are reported instead of being silently ignored.
+pub fn configure_web_client(
+    mut builder: ClientBuilder,
+    http_proxy: Option<&str>,
+    https_proxy: Option<&str>,
+    headers: &HashMap<String, String>,
+) -> Result<ClientBuilder> {
+    if let Some(value) = http_proxy.filter(|v| !v.trim().is_empty()) {
+        let url = parse_proxy_url("http_proxy", value.trim())?;
+        builder = builder.proxy(Proxy::http(url.as_str())?.no_proxy(NoProxy::from_env()));
+    }
+    if let Some(value) = https_proxy.filter(|v| !v.trim().is_empty()) {
+        let url = parse_proxy_url("https_proxy", value.trim())?;
+        builder = builder.proxy(Proxy::https(url.as_str())?.no_proxy(NoProxy::from_env()));
+    }
+
+    if !headers.is_empty() {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow!("Invalid header name '{}'", name))?;
+            let mut header_value = HeaderValue::from_str(value)
+                .map_err(|_| anyhow!("Invalid value for header '{}'", name))?;
+            // Keep credentials out of debug output
+            if [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE].contains(&header_name) {
+                header_value.set_sensitive(true);
+            }
+            header_map.insert(header_name, header_value);
+        }
+        builder = builder.default_headers(header_map);
+    }
+
+    Ok(builder)
+}
+
+/// Bytes of page read per character of extracted content, to leave room for markup
+pub const PAGE_BYTES_PER_CONTENT_CHAR: usize = 200;
+
+/// Pages without a content type are tried; otherwise only text, HTML and XML are
+fn is_scrapable_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    mime.is_empty()
+        || mime.starts_with("text/")
+        || mime.ends_with("html")
+        || mime.ends_with("+xml")
+        || mime == "application/xml"
+}
+
+/// Read a page for scraping without holding all of a huge page in memory.
+/// Non-text content types are refused before the body is read, and reading stops
+/// after `max_bytes` of body (reqwest has already decompressed it); dropping the
+/// response then closes the connection.
+pub async fn read_page_body(response: reqwest::Response, max_bytes: usize) -> Result<String> {
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if !is_scrapable_content_type(&content_type) {
+        bail!("unsupported content type: {}", content_type);
+    }
+
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        body.extend_from_slice(&chunk?);
+        if body.len() >= max_bytes {
+            break;
+        }
+    }
+
+    body.truncate(max_bytes);
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
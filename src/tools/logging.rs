@@ -1,8 +1,11 @@
 use colored::Colorize;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
 #[derive(Debug, Clone)]
 pub enum LogLevel {
@@ -408,4 +411,79 @@ pub async fn clear_logs() {
     GLOBAL_LOGGER.clear().await;
     GLOBAL_METRICS.clear().await;
     println!("{}", "Logs and metrics cleared".green());
-}
\ No newline at end of file
+}
+
+/// Environment variable with a tracing filter for the log file, e.g. `debug` or `info,reqwest=warn`
+pub const LOG_ENV_VAR: &str = "OLLAMA_AGENT_LOG";
+
+/// Rotated log files are kept this many days
+const MAX_LOG_FILES: usize = 14;
+
+/// Directory for the daily-rotated log files: `logs` in the data directory
+pub fn log_dir() -> Option<PathBuf> {
+    Some(crate::paths::data_dir().join("logs"))
+}
+
+/// Validate a `--log-level` value: a level (`trace` … `error`, `off`) or a full filter directive
+pub fn parse_log_filter(value: &str) -> Result<String, String> {
+    EnvFilter::try_new(value)
+        .map(|_| value.to_string())
+        .map_err(|e| format!("invalid log filter '{}': {}", value, e))
+}
+
+/// Send JSON-lines logs to a daily file in [`log_dir`] at the level from `--log-level`,
+/// else `OLLAMA_AGENT_LOG`, else the config's `log_level`. With `--verbose`, human-readable
+/// logs filtered by `RUST_LOG` also go to stderr. Console output from the tools is unaffected.
+pub fn init_tracing(cli_filter: Option<&str>, config_level: &str, verbose: bool) {
+    let file_filter = cli_filter
+        .map(str::to_string)
+        .or_else(|| std::env::var(LOG_ENV_VAR).ok().filter(|v| !v.trim().is_empty()))
+        .and_then(|filter| match EnvFilter::try_new(&filter) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                eprintln!("{} Ignoring invalid {} '{}': {}", "⚠".yellow(), LOG_ENV_VAR, filter, e);
+                None
+            }
+        })
+        .unwrap_or_else(|| EnvFilter::new(config_level));
+
+    let file_layer = log_dir().and_then(|dir| {
+        let appender = std::fs::create_dir_all(&dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                RollingFileAppender::builder()
+                    .rotation(Rotation::DAILY)
+                    .filename_prefix("ollama-agent")
+                    .filename_suffix("log")
+                    .max_log_files(MAX_LOG_FILES)
+                    .build(&dir)
+                    .map_err(|e| e.to_string())
+            });
+        match appender {
+            Ok(appender) => Some(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_ansi(false)
+                    .with_writer(appender)
+                    .with_filter(file_filter),
+            ),
+            Err(e) => {
+                if verbose {
+                    eprintln!("{} File logging disabled: {}", "⚠".yellow(), e);
+                }
+                None
+            }
+        }
+    });
+
+    let console_layer = verbose.then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(EnvFilter::from_default_env())
+    });
+
+    let _ = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(console_layer)
+        .try_init();
+}
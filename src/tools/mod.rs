@@ -6,19 +6,28 @@ pub mod permissions;
 // New modules
 pub mod advanced;
 pub mod async_executor;
+pub mod backup;
+pub mod complexity;
 pub mod config;
 pub mod database_api;
+pub mod diff;
 pub mod discovery;
+pub mod dry_run;
 pub mod docker;
 pub mod enhanced_errors;
 pub mod errors;
 pub mod git;
+pub mod github;
 pub mod history;
+pub mod http;
 pub mod logging;
 pub mod model_config;
 pub mod package_management;
+pub mod plugins;
 pub mod search;
+pub mod syntax_check;
 pub mod system;
+pub mod test_runner;
 pub mod web_search;
 pub mod enhanced_web;
 pub mod enhanced_websearch;
@@ -1,35 +1,117 @@
 use super::core::{ModelParameter, ToolExecutor, ToolResult};
+use crate::client::{KeepAlive, OllamaOptions};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 
+/// Context window assumed for budgeting when `context_length` isn't set, matching Ollama's default
+pub const DEFAULT_CONTEXT_LENGTH: u32 = 4096;
+
+/// Sampling settings set with `model set`; unset ones are left out of requests so
+/// Ollama (or the model's Modelfile) picks them
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
-    pub temperature: f32,
-    pub max_tokens: u32,
-    pub top_p: f32,
-    pub top_k: u32,
-    pub repeat_penalty: f32,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub top_p: Option<f32>,
+    pub top_k: Option<u32>,
+    pub repeat_penalty: Option<f32>,
     pub system_prompt: String,
-    pub context_length: u32,
+    pub context_length: Option<u32>,
     pub current_model: String,
+    /// Name of the profile applied to `current_model`, if any
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Per-model system prompt and sampling preset, stored under `model_profiles`
+/// in the config file. Unset fields keep the regular defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+}
+
+impl ModelProfile {
+    /// Copy over the fields set in `update`; an empty string clears a text field
+    fn merge(&mut self, update: ModelProfile) {
+        let text = |value: String| Some(value).filter(|v| !v.trim().is_empty());
+        if let Some(description) = update.description {
+            self.description = text(description);
+        }
+        if let Some(system_prompt) = update.system_prompt {
+            self.system_prompt = text(system_prompt);
+        }
+        if update.temperature.is_some() {
+            self.temperature = update.temperature;
+        }
+        if update.num_ctx.is_some() {
+            self.num_ctx = update.num_ctx;
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err("Temperature must be between 0.0 and 2.0".to_string());
+            }
+        }
+        if let Some(num_ctx) = self.num_ctx {
+            if !(512..=32768).contains(&num_ctx) {
+                return Err("Context length must be between 512 and 32768".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn describe(&self, name: &str) -> String {
+        let mut lines = vec![format!("Model profile: {}", name)];
+        if let Some(description) = &self.description {
+            lines.push(format!("Description: {}", description));
+        }
+        lines.push(format!(
+            "Temperature: {}",
+            self.temperature.map_or("default".to_string(), |t| t.to_string())
+        ));
+        lines.push(format!(
+            "Context length: {}",
+            self.num_ctx.map_or("default".to_string(), |n| n.to_string())
+        ));
+        lines.push(format!(
+            "System prompt: {}",
+            self.system_prompt.as_deref().unwrap_or("default")
+        ));
+        lines.join("\n")
+    }
 }
 
 impl Default for ModelConfig {
     fn default() -> Self {
         Self {
-            temperature: 0.7,
-            max_tokens: 2048,
-            top_p: 0.9,
-            top_k: 40,
-            repeat_penalty: 1.1,
+            temperature: None,
+            max_tokens: None,
+            top_p: None,
+            top_k: None,
+            repeat_penalty: None,
             system_prompt: "You are a helpful AI assistant.".to_string(),
-            context_length: 4096,
+            context_length: None,
             current_model: "llama2".to_string(),
+            profile: None,
         }
     }
 }
 
+/// A parameter's value for display, or "default" when Ollama picks it
+fn or_default<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map_or("default".to_string(), |value| value.to_string())
+}
+
 // Global model configuration state
 lazy_static::lazy_static! {
     static ref MODEL_CONFIG: Arc<Mutex<ModelConfig>> = Arc::new(Mutex::new(ModelConfig::default()));
@@ -51,7 +133,7 @@ impl ToolExecutor {
             ModelParameter::Temperature => {
                 if let Some(temp) = value.as_f64() {
                     if temp >= 0.0 && temp <= 2.0 {
-                        config.temperature = temp as f32;
+                        config.temperature = Some(temp as f32);
                         format!("Temperature set to {}", temp)
                     } else {
                         return Ok(ToolResult {
@@ -75,7 +157,7 @@ impl ToolExecutor {
             ModelParameter::MaxTokens => {
                 if let Some(tokens) = value.as_u64() {
                     if tokens > 0 && tokens <= 32768 {
-                        config.max_tokens = tokens as u32;
+                        config.max_tokens = Some(tokens as u32);
                         format!("Max tokens set to {}", tokens)
                     } else {
                         return Ok(ToolResult {
@@ -99,7 +181,7 @@ impl ToolExecutor {
             ModelParameter::TopP => {
                 if let Some(top_p) = value.as_f64() {
                     if top_p >= 0.0 && top_p <= 1.0 {
-                        config.top_p = top_p as f32;
+                        config.top_p = Some(top_p as f32);
                         format!("Top-p set to {}", top_p)
                     } else {
                         return Ok(ToolResult {
@@ -123,7 +205,7 @@ impl ToolExecutor {
             ModelParameter::TopK => {
                 if let Some(top_k) = value.as_u64() {
                     if top_k > 0 && top_k <= 100 {
-                        config.top_k = top_k as u32;
+                        config.top_k = Some(top_k as u32);
                         format!("Top-k set to {}", top_k)
                     } else {
                         return Ok(ToolResult {
@@ -147,7 +229,7 @@ impl ToolExecutor {
             ModelParameter::RepeatPenalty => {
                 if let Some(penalty) = value.as_f64() {
                     if penalty >= 0.5 && penalty <= 2.0 {
-                        config.repeat_penalty = penalty as f32;
+                        config.repeat_penalty = Some(penalty as f32);
                         format!("Repeat penalty set to {}", penalty)
                     } else {
                         return Ok(ToolResult {
@@ -185,7 +267,7 @@ impl ToolExecutor {
             ModelParameter::ContextLength => {
                 if let Some(length) = value.as_u64() {
                     if length >= 512 && length <= 32768 {
-                        config.context_length = length as u32;
+                        config.context_length = Some(length as u32);
                         format!("Context length set to {}", length)
                     } else {
                         return Ok(ToolResult {
@@ -228,23 +310,24 @@ impl ToolExecutor {
             .map_err(|e| format!("Failed to lock config: {}", e))?;
 
         let output = match parameter {
-            Some(ModelParameter::Temperature) => format!("Temperature: {}", config.temperature),
-            Some(ModelParameter::MaxTokens) => format!("Max tokens: {}", config.max_tokens),
-            Some(ModelParameter::TopP) => format!("Top-p: {}", config.top_p),
-            Some(ModelParameter::TopK) => format!("Top-k: {}", config.top_k),
+            Some(ModelParameter::Temperature) => format!("Temperature: {}", or_default(config.temperature)),
+            Some(ModelParameter::MaxTokens) => format!("Max tokens: {}", or_default(config.max_tokens)),
+            Some(ModelParameter::TopP) => format!("Top-p: {}", or_default(config.top_p)),
+            Some(ModelParameter::TopK) => format!("Top-k: {}", or_default(config.top_k)),
             Some(ModelParameter::RepeatPenalty) => {
-                format!("Repeat penalty: {}", config.repeat_penalty)
+                format!("Repeat penalty: {}", or_default(config.repeat_penalty))
             }
             Some(ModelParameter::SystemPrompt) => {
                 format!("System prompt: {}", config.system_prompt)
             }
             Some(ModelParameter::ContextLength) => {
-                format!("Context length: {}", config.context_length)
+                format!("Context length: {}", or_default(config.context_length))
             }
             None => {
                 format!(
                     "Current Model Configuration:\n\
                     Model: {}\n\
+                    Profile: {}\n\
                     Temperature: {}\n\
                     Max Tokens: {}\n\
                     Top-p: {}\n\
@@ -253,12 +336,13 @@ impl ToolExecutor {
                     Context Length: {}\n\
                     System Prompt: {}",
                     config.current_model,
-                    config.temperature,
-                    config.max_tokens,
-                    config.top_p,
-                    config.top_k,
-                    config.repeat_penalty,
-                    config.context_length,
+                    config.profile.as_deref().unwrap_or("none"),
+                    or_default(config.temperature),
+                    or_default(config.max_tokens),
+                    or_default(config.top_p),
+                    or_default(config.top_k),
+                    or_default(config.repeat_penalty),
+                    or_default(config.context_length),
                     if config.system_prompt.len() > 100 {
                         format!("{}...", &config.system_prompt[..100])
                     } else {
@@ -309,17 +393,107 @@ impl ToolExecutor {
             });
         }
 
-        let mut config = MODEL_CONFIG
-            .lock()
-            .map_err(|e| format!("Failed to lock config: {}", e))?;
-        let old_model = config.current_model.clone();
-        config.current_model = model_name.to_string();
+        let profile = self.get_model_profile(model_name);
+        let old_model = get_current_model_config().current_model;
+        activate_model(model_name, profile.as_ref());
+        let config = get_current_model_config();
+
+        let mut output = format!("Model switched from '{}' to '{}'", old_model, model_name);
+        if let Some((name, _)) = &profile {
+            output.push_str(&format!(" (profile '{}')", name));
+        }
 
         Ok(ToolResult {
             success: true,
-            output: format!("Model switched from '{}' to '{}'", old_model, model_name),
+            output,
             error: None,
-            metadata: Some(serde_json::to_value(&*config)?),
+            metadata: Some(serde_json::to_value(&config)?),
+            web_search_result: None,
+        })
+    }
+
+    /// Profile for a model: an exact name match, else one for the name without its tag
+    /// (a `codellama` profile applies to `codellama:13b`)
+    pub fn get_model_profile(&self, model_name: &str) -> Option<(String, ModelProfile)> {
+        let profiles = self.read_config().ok()?.model_profiles;
+        let base_name = model_name.split(':').next().unwrap_or(model_name);
+        [model_name, base_name]
+            .into_iter()
+            .find_map(|name| profiles.get(name).map(|profile| (name.to_string(), profile.clone())))
+    }
+
+    pub async fn show_model_profile(
+        &self,
+        model_name: &str,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let output = match self.get_model_profile(model_name) {
+            Some((name, profile)) => profile.describe(&name),
+            None => format!("No profile for '{}'; the default settings apply", model_name),
+        };
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            metadata: None,
+            web_search_result: None,
+        })
+    }
+
+    pub async fn set_model_profile(
+        &self,
+        model_name: &str,
+        update: ModelProfile,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Updating model profile: {}", "⚙️".cyan(), model_name.yellow());
+
+        let mut config = self.load_config().await?;
+        let mut profile = config.model_profiles.get(model_name).cloned().unwrap_or_default();
+        profile.merge(update);
+
+        if let Err(e) = profile.validate() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
+        config.model_profiles.insert(model_name.to_string(), profile.clone());
+        self.save_config(&config).await?;
+
+        Ok(ToolResult {
+            success: true,
+            output: profile.describe(model_name),
+            error: None,
+            metadata: Some(serde_json::to_value(&profile)?),
+            web_search_result: None,
+        })
+    }
+
+    pub async fn remove_model_profile(
+        &self,
+        model_name: &str,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let mut config = self.load_config().await?;
+        if config.model_profiles.remove(model_name).is_none() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("No profile for '{}'", model_name)),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+        self.save_config(&config).await?;
+
+        Ok(ToolResult {
+            success: true,
+            output: format!("Removed model profile '{}'", model_name),
+            error: None,
+            metadata: None,
             web_search_result: None,
         })
     }
@@ -332,16 +506,8 @@ pub struct EnhancedOllamaRequest {
     pub prompt: String,
     pub stream: bool,
     pub options: OllamaOptions,
-}
-
-#[derive(serde::Serialize, Debug)]
-pub struct OllamaOptions {
-    pub temperature: f32,
-    pub top_p: f32,
-    pub top_k: u32,
-    pub repeat_penalty: f32,
-    pub num_predict: u32, // max_tokens in Ollama
-    pub num_ctx: u32,     // context_length in Ollama
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<KeepAlive>,
 }
 
 impl From<&ModelConfig> for OllamaOptions {
@@ -353,6 +519,8 @@ impl From<&ModelConfig> for OllamaOptions {
             repeat_penalty: config.repeat_penalty,
             num_predict: config.max_tokens,
             num_ctx: config.context_length,
+            seed: None,
+            stop: None,
         }
     }
 }
@@ -362,15 +530,40 @@ pub fn get_current_model_config() -> ModelConfig {
     MODEL_CONFIG.lock().unwrap().clone()
 }
 
-// Function to update the current model in the global config
-pub fn set_current_model(model_name: &str) {
+// Make a model current and apply its profile. Settings from the previous model's
+// profile are reset first; without any profile the current settings are kept.
+pub fn activate_model(model_name: &str, profile: Option<&(String, ModelProfile)>) {
     if let Ok(mut config) = MODEL_CONFIG.lock() {
+        if config.profile.take().is_some() {
+            let defaults = ModelConfig::default();
+            config.system_prompt = defaults.system_prompt;
+            config.temperature = defaults.temperature;
+            config.context_length = defaults.context_length;
+        }
         config.current_model = model_name.to_string();
+
+        if let Some((name, profile)) = profile {
+            if let Some(system_prompt) = &profile.system_prompt {
+                config.system_prompt = system_prompt.clone();
+            }
+            if profile.temperature.is_some() {
+                config.temperature = profile.temperature;
+            }
+            if profile.num_ctx.is_some() {
+                config.context_length = profile.num_ctx;
+            }
+            config.profile = Some(name.clone());
+        }
     }
 }
 
-// Function to create enhanced request with current parameters
-pub fn create_enhanced_request(_model: &str, prompt: &str, stream: bool) -> EnhancedOllamaRequest {
+// Function to create enhanced request with current parameters, letting per-session overrides win
+pub fn create_enhanced_request(
+    _model: &str,
+    prompt: &str,
+    stream: bool,
+    overrides: &OllamaOptions,
+) -> EnhancedOllamaRequest {
     let config = get_current_model_config();
 
     EnhancedOllamaRequest {
@@ -381,6 +574,7 @@ pub fn create_enhanced_request(_model: &str, prompt: &str, stream: bool) -> Enha
             format!("{}\n\nUser: {}", config.system_prompt, prompt)
         },
         stream,
-        options: OllamaOptions::from(&config),
+        options: overrides.merged_over(&OllamaOptions::from(&config)),
+        keep_alive: crate::client::keep_alive(),
     }
 }
@@ -1,10 +1,52 @@
 use super::core::{
-    CargoOperation, NpmOperation, PackageManagerOperation, PipOperation, ServiceOperation,
-    ToolExecutor, ToolResult,
+    CargoOperation, NpmOperation, PackageManagerOperation, PipOperation, PnpmOperation,
+    PoetryOperation, ProjectPackageManager, ServiceOperation, ToolExecutor, ToolResult,
+    YarnOperation,
 };
 use colored::Colorize;
+use std::path::Path;
 use std::process::Command;
 
+/// Lockfiles checked in priority order; the first match decides the manager.
+const PROJECT_LOCKFILES: &[(&str, ProjectPackageManager)] = &[
+    ("pnpm-lock.yaml", ProjectPackageManager::Pnpm),
+    ("yarn.lock", ProjectPackageManager::Yarn),
+    ("package-lock.json", ProjectPackageManager::Npm),
+    ("poetry.lock", ProjectPackageManager::Poetry),
+    ("Cargo.lock", ProjectPackageManager::Cargo),
+];
+
+/// Project files used as a fallback when no lockfile exists yet.
+const PROJECT_MANIFESTS: &[(&str, ProjectPackageManager)] = &[
+    ("Cargo.toml", ProjectPackageManager::Cargo),
+    ("package.json", ProjectPackageManager::Npm),
+    ("requirements.txt", ProjectPackageManager::Pip),
+    ("pyproject.toml", ProjectPackageManager::Pip),
+];
+
+impl ProjectPackageManager {
+    pub fn command(&self) -> &'static str {
+        match self {
+            ProjectPackageManager::Cargo => "cargo",
+            ProjectPackageManager::Npm => "npm",
+            ProjectPackageManager::Yarn => "yarn",
+            ProjectPackageManager::Pnpm => "pnpm",
+            ProjectPackageManager::Pip => "pip",
+            ProjectPackageManager::Poetry => "poetry",
+        }
+    }
+
+    /// Detect the package manager for the project in `dir` from its lockfiles,
+    /// falling back to manifest files.
+    pub fn detect(dir: &Path) -> Option<Self> {
+        PROJECT_LOCKFILES
+            .iter()
+            .chain(PROJECT_MANIFESTS.iter())
+            .find(|(file, _)| dir.join(file).exists())
+            .map(|(_, manager)| *manager)
+    }
+}
+
 impl ToolExecutor {
     pub async fn cargo_operation(
         &self,
@@ -280,6 +322,188 @@ impl ToolExecutor {
         })
     }
 
+    pub async fn poetry_operation(
+        &self,
+        operation: PoetryOperation,
+        package: Option<&str>,
+        dev: bool,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Poetry operation: {:?}", "🐍".cyan(), operation);
+
+        let mut cmd = Command::new("poetry");
+
+        match operation {
+            PoetryOperation::Install => {
+                if let Some(pkg) = package {
+                    cmd.arg("add").arg(pkg);
+                    if dev {
+                        cmd.arg("--group").arg("dev");
+                    }
+                } else {
+                    cmd.arg("install");
+                }
+            }
+            PoetryOperation::Remove => {
+                cmd.arg("remove");
+                if let Some(pkg) = package {
+                    cmd.arg(pkg);
+                } else {
+                    return Ok(missing_package_result("remove"));
+                }
+            }
+            PoetryOperation::Run { ref script } => {
+                cmd.arg("run").arg(script);
+            }
+        }
+
+        run_package_command(cmd, "Poetry operation failed", serde_json::json!({
+            "operation": format!("{:?}", operation),
+            "package": package,
+            "dev": dev
+        }))
+    }
+
+    pub async fn yarn_operation(
+        &self,
+        operation: YarnOperation,
+        package: Option<&str>,
+        dev: bool,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Yarn operation: {:?}", "🧶".cyan(), operation);
+
+        let mut cmd = Command::new("yarn");
+
+        match operation {
+            YarnOperation::Install => {
+                if let Some(pkg) = package {
+                    cmd.arg("add").arg(pkg);
+                    if dev {
+                        cmd.arg("--dev");
+                    }
+                } else {
+                    cmd.arg("install");
+                }
+            }
+            YarnOperation::Remove => {
+                cmd.arg("remove");
+                if let Some(pkg) = package {
+                    cmd.arg(pkg);
+                } else {
+                    return Ok(missing_package_result("remove"));
+                }
+            }
+            YarnOperation::Run { ref script } => {
+                cmd.arg("run").arg(script);
+            }
+        }
+
+        run_package_command(cmd, "Yarn operation failed", serde_json::json!({
+            "operation": format!("{:?}", operation),
+            "package": package,
+            "dev": dev
+        }))
+    }
+
+    pub async fn pnpm_operation(
+        &self,
+        operation: PnpmOperation,
+        package: Option<&str>,
+        dev: bool,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} pnpm operation: {:?}", "📦".cyan(), operation);
+
+        let mut cmd = Command::new("pnpm");
+
+        match operation {
+            PnpmOperation::Install => {
+                if let Some(pkg) = package {
+                    cmd.arg("add").arg(pkg);
+                    if dev {
+                        cmd.arg("--save-dev");
+                    }
+                } else {
+                    cmd.arg("install");
+                }
+            }
+            PnpmOperation::Remove => {
+                cmd.arg("remove");
+                if let Some(pkg) = package {
+                    cmd.arg(pkg);
+                } else {
+                    return Ok(missing_package_result("remove"));
+                }
+            }
+            PnpmOperation::Run { ref script } => {
+                cmd.arg("run").arg(script);
+            }
+        }
+
+        run_package_command(cmd, "pnpm operation failed", serde_json::json!({
+            "operation": format!("{:?}", operation),
+            "package": package,
+            "dev": dev
+        }))
+    }
+
+    /// Install a package (or the project's dependencies when `package` is
+    /// `None`) with whichever manager the current directory's lockfiles point to.
+    pub async fn package_install(
+        &self,
+        package: Option<&str>,
+        dev: bool,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let current_dir = std::env::current_dir()?;
+        let Some(manager) = ProjectPackageManager::detect(&current_dir) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Could not detect a package manager in {} (no lockfile or manifest found)",
+                    current_dir.display()
+                )),
+                metadata: None,
+                web_search_result: None,
+            });
+        };
+
+        println!(
+            "{} Detected {} project",
+            "🔎".cyan(),
+            manager.command().yellow()
+        );
+
+        match manager {
+            ProjectPackageManager::Cargo => {
+                let operation = if package.is_some() {
+                    CargoOperation::Add
+                } else {
+                    CargoOperation::Build
+                };
+                self.cargo_operation(operation, package, None).await
+            }
+            ProjectPackageManager::Npm => {
+                self.npm_operation(NpmOperation::Install, package, dev).await
+            }
+            ProjectPackageManager::Yarn => {
+                self.yarn_operation(YarnOperation::Install, package, dev).await
+            }
+            ProjectPackageManager::Pnpm => {
+                self.pnpm_operation(PnpmOperation::Install, package, dev).await
+            }
+            ProjectPackageManager::Poetry => {
+                self.poetry_operation(PoetryOperation::Install, package, dev).await
+            }
+            ProjectPackageManager::Pip => {
+                let requirements = if package.is_none() && current_dir.join("requirements.txt").exists() {
+                    Some("requirements.txt")
+                } else {
+                    None
+                };
+                self.pip_operation(PipOperation::Install, package, requirements).await
+            }
+        }
+    }
+
     pub async fn system_package_manager(
         &self,
         operation: PackageManagerOperation,
@@ -761,7 +985,35 @@ impl ToolExecutor {
             }
         }
 
-        if available_managers.is_empty() {
+        let project_managers = [
+            ProjectPackageManager::Cargo,
+            ProjectPackageManager::Npm,
+            ProjectPackageManager::Yarn,
+            ProjectPackageManager::Pnpm,
+            ProjectPackageManager::Pip,
+            ProjectPackageManager::Poetry,
+        ];
+        let mut project_status = Vec::new();
+        let mut available_project_managers = Vec::new();
+        for manager in &project_managers {
+            let command = manager.command();
+            let available = Command::new("which").arg(command).output().map(|o| o.status.success()).unwrap_or(false);
+            if available {
+                available_project_managers.push(command);
+            }
+            project_status.push(format!(
+                "{} {}",
+                if available { "✅" } else { "❌" },
+                command
+            ));
+        }
+
+        let detected = std::env::current_dir()
+            .ok()
+            .and_then(|dir| ProjectPackageManager::detect(&dir))
+            .map(|manager| manager.command());
+
+        if available_managers.is_empty() && available_project_managers.is_empty() {
             Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -770,12 +1022,28 @@ impl ToolExecutor {
             web_search_result: None,
             })
         } else {
+            let system_section = if available_managers.is_empty() {
+                "  none".to_string()
+            } else {
+                available_managers.iter().map(|m| format!("  {}", m)).collect::<Vec<_>>().join("\n")
+            };
+            let mut output = format!(
+                "System package managers:\n{}\n\nProject package managers:\n{}",
+                system_section,
+                project_status.iter().map(|m| format!("  {}", m)).collect::<Vec<_>>().join("\n")
+            );
+            if let Some(detected) = detected {
+                output.push_str(&format!("\n\nDetected for current directory: {}", detected));
+            }
+
             Ok(ToolResult {
                 success: true,
-                output: format!("Available package managers:\n{}", available_managers.join("\n")),
+                output,
                 error: None,
                 metadata: Some(serde_json::json!({
-                    "available_managers": available_managers
+                    "available_managers": available_managers,
+                    "project_managers": available_project_managers,
+                    "detected_project_manager": detected
                 })),
             web_search_result: None,
             })
@@ -845,4 +1113,36 @@ struct PackageManagerInfo {
 struct ServiceManagerInfo {
     name: String,
     command: String,
-}
\ No newline at end of file
+}
+fn missing_package_result(operation: &str) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(format!("Package name required for {} operation", operation)),
+        metadata: None,
+        web_search_result: None,
+    }
+}
+
+fn run_package_command(
+    mut cmd: Command,
+    failure_message: &str,
+    metadata: serde_json::Value,
+) -> Result<ToolResult, Box<dyn std::error::Error>> {
+    let output = cmd.output()?;
+    let success = output.status.success();
+
+    let output_text = if success {
+        String::from_utf8_lossy(&output.stdout)
+    } else {
+        String::from_utf8_lossy(&output.stderr)
+    };
+
+    Ok(ToolResult {
+        success,
+        output: output_text.to_string(),
+        error: if success { None } else { Some(failure_message.to_string()) },
+        metadata: Some(metadata),
+        web_search_result: None,
+    })
+}
@@ -1,6 +1,6 @@
 use super::core::{
-    AvailableTool, HttpMethod, ModelParameter, TextOperation, ExportFormat,
-    CargoOperation, NpmOperation, PipOperation, DockerResourceType,
+    AvailableTool, ContentSearchOptions, HttpMethod, ModelParameter, TextOperation, ExportFormat,
+    CargoOperation, NpmOperation, PipOperation, PoetryOperation, YarnOperation, PnpmOperation, DockerResourceType, DockerPruneTarget,
 };
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
@@ -19,6 +19,31 @@ struct ToolRequest {
     reasoning: String,
 }
 
+/// Tools parsed from a request, with the model's stated reason for each
+#[derive(Debug, Clone, Default)]
+pub struct ToolPlan {
+    /// What the model says the request is after, or the agent step being taken
+    pub reasoning: Option<String>,
+    pub tools: Vec<AvailableTool>,
+    /// Per-tool reasoning, aligned with `tools`
+    pub rationales: Vec<Option<String>>,
+}
+
+impl ToolPlan {
+    fn without_rationale(tools: Vec<AvailableTool>) -> Self {
+        let rationales = vec![None; tools.len()];
+        Self { reasoning: None, tools, rationales }
+    }
+
+    /// Why tool `index` is run: its own reasoning, else the plan's
+    pub fn rationale(&self, index: usize) -> Option<&str> {
+        self.rationales
+            .get(index)
+            .and_then(|rationale| rationale.as_deref())
+            .or(self.reasoning.as_deref())
+    }
+}
+
 pub struct NaturalLanguageParser {
     // Enhanced with model awareness
 }
@@ -32,10 +57,10 @@ impl NaturalLanguageParser {
         &self,
         input: &str,
         llm_client: &crate::client::SelectedModel,
-    ) -> Vec<AvailableTool> {
+    ) -> ToolPlan {
         // First check for immediate commands that don't need LLM parsing
         if let Some(tool) = self.parse_immediate_commands(input) {
-            return vec![tool];
+            return ToolPlan::without_rationale(vec![tool]);
         }
 
         let analysis_prompt = self.build_enhanced_analysis_prompt(input);
@@ -43,6 +68,8 @@ impl NaturalLanguageParser {
         // Get LLM response
         match crate::client::stream_response(llm_client, &analysis_prompt).await {
             Ok(response) => {
+                let tags = crate::client::thinking_settings().tags;
+                let response = crate::thinking::strip_thinking(&response, &tags);
                 if let Ok(analysis) = self.parse_llm_response(&response) {
                     return self.convert_to_tools(analysis);
                 }
@@ -53,7 +80,7 @@ impl NaturalLanguageParser {
         }
 
         // Enhanced fallback with more sophisticated parsing
-        self.enhanced_fallback_parse(input)
+        ToolPlan::without_rationale(self.enhanced_fallback_parse(input))
     }
 
     fn parse_immediate_commands(&self, _input: &str) -> Option<AvailableTool> {
@@ -82,13 +109,25 @@ AVAILABLE TOOLS AND THEIR USAGE PATTERNS:
   Examples: "edit main.rs", "modify config", "update the dockerfile"
   Parameters: path (string), operation (object)
 
+- FileMove: Move or rename a file or directory (never overwrites)
+  Examples: "rename utils.rs to helpers.rs", "move old.txt into archive/"
+  Parameters: from (string), to (string)
+
+- FileCopy: Copy a file or directory (never overwrites)
+  Examples: "copy config.json to config.backup.json", "duplicate the templates folder"
+  Parameters: from (string), to (string)
+
+- FileDelete: Delete a file, or a directory with recursive=true
+  Examples: "delete tmp.log", "remove the build_old directory"
+  Parameters: path (string), recursive (boolean, default false)
+
 - FileSearch: Find files by pattern
   Examples: "find *.rs files", "search for config files", "locate all json files"
   Parameters: pattern (string), directory (optional string)
 
-- ContentSearch: Search text within files
+- ContentSearch: Search text within files; results are grouped by file with surrounding lines
   Examples: "find TODO in code", "search for main function", "look for error messages"
-  Parameters: pattern (string), directory (optional string)
+  Parameters: pattern (string, regex), directory (optional string), context_lines (optional number, default 2), case_sensitive (optional bool, default true), whole_word (optional bool)
 
 - ListDirectory: List directory contents
   Examples: "list files", "show directory", "what's in src/", "ls"
@@ -124,6 +163,10 @@ AVAILABLE TOOLS AND THEIR USAGE PATTERNS:
   Examples: "git log", "show commits", "last 5 commits", "commit history"
   Parameters: count (optional number), oneline (boolean), repository_path (optional string - OMIT unless user specifies a specific directory)
 
+- GitBlame: Show who last changed each line of a file
+  Examples: "who changed src/main.rs", "git blame lib.rs lines 10-40", "who wrote line 42 of config.rs"
+  Parameters: file (string), start (optional number: first line), end (optional number: last line), repository_path (optional string - OMIT unless user specifies a specific directory)
+
 ## System Operations
 - SystemInfo: Get system information
   Examples: "system info", "system details", "show system", "hardware info"
@@ -133,9 +176,13 @@ AVAILABLE TOOLS AND THEIR USAGE PATTERNS:
   Examples: "memory usage", "check memory", "show ram", "memory info"
   Parameters: none
 
-- DiskUsage: Check disk space
-  Examples: "disk usage", "check disk space", "storage info", "disk space in /home"
-  Parameters: path (optional string)
+- DiskUsage: Check disk space, or find the largest directories
+  Examples: "disk usage", "check disk space", "storage info", "disk space in /home", "what's taking up space in ~/projects"
+  Parameters: path (optional string), top (optional number: list the N largest subdirectories), depth (optional number, default 1)
+
+- Sensors: Read CPU/GPU temperatures and fan speeds (Linux)
+  Examples: "cpu temperature", "how hot is the gpu", "fan speeds", "check thermals"
+  Parameters: none
 
 - ProcessList: List running processes
   Examples: "list processes", "show processes", "running apps", "ps aux"
@@ -167,6 +214,18 @@ AVAILABLE TOOLS AND THEIR USAGE PATTERNS:
   Examples: "npm install", "npm run dev", "install express", "run tests"
   Parameters: operation (enum), package (optional string), dev (boolean)
 
+- YarnOperation / PnpmOperation / PoetryOperation: Yarn, pnpm and Poetry operations
+  Examples: "yarn add react", "pnpm run build", "poetry add requests"
+  Parameters: action (install|remove|<script>), package (optional string), dev (boolean)
+
+- PackageInstall: Install using the project's package manager (detected from lockfiles)
+  Examples: "install dependencies", "add lodash to this project"
+  Parameters: package (optional string), dev (boolean)
+
+- RunTests: Run the project's test suite (cargo, npm/yarn/pnpm or pytest) and report failing tests
+  Examples: "run the tests", "are the parser tests passing?"
+  Parameters: filter (optional string), path (optional string)
+
 ## Web & API
 - WebSearch: Search internet
   Examples: "search rust tutorials", "google python guides", "find documentation"
@@ -176,6 +235,10 @@ AVAILABLE TOOLS AND THEIR USAGE PATTERNS:
   Examples: "scrape https://example.com", "get content from url", "extract webpage"
   Parameters: url (string)
 
+- GithubIssue: Fetch a GitHub issue or pull request with its comments
+  Examples: "show rust-lang/rust#12345", "what is github.com/owner/repo/pull/7 about", "get the linked issue"
+  Parameters: reference (string, owner/repo#123 or issue/PR URL)
+
 - HttpRequest: Make HTTP requests
   Examples: "GET api.example.com", "POST to webhook", "HTTP request to server"
   Parameters: method (enum), url (string), headers (optional object), body (optional string)
@@ -194,7 +257,7 @@ AVAILABLE TOOLS AND THEIR USAGE PATTERNS:
   Examples: "clear history", "clear conversation", "reset chat", "new session"
   Parameters: none
 
-PARSING RULES:
+{}PARSING RULES:
 1. Understand user intent, not just keywords
 2. Handle natural language variations and synonyms
 3. Extract parameters intelligently from context
@@ -219,7 +282,8 @@ RESPONSE FORMAT (JSON only):
 }}
 
 Analyze the request and respond with JSON only:"#,
-            user_input
+            user_input,
+            super::plugins::prompt_section()
         )
     }
 
@@ -310,8 +374,9 @@ Analyze the request and respond with JSON only:"#,
         None
     }
 
-    fn convert_to_tools(&self, analysis: ToolAnalysis) -> Vec<AvailableTool> {
+    fn convert_to_tools(&self, analysis: ToolAnalysis) -> ToolPlan {
         let mut tools = Vec::new();
+        let mut rationales = Vec::new();
 
         println!(
             "{} LLM Analysis: {}",
@@ -364,6 +429,36 @@ Analyze the request and respond with JSON only:"#,
                         });
                     }
                 }
+                "FileMove" | "FileCopy" => {
+                    let from = tool_req.parameters.get("from")
+                        .or_else(|| tool_req.parameters.get("source"))
+                        .and_then(|v| v.as_str());
+                    let to = tool_req.parameters.get("to")
+                        .or_else(|| tool_req.parameters.get("destination"))
+                        .and_then(|v| v.as_str());
+
+                    if let (Some(from), Some(to)) = (from, to) {
+                        let (from, to) = (from.to_string(), to.to_string());
+                        tools.push(if tool_req.tool_type == "FileMove" {
+                            AvailableTool::FileMove { from, to }
+                        } else {
+                            AvailableTool::FileCopy { from, to }
+                        });
+                    }
+                }
+                "FileDelete" => {
+                    let path = tool_req.parameters.get("path")
+                        .or_else(|| tool_req.parameters.get("file"))
+                        .and_then(|v| v.as_str());
+
+                    if let Some(path) = path {
+                        let recursive = tool_req.parameters.get("recursive").and_then(|v| v.as_bool()).unwrap_or(false);
+                        tools.push(AvailableTool::FileDelete {
+                            path: path.to_string(),
+                            recursive,
+                        });
+                    }
+                }
                 "WebSearch" => {
                     if let Some(query) = tool_req.parameters.get("query").and_then(|v| v.as_str()) {
                         tools.push(AvailableTool::WebSearch {
@@ -376,6 +471,14 @@ Analyze the request and respond with JSON only:"#,
                     }
                 }
 
+                "GithubIssue" => {
+                    if let Some(reference) = tool_req.parameters.get("reference").and_then(|v| v.as_str()) {
+                        tools.push(AvailableTool::GithubIssue {
+                            reference: reference.to_string(),
+                        });
+                    }
+                }
+
                 // Git operations
                 "GitStatus" => {
                     let repository_path = tool_req
@@ -426,6 +529,30 @@ Analyze the request and respond with JSON only:"#,
                     }
                 }
 
+                "GitBlame" => {
+                    if let Some(file) = tool_req.parameters.get("file").and_then(|v| v.as_str()) {
+                        let line = |key: &str| {
+                            tool_req
+                                .parameters
+                                .get(key)
+                                .and_then(|v| v.as_u64())
+                                .map(|n| n as usize)
+                        };
+                        let repository_path = tool_req
+                            .parameters
+                            .get("repository_path")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+
+                        tools.push(AvailableTool::GitBlame {
+                            file: file.to_string(),
+                            start: line("start"),
+                            end: line("end"),
+                            repository_path,
+                        });
+                    }
+                }
+
                 // HTTP/API operations
                 "HttpRequest" => {
                     if let Some(url) = tool_req.parameters.get("url").and_then(|v| v.as_str()) {
@@ -543,13 +670,18 @@ Analyze the request and respond with JSON only:"#,
                 "MemoryUsage" => {
                     tools.push(AvailableTool::MemoryUsage);
                 }
+                "Sensors" => {
+                    tools.push(AvailableTool::Sensors);
+                }
                 "DiskUsage" => {
                     let path = tool_req
                         .parameters
                         .get("path")
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string());
-                    tools.push(AvailableTool::DiskUsage { path });
+                    let top = tool_req.parameters.get("top").and_then(|v| v.as_u64()).map(|n| n as usize);
+                    let depth = tool_req.parameters.get("depth").and_then(|v| v.as_u64()).map(|n| n as usize);
+                    tools.push(AvailableTool::DiskUsage { path, top, depth });
                 }
                 "ProcessList" => {
                     let filter = tool_req
@@ -617,10 +749,27 @@ Analyze the request and respond with JSON only:"#,
                 }
                 "ContentSearch" => {
                     if let Some(pattern) = tool_req.parameters.get("pattern").and_then(|v| v.as_str()) {
-                        let directory = tool_req.parameters.get("path").and_then(|v| v.as_str()).map(|s| s.to_string());
+                        let directory = tool_req
+                            .parameters
+                            .get("directory")
+                            .or_else(|| tool_req.parameters.get("path"))
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+                        let mut options = ContentSearchOptions::default();
+                        if let Some(lines) = tool_req.parameters.get("context_lines").and_then(|v| v.as_u64()) {
+                            // Keep the tool output small enough to feed back to the model
+                            options.context_lines = (lines as usize).min(10);
+                        }
+                        if let Some(case_sensitive) = tool_req.parameters.get("case_sensitive").and_then(|v| v.as_bool()) {
+                            options.case_sensitive = case_sensitive;
+                        }
+                        if let Some(whole_word) = tool_req.parameters.get("whole_word").and_then(|v| v.as_bool()) {
+                            options.whole_word = whole_word;
+                        }
                         tools.push(AvailableTool::ContentSearch {
                             pattern: pattern.to_string(),
                             directory,
+                            options,
                         });
                     }
                 }
@@ -705,7 +854,54 @@ Analyze the request and respond with JSON only:"#,
                         });
                     }
                 }
-                
+
+                "PoetryOperation" | "YarnOperation" | "PnpmOperation" => {
+                    if let Some(action) = tool_req.parameters.get("action").and_then(|v| v.as_str()) {
+                        let package = tool_req.parameters.get("package").and_then(|v| v.as_str()).map(|s| s.to_string());
+                        let dev = tool_req.parameters.get("dev").and_then(|v| v.as_bool()).unwrap_or(false);
+                        let action = action.to_lowercase();
+                        let tool = match tool_req.tool_type.as_str() {
+                            "PoetryOperation" => AvailableTool::PoetryOperation {
+                                operation: match action.as_str() {
+                                    "install" | "add" => PoetryOperation::Install,
+                                    "remove" | "uninstall" => PoetryOperation::Remove,
+                                    script => PoetryOperation::Run { script: script.to_string() },
+                                },
+                                package,
+                                dev,
+                            },
+                            "YarnOperation" => AvailableTool::YarnOperation {
+                                operation: match action.as_str() {
+                                    "install" | "add" => YarnOperation::Install,
+                                    "remove" | "uninstall" => YarnOperation::Remove,
+                                    script => YarnOperation::Run { script: script.to_string() },
+                                },
+                                package,
+                                dev,
+                            },
+                            _ => AvailableTool::PnpmOperation {
+                                operation: match action.as_str() {
+                                    "install" | "add" => PnpmOperation::Install,
+                                    "remove" | "uninstall" => PnpmOperation::Remove,
+                                    script => PnpmOperation::Run { script: script.to_string() },
+                                },
+                                package,
+                                dev,
+                            },
+                        };
+                        tools.push(tool);
+                    }
+                }
+                "PackageInstall" => {
+                    let package = tool_req.parameters.get("package").and_then(|v| v.as_str()).map(|s| s.to_string());
+                    let dev = tool_req.parameters.get("dev").and_then(|v| v.as_bool()).unwrap_or(false);
+                    tools.push(AvailableTool::PackageInstall { package, dev });
+                }
+                "RunTests" => {
+                    let filter = tool_req.parameters.get("filter").and_then(|v| v.as_str()).map(|s| s.to_string());
+                    let path = tool_req.parameters.get("path").and_then(|v| v.as_str()).map(|s| s.to_string());
+                    tools.push(AvailableTool::RunTests { filter, path });
+                }                
                 // Docker operations
                 "DockerList" => {
                     tools.push(AvailableTool::DockerList {
@@ -740,6 +936,28 @@ Analyze the request and respond with JSON only:"#,
                         });
                     }
                 }
+                "DockerStats" => {
+                    let container = tool_req.parameters.get("container").and_then(|v| v.as_str()).map(|s| s.to_string());
+                    tools.push(AvailableTool::DockerStats { container });
+                }
+                "DockerPrune" => {
+                    // Pruning can't be undone, so a target is never guessed
+                    let target = match tool_req.parameters.get("target").and_then(|v| v.as_str()) {
+                        Some("containers") => DockerPruneTarget::Containers,
+                        Some("images") => DockerPruneTarget::Images,
+                        Some("volumes") => DockerPruneTarget::Volumes,
+                        Some("system") => DockerPruneTarget::System,
+                        other => {
+                            println!(
+                                "  {} DockerPrune needs a target of containers, images, volumes or system, got {}",
+                                "⚠".yellow(),
+                                other.map_or_else(|| "none".to_string(), |target| format!("'{}'", target))
+                            );
+                            continue;
+                        }
+                    };
+                    tools.push(AvailableTool::DockerPrune { target });
+                }
                 
                 // Text processing
                 "JsonFormat" => {
@@ -839,6 +1057,14 @@ Analyze the request and respond with JSON only:"#,
                     }
                 }
                 
+                // User-registered external tools; inputs are validated when the tool runs
+                name if super::plugins::external_tool(name).is_some() => {
+                    tools.push(AvailableTool::ExternalTool {
+                        name: name.to_string(),
+                        input: tool_req.parameters.clone(),
+                    });
+                }
+
                 // Add more tool conversions here...
                 _ => {
                     println!(
@@ -848,9 +1074,16 @@ Analyze the request and respond with JSON only:"#,
                     );
                 }
             }
+
+            let rationale = Some(tool_req.reasoning.trim().to_string()).filter(|r| !r.is_empty());
+            rationales.resize(tools.len(), rationale);
         }
 
-        tools
+        ToolPlan {
+            reasoning: Some(analysis.reasoning.trim().to_string()).filter(|r| !r.is_empty()),
+            tools,
+            rationales,
+        }
     }
 
     fn enhanced_fallback_parse(&self, input: &str) -> Vec<AvailableTool> {
@@ -984,3 +1217,89 @@ Analyze the request and respond with JSON only:"#,
         }
     }
 }
+
+/// One `<<<<<<< SEARCH` / `=======` / `>>>>>>> REPLACE` edit from a model response
+#[derive(Debug, Clone)]
+pub struct SearchReplaceBlock {
+    /// File named on the line before the block, if any
+    pub path: Option<String>,
+    pub search: String,
+    pub replace: String,
+}
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+/// Parse every search/replace block in `response`. The file path is taken from
+/// the last non-fence line before each block; a malformed block is an error.
+pub fn parse_search_replace_blocks(response: &str) -> Result<Vec<SearchReplaceBlock>, String> {
+    let lines: Vec<&str> = response.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if lines[i].trim() != SEARCH_MARKER {
+            i += 1;
+            continue;
+        }
+
+        let path = lines[..i]
+            .iter()
+            .rev()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty() && !line.starts_with("```"))
+            .filter(|line| *line != REPLACE_MARKER)
+            .map(clean_path_line)
+            .filter(|path| !path.is_empty());
+        let block_number = blocks.len() + 1;
+
+        let divider = (i + 1..lines.len())
+            .find(|&j| lines[j].trim() == DIVIDER_MARKER)
+            .ok_or_else(|| format!("Block {} has no '{}' line", block_number, DIVIDER_MARKER))?;
+        let end = (divider + 1..lines.len())
+            .find(|&j| lines[j].trim() == REPLACE_MARKER)
+            .ok_or_else(|| format!("Block {} has no '{}' line", block_number, REPLACE_MARKER))?;
+        if let Some(nested) = (i + 1..end).find(|&j| lines[j].trim() == SEARCH_MARKER) {
+            return Err(format!(
+                "Block {} is not closed before the next '{}' on line {}",
+                block_number,
+                SEARCH_MARKER,
+                nested + 1
+            ));
+        }
+
+        blocks.push(SearchReplaceBlock {
+            path,
+            search: lines[i + 1..divider].join("\n"),
+            replace: lines[divider + 1..end].join("\n"),
+        });
+        i = end + 1;
+    }
+
+    Ok(blocks)
+}
+
+/// Models decorate the file name line, e.g. "File: `src/main.rs`" or "**src/main.rs**"
+fn clean_path_line(line: &str) -> String {
+    let line = line.strip_prefix("File:").or_else(|| line.strip_prefix("file:")).unwrap_or(line);
+    line.trim()
+        .trim_matches(|c| matches!(c, '`' | '*' | '#' | ':' | '"'))
+        .trim()
+        .to_string()
+}
+
+/// Apply `block` to `content`, refusing unless its search text occurs exactly once
+pub fn apply_search_replace_block(content: &str, block: &SearchReplaceBlock) -> Result<String, String> {
+    if block.search.trim().is_empty() {
+        return Err("the SEARCH section is empty".to_string());
+    }
+    match content.matches(&block.search).count() {
+        1 => Ok(content.replacen(&block.search, &block.replace, 1)),
+        0 => Err("the SEARCH text was not found".to_string()),
+        count => Err(format!(
+            "the SEARCH text matches {} locations; include more context to make it unique",
+            count
+        )),
+    }
+}
@@ -1,12 +1,225 @@
 use colored::Colorize;
-use dialoguer::{Confirm, theme::ColorfulTheme};
-use std::collections::HashMap;
+use dialoguer::{Input, theme::ColorfulTheme};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::IsTerminal;
 
 use super::core::AvailableTool;
+use super::dry_run;
+
+// Blocked in every mode and cannot be allow-listed
+const ALWAYS_BLOCKED_PATTERNS: &[&str] = &["rm -rf /", "rm -rf /*", ":(){ :|:& };:"];
+
+// Pipes, chaining and redirection
+const SHELL_OPERATOR_PATTERNS: &[&str] = &["|", "&&", "||", ";", "&", ">", "<", ">>", "<<"];
+
+// Command substitution
+const SUBSTITUTION_PATTERNS: &[&str] = &["`", "$("];
+
+const NETWORK_BINARY_PATTERNS: &[&str] = &["curl", "wget", "nc", "netcat"];
+
+const SQL_PATTERNS: &[&str] = &[
+    "DROP TABLE",
+    "DELETE FROM",
+    "UPDATE",
+    "INSERT INTO",
+    "CREATE TABLE",
+    "ALTER TABLE",
+];
+
+const PATH_TRAVERSAL_PATTERNS: &[&str] = &[
+    "../", "..\\", "/etc/", "/var/", "/usr/", "/home/", "C:\\", "~/",
+];
+
+/// How strictly shell commands are validated before execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandPolicyMode {
+    /// Block shell operators, network tools, SQL keywords and sensitive paths (the historical default)
+    #[default]
+    Strict,
+    /// Allow pipes, chaining, redirection and paths; still block substitution, network tools and SQL
+    Moderate,
+    /// Only block catastrophic patterns and the user's deny list
+    Permissive,
+}
+
+impl std::str::FromStr for CommandPolicyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "strict" => Ok(CommandPolicyMode::Strict),
+            "moderate" => Ok(CommandPolicyMode::Moderate),
+            "permissive" => Ok(CommandPolicyMode::Permissive),
+            _ => Err(format!(
+                "Unknown command policy '{}': expected strict, moderate or permissive",
+                s
+            )),
+        }
+    }
+}
+
+/// Allow/deny rules applied to `ExecuteCommand`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandPolicy {
+    pub mode: CommandPolicyMode,
+    /// Built-in patterns to exempt, e.g. `curl` or `|`
+    pub allow: Vec<String>,
+    /// Extra patterns that are always rejected, checked before anything else
+    pub deny: Vec<String>,
+}
+
+impl CommandPolicy {
+    pub fn validate(&self, command: &str) -> Result<(), String> {
+        let command_lower = command.to_lowercase();
+
+        for pattern in &self.deny {
+            if command_lower.contains(&pattern.to_lowercase()) {
+                return Err(format!("Command matches deny list entry: {}", pattern));
+            }
+        }
+
+        for pattern in ALWAYS_BLOCKED_PATTERNS {
+            if command_lower.contains(pattern) {
+                return Err(format!(
+                    "Command contains potentially dangerous pattern: {}",
+                    pattern
+                ));
+            }
+        }
+
+        let (dangerous, sql, paths): (Vec<&str>, &[&str], &[&str]) = match self.mode {
+            CommandPolicyMode::Strict => (
+                SHELL_OPERATOR_PATTERNS
+                    .iter()
+                    .chain(SUBSTITUTION_PATTERNS)
+                    .chain(NETWORK_BINARY_PATTERNS)
+                    .copied()
+                    .collect(),
+                SQL_PATTERNS,
+                PATH_TRAVERSAL_PATTERNS,
+            ),
+            CommandPolicyMode::Moderate => (
+                SUBSTITUTION_PATTERNS
+                    .iter()
+                    .chain(NETWORK_BINARY_PATTERNS)
+                    .copied()
+                    .collect(),
+                SQL_PATTERNS,
+                &[],
+            ),
+            CommandPolicyMode::Permissive => (Vec::new(), &[], &[]),
+        };
+
+        for pattern in dangerous {
+            if command_lower.contains(pattern) && !self.is_allowed(pattern) {
+                return Err(format!(
+                    "Command contains potentially dangerous pattern: {}",
+                    pattern
+                ));
+            }
+        }
+
+        for pattern in sql {
+            if command_lower.contains(&pattern.to_lowercase()) && !self.is_allowed(pattern) {
+                return Err(format!(
+                    "Command contains SQL injection pattern: {}",
+                    pattern
+                ));
+            }
+        }
+
+        for pattern in paths {
+            if command_lower.contains(&pattern.to_lowercase()) && !self.is_allowed(pattern) {
+                return Err(format!(
+                    "Command contains path traversal pattern: {}",
+                    pattern
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_allowed(&self, pattern: &str) -> bool {
+        self.allow
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(pattern))
+    }
+}
+
+/// What to do with actions that need approval when there is no terminal to ask on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NonInteractivePermission {
+    #[default]
+    Deny,
+    Allow,
+}
+
+impl std::str::FromStr for NonInteractivePermission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "deny" => Ok(NonInteractivePermission::Deny),
+            "allow" => Ok(NonInteractivePermission::Allow),
+            _ => Err(format!(
+                "Unknown permission default '{}': expected deny or allow",
+                s
+            )),
+        }
+    }
+}
+
+/// Groups of actions that an "always" answer approves for the rest of the session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PermissionCategory {
+    ShellCommand,
+    FileWrite,
+    GitPush,
+    DockerRun,
+}
+
+impl PermissionCategory {
+    fn of(tool: &AvailableTool) -> Option<Self> {
+        match tool {
+            AvailableTool::ExecuteCommand { .. }
+            | AvailableTool::ScheduleTask { .. }
+            | AvailableTool::ExternalTool { .. } => Some(PermissionCategory::ShellCommand),
+            AvailableTool::FileWrite { .. }
+            | AvailableTool::FileEdit { .. }
+            | AvailableTool::FileMove { .. }
+            | AvailableTool::FileCopy { .. }
+            | AvailableTool::CreateProject { .. } => Some(PermissionCategory::FileWrite),
+            AvailableTool::GitPush { .. } => Some(PermissionCategory::GitPush),
+            AvailableTool::DockerRun { .. } => Some(PermissionCategory::DockerRun),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            PermissionCategory::ShellCommand => "shell commands",
+            PermissionCategory::FileWrite => "file writes",
+            PermissionCategory::GitPush => "git push",
+            PermissionCategory::DockerRun => "docker run",
+        }
+    }
+}
+
+enum PromptAnswer {
+    Yes,
+    No,
+    Always,
+}
 
 pub struct PermissionManager {
     auto_approve_safe: bool,
-    session_approvals: HashMap<String, bool>,
+    non_interactive: NonInteractivePermission,
+    // Categories (or, for uncategorized tools, action keys) answered with "always"
+    always_allowed: HashSet<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -17,10 +230,11 @@ enum RiskLevel {
 }
 
 impl PermissionManager {
-    pub fn new() -> Self {
+    pub fn with_settings(auto_approve_safe: bool, non_interactive: NonInteractivePermission) -> Self {
         Self {
-            auto_approve_safe: true,
-            session_approvals: HashMap::new(),
+            auto_approve_safe,
+            non_interactive,
+            always_allowed: HashSet::new(),
         }
     }
 
@@ -35,51 +249,91 @@ impl PermissionManager {
             return Ok(true);
         }
 
-        let action_key = self.get_action_key(tool);
-        if let Some(&approved) = self.session_approvals.get(&action_key) {
-            if approved {
-                println!(
-                    "{} {} {}",
-                    "✓".green(),
-                    action_desc.dimmed(),
-                    "(previously approved)".dimmed()
-                );
-                return Ok(true);
-            } else {
-                println!(
-                    "{} {} {}",
-                    "✗".red(),
-                    action_desc.dimmed(),
-                    "(previously denied)".dimmed()
-                );
-                return Ok(false);
-            }
+        // Nothing runs under --dry-run, so there is nothing to approve
+        if dry_run::dry_run() && dry_run::describe(tool).is_some() {
+            println!("{} {} {}", "✓".green(), action_desc.dimmed(), "(dry run)".dimmed());
+            return Ok(true);
+        }
+
+        let category = PermissionCategory::of(tool);
+        let always_key = match category {
+            Some(category) => format!("category:{:?}", category),
+            None => self.get_action_key(tool),
+        };
+        if self.always_allowed.contains(&always_key) {
+            let reason = match category {
+                Some(category) => format!("(all {} allowed this session)", category.label()),
+                None => "(always allowed this session)".to_string(),
+            };
+            println!("{} {} {}", "✓".green(), action_desc.dimmed(), reason.dimmed());
+            return Ok(true);
         }
 
         self.show_action_preview(tool);
 
-        let prompt = match risk_level {
-            RiskLevel::Safe => format!("{} Execute this action?", "🔵".blue()),
-            RiskLevel::Moderate => format!("{} Execute this action?", "🟡".yellow()),
-            RiskLevel::Dangerous => {
-                format!("{} Execute this POTENTIALLY DANGEROUS action?", "🔴".red())
-            }
+        if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+            let approved = self.non_interactive == NonInteractivePermission::Allow;
+            let (mark, verdict) = if approved {
+                ("✓".green(), "allowed")
+            } else {
+                ("✗".red(), "denied")
+            };
+            println!(
+                "{} {} {}",
+                mark,
+                action_desc.dimmed(),
+                format!("({} without a terminal; see non_interactive_permission)", verdict).dimmed()
+            );
+            return Ok(approved);
+        }
+
+        let warning = match risk_level {
+            RiskLevel::Safe => "🔵".blue(),
+            RiskLevel::Moderate => "🟡".yellow(),
+            RiskLevel::Dangerous => "🔴".red(),
+        };
+        let always_hint = match category {
+            Some(category) => format!("always = allow all {} this session", category.label()),
+            None => "always = allow this action for the rest of the session".to_string(),
         };
+        println!("  {}", always_hint.dimmed());
 
-        let approved = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(prompt)
-            .default(risk_level == RiskLevel::Safe)
-            .interact()?;
+        let answer = loop {
+            let input: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("{} Allow this action? [y/N/always]", warning))
+                .allow_empty(true)
+                .interact_text()?;
 
-        self.session_approvals.insert(action_key, approved);
+            match input.trim().to_lowercase().as_str() {
+                "y" | "yes" => break PromptAnswer::Yes,
+                "" | "n" | "no" => break PromptAnswer::No,
+                "a" | "always" => break PromptAnswer::Always,
+                _ => println!("{} Please answer y, n or always", "?".yellow()),
+            }
+        };
 
-        if approved {
-            println!("{} Action approved", "✓".green());
-        } else {
-            println!("{} Action denied", "✗".red());
+        match answer {
+            PromptAnswer::Yes => {
+                println!("{} Action approved", "✓".green());
+                Ok(true)
+            }
+            PromptAnswer::Always => {
+                self.always_allowed.insert(always_key);
+                match category {
+                    Some(category) => println!(
+                        "{} Action approved; {} are allowed for the rest of this session",
+                        "✓".green(),
+                        category.label()
+                    ),
+                    None => println!("{} Action approved for the rest of this session", "✓".green()),
+                }
+                Ok(true)
+            }
+            PromptAnswer::No => {
+                println!("{} Action denied", "✗".red());
+                Ok(false)
+            }
         }
-
-        Ok(approved)
     }
 
     fn describe_action(&self, tool: &AvailableTool) -> (String, RiskLevel) {
@@ -90,6 +344,9 @@ impl PermissionManager {
             AvailableTool::WebScrape { url } => {
                 (format!("Scrape content from: {}", url), RiskLevel::Safe)
             }
+            AvailableTool::GithubIssue { reference } => {
+                (format!("Fetch GitHub issue: {}", reference), RiskLevel::Safe)
+            }
             AvailableTool::FileSearch { pattern, directory } => (
                 format!(
                     "Search for files matching '{}' in {}",
@@ -105,7 +362,16 @@ impl PermissionManager {
             AvailableTool::FileEdit { path, operation: _ } => {
                 (format!("Edit file: {}", path), RiskLevel::Moderate)
             }
-            AvailableTool::ContentSearch { pattern, directory } => (
+            AvailableTool::FileMove { from, to } => {
+                (format!("Move {} to {}", from, to), RiskLevel::Moderate)
+            }
+            AvailableTool::FileCopy { from, to } => {
+                (format!("Copy {} to {}", from, to), RiskLevel::Moderate)
+            }
+            AvailableTool::FileDelete { path, .. } => {
+                (format!("Delete: {}", path), RiskLevel::Dangerous)
+            }
+            AvailableTool::ContentSearch { pattern, directory, .. } => (
                 format!(
                     "Search for content '{}' in {}",
                     pattern,
@@ -134,9 +400,28 @@ impl PermissionManager {
                 format!("Generate command for: {}", user_request),
                 RiskLevel::Safe,
             ),
+            AvailableTool::ExternalTool { name, input } => (
+                format!("Run external tool '{}' with {}", name, input),
+                RiskLevel::Dangerous,
+            ),
             AvailableTool::ListDirectory { path } => {
                 (format!("List directory: {}", path), RiskLevel::Safe)
             }
+            AvailableTool::GitPush { remote, .. } => (
+                format!("Push to {}", remote.as_deref().unwrap_or("origin")),
+                RiskLevel::Moderate,
+            ),
+            AvailableTool::DockerRun { image, .. } => {
+                (format!("Run Docker container: {}", image), RiskLevel::Moderate)
+            }
+            AvailableTool::DockerStats { container } => (
+                format!("Show Docker stats for {}", container.as_deref().unwrap_or("all containers")),
+                RiskLevel::Safe,
+            ),
+            AvailableTool::DockerPrune { target } => (
+                format!("Prune Docker {}", target.description()),
+                RiskLevel::Dangerous,
+            ),
             AvailableTool::FileWatch { path, duration_seconds } => {
                 let duration_desc = if let Some(duration) = duration_seconds {
                     format!(" for {} seconds", duration)
@@ -145,6 +430,14 @@ impl PermissionManager {
                 };
                 (format!("Watch file '{}'{}", path, duration_desc), RiskLevel::Safe)
             }
+            AvailableTool::RunTests { filter, path } => (
+                format!(
+                    "Run tests in {}{}",
+                    path.as_deref().unwrap_or("."),
+                    filter.as_ref().map(|f| format!(" matching '{}'", f)).unwrap_or_default()
+                ),
+                RiskLevel::Moderate,
+            ),
             _ => (format!("Run tool: {:?}", tool), RiskLevel::Moderate),
         }
     }
@@ -201,8 +494,69 @@ impl PermissionManager {
                     "Effect:".blue()
                 );
             }
+            AvailableTool::ExternalTool { name, input } => {
+                println!("  {} {}", "Type:".blue(), "External Tool".red());
+                println!("  {} {}", "Tool:".blue(), name.yellow());
+                if let Some(spec) = super::plugins::external_tool(name) {
+                    println!("  {} {} {}", "Runs:".blue(), spec.executable, spec.args.join(" ").dimmed());
+                }
+                println!("  {} {}", "Inputs:".blue(), input);
+            }
+            AvailableTool::FileEdit { path, .. } => {
+                println!("  {} File Edit", "Type:".blue());
+                println!("  {} {}", "Path:".blue(), path.yellow());
+                if let Some(action) = dry_run::describe(tool) {
+                    println!("  {} {}", "Change:".blue(), action);
+                }
+            }
+            AvailableTool::GitPush {
+                remote,
+                branch,
+                repository_path,
+            } => {
+                let mut command = String::from("git");
+                if let Some(path) = repository_path {
+                    command.push_str(&format!(" -C {}", path));
+                }
+                command.push_str(&format!(" push {}", remote.as_deref().unwrap_or("origin")));
+                if let Some(branch) = branch {
+                    command.push_str(&format!(" {}", branch));
+                }
+                println!("  {} {}", "Type:".blue(), "Git Push".red());
+                println!("  {} {}", "Command:".blue(), command.yellow());
+                println!("  {} Upload local commits to the remote repository", "Effect:".blue());
+            }
+            AvailableTool::DockerRun {
+                image,
+                command,
+                ports,
+                volumes,
+                environment,
+            } => {
+                let command = dry_run::docker_run_command(image, command, ports, volumes, environment);
+                println!("  {} {}", "Type:".blue(), "Docker Run".red());
+                println!("  {} {}", "Command:".blue(), command.yellow());
+                println!("  {} Start a detached container from {}", "Effect:".blue(), image);
+            }
+            AvailableTool::FileDelete { path, recursive } => {
+                println!("  {} {}", "Type:".blue(), "File Delete".red());
+                println!("  {} {}", "Path:".blue(), path.yellow());
+                if *recursive {
+                    println!("  {} Permanently remove it and everything in it", "Effect:".blue());
+                } else {
+                    println!("  {} Remove it (a backup is kept for `tool file restore`)", "Effect:".blue());
+                }
+            }
+            AvailableTool::DockerPrune { target } => {
+                println!("  {} {}", "Type:".blue(), "Docker Prune".red());
+                println!("  {} docker {}", "Command:".blue(), target.docker_args().join(" ").yellow());
+                println!("  {} Permanently remove {}", "Effect:".blue(), target.description());
+            }
             _ => {
                 println!("  {} {:?}", "Type:".blue(), tool);
+                if let Some(action) = dry_run::describe(tool) {
+                    println!("  {} {}", "Action:".blue(), action.yellow());
+                }
             }
         }
         println!();
@@ -213,7 +567,9 @@ impl PermissionManager {
             AvailableTool::WebSearch { .. } => "web_search".to_string(),
             AvailableTool::WebScrape { url } => format!("web_scrape:{}", url),
             AvailableTool::FileWrite { path, .. } => format!("file_write:{}", path),
+            AvailableTool::FileDelete { path, .. } => format!("file_delete:{}", path),
             AvailableTool::ExecuteCommand { command } => format!("execute_command:{}", command),
+            AvailableTool::ExternalTool { name, .. } => format!("external_tool:{}", name),
             AvailableTool::GenerateCommand { user_request, .. } => format!("generate_command:{}", user_request),
             _ => format!("{:?}", std::mem::discriminant(tool)),
         }
@@ -0,0 +1,318 @@
+use colored::Colorize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+use super::core::{ToolExecutor, ToolResult};
+
+/// A user script exposed to the model as a tool, configured under
+/// `external_tools.<name>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalToolSpec {
+    /// Shown to the model in its tool list
+    pub description: String,
+    /// Program to run; it is started directly, not through a shell
+    pub executable: String,
+    /// Arguments with `{input}` placeholders. An argument whose placeholders
+    /// have no value is dropped, and an argument that is just `{input}` for an
+    /// array input expands to one argument per element.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// JSON Schema for the inputs: an object schema with `properties`
+    /// (`type`, `enum`, `description`), `required` and `additionalProperties`
+    #[serde(default = "empty_object_schema")]
+    pub input_schema: Value,
+}
+
+fn empty_object_schema() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+const SCHEMA_TYPES: &[&str] = &["string", "number", "integer", "boolean", "array", "object", "null"];
+
+lazy_static::lazy_static! {
+    static ref REGISTRY: RwLock<BTreeMap<String, ExternalToolSpec>> = RwLock::new(BTreeMap::new());
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+}
+
+/// Replace the registered external tools; invalid specs are reported and skipped
+pub fn register_external_tools(specs: HashMap<String, ExternalToolSpec>) {
+    let mut registry = REGISTRY.write().unwrap();
+    registry.clear();
+    for (name, spec) in specs {
+        match check_spec(&name, &spec) {
+            Ok(()) => {
+                registry.insert(name, spec);
+            }
+            Err(e) => eprintln!("{} Skipping external tool '{}': {}", "⚠️".yellow(), name, e),
+        }
+    }
+}
+
+pub fn external_tool(name: &str) -> Option<ExternalToolSpec> {
+    REGISTRY.read().unwrap().get(name).cloned()
+}
+
+/// Registered external tools, sorted by name
+pub fn external_tools() -> Vec<(String, ExternalToolSpec)> {
+    REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(name, spec)| (name.clone(), spec.clone()))
+        .collect()
+}
+
+/// Problems with a spec that would make every call to it fail
+pub fn check_spec(name: &str, spec: &ExternalToolSpec) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err("names may only use letters, digits, '_' and '-'".to_string());
+    }
+    if spec.executable.trim().is_empty() {
+        return Err("executable is empty".to_string());
+    }
+
+    let schema = spec
+        .input_schema
+        .as_object()
+        .ok_or("input_schema must be a JSON object")?;
+    if schema.get("type").is_some_and(|t| t != "object") {
+        return Err("input_schema must have type \"object\"".to_string());
+    }
+    let properties = match schema.get("properties") {
+        None => serde_json::Map::new(),
+        Some(Value::Object(properties)) => properties.clone(),
+        Some(_) => return Err("input_schema.properties must be an object".to_string()),
+    };
+    for (property, definition) in &properties {
+        let types = match definition.get("type") {
+            None => Vec::new(),
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            Some(_) => return Err(format!("type of '{}' must be a string or list", property)),
+        };
+        if let Some(unknown) = types.iter().find(|t| !SCHEMA_TYPES.contains(t)) {
+            return Err(format!(
+                "unsupported type '{}' for '{}' (expected one of: {})",
+                unknown,
+                property,
+                SCHEMA_TYPES.join(", ")
+            ));
+        }
+    }
+    for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+        let required = required.as_str().ok_or("input_schema.required must list property names")?;
+        if !properties.contains_key(required) {
+            return Err(format!("required input '{}' is not in properties", required));
+        }
+    }
+    for arg in &spec.args {
+        for placeholder in PLACEHOLDER.captures_iter(arg) {
+            if !properties.contains_key(&placeholder[1]) {
+                return Err(format!("argument '{}' uses undeclared input '{}'", arg, &placeholder[1]));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_type(value: &Value, schema_type: &str) -> bool {
+    match schema_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+/// Check tool inputs against the spec's schema
+pub fn validate_input(schema: &Value, input: &Value) -> Result<(), String> {
+    let empty = serde_json::Map::new();
+    let input = match input {
+        Value::Object(input) => input,
+        Value::Null => &empty,
+        _ => return Err("inputs must be a JSON object".to_string()),
+    };
+    let properties = schema.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+
+    for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+        if let Some(required) = required.as_str() {
+            if input.get(required).is_none_or(Value::is_null) {
+                return Err(format!("missing required input '{}'", required));
+            }
+        }
+    }
+
+    let allow_additional = schema.get("additionalProperties") != Some(&Value::Bool(false));
+    for (key, value) in input {
+        let Some(definition) = properties.get(key) else {
+            if allow_additional {
+                continue;
+            }
+            let mut known: Vec<_> = properties.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            return Err(format!("unknown input '{}' (expected: {})", key, known.join(", ")));
+        };
+
+        let types: Vec<&str> = match definition.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+            return Err(format!("input '{}' must be of type {}, got {}", key, types.join(" or "), value));
+        }
+        if let Some(allowed) = definition.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(value) {
+                let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                return Err(format!("input '{}' must be one of: {}", key, allowed.join(", ")));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn argument_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The spec's arguments with inputs substituted
+pub fn render_args(spec: &ExternalToolSpec, input: &Value) -> Vec<String> {
+    let lookup = |name: &str| input.get(name).filter(|value| !value.is_null());
+    let mut args = Vec::new();
+
+    for arg in &spec.args {
+        if let Some(whole) = PLACEHOLDER.captures(arg).filter(|c| c[0].len() == arg.len()) {
+            match lookup(&whole[1]) {
+                Some(Value::Array(items)) => args.extend(items.iter().map(argument_text)),
+                Some(value) => args.push(argument_text(value)),
+                None => {}
+            }
+            continue;
+        }
+        if PLACEHOLDER.captures_iter(arg).any(|c| lookup(&c[1]).is_none()) {
+            continue;
+        }
+        let rendered = PLACEHOLDER.replace_all(arg, |c: &regex::Captures| {
+            lookup(&c[1]).map(argument_text).unwrap_or_default()
+        });
+        args.push(rendered.into_owned());
+    }
+
+    args
+}
+
+/// Quote an argument for display and command policy checks
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Tool list entries for the registered external tools, for the parser prompt
+pub fn prompt_section() -> String {
+    let tools = external_tools();
+    if tools.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("## Custom Tools (use the tool name as tool_type)\n");
+    for (name, spec) in tools {
+        section.push_str(&format!("- {}: {}\n", name, spec.description));
+        let required: Vec<&str> = spec.input_schema["required"]
+            .as_array()
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let mut parameters = Vec::new();
+        if let Some(properties) = spec.input_schema["properties"].as_object() {
+            for (property, definition) in properties {
+                let mut text = format!(
+                    "{} ({}{})",
+                    property,
+                    definition["type"].as_str().unwrap_or("any"),
+                    if required.contains(&property.as_str()) { "" } else { ", optional" }
+                );
+                if let Some(description) = definition["description"].as_str() {
+                    text.push_str(&format!(": {}", description));
+                }
+                parameters.push(text);
+            }
+        }
+        if parameters.is_empty() {
+            section.push_str("  Parameters: none\n");
+        } else {
+            section.push_str(&format!("  Parameters: {}\n", parameters.join(", ")));
+        }
+    }
+    section.push('\n');
+    section
+}
+
+impl ToolExecutor {
+    /// Run a registered external tool: validate the inputs against its schema,
+    /// fill in the argument template and return the program's output
+    pub async fn run_external_tool(
+        &self,
+        name: &str,
+        input: &Value,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let failure = |error: String| ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            metadata: Some(serde_json::json!({ "external_tool": name })),
+            web_search_result: None,
+        };
+
+        let Some(spec) = external_tool(name) else {
+            return Ok(failure(format!("Unknown external tool: {}", name)));
+        };
+        if let Err(e) = validate_input(&spec.input_schema, input) {
+            return Ok(failure(format!("Invalid input for {}: {}", name, e)));
+        }
+
+        let args = render_args(&spec, input);
+        let command_line = std::iter::once(spec.executable.as_str())
+            .map(shell_quote)
+            .chain(args.iter().map(|arg| shell_quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("{} Running external tool {}: {}", "🔌".cyan(), name.yellow(), command_line);
+
+        // Same policy and timeout as ExecuteCommand
+        let policy = self.get_command_policy().await;
+        if let Err(e) = self.validate_command(&command_line, &policy) {
+            return Ok(failure(e));
+        }
+
+        let mut command = tokio::process::Command::new(&spec.executable);
+        command.args(&args);
+        let timeout_secs = self.get_command_timeout().await;
+        let mut result = match self.stream_process(command, timeout_secs).await {
+            Ok(result) => result,
+            Err(e) => return Ok(failure(format!("Failed to run {}: {}", spec.executable, e))),
+        };
+
+        let mut metadata = match result.metadata.take() {
+            Some(Value::Object(metadata)) => metadata,
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert("external_tool".to_string(), Value::String(name.to_string()));
+        metadata.insert("command".to_string(), Value::String(command_line));
+        result.metadata = Some(Value::Object(metadata));
+        Ok(result)
+    }
+}
@@ -3,10 +3,12 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use ignore::{WalkBuilder, WalkState};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
 use tokio::sync::RwLock;
-use walkdir::WalkDir;
 
 use super::core::ToolResult;
 
@@ -48,6 +50,8 @@ pub struct SearchQuery {
     pub search_content: bool,
     pub search_filenames: bool,
     pub fuzzy_matching: bool,
+    /// Worker threads for walking and scoring; 0 uses one per CPU
+    pub threads: usize,
 }
 
 impl Default for SearchQuery {
@@ -68,12 +72,66 @@ impl Default for SearchQuery {
             search_content: true,
             search_filenames: true,
             fuzzy_matching: true,
+            threads: 0,
         }
     }
 }
 
+/// Resolves a configured thread count, where 0 means one per CPU
+pub fn resolve_search_threads(threads: usize) -> usize {
+    if threads > 0 {
+        threads
+    } else {
+        std::thread::available_parallelism().map_or(1, |count| count.get())
+    }
+}
+
+/// Walks `root` on `threads` worker threads, honouring .gitignore and hidden-file rules,
+/// and collects what `visit` returns for each file. `visit` flags strong matches; once
+/// `stop_after` of them are found the walk stops early, which the returned bool reports.
+pub fn parallel_file_walk<T, F>(root: &Path, threads: usize, stop_after: Option<usize>, visit: F) -> (Vec<T>, bool)
+where
+    T: Send,
+    F: Fn(&Path) -> Option<(T, bool)> + Sync,
+{
+    let found = Mutex::new(Vec::new());
+    let strong_matches = AtomicUsize::new(0);
+    let stopped_early = AtomicBool::new(false);
+
+    WalkBuilder::new(root)
+        .threads(resolve_search_threads(threads))
+        .build_parallel()
+        .run(|| {
+            let (found, strong_matches, stopped_early, visit) = (&found, &strong_matches, &stopped_early, &visit);
+            Box::new(move |entry| {
+                if stop_after.is_some_and(|max| strong_matches.load(Ordering::Relaxed) >= max) {
+                    stopped_early.store(true, Ordering::Relaxed);
+                    return WalkState::Quit;
+                }
+                let Ok(entry) = entry else {
+                    return WalkState::Continue;
+                };
+                if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+                    return WalkState::Continue;
+                }
+                if let Some((item, strong)) = visit(entry.path()) {
+                    if strong {
+                        strong_matches.fetch_add(1, Ordering::Relaxed);
+                    }
+                    found.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(item);
+                }
+                WalkState::Continue
+            })
+        });
+
+    (
+        found.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner()),
+        stopped_early.into_inner(),
+    )
+}
+
 pub struct SearchIndex {
-    file_index: RwLock<HashMap<PathBuf, FileMetadata>>,
+    file_index: Arc<RwLock<HashMap<PathBuf, FileMetadata>>>,
     ignore_patterns: Vec<glob::Pattern>,
     root_path: PathBuf,
 }
@@ -97,67 +155,72 @@ impl SearchIndex {
             .collect();
 
         Self {
-            file_index: RwLock::new(HashMap::new()),
+            file_index: Arc::new(RwLock::new(HashMap::new())),
             ignore_patterns,
             root_path,
         }
     }
 
-    pub async fn build_index(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn build_index(self: &Arc<Self>, threads: usize) -> Result<(), Box<dyn std::error::Error>> {
         println!("{} Building search index...", "🔍".cyan());
-        
-        let mut index = self.file_index.write().await;
-        index.clear();
 
-        for entry in WalkDir::new(&self.root_path).follow_links(false) {
-            let entry = entry?;
-            if entry.file_type().is_file() {
-                let path = entry.path();
-                
+        // The walk blocks its threads on disk I/O, so keep it off the async runtime
+        let this = Arc::clone(self);
+        let (files, _) = tokio::task::spawn_blocking(move || {
+            parallel_file_walk(&this.root_path, threads, None, |path| {
                 // Check if file should be ignored
-                if self.should_ignore(path) {
-                    continue;
+                if this.should_ignore(path) {
+                    return None;
                 }
+                let metadata = fs::metadata(path).ok()?;
+                let file_metadata = FileMetadata {
+                    path: path.to_path_buf(),
+                    size: metadata.len(),
+                    modified: metadata.modified().ok()?,
+                    file_type: this.get_file_type(path),
+                    content_hash: None, // TODO: Implement content hashing for change detection
+                };
+                Some((file_metadata, false))
+            })
+        })
+        .await?;
 
-                if let Ok(metadata) = entry.metadata() {
-                    let file_metadata = FileMetadata {
-                        path: path.to_path_buf(),
-                        size: metadata.len(),
-                        modified: metadata.modified()?,
-                        file_type: self.get_file_type(path),
-                        content_hash: None, // TODO: Implement content hashing for change detection
-                    };
-
-                    index.insert(path.to_path_buf(), file_metadata);
-                }
-            }
-        }
+        let mut index = self.file_index.write().await;
+        index.clear();
+        index.extend(files.into_iter().map(|file| (file.path.clone(), file)));
 
         println!("{} Indexed {} files", "✅".green(), index.len());
         Ok(())
     }
 
-    pub async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
-        let index = self.file_index.read().await;
-        let mut results = Vec::new();
+    pub async fn search(self: &Arc<Self>, query: &SearchQuery) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+        let index = Arc::clone(&self.file_index).read_owned().await;
 
         // Use fuzzy matching if enabled, otherwise use regex
-        if query.fuzzy_matching {
-            self.fuzzy_search(&index, query, &mut results);
+        let regex = if query.fuzzy_matching {
+            None
+        } else if query.is_regex {
+            Some(Regex::new(&query.pattern)?)
         } else {
-            let regex = if query.is_regex {
-                Regex::new(&query.pattern)?
+            let escaped_pattern = if query.case_sensitive {
+                regex::escape(&query.pattern)
             } else {
-                let escaped_pattern = if query.case_sensitive {
-                    regex::escape(&query.pattern)
-                } else {
-                    format!("(?i){}", regex::escape(&query.pattern))
-                };
-                Regex::new(&escaped_pattern)?
+                format!("(?i){}", regex::escape(&query.pattern))
             };
+            Some(Regex::new(&escaped_pattern)?)
+        };
+        let pattern = if query.case_sensitive {
+            query.pattern.clone()
+        } else {
+            query.pattern.to_lowercase()
+        };
 
-            self.regex_search(&index, query, &regex, &mut results)?;
-        }
+        // Scoring reads every file, so it runs off the async runtime like the index walk
+        let (this, query_owned) = (Arc::clone(self), query.clone());
+        let mut results = tokio::task::spawn_blocking(move || {
+            this.score_files(&index, &query_owned, regex.as_ref(), &pattern)
+        })
+        .await?;
 
         // Sort by relevance score (descending)
         results.sort_by(|a, b| {
@@ -174,6 +237,51 @@ impl SearchIndex {
         Ok(results)
     }
 
+    /// Scores the indexed files on worker threads; each stops once enough strong matches are in
+    fn score_files(
+        &self,
+        index: &HashMap<PathBuf, FileMetadata>,
+        query: &SearchQuery,
+        regex: Option<&Regex>,
+        pattern: &str,
+    ) -> Vec<SearchResult> {
+        let entries: Vec<(&PathBuf, &FileMetadata)> = index.iter().collect();
+        let threads = resolve_search_threads(query.threads);
+        let chunk_size = entries.len().div_ceil(threads).max(1);
+        let strong_matches = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = entries
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let strong_matches = &strong_matches;
+                    scope.spawn(move || {
+                        let mut found = Vec::new();
+                        for (path, metadata) in chunk {
+                            if query.max_results.is_some_and(|max| strong_matches.load(Ordering::Relaxed) >= max) {
+                                break;
+                            }
+                            let scored = match regex {
+                                Some(regex) => self.regex_match_file(path, metadata, query, regex),
+                                None => self.fuzzy_match_file(path, metadata, query, pattern),
+                            };
+                            if let Some((result, strong)) = scored {
+                                if strong {
+                                    strong_matches.fetch_add(1, Ordering::Relaxed);
+                                }
+                                found.push(result);
+                            }
+                        }
+                        found
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        })
+    }
+
     pub async fn find_file_by_name(&self, name: &str) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
         let index = self.file_index.read().await;
         let mut results = Vec::new();
@@ -229,172 +337,181 @@ impl SearchIndex {
         }
     }
 
-    fn fuzzy_search(&self, index: &HashMap<PathBuf, FileMetadata>, query: &SearchQuery, results: &mut Vec<SearchResult>) {
-        let pattern = if query.case_sensitive {
-            query.pattern.clone()
-        } else {
-            query.pattern.to_lowercase()
-        };
+    /// Scores one file; the flag marks a strong match (the pattern appears verbatim in the filename)
+    fn fuzzy_match_file(&self, path: &Path, metadata: &FileMetadata, query: &SearchQuery, pattern: &str) -> Option<(SearchResult, bool)> {
+        // Check include/exclude patterns
+        if !self.matches_include_patterns(path, &query.include_patterns) {
+            return None;
+        }
+        if self.matches_exclude_patterns(path, &query.exclude_patterns) {
+            return None;
+        }
 
-        for (path, metadata) in index.iter() {
-            // Check include/exclude patterns
-            if !self.matches_include_patterns(path, &query.include_patterns) {
-                continue;
-            }
-            if self.matches_exclude_patterns(path, &query.exclude_patterns) {
-                continue;
-            }
+        let mut matches = Vec::new();
+        let mut relevance_score = 0.0;
+        let mut fuzzy_score = None;
+        let mut fuzzy_matches = Vec::new();
 
-            let mut matches = Vec::new();
-            let mut relevance_score = 0.0;
-            let mut fuzzy_score = None;
-            let mut fuzzy_matches = Vec::new();
+        // Fuzzy search in filename
+        if query.search_filenames {
+            if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
+                let search_text = if query.case_sensitive {
+                    filename.to_string()
+                } else {
+                    filename.to_lowercase()
+                };
 
-            // Fuzzy search in filename
-            if query.search_filenames {
-                if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                    let search_text = if query.case_sensitive {
-                        filename.to_string()
-                    } else {
-                        filename.to_lowercase()
-                    };
+                if let Some((score, match_positions)) = self.fuzzy_match(pattern, &search_text) {
+                    relevance_score += score * 10.0; // Filename matches are highly relevant
+                    fuzzy_score = Some(score);
+                    fuzzy_matches = match_positions;
+                    matches.push(Match {
+                        line_number: 0,
+                        line_content: filename.to_string(),
+                        match_start: 0,
+                        match_end: filename.len(),
+                    });
+                }
+            }
+        }
+
+        // Fuzzy search in full path for better subdirectory matching
+        if query.search_filenames {
+            let full_path = path.to_string_lossy();
+            let search_text = if query.case_sensitive {
+                full_path.to_string()
+            } else {
+                full_path.to_lowercase()
+            };
 
-                    if let Some((score, match_positions)) = self.fuzzy_match(&pattern, &search_text) {
-                        relevance_score += score * 10.0; // Filename matches are highly relevant
-                        fuzzy_score = Some(score);
-                        fuzzy_matches = match_positions;
+            if let Some((score, match_positions)) = self.fuzzy_match(pattern, &search_text) {
+                // Only update if this is a better match than filename alone
+                if fuzzy_score.is_none_or(|existing| score > existing) {
+                    relevance_score = score * 8.0; // Path matches are also highly relevant
+                    fuzzy_score = Some(score);
+                    fuzzy_matches = match_positions;
+                    
+                    // Add or update the match
+                    if matches.is_empty() {
                         matches.push(Match {
                             line_number: 0,
-                            line_content: filename.to_string(),
+                            line_content: full_path.to_string(),
                             match_start: 0,
-                            match_end: filename.len(),
+                            match_end: full_path.len(),
                         });
                     }
                 }
             }
+        }
 
-            // Fuzzy search in full path for better subdirectory matching
-            if query.search_filenames {
-                let full_path = path.to_string_lossy();
-                let search_text = if query.case_sensitive {
-                    full_path.to_string()
-                } else {
-                    full_path.to_lowercase()
-                };
+        // Fuzzy search in file content
+        if query.search_content && self.is_text_file(path) {
+            if let Ok(content) = fs::read_to_string(path) {
+                for (line_number, line) in content.lines().enumerate() {
+                    let search_text = if query.case_sensitive {
+                        line.to_string()
+                    } else {
+                        line.to_lowercase()
+                    };
 
-                if let Some((score, match_positions)) = self.fuzzy_match(&pattern, &search_text) {
-                    // Only update if this is a better match than filename alone
-                    if fuzzy_score.map_or(true, |existing| score > existing) {
-                        relevance_score = score * 8.0; // Path matches are also highly relevant
-                        fuzzy_score = Some(score);
-                        fuzzy_matches = match_positions;
-                        
-                        // Add or update the match
-                        if matches.is_empty() {
-                            matches.push(Match {
-                                line_number: 0,
-                                line_content: full_path.to_string(),
-                                match_start: 0,
-                                match_end: full_path.len(),
-                            });
-                        }
+                    if let Some((score, _)) = self.fuzzy_match(pattern, &search_text) {
+                        relevance_score += score;
+                        matches.push(Match {
+                            line_number: line_number + 1,
+                            line_content: line.to_string(),
+                            match_start: 0,
+                            match_end: line.len(),
+                        });
                     }
                 }
             }
+        }
 
-            // Fuzzy search in file content
-            if query.search_content && self.is_text_file(path) {
-                if let Ok(content) = fs::read_to_string(path) {
-                    for (line_number, line) in content.lines().enumerate() {
-                        let search_text = if query.case_sensitive {
-                            line.to_string()
-                        } else {
-                            line.to_lowercase()
-                        };
-
-                        if let Some((score, _)) = self.fuzzy_match(&pattern, &search_text) {
-                            relevance_score += score;
-                            matches.push(Match {
-                                line_number: line_number + 1,
-                                line_content: line.to_string(),
-                                match_start: 0,
-                                match_end: line.len(),
-                            });
-                        }
-                    }
+        let strong = query.search_filenames
+            && path.file_name().and_then(|n| n.to_str()).is_some_and(|filename| {
+                if query.case_sensitive {
+                    filename.contains(pattern)
+                } else {
+                    filename.to_lowercase().contains(pattern)
                 }
-            }
-
-            if !matches.is_empty() {
-                results.push(SearchResult {
-                    path: path.clone(),
-                    relevance_score,
-                    matches,
-                    metadata: metadata.clone(),
-                    fuzzy_score,
-                    fuzzy_matches,
-                });
-            }
+            });
+        if matches.is_empty() {
+            return None;
         }
+        Some((
+            SearchResult {
+                path: path.to_path_buf(),
+                relevance_score,
+                matches,
+                metadata: metadata.clone(),
+                fuzzy_score,
+                fuzzy_matches,
+            },
+            strong,
+        ))
     }
 
-    fn regex_search(&self, index: &HashMap<PathBuf, FileMetadata>, query: &SearchQuery, regex: &Regex, results: &mut Vec<SearchResult>) -> Result<(), Box<dyn std::error::Error>> {
-        for (path, metadata) in index.iter() {
-            // Check include/exclude patterns
-            if !self.matches_include_patterns(path, &query.include_patterns) {
-                continue;
-            }
-            if self.matches_exclude_patterns(path, &query.exclude_patterns) {
-                continue;
-            }
+    /// Scores one file; the flag marks a strong match (the filename itself matches)
+    fn regex_match_file(&self, path: &Path, metadata: &FileMetadata, query: &SearchQuery, regex: &Regex) -> Option<(SearchResult, bool)> {
+        // Check include/exclude patterns
+        if !self.matches_include_patterns(path, &query.include_patterns) {
+            return None;
+        }
+        if self.matches_exclude_patterns(path, &query.exclude_patterns) {
+            return None;
+        }
 
-            let mut matches = Vec::new();
-            let mut relevance_score = 0.0;
+        let mut matches = Vec::new();
+        let mut relevance_score = 0.0;
 
-            // Search in filename
-            if query.search_filenames {
-                if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                    if regex.is_match(filename) {
-                        relevance_score += 10.0; // Filename matches are highly relevant
-                        matches.push(Match {
-                            line_number: 0,
-                            line_content: filename.to_string(),
-                            match_start: 0,
-                            match_end: filename.len(),
-                        });
-                    }
+        // Search in filename
+        if query.search_filenames {
+            if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
+                if regex.is_match(filename) {
+                    relevance_score += 10.0; // Filename matches are highly relevant
+                    matches.push(Match {
+                        line_number: 0,
+                        line_content: filename.to_string(),
+                        match_start: 0,
+                        match_end: filename.len(),
+                    });
                 }
             }
+        }
 
-            // Search in file content
-            if query.search_content && self.is_text_file(path) {
-                if let Ok(content) = fs::read_to_string(path) {
-                    for (line_number, line) in content.lines().enumerate() {
-                        if let Some(mat) = regex.find(line) {
-                            relevance_score += 1.0;
-                            matches.push(Match {
-                                line_number: line_number + 1,
-                                line_content: line.to_string(),
-                                match_start: mat.start(),
-                                match_end: mat.end(),
-                            });
-                        }
+        // Search in file content
+        if query.search_content && self.is_text_file(path) {
+            if let Ok(content) = fs::read_to_string(path) {
+                for (line_number, line) in content.lines().enumerate() {
+                    if let Some(mat) = regex.find(line) {
+                        relevance_score += 1.0;
+                        matches.push(Match {
+                            line_number: line_number + 1,
+                            line_content: line.to_string(),
+                            match_start: mat.start(),
+                            match_end: mat.end(),
+                        });
                     }
                 }
             }
+        }
 
-            if !matches.is_empty() {
-                results.push(SearchResult {
-                    path: path.clone(),
-                    relevance_score,
-                    matches,
-                    metadata: metadata.clone(),
-                    fuzzy_score: None,
-                    fuzzy_matches: Vec::new(),
-                });
-            }
+        // The filename match, when there is one, is always pushed first
+        let strong = matches.first().is_some_and(|m| m.line_number == 0);
+        if matches.is_empty() {
+            return None;
         }
-        Ok(())
+        Some((
+            SearchResult {
+                path: path.to_path_buf(),
+                relevance_score,
+                matches,
+                metadata: metadata.clone(),
+                fuzzy_score: None,
+                fuzzy_matches: Vec::new(),
+            },
+            strong,
+        ))
     }
 
     // FZF-like fuzzy matching algorithm
@@ -472,6 +589,20 @@ pub enum ErrorStrategy {
     RetryWithBackoff { max_retries: u32, backoff_ms: u64 },
 }
 
+impl ErrorStrategy {
+    /// Retries allowed after a failure and the pause before each one; only
+    /// `RetryWithBackoff` retries
+    pub fn retry_budget(&self) -> (u32, u64) {
+        match self {
+            ErrorStrategy::RetryWithBackoff {
+                max_retries,
+                backoff_ms,
+            } => (*max_retries, *backoff_ms),
+            _ => (0, 0),
+        }
+    }
+}
+
 impl ToolChain {
     pub fn new() -> Self {
         Self {
@@ -534,10 +665,17 @@ pub async fn enhanced_file_search(
     root_path: &Path,
     query: &SearchQuery,
 ) -> Result<ToolResult, Box<dyn std::error::Error>> {
-    let index = SearchIndex::new(root_path.to_path_buf());
-    index.build_index().await?;
+    let started = Instant::now();
+    let index = Arc::new(SearchIndex::new(root_path.to_path_buf()));
+    index.build_index(query.threads).await?;
     
     let results = index.search(query).await?;
+    println!(
+        "{} Searched in {:.2}s with {} thread(s)",
+        "⏱".cyan(),
+        started.elapsed().as_secs_f64(),
+        resolve_search_threads(query.threads)
+    );
     
     if results.is_empty() {
         return Ok(ToolResult {
@@ -0,0 +1,191 @@
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Lines of checker output passed back to the model
+const MAX_ERROR_LINES: usize = 30;
+/// How long a checker may run; `cargo check` of a cold package can take a while
+const CHECK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Outcome of checking a file the agent just wrote
+pub enum SyntaxCheck {
+    Passed { command: String },
+    Failed { command: String, errors: String },
+    /// No checker for the file type, or the checker could not be started
+    Skipped(String),
+}
+
+/// Checker command line for `path` and the directory to run it in. Rust files inside a
+/// Cargo package are checked with the whole package, since `mod` files can't compile alone.
+fn checker_for(path: &Path) -> Option<(Vec<String>, Option<PathBuf>)> {
+    let file = path.to_string_lossy().to_string();
+    let args: Vec<String> = match path.extension()?.to_str()? {
+        "rs" => match cargo_package_root(path) {
+            Some(root) => {
+                let args = ["cargo", "check", "--quiet", "--message-format", "short"];
+                return Some((args.iter().map(|arg| arg.to_string()).collect(), Some(root)));
+            }
+            None => {
+                let out_dir = std::env::temp_dir().to_string_lossy().to_string();
+                vec![
+                    "rustc", "--edition", "2021", "--emit=metadata", "--crate-type", "lib",
+                    "--crate-name", "syntax_check", "--cap-lints", "allow", "--out-dir", &out_dir, &file,
+                ]
+                .into_iter()
+                .map(str::to_string)
+                .collect()
+            }
+        },
+        // What `python -m py_compile` does, without writing __pycache__ next to the file
+        "py" => vec![
+            if cfg!(windows) { "python" } else { "python3" }.to_string(),
+            "-c".to_string(),
+            "import sys; compile(open(sys.argv[1], 'rb').read(), sys.argv[1], 'exec')".to_string(),
+            file,
+        ],
+        "js" | "mjs" | "cjs" => vec!["node".to_string(), "--check".to_string(), file],
+        _ => return None,
+    };
+    Some((args, None))
+}
+
+/// Nearest directory above `path` with a Cargo.toml
+fn cargo_package_root(path: &Path) -> Option<PathBuf> {
+    let absolute = std::path::absolute(path).ok()?;
+    absolute
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Whether `file` is compiled as part of the package at `root`: a target root such as
+/// `src/main.rs` or `tests/*.rs`, or a module every parent of which declares it with `mod`.
+/// `#[path]` attributes aren't followed.
+fn part_of_crate(root: &Path, file: &Path) -> bool {
+    let Ok(relative) = file.strip_prefix(root) else {
+        return false;
+    };
+    let components: Vec<&str> = relative.iter().filter_map(|part| part.to_str()).collect();
+    match components.as_slice() {
+        ["build.rs"] | ["src", "main.rs"] | ["src", "lib.rs"] => return true,
+        ["src", "bin", _] | ["src", "bin", _, "main.rs"] => return true,
+        [dir, _] | [dir, _, "main.rs"] if ["tests", "examples", "benches"].contains(dir) => return true,
+        _ => {}
+    }
+
+    // `src/a/b.rs` and `src/a/b/mod.rs` are module `b`, declared in `src/a.rs` or `src/a/mod.rs`
+    let Some(stem) = file.file_stem().and_then(|stem| stem.to_str()) else {
+        return false;
+    };
+    let (name, dir) = match (stem, file.parent()) {
+        ("mod", Some(dir)) => match (dir.file_name().and_then(|name| name.to_str()), dir.parent()) {
+            (Some(name), Some(parent)) => (name, parent),
+            _ => return false,
+        },
+        (name, Some(dir)) => (name, dir),
+        _ => return false,
+    };
+    let pattern = format!(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+{}\s*;", regex::escape(name));
+    let Ok(declaration) = Regex::new(&pattern) else {
+        return false;
+    };
+    let mut parents = vec![dir.join("mod.rs"), dir.with_extension("rs")];
+    let target_dir = dir.file_name().is_some_and(|name| name == "src")
+        || dir.parent().is_some_and(|up| up.ends_with("src/bin"));
+    if target_dir {
+        parents.extend([dir.join("main.rs"), dir.join("lib.rs")]);
+    }
+    parents.into_iter().any(|parent| {
+        std::fs::read_to_string(&parent).is_ok_and(|source| declaration.is_match(&source))
+            && part_of_crate(root, &parent)
+    })
+}
+
+/// `cargo check --message-format short` lines about `file`, which cargo prints relative to
+/// the workspace root: `src/lib.rs:3:5: error[E0425]: ...`
+fn diagnostics_for(output: &str, file: &Path) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| {
+            let Some((location, _)) = line.split_once(": ") else {
+                return false;
+            };
+            let shown = location.split(':').next().unwrap_or(location);
+            !shown.is_empty() && file.ends_with(shown)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Quick syntax check of an edited file, picked by its extension
+pub async fn check_syntax(path: &str) -> SyntaxCheck {
+    let Some((args, dir)) = checker_for(Path::new(path)) else {
+        return SyntaxCheck::Skipped(format!("no syntax check for {}", path));
+    };
+    let file = std::path::absolute(path).unwrap_or_else(|_| PathBuf::from(path));
+    if let Some(root) = &dir {
+        if !part_of_crate(root, &file) {
+            return SyntaxCheck::Skipped(format!(
+                "not checked, {} is not part of the crate in {}",
+                path,
+                root.display()
+            ));
+        }
+    }
+    let shown = args
+        .iter()
+        .map(|arg| if arg.contains(' ') { format!("\"{}\"", arg) } else { arg.clone() })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let command = match &dir {
+        Some(dir) => format!("{} (in {})", shown, dir.display()),
+        None => shown,
+    };
+
+    let mut checker = tokio::process::Command::new(&args[0]);
+    checker
+        .args(&args[1..])
+        .env("CARGO_TERM_COLOR", "never")
+        .kill_on_drop(true);
+    if let Some(dir) = &dir {
+        checker.current_dir(dir);
+    }
+    let output = match tokio::time::timeout(CHECK_TIMEOUT, checker.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return SyntaxCheck::Skipped(format!("could not run {}: {}", args[0], e)),
+        Err(_) => {
+            return SyntaxCheck::Skipped(format!(
+                "{} did not finish within {}s",
+                args[0],
+                CHECK_TIMEOUT.as_secs()
+            ))
+        }
+    };
+    if output.status.success() {
+        return SyntaxCheck::Passed { command };
+    }
+
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // The rest of the package may be broken already; only this file's errors are the edit's
+    let text = match dir {
+        Some(_) => {
+            let own = diagnostics_for(&text, &file);
+            if !own.iter().any(|line| line.contains(": error")) {
+                return SyntaxCheck::Passed { command };
+            }
+            own.join("\n")
+        }
+        None => text,
+    };
+    let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+    let mut errors = lines.iter().take(MAX_ERROR_LINES).copied().collect::<Vec<_>>().join("\n");
+    if lines.len() > MAX_ERROR_LINES {
+        errors.push_str(&format!("\n... {} more lines", lines.len() - MAX_ERROR_LINES));
+    }
+    SyntaxCheck::Failed { command, errors }
+}
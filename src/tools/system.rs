@@ -1,7 +1,13 @@
 use super::core::{ToolExecutor, ToolResult};
 use colored::Colorize;
+use indicatif::HumanBytes;
+#[cfg(target_os = "linux")]
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use walkdir::WalkDir;
 
 impl ToolExecutor {
     pub async fn system_info(&self) -> Result<ToolResult, Box<dyn std::error::Error>> {
@@ -343,6 +349,81 @@ impl ToolExecutor {
         })
     }
 
+    /// The `top` largest directories up to `depth` levels below `path`, like a sorted `du`
+    pub async fn disk_breakdown(
+        &self,
+        path: Option<&str>,
+        top: usize,
+        depth: usize,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let root = PathBuf::from(path.unwrap_or("."));
+        let depth = depth.max(1);
+        println!(
+            "{} Measuring directories under {} (depth {})",
+            "💾".cyan(),
+            root.display().to_string().yellow(),
+            depth
+        );
+
+        if !root.is_dir() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("{} is not a directory", root.display())),
+                metadata: None,
+                web_search_result: None,
+            });
+        }
+
+        let walk_root = root.clone();
+        let usage = tokio::task::spawn_blocking(move || measure_directories(&walk_root, depth)).await?;
+
+        let mut directories: Vec<(PathBuf, u64)> = usage.sizes.into_iter().collect();
+        directories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        directories.truncate(top);
+
+        let mut output = format!(
+            "{} in {} file(s) under {}\n",
+            HumanBytes(usage.total),
+            usage.files,
+            root.display()
+        );
+        if directories.is_empty() {
+            output.push_str("No subdirectories\n");
+        }
+        for (dir, size) in &directories {
+            let share = if usage.total > 0 { *size as f64 * 100.0 / usage.total as f64 } else { 0.0 };
+            let name = dir.strip_prefix(&root).unwrap_or(dir);
+            output.push_str(&format!(
+                "{:>11}  {:>5.1}%  {}\n",
+                HumanBytes(*size).to_string(),
+                share,
+                name.display()
+            ));
+        }
+        if usage.unreadable > 0 {
+            output.push_str(&format!("{} unreadable entries skipped\n", usage.unreadable));
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output: output.trim_end().to_string(),
+            error: None,
+            metadata: Some(serde_json::json!({
+                "path": root.display().to_string(),
+                "type": "disk_breakdown",
+                "total_bytes": usage.total,
+                "files": usage.files,
+                "unreadable": usage.unreadable,
+                "directories": directories
+                    .iter()
+                    .map(|(dir, size)| serde_json::json!({ "path": dir.display().to_string(), "bytes": size }))
+                    .collect::<Vec<_>>()
+            })),
+            web_search_result: None,
+        })
+    }
+
     pub async fn process_list(
         &self,
         filter: Option<&str>,
@@ -472,6 +553,57 @@ impl ToolExecutor {
         })
     }
 
+    /// Temperatures and fan speeds from the kernel's hwmon and thermal zone interfaces
+    pub async fn sensors(&self) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        println!("{} Reading hardware sensors", "🌡️".cyan());
+
+        #[cfg(target_os = "linux")]
+        {
+            let readings = read_sensors(Path::new("/sys/class"));
+            if readings.is_empty() {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(
+                        "No temperature or fan sensors found under /sys/class/hwmon or /sys/class/thermal"
+                            .to_string(),
+                    ),
+                    metadata: None,
+                    web_search_result: None,
+                });
+            }
+
+            Ok(ToolResult {
+                success: true,
+                output: format_sensor_readings(&readings),
+                error: None,
+                metadata: Some(serde_json::json!({
+                    "type": "sensors",
+                    "readings": readings,
+                    "timestamp": chrono::Utc::now().to_rfc3339()
+                })),
+                web_search_result: None,
+            })
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Sensor readings are not supported on this platform ({}); they are only available on Linux",
+                    std::env::consts::OS
+                ),
+                error: None,
+                metadata: Some(serde_json::json!({
+                    "type": "sensors",
+                    "supported": false
+                })),
+                web_search_result: None,
+            })
+        }
+    }
+
     // File watching capability
     pub async fn file_watch(
         &self,
@@ -548,3 +680,224 @@ impl ToolExecutor {
 // Add these dependencies to Cargo.toml if not already present:
 // hostname = "0.3"
 // chrono = { version = "0.4", features = ["serde"] }
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Serialize)]
+struct SensorReading {
+    /// Group shown in the output: CPU, GPU, Storage, Board or Other
+    category: &'static str,
+    chip: String,
+    label: String,
+    /// "temperature" in °C or "fan" in RPM
+    kind: &'static str,
+    value: f64,
+    high: Option<f64>,
+    critical: Option<f64>,
+}
+
+#[cfg(target_os = "linux")]
+fn sensor_category(chip: &str) -> &'static str {
+    match chip {
+        "coretemp" | "k10temp" | "k8temp" | "zenpower" | "cpu_thermal" | "x86_pkg_temp" | "soc_thermal" => "CPU",
+        "amdgpu" | "radeon" | "nouveau" | "i915" | "xe" | "nvidia" | "gpu_thermal" => "GPU",
+        "nvme" | "drivetemp" => "Storage",
+        "acpitz" | "pch_skylake" | "pch_cannonlake" | "nct6775" | "nct6779" | "it87" => "Board",
+        _ => "Other",
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn read_number(path: &Path) -> Option<f64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+/// Sorted entries of `dir` whose names start with `prefix`
+#[cfg(target_os = "linux")]
+fn sys_entries(dir: &Path, prefix: &str) -> Vec<std::path::PathBuf> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+    entries
+}
+
+/// Read `hwmon/*/{temp,fan}N_input` and `thermal/thermal_zone*/temp` under `sys_class`.
+/// Thermal zones that duplicate an hwmon chip (e.g. `acpitz`) are skipped.
+#[cfg(target_os = "linux")]
+fn read_sensors(sys_class: &Path) -> Vec<SensorReading> {
+    let mut readings = Vec::new();
+    let mut chips = std::collections::HashSet::new();
+
+    for hwmon in sys_entries(&sys_class.join("hwmon"), "hwmon") {
+        let chip = read_trimmed(&hwmon.join("name")).unwrap_or_else(|| hwmon.display().to_string());
+        for input in sys_entries(&hwmon, "") {
+            let file_name = input.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let Some(sensor) = file_name.strip_suffix("_input") else {
+                continue;
+            };
+            let (kind, scale) = if sensor.starts_with("temp") {
+                ("temperature", 1000.0)
+            } else if sensor.starts_with("fan") {
+                ("fan", 1.0)
+            } else {
+                continue;
+            };
+            // Unreadable inputs (e.g. a sleeping GPU) report errors instead of values
+            let Some(value) = read_number(&input) else {
+                continue;
+            };
+            let limit = |suffix: &str| read_number(&hwmon.join(format!("{}_{}", sensor, suffix))).map(|v| v / scale);
+            readings.push(SensorReading {
+                category: sensor_category(&chip),
+                chip: chip.clone(),
+                label: read_trimmed(&hwmon.join(format!("{}_label", sensor))).unwrap_or_else(|| sensor.to_string()),
+                kind,
+                value: value / scale,
+                high: if kind == "fan" { None } else { limit("max") },
+                critical: if kind == "fan" { None } else { limit("crit") },
+            });
+        }
+        chips.insert(chip);
+    }
+
+    for zone in sys_entries(&sys_class.join("thermal"), "thermal_zone") {
+        let zone_type = read_trimmed(&zone.join("type")).unwrap_or_else(|| "thermal".to_string());
+        if chips.contains(&zone_type) {
+            continue;
+        }
+        let Some(value) = read_number(&zone.join("temp")) else {
+            continue;
+        };
+        readings.push(SensorReading {
+            category: sensor_category(&zone_type),
+            chip: zone_type,
+            label: zone.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            kind: "temperature",
+            value: value / 1000.0,
+            high: None,
+            critical: None,
+        });
+    }
+
+    readings
+}
+
+#[cfg(target_os = "linux")]
+fn format_sensor_readings(readings: &[SensorReading]) -> String {
+    let width = readings
+        .iter()
+        .map(|r| r.chip.len() + r.label.len() + 1)
+        .max()
+        .unwrap_or(0);
+    let mut lines = Vec::new();
+
+    for category in ["CPU", "GPU", "Storage", "Board", "Other"] {
+        for kind in ["temperature", "fan"] {
+            let group: Vec<_> = readings
+                .iter()
+                .filter(|r| r.category == category && r.kind == kind)
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(if kind == "fan" { format!("{} fans:", category) } else { format!("{}:", category) });
+
+            for reading in group {
+                let name = format!("{} {}", reading.chip, reading.label);
+                let mut line = if kind == "fan" {
+                    format!("  {:<width$}  {:>6.0} RPM", name, reading.value)
+                } else {
+                    format!("  {:<width$}  {:>6.1}°C", name, reading.value)
+                };
+                let limits: Vec<String> = [("high", reading.high), ("crit", reading.critical)]
+                    .into_iter()
+                    .filter_map(|(name, limit)| limit.map(|limit| format!("{} {:.1}°C", name, limit)))
+                    .collect();
+                if !limits.is_empty() {
+                    line.push_str(&format!("  ({})", limits.join(", ")));
+                }
+                if reading.high.or(reading.critical).is_some_and(|limit| reading.value >= limit) {
+                    line.push_str("  ⚠ hot");
+                }
+                lines.push(line);
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+struct DirectoryUsage {
+    /// Bytes per directory, for directories 1..=depth levels below the root
+    sizes: HashMap<PathBuf, u64>,
+    total: u64,
+    files: u64,
+    unreadable: u64,
+}
+
+fn measure_directories(root: &Path, depth: usize) -> DirectoryUsage {
+    let mut usage = DirectoryUsage { sizes: HashMap::new(), total: 0, files: 0, unreadable: 0 };
+    // Hard-linked files are counted once, as du does
+    #[cfg(unix)]
+    let mut seen_inodes = std::collections::HashSet::new();
+
+    for entry in WalkDir::new(root).follow_links(false) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => {
+                usage.unreadable += 1;
+                continue;
+            }
+        };
+        if entry.file_type().is_dir() {
+            if (1..=depth).contains(&entry.depth()) {
+                usage.sizes.entry(entry.path().to_path_buf()).or_insert(0);
+            }
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            usage.unreadable += 1;
+            continue;
+        };
+        #[cfg(unix)]
+        let size = {
+            use std::os::unix::fs::MetadataExt;
+            if metadata.nlink() > 1 && !seen_inodes.insert((metadata.dev(), metadata.ino())) {
+                continue;
+            }
+            // Allocated blocks rather than length, so sparse files count what they occupy
+            metadata.blocks() * 512
+        };
+        #[cfg(not(unix))]
+        let size = metadata.len();
+
+        usage.total += size;
+        usage.files += 1;
+        for ancestor in entry.path().ancestors().skip(1) {
+            let Ok(relative) = ancestor.strip_prefix(root) else {
+                break;
+            };
+            let level = relative.components().count();
+            if level == 0 {
+                break;
+            }
+            if level <= depth {
+                *usage.sizes.entry(ancestor.to_path_buf()).or_insert(0) += size;
+            }
+        }
+    }
+
+    usage
+}
@@ -0,0 +1,346 @@
+use super::core::{ProjectPackageManager, ToolExecutor, ToolResult};
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+
+/// Failing tests listed individually; any beyond this are only counted
+const MAX_REPORTED_FAILURES: usize = 20;
+/// Lines of a failure's output kept as its error snippet
+const SNIPPET_LINES: usize = 12;
+/// Lines of raw output shown when no test summary could be found
+const UNPARSED_TAIL_LINES: usize = 40;
+
+lazy_static::lazy_static! {
+    static ref ANSI_ESCAPE: Regex = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap();
+    static ref CARGO_RESULT: Regex =
+        Regex::new(r"^test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored;").unwrap();
+    static ref CARGO_FAILED: Regex = Regex::new(r"^test (\S+) \.\.\. FAILED$").unwrap();
+    static ref PYTEST_SUMMARY: Regex = Regex::new(r"^=+ .*\b\d+ (passed|failed|skipped|errors?)\b.* =+$").unwrap();
+    static ref PYTEST_FAILED: Regex = Regex::new(r"^(?:FAILED|ERROR) (\S+)(?: - (.*))?$").unwrap();
+    static ref JS_SUMMARY: Regex = Regex::new(r"^\s*Tests:?\s+\d").unwrap();
+    static ref MOCHA_COUNT: Regex = Regex::new(r"^\s*\d+ (passing|failing|pending)\b").unwrap();
+    static ref JS_FAILED: Regex = Regex::new(r"^\s*(?:●|×|✕|FAIL)\s+(.+)$").unwrap();
+    static ref COUNT: Regex =
+        Regex::new(r"(\d+) (passed|passing|failed|failing|skipped|pending|todo|xfailed|errors?)\b").unwrap();
+}
+
+/// Test command `run_tests` picked for a project, which also decides how its output is parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestRunner {
+    Cargo,
+    Npm,
+    Yarn,
+    Pnpm,
+    Pytest,
+    Poetry,
+}
+
+impl TestRunner {
+    /// Runner for the project in `dir`, from the same lockfiles and manifests as `PackageInstall`
+    pub fn detect(dir: &Path) -> Option<Self> {
+        ProjectPackageManager::detect(dir).map(|manager| match manager {
+            ProjectPackageManager::Cargo => TestRunner::Cargo,
+            ProjectPackageManager::Npm => TestRunner::Npm,
+            ProjectPackageManager::Yarn => TestRunner::Yarn,
+            ProjectPackageManager::Pnpm => TestRunner::Pnpm,
+            ProjectPackageManager::Pip => TestRunner::Pytest,
+            ProjectPackageManager::Poetry => TestRunner::Poetry,
+        })
+    }
+
+    /// Program and arguments, with `filter` passed the way each runner selects tests
+    fn command_line(&self, filter: Option<&str>) -> Vec<String> {
+        let mut args: Vec<&str> = match self {
+            TestRunner::Cargo => vec!["cargo", "test"],
+            TestRunner::Npm => vec!["npm", "test"],
+            TestRunner::Yarn => vec!["yarn", "test"],
+            TestRunner::Pnpm => vec!["pnpm", "test"],
+            TestRunner::Pytest => vec!["pytest", "-rfE"],
+            TestRunner::Poetry => vec!["poetry", "run", "pytest", "-rfE"],
+        };
+        if let Some(filter) = filter {
+            match self {
+                TestRunner::Cargo | TestRunner::Yarn | TestRunner::Pnpm => args.push(filter),
+                TestRunner::Npm => args.extend(["--", filter]),
+                TestRunner::Pytest | TestRunner::Poetry => args.extend(["-k", filter]),
+            }
+        }
+        args.into_iter().map(str::to_string).collect()
+    }
+
+    fn parse(&self, output: &str) -> TestSummary {
+        match self {
+            TestRunner::Cargo => parse_cargo_output(output),
+            TestRunner::Npm | TestRunner::Yarn | TestRunner::Pnpm => parse_js_output(output),
+            TestRunner::Pytest | TestRunner::Poetry => parse_pytest_output(output),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestFailure {
+    pub name: String,
+    /// The start of the failure's output: assertion message, panic or stack trace
+    pub message: String,
+}
+
+/// Counts and failures read from a test run's output
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TestSummary {
+    pub passed: u64,
+    pub failed: u64,
+    pub ignored: u64,
+    pub failures: Vec<TestFailure>,
+    /// False when the output had no summary line, e.g. because the build failed
+    pub parsed: bool,
+}
+
+impl TestSummary {
+    fn add_failure(&mut self, name: &str, message: String) {
+        if self.failures.len() < MAX_REPORTED_FAILURES && !self.failures.iter().any(|f| f.name == name) {
+            self.failures.push(TestFailure { name: name.to_string(), message });
+        }
+    }
+}
+
+impl ToolExecutor {
+    /// Run the project's test suite and summarize it: counts plus each failing
+    /// test with the start of its output, also returned as metadata
+    pub async fn run_tests(
+        &self,
+        filter: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let dir = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => std::env::current_dir()?,
+        };
+        let Some(runner) = TestRunner::detect(&dir) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Could not detect a test runner in {} (no Cargo.toml, package.json, pyproject.toml or requirements.txt)",
+                    dir.display()
+                )),
+                metadata: None,
+                web_search_result: None,
+            });
+        };
+
+        let command_line = runner.command_line(filter);
+        let command = command_line.join(" ");
+        println!("{} Running {}", "🧪".cyan(), command.yellow());
+
+        // Colors off and no watch mode, so the output can be parsed
+        let mut cmd = tokio::process::Command::new(&command_line[0]);
+        cmd.args(&command_line[1..])
+            .current_dir(&dir)
+            .env("CI", "true")
+            .env("NO_COLOR", "1")
+            .env("FORCE_COLOR", "0")
+            .env("CARGO_TERM_COLOR", "never");
+        let timeout_secs = self.get_command_timeout().await;
+        let run = match self.stream_process(cmd, timeout_secs).await {
+            Ok(run) => run,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to run {}: {}", command, e)),
+                    metadata: None,
+                    web_search_result: None,
+                })
+            }
+        };
+        let exit_code = run
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("exit_code"))
+            .and_then(|code| code.as_i64());
+        let timed_out = run.error.is_some();
+
+        let text = ANSI_ESCAPE.replace_all(&run.output, "");
+        let summary = runner.parse(&text);
+        let success = run.success;
+
+        let mut report = if summary.parsed {
+            format!(
+                "{}: {} passed, {} failed, {} ignored",
+                command, summary.passed, summary.failed, summary.ignored
+            )
+        } else {
+            let lines: Vec<&str> = text.trim_end().lines().collect();
+            let tail = lines[lines.len().saturating_sub(UNPARSED_TAIL_LINES)..].join("\n");
+            format!("{}: no test summary found in the output. Last lines:\n{}", command, tail)
+        };
+        if !summary.failures.is_empty() {
+            report.push_str("\n\nFailing tests:");
+            for failure in &summary.failures {
+                report.push_str(&format!("\n- {}", failure.name));
+                for line in failure.message.lines() {
+                    report.push_str(&format!("\n    {}", line));
+                }
+            }
+            let unlisted = summary.failed.saturating_sub(summary.failures.len() as u64);
+            if unlisted > 0 {
+                report.push_str(&format!("\n... and {} more", unlisted));
+            }
+        }
+
+        let error = match (success, summary.failed) {
+            (true, _) => None,
+            _ if timed_out => run.error,
+            (false, 0) => Some(format!(
+                "{} exited with code {}",
+                command,
+                exit_code.map_or_else(|| "unknown".to_string(), |code| code.to_string())
+            )),
+            (false, failed) => Some(format!("{} test(s) failed", failed)),
+        };
+
+        Ok(ToolResult {
+            success,
+            output: report,
+            error,
+            metadata: Some(serde_json::json!({
+                "runner": runner,
+                "command": command,
+                "directory": dir,
+                "exit_code": exit_code,
+                "passed": summary.passed,
+                "failed": summary.failed,
+                "ignored": summary.ignored,
+                "failures": summary.failures,
+                "parsed": summary.parsed,
+            })),
+            web_search_result: None,
+        })
+    }
+}
+
+/// Sum the `test result:` line of every test binary; failures come from the
+/// `---- name stdout ----` sections
+fn parse_cargo_output(output: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+    let lines: Vec<&str> = output.lines().collect();
+
+    for line in &lines {
+        if let Some(counts) = CARGO_RESULT.captures(line) {
+            summary.parsed = true;
+            summary.passed += counts[1].parse::<u64>().unwrap_or(0);
+            summary.failed += counts[2].parse::<u64>().unwrap_or(0);
+            summary.ignored += counts[3].parse::<u64>().unwrap_or(0);
+        }
+    }
+
+    for line in &lines {
+        if let Some(failed) = CARGO_FAILED.captures(line) {
+            let name = &failed[1];
+            let header = format!("---- {} stdout ----", name);
+            let message = lines.iter()
+                .position(|line| line.trim() == header)
+                .map(|start| {
+                    snippet(lines[start + 1..].iter().take_while(|line| {
+                        !line.starts_with("---- ") && line.trim() != "failures:"
+                    }))
+                })
+                .unwrap_or_default();
+            summary.add_failure(name, message);
+        }
+    }
+    summary
+}
+
+/// Counts from the final `=== ... ===` line; failures from the `-rfE` short summary,
+/// with the `E` lines of each failure's section as the snippet
+fn parse_pytest_output(output: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+    let lines: Vec<&str> = output.lines().collect();
+
+    if let Some(line) = lines.iter().rev().find(|line| PYTEST_SUMMARY.is_match(line)) {
+        summary.parsed = true;
+        for count in COUNT.captures_iter(line) {
+            let n = count[1].parse::<u64>().unwrap_or(0);
+            match &count[2] {
+                "passed" => summary.passed += n,
+                "failed" | "error" | "errors" => summary.failed += n,
+                "skipped" | "xfailed" => summary.ignored += n,
+                _ => {}
+            }
+        }
+    }
+
+    for line in &lines {
+        if let Some(failed) = PYTEST_FAILED.captures(line) {
+            let name = &failed[1];
+            let short_name = name.rsplit("::").next().unwrap_or(name);
+            let section = lines.iter()
+                .position(|line| {
+                    line.starts_with('_') && line.trim_matches(|c| c == '_' || c == ' ') == short_name
+                })
+                .map(|start| {
+                    snippet(lines[start + 1..].iter()
+                        .take_while(|line| !line.starts_with("____") && !line.starts_with("===="))
+                        .filter(|line| line.starts_with("E ")))
+                })
+                .unwrap_or_default();
+            let message = if section.is_empty() {
+                failed.get(2).map(|m| m.as_str().to_string()).unwrap_or_default()
+            } else {
+                section
+            };
+            summary.add_failure(name, message);
+        }
+    }
+    summary
+}
+
+/// Jest (`Tests: 1 failed, 4 passed, 5 total`), Vitest (`Tests  1 failed | 4 passed (5)`)
+/// or Mocha (`4 passing` / `1 failing`) counts; failures from Jest's `●` and Vitest's `×` lines
+fn parse_js_output(output: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+    let lines: Vec<&str> = output.lines().collect();
+
+    let counted: Vec<&str> = match lines.iter().rev().find(|line| JS_SUMMARY.is_match(line)) {
+        Some(line) => vec![*line],
+        None => lines.iter().filter(|line| MOCHA_COUNT.is_match(line)).copied().collect(),
+    };
+    for line in counted {
+        for count in COUNT.captures_iter(line) {
+            summary.parsed = true;
+            let n = count[1].parse::<u64>().unwrap_or(0);
+            match &count[2] {
+                "passed" | "passing" => summary.passed += n,
+                "failed" | "failing" => summary.failed += n,
+                "skipped" | "pending" | "todo" => summary.ignored += n,
+                _ => {}
+            }
+        }
+    }
+
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(failed) = JS_FAILED.captures(line) {
+            let name = failed[1].trim();
+            // Vitest prints passing and failing files alike, only test names matter here
+            if name.ends_with(".js") || name.ends_with(".ts") || name.contains(".test.") || name.contains(".spec.") {
+                continue;
+            }
+            let message = snippet(lines[index + 1..].iter().take_while(|line| !JS_FAILED.is_match(line)));
+            summary.add_failure(name, message);
+        }
+    }
+    summary
+}
+
+/// First non-blank lines of a failure's output, trimmed
+fn snippet<'a>(lines: impl Iterator<Item = &'a &'a str>) -> String {
+    lines
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .take(SNIPPET_LINES)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
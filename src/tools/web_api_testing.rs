@@ -1,12 +1,18 @@
 use super::core::{ToolExecutor, ToolResult};
 use anyhow::{anyhow, Result};
 use colored::Colorize;
+use regex::Regex;
 use reqwest::{Client, Method, Response};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 
+lazy_static::lazy_static! {
+    static ref VARIABLE: Regex = Regex::new(r"\{\{\s*([\w.-]+)\s*\}\}").unwrap();
+}
+
 /// API testing configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTestConfig {
@@ -81,25 +87,42 @@ pub struct ValidationResult {
 /// Types of API validations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ValidationType {
+    #[serde(alias = "status_code", alias = "status")]
     StatusCode,
+    #[serde(alias = "response_time")]
     ResponseTime,
+    #[serde(alias = "header_present")]
     HeaderPresent,
+    #[serde(alias = "header_value")]
     HeaderValue,
+    #[serde(alias = "json_schema")]
     JsonSchema,
+    #[serde(alias = "json_path")]
     JsonPath,
+    #[serde(alias = "content_type")]
     ContentType,
+    #[serde(alias = "response_size")]
     ResponseSize,
+    #[serde(alias = "custom")]
     Custom,
 }
 
-/// API test suite configuration
+/// API test suite configuration, loadable from a JSON or YAML collection file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTestSuite {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub base_url: String,
+    #[serde(default)]
     pub global_headers: HashMap<String, String>,
+    /// Initial values for `{{name}}` placeholders
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
     pub tests: Vec<ApiTest>,
+    #[serde(default)]
     pub setup_requests: Vec<ApiTest>,
+    #[serde(default)]
     pub teardown_requests: Vec<ApiTest>,
 }
 
@@ -107,26 +130,96 @@ pub struct ApiTestSuite {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTest {
     pub name: String,
+    #[serde(default = "default_method")]
     pub method: String,
+    #[serde(alias = "url")]
     pub endpoint: String,
+    #[serde(default)]
     pub headers: HashMap<String, String>,
+    #[serde(default, deserialize_with = "optional_string_or_json")]
     pub body: Option<String>,
+    #[serde(default)]
     pub query_params: HashMap<String, String>,
+    #[serde(default, alias = "assertions")]
     pub validations: Vec<ApiValidation>,
+    #[serde(default)]
     pub depends_on: Vec<String>,
+    /// Variable name -> JSON path in the response body
+    #[serde(default, alias = "capture")]
     pub extract_variables: HashMap<String, String>,
 }
 
 /// API validation definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiValidation {
+    #[serde(alias = "type")]
     pub validation_type: ValidationType,
+    #[serde(default, alias = "expected", deserialize_with = "string_or_json")]
     pub expected_value: String,
+    #[serde(default, alias = "path")]
     pub json_path: Option<String>,
+    #[serde(default, alias = "header")]
     pub header_name: Option<String>,
+    #[serde(default)]
     pub custom_script: Option<String>,
 }
 
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// Accept any value where a string is expected, so collections can write
+/// `expected: 200` or an inline JSON `body`
+fn string_or_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(text) => text,
+        other => other.to_string(),
+    })
+}
+
+fn optional_string_or_json<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(text) => Some(text),
+        other => Some(other.to_string()),
+    })
+}
+
+/// Replace `{{name}}` placeholders; unknown names are left untouched
+fn substitute_variables(text: &str, variables: &HashMap<String, String>) -> String {
+    VARIABLE
+        .replace_all(text, |caps: &regex::Captures| {
+            variables
+                .get(&caps[1])
+                .cloned()
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Load a collection file; `.yaml`/`.yml` are parsed as YAML, anything else as JSON
+fn load_api_collection(path: &Path) -> Result<ApiTestSuite> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read collection {}: {}", path.display(), e))?;
+
+    let mut suite: ApiTestSuite = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml" | "yml") => serde_yaml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid collection {}: {}", path.display(), e))?,
+        _ => serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Invalid collection {}: {}", path.display(), e))?,
+    };
+
+    if suite.name.is_empty() {
+        suite.name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "collection".to_string());
+    }
+    Ok(suite)
+}
+
 impl ToolExecutor {
     /// Perform comprehensive API testing
     pub async fn api_test_comprehensive(
@@ -485,28 +578,34 @@ impl ToolExecutor {
         }
     }
 
-    /// Simple JSON path evaluation
+    /// Simple JSON path evaluation: `$.data.items[0].id` or `data.items.0.id`.
+    /// Strings are returned without quotes.
     fn simple_json_path_eval(&self, json: &serde_json::Value, path: &str) -> String {
-        let parts: Vec<&str> = path.split('.').collect();
         let mut current = json;
 
-        for part in parts {
+        for part in path.trim_start_matches('$').split(['.', '[', ']']) {
             if part.is_empty() {
                 continue;
             }
 
-            if let Some(obj) = current.as_object() {
-                if let Some(value) = obj.get(part) {
-                    current = value;
-                } else {
-                    return "null".to_string();
+            let next = match current {
+                serde_json::Value::Object(obj) => obj.get(part),
+                serde_json::Value::Array(items) => {
+                    part.parse::<usize>().ok().and_then(|index| items.get(index))
                 }
-            } else {
-                return "null".to_string();
+                _ => None,
+            };
+
+            match next {
+                Some(value) => current = value,
+                None => return "null".to_string(),
             }
         }
 
-        current.to_string()
+        match current {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }
     }
 
     /// Validate content type
@@ -621,6 +720,202 @@ impl ToolExecutor {
         output.join("\n")
     }
 
+    /// Run a saved collection: requests execute in order, values captured from one
+    /// response can be used by later steps, and the result fails if any step fails
+    pub async fn api_run_collection(
+        &self,
+        path: &str,
+    ) -> Result<ToolResult, Box<dyn std::error::Error>> {
+        let suite = load_api_collection(Path::new(path))?;
+        let config = ApiTestConfig::default();
+        let client = self.build_api_test_client(&config)?;
+        let start_time = Instant::now();
+
+        println!("{} Running collection: {}", "🧪".cyan(), suite.name.bold());
+
+        let mut variables = suite.variables.clone();
+        let mut passed: HashSet<String> = HashSet::new();
+        let mut results = Vec::new();
+        let mut failed = Vec::new();
+        let mut skipped = Vec::new();
+
+        let steps = suite
+            .setup_requests
+            .iter()
+            .chain(&suite.tests)
+            .chain(&suite.teardown_requests);
+
+        for test in steps {
+            if let Some(dependency) = test.depends_on.iter().find(|dep| !passed.contains(*dep)) {
+                println!(
+                    "  {} {} {}",
+                    "⏭".yellow(),
+                    test.name,
+                    format!("(skipped: depends on '{}')", dependency).dimmed()
+                );
+                skipped.push(test.name.clone());
+                continue;
+            }
+
+            let result = self
+                .run_collection_step(&client, &config, &suite, test, &variables)
+                .await?;
+
+            println!(
+                "  {} {} {}",
+                if result.success { "✅".green() } else { "❌".red() },
+                test.name.bold(),
+                format!(
+                    "{} {} → {} ({}ms)",
+                    result.method, result.url, result.status_code, result.response_time_ms
+                )
+                .dimmed()
+            );
+            if let Some(error) = &result.error_message {
+                println!("      {} {}", "•".red(), error);
+            }
+            for validation in result.validation_results.iter().filter(|v| !v.passed) {
+                println!("      {} {}", "•".red(), validation.message);
+            }
+
+            if result.success {
+                passed.insert(test.name.clone());
+                self.capture_variables(test, &result.response_body, &mut variables);
+            } else {
+                failed.push(test.name.clone());
+            }
+            results.push(result);
+        }
+
+        let summary = format!(
+            "{} passed, {} failed, {} skipped in {:.2}s",
+            passed.len(),
+            failed.len(),
+            skipped.len(),
+            start_time.elapsed().as_secs_f64()
+        );
+        let success = failed.is_empty() && skipped.is_empty();
+
+        println!();
+        println!(
+            "{} {}",
+            if success { "✅".green() } else { "❌".red() },
+            summary
+        );
+
+        Ok(ToolResult {
+            success,
+            output: summary.clone(),
+            error: (!success).then(|| format!("Collection '{}' failed", suite.name)),
+            metadata: Some(serde_json::json!({
+                "collection": suite.name,
+                "failed": failed,
+                "skipped": skipped,
+                "results": results,
+            })),
+            web_search_result: None,
+        })
+    }
+
+    /// Resolve placeholders for one collection step and execute it
+    async fn run_collection_step(
+        &self,
+        client: &Client,
+        config: &ApiTestConfig,
+        suite: &ApiTestSuite,
+        test: &ApiTest,
+        variables: &HashMap<String, String>,
+    ) -> Result<ApiTestResult> {
+        let endpoint = substitute_variables(&test.endpoint, variables);
+        let mut url = if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            endpoint
+        } else {
+            format!(
+                "{}/{}",
+                substitute_variables(&suite.base_url, variables).trim_end_matches('/'),
+                endpoint.trim_start_matches('/')
+            )
+        };
+
+        if !test.query_params.is_empty() {
+            let mut parsed = url::Url::parse(&url).map_err(|e| anyhow!("Invalid URL {}: {}", url, e))?;
+            parsed.query_pairs_mut().extend_pairs(
+                test.query_params
+                    .iter()
+                    .map(|(key, value)| (key, substitute_variables(value, variables))),
+            );
+            url = parsed.to_string();
+        }
+
+        let mut headers = config.default_headers.clone();
+        for (key, value) in suite.global_headers.iter().chain(&test.headers) {
+            headers.insert(key.clone(), substitute_variables(value, variables));
+        }
+
+        let body = test.body.as_deref().map(|body| substitute_variables(body, variables));
+        let validations: Vec<ApiValidation> = test
+            .validations
+            .iter()
+            .map(|validation| ApiValidation {
+                expected_value: substitute_variables(&validation.expected_value, variables),
+                ..validation.clone()
+            })
+            .collect();
+
+        // An explicit status assertion decides whether e.g. a 404 is the expected outcome
+        let checks_status = validations
+            .iter()
+            .any(|v| matches!(v.validation_type, ValidationType::StatusCode));
+
+        let mut result = self
+            .execute_api_test(
+                client,
+                &test.method.to_uppercase(),
+                &url,
+                headers,
+                body,
+                validations,
+                config,
+                Instant::now(),
+            )
+            .await?;
+
+        result.test_name = test.name.clone();
+        result.success = result.error_message.is_none()
+            && result.validation_results.iter().all(|v| v.passed)
+            && (checks_status || result.status_code < 400);
+        Ok(result)
+    }
+
+    /// Store values from a JSON response for `{{name}}` placeholders in later steps
+    fn capture_variables(
+        &self,
+        test: &ApiTest,
+        body: &str,
+        variables: &mut HashMap<String, String>,
+    ) {
+        if test.extract_variables.is_empty() {
+            return;
+        }
+
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+            println!(
+                "      {} Cannot capture variables: response is not JSON",
+                "⚠".yellow()
+            );
+            return;
+        };
+
+        for (name, path) in &test.extract_variables {
+            let value = self.simple_json_path_eval(&json, path);
+            if value == "null" {
+                println!("      {} '{}' not found for {{{{{}}}}}", "⚠".yellow(), path, name);
+            } else {
+                variables.insert(name.clone(), value);
+            }
+        }
+    }
+
     /// Run API load test
     pub async fn api_load_test(
         &self,
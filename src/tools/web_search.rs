@@ -2,6 +2,9 @@ use crate::tools::core::{
     WebSearchConfig, WebSearchResult, SearchResultItem, Citation, SearchMetadata, 
     SearchContextSize, UserLocation
 };
+use crate::tools::http::{
+    configure_web_client, read_page_body, PAGE_BYTES_PER_CONTENT_CHAR,
+};
 use anyhow::Result;
 use colored::Colorize;
 use reqwest::Client;
@@ -13,17 +16,65 @@ use tokio::time::timeout;
 use url::Url;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 /// Search performance metrics
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct SearchMetrics {
     pub total_searches: AtomicUsize,
     pub successful_searches: AtomicUsize,
     pub failed_searches: AtomicUsize,
     pub cache_hits: AtomicUsize,
     pub total_results_found: AtomicUsize,
-    pub average_response_time_ms: AtomicUsize,
+    /// Sum and count of timed searches; the average is computed from both when reported
+    pub total_response_time_ms: AtomicU64,
+    pub timed_searches: AtomicUsize,
+    /// Fastest timed search, `u64::MAX` until the first one
+    pub min_response_time_ms: AtomicU64,
+    pub max_response_time_ms: AtomicU64,
+    /// Timed searches per bucket of [`RESPONSE_TIME_BUCKETS_MS`], plus one for slower searches
+    pub response_time_buckets: [AtomicUsize; RESPONSE_TIME_BUCKETS_MS.len() + 1],
+}
+
+lazy_static::lazy_static! {
+    /// Shared by every search engine in the process, so the analytics cover the
+    /// per-engine timings recorded by the enhanced search as well
+    pub static ref SEARCH_METRICS: Arc<SearchMetrics> = Arc::new(SearchMetrics::default());
+}
+
+/// Upper bounds (exclusive) of the response time histogram buckets
+pub const RESPONSE_TIME_BUCKETS_MS: [u64; 5] = [250, 500, 1000, 2500, 5000];
+
+impl Default for SearchMetrics {
+    fn default() -> Self {
+        Self {
+            total_searches: AtomicUsize::new(0),
+            successful_searches: AtomicUsize::new(0),
+            failed_searches: AtomicUsize::new(0),
+            cache_hits: AtomicUsize::new(0),
+            total_results_found: AtomicUsize::new(0),
+            total_response_time_ms: AtomicU64::new(0),
+            timed_searches: AtomicUsize::new(0),
+            min_response_time_ms: AtomicU64::new(u64::MAX),
+            max_response_time_ms: AtomicU64::new(0),
+            response_time_buckets: Default::default(),
+        }
+    }
+}
+
+impl SearchMetrics {
+    /// Adds one search's response time to the sum, min/max and histogram
+    pub fn record_response_time(&self, ms: u64) {
+        self.total_response_time_ms.fetch_add(ms, Ordering::Relaxed);
+        self.min_response_time_ms.fetch_min(ms, Ordering::Relaxed);
+        self.max_response_time_ms.fetch_max(ms, Ordering::Relaxed);
+        let bucket = RESPONSE_TIME_BUCKETS_MS
+            .iter()
+            .position(|&bound| ms < bound)
+            .unwrap_or(RESPONSE_TIME_BUCKETS_MS.len());
+        self.response_time_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.timed_searches.fetch_add(1, Ordering::Relaxed);
+    }
 }
 
 /// Search analytics report
@@ -36,6 +87,10 @@ pub struct SearchAnalytics {
     pub cache_hit_rate: f64,
     pub average_results_per_search: f64,
     pub average_response_time_ms: u64,
+    pub min_response_time_ms: u64,
+    pub max_response_time_ms: u64,
+    /// Bucket label (e.g. `<500ms`) and how many searches fell into it
+    pub response_time_histogram: Vec<(String, usize)>,
 }
 
 /// Cache statistics
@@ -106,19 +161,24 @@ pub struct SearchStrategy {
 
 impl WebSearchEngine {
     /// Create a new WebSearch engine with configuration
-    pub fn new(config: WebSearchConfig) -> Self {
-        let client = Client::builder()
+    pub fn new(config: WebSearchConfig) -> Result<Self> {
+        let builder = Client::builder()
             .timeout(Duration::from_secs(config.max_uses as u64 * 10)) // Generous timeout
-            .user_agent("Mozilla/5.0 (compatible; AI-Assistant/1.0)")
-            .build()
-            .unwrap_or_default();
-
-        Self {
+            .user_agent("Mozilla/5.0 (compatible; AI-Assistant/1.0)");
+        let client = configure_web_client(
+            builder,
+            config.http_proxy.as_deref(),
+            config.https_proxy.as_deref(),
+            &config.default_headers,
+        )?
+        .build()?;
+
+        Ok(Self {
             client: Arc::new(client),
             config,
             cache: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-            metrics: Arc::new(SearchMetrics::default()),
-        }
+            metrics: SEARCH_METRICS.clone(),
+        })
     }
 
     /// Main search method - industry standard interface with progressive search
@@ -261,7 +321,7 @@ impl WebSearchEngine {
         // Update final metrics
         self.metrics.successful_searches.fetch_add(1, Ordering::Relaxed);
         self.metrics.total_results_found.fetch_add(result.results.len(), Ordering::Relaxed);
-        self.metrics.average_response_time_ms.store(result.search_metadata.processing_time_ms as usize, Ordering::Relaxed);
+        self.metrics.record_response_time(result.search_metadata.processing_time_ms);
         
         // Cache the result
         self.cache_result(query, &result).await;
@@ -1013,7 +1073,7 @@ impl WebSearchEngine {
             client.get(url).send()
         ).await??;
 
-        let html = response.text().await?;
+        let html = read_page_body(response, 1500 * PAGE_BYTES_PER_CONTENT_CHAR).await?;
         let document = Html::parse_document(&html);
 
         // Try multiple content selectors
@@ -1303,16 +1363,7 @@ impl WebSearchEngine {
 
     /// Create citations from search results
     fn create_citations(&self, results: &[SearchResultItem]) -> Vec<Citation> {
-        results
-            .iter()
-            .take(5) // Limit citations to top 5 results
-            .map(|result| Citation {
-                url: result.url.clone(),
-                title: result.title.clone(),
-                domain: result.source_domain.clone(),
-                excerpt: result.snippet.clone(),
-            })
-            .collect()
+        citations_for(&results[..results.len().min(5)]) // Limit citations to top 5 results
     }
 
     /// Get cached search result
@@ -1365,7 +1416,21 @@ impl WebSearchEngine {
         let failed = self.metrics.failed_searches.load(Ordering::Relaxed);
         let cache_hits = self.metrics.cache_hits.load(Ordering::Relaxed);
         let total_results = self.metrics.total_results_found.load(Ordering::Relaxed);
-        let avg_time = self.metrics.average_response_time_ms.load(Ordering::Relaxed);
+        let timed = self.metrics.timed_searches.load(Ordering::Relaxed) as u64;
+        let total_time = self.metrics.total_response_time_ms.load(Ordering::Relaxed);
+        let histogram = self
+            .metrics
+            .response_time_buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let label = match RESPONSE_TIME_BUCKETS_MS.get(i) {
+                    Some(bound) => format!("<{}ms", bound),
+                    None => format!(">={}ms", RESPONSE_TIME_BUCKETS_MS[i - 1]),
+                };
+                (label, count.load(Ordering::Relaxed))
+            })
+            .collect();
         
         SearchAnalytics {
             total_searches: total,
@@ -1374,7 +1439,10 @@ impl WebSearchEngine {
             success_rate: if total > 0 { successful as f64 / total as f64 } else { 0.0 },
             cache_hit_rate: if total > 0 { cache_hits as f64 / total as f64 } else { 0.0 },
             average_results_per_search: if successful > 0 { total_results as f64 / successful as f64 } else { 0.0 },
-            average_response_time_ms: avg_time as u64,
+            average_response_time_ms: total_time.checked_div(timed).unwrap_or(0),
+            min_response_time_ms: if timed > 0 { self.metrics.min_response_time_ms.load(Ordering::Relaxed) } else { 0 },
+            max_response_time_ms: self.metrics.max_response_time_ms.load(Ordering::Relaxed),
+            response_time_histogram: histogram,
         }
     }
     
@@ -1390,6 +1458,10 @@ impl WebSearchEngine {
         println!("{} Cache Hit Rate: {:.1}%", "💾", analytics.cache_hit_rate * 100.0);
         println!("{} Avg Results/Search: {:.1}", "🎯", analytics.average_results_per_search);
         println!("{} Avg Response Time: {}ms", "⚡", analytics.average_response_time_ms);
+        println!("⏱ Min/Max Response Time: {}ms / {}ms", analytics.min_response_time_ms, analytics.max_response_time_ms);
+        for (label, count) in analytics.response_time_histogram.iter().filter(|(_, count)| *count > 0) {
+            println!("   {:>8}: {}", label, count);
+        }
         println!("{}", "=".repeat(40));
     }
     
@@ -1400,7 +1472,13 @@ impl WebSearchEngine {
         self.metrics.failed_searches.store(0, Ordering::Relaxed);
         self.metrics.cache_hits.store(0, Ordering::Relaxed);
         self.metrics.total_results_found.store(0, Ordering::Relaxed);
-        self.metrics.average_response_time_ms.store(0, Ordering::Relaxed);
+        self.metrics.timed_searches.store(0, Ordering::Relaxed);
+        self.metrics.total_response_time_ms.store(0, Ordering::Relaxed);
+        self.metrics.min_response_time_ms.store(u64::MAX, Ordering::Relaxed);
+        self.metrics.max_response_time_ms.store(0, Ordering::Relaxed);
+        for bucket in &self.metrics.response_time_buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
     }
     
     /// Clear search cache
@@ -1499,6 +1577,19 @@ pub fn format_search_results(result: &WebSearchResult) -> String {
     output
 }
 
+/// One citation per search result, in ranking order
+pub fn citations_for(results: &[SearchResultItem]) -> Vec<Citation> {
+    results
+        .iter()
+        .map(|result| Citation {
+            url: result.url.clone(),
+            title: result.title.clone(),
+            domain: result.source_domain.clone(),
+            excerpt: result.snippet.clone(),
+        })
+        .collect()
+}
+
 /// Get fallback resources when search fails
 pub fn get_fallback_resources(query: &str) -> Vec<SearchResultItem> {
     vec![
@@ -0,0 +1,181 @@
+use colored::Colorize;
+use ignore::gitignore::Gitignore;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::workspace::{WorkspaceContext, WorkspaceManager};
+
+/// Quiet period before a burst of saves triggers a run
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Editor swap, backup and probe files that should never trigger a run
+const EDITOR_TEMP_PATTERNS: &[&str] = &["*~", "*.swp", "*.swo", "*.swx", ".#*", "#*#", "4913"];
+
+/// Decides which changed paths are worth a re-run, using the workspace ignore rules
+struct ChangeFilter {
+    root: PathBuf,
+    ignore: Gitignore,
+    editor_temp: Gitignore,
+    explicit: Vec<PathBuf>,
+}
+
+impl ChangeFilter {
+    fn new(explicit: Vec<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut manager = WorkspaceManager::new();
+        manager.load_context()?;
+        let current_dir = std::env::current_dir()?.canonicalize()?;
+        // Use the saved workspace's rules when watching inside it
+        let context = manager
+            .get_context()
+            .filter(|context| {
+                context
+                    .root_path
+                    .canonicalize()
+                    .is_ok_and(|root| current_dir.starts_with(root))
+            })
+            .cloned()
+            .unwrap_or_else(|| WorkspaceContext::new(current_dir));
+        let root = context.root_path.canonicalize()?;
+
+        let mut editor_temp = ignore::gitignore::GitignoreBuilder::new(&root);
+        for pattern in EDITOR_TEMP_PATTERNS {
+            editor_temp.add_line(None, pattern)?;
+        }
+
+        Ok(Self {
+            ignore: context.ignore_matcher()?,
+            editor_temp: editor_temp.build()?,
+            root,
+            explicit,
+        })
+    }
+
+    fn is_relevant(&self, path: &Path) -> bool {
+        // A directory's own events just echo changes to the files inside it
+        if path.is_dir() || self.editor_temp.matched(path.file_name().unwrap_or_default(), false).is_ignore() {
+            return false;
+        }
+        // Files named on the command line always count, even if ignored
+        if self.explicit.iter().any(|explicit| explicit == path) {
+            return true;
+        }
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        let hidden = relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+        !hidden && !self.ignore.matched_path_or_any_parents(relative, false).is_ignore()
+    }
+}
+
+fn is_change(kind: &EventKind) -> bool {
+    matches!(kind, EventKind::Create(_) | EventKind::Remove(_))
+        || matches!(kind, EventKind::Modify(modify) if !matches!(modify, notify::event::ModifyKind::Metadata(_)))
+}
+
+/// Run `run` now and again whenever one of `paths` changes, until Ctrl+C.
+/// Rapid saves are debounced into a single run.
+pub async fn watch<F, Fut>(paths: &[String], mut run: F) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), Box<dyn std::error::Error>>>,
+{
+    let watched: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| Path::new(path).canonicalize().map_err(|e| format!("Cannot watch {}: {}", path, e)))
+        .collect::<Result<_, _>>()?;
+    let filter = ChangeFilter::new(watched.iter().filter(|path| path.is_file()).cloned().collect())?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        let _ = tx.send(result);
+    })?;
+    for path in &watched {
+        let mode = if path.is_dir() { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+        watcher.watch(path, mode)?;
+    }
+
+    if !run_until_interrupted(run()).await {
+        println!("\n{} Stopped watching", "⏹️".yellow());
+        return Ok(());
+    }
+
+    loop {
+        println!(
+            "\n{} Watching {} path(s) for changes (Ctrl+C to stop)",
+            "👀".cyan(),
+            watched.len()
+        );
+
+        // Wait for a relevant change, then keep collecting until things go quiet
+        let mut changed: Vec<PathBuf> = Vec::new();
+        loop {
+            let result = if changed.is_empty() {
+                tokio::select! {
+                    result = rx.recv() => result,
+                    _ = crate::client::idle_interrupt() => None,
+                }
+            } else {
+                match tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                    Ok(result) => result,
+                    Err(_) => break,
+                }
+            };
+            let Some(result) = result else {
+                println!("\n{} Stopped watching", "⏹️".yellow());
+                return Ok(());
+            };
+
+            match result {
+                // Reads (including our own) show up as access events
+                Ok(event) if is_change(&event.kind) => changed.extend(
+                    event.paths.into_iter().filter(|path| filter.is_relevant(path)),
+                ),
+                Ok(_) => {}
+                Err(e) => eprintln!("{} Watch error: {}", "⚠️".yellow(), e),
+            }
+        }
+        changed.sort();
+        changed.dedup();
+
+        println!("\n{}", "─".repeat(60).dimmed());
+        println!(
+            "{} {} changed at {}",
+            "🔄".cyan(),
+            changed
+                .iter()
+                .map(|path| path.strip_prefix(&filter.root).unwrap_or(path).display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+                .yellow(),
+            chrono::Local::now().format("%H:%M:%S")
+        );
+        println!("{}\n", "─".repeat(60).dimmed());
+
+        if !run_until_interrupted(run()).await {
+            println!("\n{} Stopped watching", "⏹️".yellow());
+            return Ok(());
+        }
+    }
+}
+
+/// Run one pass, reporting its error. Ctrl+C during a generation cancels just that
+/// response, as it does outside watch mode; at any other point it stops watching.
+/// Returns false if the user asked to stop.
+async fn run_until_interrupted<Fut>(pass: Fut) -> bool
+where
+    Fut: Future<Output = Result<(), Box<dyn std::error::Error>>>,
+{
+    tokio::select! {
+        result = pass => {
+            if let Err(e) = result {
+                eprintln!("{} {}", "❌".red(), e);
+            }
+            true
+        }
+        _ = crate::client::idle_interrupt() => false,
+    }
+}
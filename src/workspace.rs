@@ -1,9 +1,11 @@
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceContext {
@@ -14,8 +16,130 @@ pub struct WorkspaceContext {
     pub metadata: HashMap<String, String>,
     pub created_at: String,
     pub last_updated: String,
+    /// Files larger than this are truncated before being added to the prompt
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: usize,
+    /// Files are dropped once the combined context reaches this size
+    #[serde(default = "default_max_total_context_bytes")]
+    pub max_total_context_bytes: usize,
+    /// Modification time and size of each included file at the last scan, so a
+    /// rescan only re-reads files that changed
+    #[serde(default)]
+    pub file_index: HashMap<PathBuf, FileFingerprint>,
+    /// Projects found by marker files, the root project first
+    #[serde(default)]
+    pub projects: Vec<DetectedProject>,
+    /// Copies of the included files as of the last scan, kept so `workspace diff
+    /// --content` can show what changed. Set by `WorkspaceManager`.
+    #[serde(skip)]
+    pub snapshot_dir: Option<PathBuf>,
 }
 
+/// A project found by its marker file, with the commands to build, test and run it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedProject {
+    /// Directory relative to the workspace root; empty for the root itself
+    pub path: PathBuf,
+    pub project_type: String,
+    pub build_tool: String,
+    pub build_command: Option<String>,
+    pub test_command: Option<String>,
+    pub run_command: Option<String>,
+}
+
+impl DetectedProject {
+    fn new(path: &Path, project_type: &str, build_tool: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            project_type: project_type.to_string(),
+            build_tool: build_tool.to_string(),
+            build_command: None,
+            test_command: None,
+            run_command: None,
+        }
+    }
+
+    fn commands(mut self, build: Option<&str>, test: Option<&str>, run: Option<&str>) -> Self {
+        self.build_command = build.map(str::to_string);
+        self.test_command = test.map(str::to_string);
+        self.run_command = run.map(str::to_string);
+        self
+    }
+
+    /// The commands that were detected, labelled `build`, `test` and `run`
+    pub fn known_commands(&self) -> Vec<(&'static str, &str)> {
+        [("build", &self.build_command), ("test", &self.test_command), ("run", &self.run_command)]
+            .into_iter()
+            .filter_map(|(label, command)| command.as_deref().map(|command| (label, command)))
+            .collect()
+    }
+
+    /// `src/web` prefixes each command with `cd src/web && `
+    pub fn command_in_root(&self, command: &str) -> String {
+        if self.path.as_os_str().is_empty() {
+            command.to_string()
+        } else {
+            format!("cd {} && {}", self.path.display(), command)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFingerprint {
+    /// Nanoseconds since the Unix epoch
+    pub modified: u64,
+    pub size: u64,
+}
+
+impl FileFingerprint {
+    fn of(metadata: &fs::Metadata) -> Self {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |age| age.as_nanos() as u64);
+        Self { modified, size: metadata.len() }
+    }
+}
+
+/// How a rescan compared with the previous one
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanSummary {
+    pub changed: usize,
+    pub unchanged: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Files that changed on disk since the last scan, relative to the workspace root
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorkspaceDiff {
+    pub added: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    pub unchanged: usize,
+}
+
+impl WorkspaceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Metadata key recording when the workspace was last scanned
+const LAST_SCAN_KEY: &str = "last_scan";
+
+fn default_max_file_bytes() -> usize {
+    100 * 1024
+}
+
+fn default_max_total_context_bytes() -> usize {
+    400 * 1024
+}
+
+/// Bytes inspected for a null byte when deciding whether a file is binary
+const BINARY_SNIFF_BYTES: usize = 8000;
+
 impl WorkspaceContext {
     pub fn new(root_path: PathBuf) -> Self {
         let now = chrono::Utc::now().to_rfc3339();
@@ -36,97 +160,33 @@ impl WorkspaceContext {
             metadata: HashMap::new(),
             created_at: now.clone(),
             last_updated: now,
+            max_file_bytes: default_max_file_bytes(),
+            max_total_context_bytes: default_max_total_context_bytes(),
+            file_index: HashMap::new(),
+            projects: Vec::new(),
+            snapshot_dir: None,
         }
     }
 
+    /// Detect the projects in the workspace and record the primary one as `project_type`
     pub fn detect_project_type(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Multi-language project detection with better priority
-        let mut detected_types = Vec::new();
-        
-        // Rust
-        if self.root_path.join("Cargo.toml").exists() {
-            detected_types.push(("rust", "cargo"));
-        }
-        
-        // JavaScript/TypeScript
-        if self.root_path.join("package.json").exists() {
-            detected_types.push(("javascript", "npm"));
-            
-            // Check for TypeScript
-            if self.root_path.join("tsconfig.json").exists() {
-                detected_types.push(("typescript", "npm"));
-            }
-        }
-        
-        // Python
-        if self.root_path.join("pyproject.toml").exists() {
-            detected_types.push(("python", "pip"));
-        } else if self.root_path.join("setup.py").exists() {
-            detected_types.push(("python", "pip"));
-        } else if self.root_path.join("requirements.txt").exists() {
-            detected_types.push(("python", "pip"));
-        }
-        
-        // Go
-        if self.root_path.join("go.mod").exists() {
-            detected_types.push(("go", "go"));
-        }
-        
-        // Java
-        if self.root_path.join("pom.xml").exists() {
-            detected_types.push(("java", "maven"));
-        } else if self.root_path.join("build.gradle").exists() || self.root_path.join("build.gradle.kts").exists() {
-            detected_types.push(("java", "gradle"));
-        }
-        
-        // C/C++
-        if self.root_path.join("CMakeLists.txt").exists() {
-            detected_types.push(("cpp", "cmake"));
-        } else if self.root_path.join("Makefile").exists() {
-            detected_types.push(("c", "make"));
-        }
-        
-        // C#
-        if self.root_path.join("*.sln").exists() || self.root_path.join("*.csproj").exists() {
-            detected_types.push(("csharp", "dotnet"));
-        }
-        
-        // PHP
-        if self.root_path.join("composer.json").exists() {
-            detected_types.push(("php", "composer"));
-        }
-        
-        // Ruby
-        if self.root_path.join("Gemfile").exists() {
-            detected_types.push(("ruby", "bundle"));
-        }
-        
-        // Dart/Flutter
-        if self.root_path.join("pubspec.yaml").exists() {
-            detected_types.push(("dart", "pub"));
-        }
-        
-        // Swift
-        if self.root_path.join("Package.swift").exists() {
-            detected_types.push(("swift", "swift"));
-        }
-        
-        // Kotlin
-        if self.root_path.join("build.gradle.kts").exists() {
-            detected_types.push(("kotlin", "gradle"));
-        }
-        
-        // Select the primary project type (first detected has priority)
-        if let Some((project_type, build_tool)) = detected_types.first() {
-            self.project_type = Some(project_type.to_string());
-            self.metadata.insert("build_tool".to_string(), build_tool.to_string());
+        self.projects = detect_project_types(&self.root_path);
+
+        // Select the primary project type (a project at the root wins)
+        if let Some(primary) = self.projects.first() {
+            self.project_type = Some(primary.project_type.clone());
+            self.metadata.insert("build_tool".to_string(), primary.build_tool.clone());
             
             // Add all detected types as metadata
-            if detected_types.len() > 1 {
-                let additional_types: Vec<String> = detected_types.iter()
-                    .skip(1)
-                    .map(|(t, _)| t.to_string())
-                    .collect();
+            let mut additional_types: Vec<&str> = Vec::new();
+            for project in self.projects.iter().skip(1) {
+                if project.project_type != primary.project_type && !additional_types.contains(&project.project_type.as_str()) {
+                    additional_types.push(&project.project_type);
+                }
+            }
+            if additional_types.is_empty() {
+                self.metadata.remove("additional_types");
+            } else {
                 self.metadata.insert("additional_types".to_string(), additional_types.join(","));
             }
         } else {
@@ -203,64 +263,329 @@ impl WorkspaceContext {
         Ok(())
     }
 
-    pub fn scan_project(&mut self, include_hidden: bool) -> Result<(), Box<dyn std::error::Error>> {
+    /// Walk the workspace and collect text files, skipping anything git would
+    /// ignore (nested `.gitignore`s, `.git/info/exclude` and the global
+    /// gitignore) as well as `excluded_patterns`. `include_ignored` disables
+    /// all of those rules. Files whose modification time and size match the
+    /// last scan are kept without being read again.
+    pub fn scan_project(&mut self, include_hidden: bool, include_ignored: bool) -> Result<ScanSummary, Box<dyn std::error::Error>> {
         println!("{} Scanning project at: {}", "🔍".cyan(), self.root_path.display());
         
         self.included_files.clear();
-        
-        for entry in WalkDir::new(&self.root_path)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if !entry.file_type().is_file() {
+        let mut previous = std::mem::take(&mut self.file_index);
+        let mut summary = ScanSummary::default();
+
+        let walker = self.walker(&self.root_path, include_hidden, include_ignored)?;
+        for entry in walker.build().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
                 continue;
             }
 
             let path = entry.path();
-            let relative_path = path.strip_prefix(&self.root_path)?;
-            
-            // Skip hidden files unless specified
-            if !include_hidden && self.is_hidden_file(relative_path) {
+            let relative_path = path.strip_prefix(&self.root_path)?.to_path_buf();
+
+            // Only include text files
+            if !self.is_text_file(path) {
                 continue;
             }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let fingerprint = FileFingerprint::of(&metadata);
+
+            match previous.remove(&relative_path) {
+                Some(known) if known == fingerprint => summary.unchanged += 1,
+                known => {
+                    // New or modified: sniff the content, since a text extension can still hold binary data
+                    if is_binary(path).unwrap_or(true) {
+                        if known.is_some() {
+                            summary.removed += 1;
+                            self.drop_snapshot(&relative_path);
+                        }
+                        continue;
+                    }
+                    if known.is_some() {
+                        summary.changed += 1;
+                    } else {
+                        summary.added += 1;
+                    }
+                    self.store_snapshot(&relative_path);
+                }
+            }
+
+            self.file_index.insert(relative_path.clone(), fingerprint);
+            self.included_files.push(relative_path);
+        }
+        summary.removed += previous.len();
+        for relative_path in previous.keys() {
+            self.drop_snapshot(relative_path);
+        }
+
+        self.included_files.sort();
+        self.last_updated = chrono::Utc::now().to_rfc3339();
+        self.metadata.insert(LAST_SCAN_KEY.to_string(), self.last_updated.clone());
+        println!(
+            "{} Found {} files: {} changed, {} unchanged, {} new, {} removed",
+            "✅".green(),
+            self.included_files.len(),
+            summary.changed,
+            summary.unchanged,
+            summary.added,
+            summary.removed
+        );
+        
+        Ok(summary)
+    }
 
-            // Skip excluded patterns
-            if self.should_exclude(relative_path) {
+    /// Compare the included files with their fingerprints from the last scan.
+    /// Files whose time or size changed but whose content matches the snapshot
+    /// count as unchanged. New files are looked for with the default scan rules,
+    /// and only once the workspace has been scanned.
+    pub fn diff_since_scan(&self) -> Result<WorkspaceDiff, Box<dyn std::error::Error>> {
+        let mut diff = WorkspaceDiff::default();
+
+        for relative_path in &self.included_files {
+            let path = self.root_path.join(relative_path);
+            let Ok(metadata) = fs::metadata(&path) else {
+                diff.removed.push(relative_path.clone());
                 continue;
+            };
+            match self.file_index.get(relative_path) {
+                Some(known) if *known == FileFingerprint::of(&metadata) => diff.unchanged += 1,
+                Some(_) => match (self.snapshot(relative_path), fs::read_to_string(&path)) {
+                    (Some(old), Ok(new)) if old == new => diff.unchanged += 1,
+                    _ => diff.modified.push(relative_path.clone()),
+                },
+                // Included before fingerprints were recorded
+                None => diff.modified.push(relative_path.clone()),
             }
+        }
 
-            // Only include text files
-            if self.is_text_file(path) {
-                self.included_files.push(relative_path.to_path_buf());
+        if self.metadata.contains_key(LAST_SCAN_KEY) {
+            let included: HashSet<&PathBuf> = self.included_files.iter().collect();
+            for entry in self.walker(&self.root_path, false, false)?.build().filter_map(|e| e.ok()) {
+                if !entry.file_type().is_some_and(|t| t.is_file()) || !self.is_text_file(entry.path()) {
+                    continue;
+                }
+                let relative_path = entry.path().strip_prefix(&self.root_path)?.to_path_buf();
+                if !included.contains(&relative_path) && !is_binary(entry.path()).unwrap_or(true) {
+                    diff.added.push(relative_path);
+                }
             }
+            diff.added.sort();
         }
+        Ok(diff)
+    }
+
+    /// The copy of `relative_path` taken at the last scan, if one was kept
+    pub fn snapshot(&self, relative_path: &Path) -> Option<String> {
+        let dir = self.snapshot_dir.as_ref()?;
+        fs::read_to_string(dir.join(relative_path)).ok()
+    }
+
+    /// Copy `relative_path` into the snapshot directory; files over
+    /// `max_file_bytes` aren't kept
+    fn store_snapshot(&self, relative_path: &Path) {
+        let Some(dir) = &self.snapshot_dir else {
+            return;
+        };
+        let source = self.root_path.join(relative_path);
+        let target = dir.join(relative_path);
+        let copied = fs::metadata(&source).is_ok_and(|metadata| metadata.len() as usize <= self.max_file_bytes)
+            && target.parent().is_some_and(|parent| fs::create_dir_all(parent).is_ok())
+            && fs::copy(&source, &target).is_ok();
+        if !copied {
+            // A stale copy would make the next diff misleading
+            let _ = fs::remove_file(&target);
+        }
+    }
+
+    fn drop_snapshot(&self, relative_path: &Path) {
+        if let Some(dir) = &self.snapshot_dir {
+            let _ = fs::remove_file(dir.join(relative_path));
+        }
+    }
+
+    /// Recursive walk of `dir` applying the ignore rules described on `scan_project`
+    fn walker(&self, dir: &Path, include_hidden: bool, include_ignored: bool) -> Result<WalkBuilder, Box<dyn std::error::Error>> {
+        let mut builder = WalkBuilder::new(dir);
+        builder
+            .standard_filters(!include_ignored)
+            .hidden(!include_hidden)
+            .require_git(false)
+            .follow_links(false);
 
+        let extra_ignore = if include_ignored {
+            None
+        } else {
+            Some(self.build_extra_ignore()?)
+        };
+        builder.filter_entry(move |entry| {
+            if entry.file_name() == ".git" {
+                return false;
+            }
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            extra_ignore.as_ref().is_none_or(|ignore| {
+                !ignore.matched_path_or_any_parents(entry.path(), is_dir).is_ignore()
+            })
+        });
+        Ok(builder)
+    }
+
+    /// Compile `excluded_patterns` (gitignore syntax) relative to the workspace root.
+    fn build_extra_ignore(&self) -> Result<Gitignore, Box<dyn std::error::Error>> {
+        let mut builder = GitignoreBuilder::new(&self.root_path);
+        for pattern in &self.excluded_patterns {
+            builder.add_line(None, pattern)?;
+        }
+        Ok(builder.build()?)
+    }
+
+    /// The ignore rules `scan_project` applies, as a matcher for single paths:
+    /// the root `.gitignore`, `.git/info/exclude` and `excluded_patterns`.
+    pub fn ignore_matcher(&self) -> Result<Gitignore, Box<dyn std::error::Error>> {
+        let mut builder = GitignoreBuilder::new(&self.root_path);
+        for file in [".gitignore", ".git/info/exclude"] {
+            let path = self.root_path.join(file);
+            if path.exists() {
+                if let Some(e) = builder.add(path) {
+                    return Err(e.into());
+                }
+            }
+        }
+        for pattern in &self.excluded_patterns {
+            builder.add_line(None, pattern)?;
+        }
+        Ok(builder.build()?)
+    }
+
+    pub fn add_ignore_patterns(&mut self, patterns: &[String]) {
+        for pattern in patterns {
+            if !self.excluded_patterns.contains(pattern) {
+                self.excluded_patterns.push(pattern.clone());
+            }
+        }
+        self.last_updated = chrono::Utc::now().to_rfc3339();
+    }
+
+    pub fn remove_ignore_patterns(&mut self, patterns: &[String]) {
+        self.excluded_patterns.retain(|p| !patterns.contains(p));
         self.last_updated = chrono::Utc::now().to_rfc3339();
-        println!("{} Found {} files", "✅".green(), self.included_files.len());
-        
-        Ok(())
     }
 
+    /// Include files, directories or glob patterns (`src/*.rs`, `**/*.toml`).
+    /// Directories are walked recursively and glob matches filtered with the
+    /// same ignore rules as `scan_project`; in both cases binary files and files
+    /// over `max_file_bytes` are left out. Named files are always included.
     pub fn add_files(&mut self, files: &[String]) -> Result<(), Box<dyn std::error::Error>> {
-        for file in files {
-            let path = Path::new(file);
-            if path.exists() {
-                let relative_path = if path.is_absolute() {
-                    path.strip_prefix(&self.root_path)?.to_path_buf()
-                } else {
-                    path.to_path_buf()
-                };
-                
+        for pattern in files {
+            let path = Path::new(pattern);
+            let is_glob = pattern.contains(['*', '?', '[']);
+
+            let (matched, skipped) = if path.is_dir() {
+                // Checked first: the ignore matchers only accept paths inside the workspace
+                self.relative_to_root(path)?;
+                self.expand_directory(path)?
+            } else if is_glob {
+                self.expand_glob(pattern)?
+            } else if path.is_file() {
+                (vec![self.relative_to_root(path)?], 0)
+            } else {
+                println!("{} No such file: {}", "⚠️".yellow(), pattern);
+                continue;
+            };
+
+            let count = matched.len();
+            for relative_path in matched {
                 if !self.included_files.contains(&relative_path) {
+                    // Fingerprint it so `workspace diff` can tell when it changes
+                    if let Ok(metadata) = fs::metadata(self.root_path.join(&relative_path)) {
+                        self.file_index.insert(relative_path.clone(), FileFingerprint::of(&metadata));
+                        self.store_snapshot(&relative_path);
+                    }
                     self.included_files.push(relative_path);
                 }
             }
+
+            if path.is_dir() || is_glob {
+                let skipped_note = if skipped > 0 {
+                    format!(" ({} skipped: binary or over {} KB)", skipped, self.max_file_bytes / 1024)
+                } else {
+                    String::new()
+                };
+                if count == 0 {
+                    println!("{} {} matched no files{}", "⚠️".yellow(), pattern, skipped_note);
+                } else {
+                    println!("{} {} matched {} file(s){}", "📎".cyan(), pattern, count, skipped_note);
+                }
+            }
         }
         self.last_updated = chrono::Utc::now().to_rfc3339();
         Ok(())
     }
 
+    /// Text files under `dir`, and how many were skipped as binary or too large
+    fn expand_directory(&self, dir: &Path) -> Result<(Vec<PathBuf>, usize), Box<dyn std::error::Error>> {
+        let mut files = Vec::new();
+        let mut skipped = 0;
+        for entry in self.walker(dir, false, false)?.build().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_some_and(|t| t.is_file()) || !self.is_text_file(entry.path()) {
+                continue;
+            }
+            if self.fits_context(entry.path()) {
+                files.push(self.relative_to_root(entry.path())?);
+            } else {
+                skipped += 1;
+            }
+        }
+        files.sort();
+        Ok((files, skipped))
+    }
+
+    fn expand_glob(&self, pattern: &str) -> Result<(Vec<PathBuf>, usize), Box<dyn std::error::Error>> {
+        let ignore = self.ignore_matcher()?;
+        let mut files = Vec::new();
+        let mut skipped = 0;
+
+        for path in glob::glob(pattern)?.filter_map(|entry| entry.ok()) {
+            let relative_path = self.relative_to_root(&path)?;
+            if ignore.matched_path_or_any_parents(&relative_path, path.is_dir()).is_ignore() {
+                continue;
+            }
+            if path.is_dir() {
+                let (nested, nested_skipped) = self.expand_directory(&path)?;
+                files.extend(nested);
+                skipped += nested_skipped;
+            } else if path.is_file() {
+                if self.fits_context(&path) {
+                    files.push(relative_path);
+                } else {
+                    skipped += 1;
+                }
+            }
+        }
+        files.sort();
+        files.dedup();
+        Ok((files, skipped))
+    }
+
+    /// Whether a file found by a directory or glob is small enough and not binary
+    fn fits_context(&self, path: &Path) -> bool {
+        let small = fs::metadata(path).is_ok_and(|metadata| metadata.len() as usize <= self.max_file_bytes);
+        small && !is_binary(path).unwrap_or(true)
+    }
+
+    fn relative_to_root(&self, path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        if path.is_absolute() {
+            return path
+                .strip_prefix(&self.root_path)
+                .map(Path::to_path_buf)
+                .map_err(|_| format!("{} is outside the workspace {}", path.display(), self.root_path.display()).into());
+        }
+        // Drop `./` so the same file isn't included twice under two spellings
+        Ok(path.components().filter(|c| !matches!(c, std::path::Component::CurDir)).collect())
+    }
+
     pub fn remove_files(&mut self, files: &[String]) -> Result<(), Box<dyn std::error::Error>> {
         for file in files {
             let path = Path::new(file);
@@ -271,25 +596,77 @@ impl WorkspaceContext {
             };
             
             self.included_files.retain(|p| p != &relative_path);
+            self.file_index.remove(&relative_path);
+            self.drop_snapshot(&relative_path);
         }
         self.last_updated = chrono::Utc::now().to_rfc3339();
         Ok(())
     }
 
+    /// Read the included files for the prompt, enforcing `max_file_bytes` and
+    /// `max_total_context_bytes`. Oversized files are truncated with a note,
+    /// binary files are skipped, and files past the total budget are dropped.
     pub fn get_file_contents(&self) -> Result<HashMap<PathBuf, String>, Box<dyn std::error::Error>> {
         let mut contents = HashMap::new();
+        let mut total_bytes = 0usize;
+        let mut dropped = Vec::new();
         
         for file_path in &self.included_files {
             let full_path = self.root_path.join(file_path);
-            if full_path.exists() {
-                match fs::read_to_string(&full_path) {
-                    Ok(content) => {
-                        contents.insert(file_path.clone(), content);
-                    }
-                    Err(e) => {
-                        eprintln!("{} Failed to read {}: {}", "⚠️".yellow(), file_path.display(), e);
-                    }
+            if !full_path.exists() {
+                continue;
+            }
+
+            let bytes = match fs::read(&full_path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    eprintln!("{} Failed to read {}: {}", "⚠️".yellow(), file_path.display(), e);
+                    continue;
                 }
+            };
+
+            if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+                println!("{} Skipping binary file: {}", "⚠️".yellow(), file_path.display());
+                continue;
+            }
+
+            let content = if bytes.len() > self.max_file_bytes {
+                println!(
+                    "{} Truncating {} ({} bytes, limit {})",
+                    "✂️".yellow(),
+                    file_path.display(),
+                    bytes.len(),
+                    self.max_file_bytes
+                );
+                let mut truncated = utf8_prefix(&bytes, self.max_file_bytes);
+                truncated.push_str(&format!(
+                    "\n\n[... truncated: file is {} bytes, only the first {} bytes are included ...]\n",
+                    bytes.len(),
+                    self.max_file_bytes
+                ));
+                truncated
+            } else {
+                String::from_utf8_lossy(&bytes).into_owned()
+            };
+
+            if total_bytes + content.len() > self.max_total_context_bytes {
+                dropped.push(file_path.clone());
+                continue;
+            }
+
+            total_bytes += content.len();
+            contents.insert(file_path.clone(), content);
+        }
+
+        if !dropped.is_empty() {
+            println!(
+                "{} Context budget of {} bytes reached; dropped {} file(s):",
+                "⚠️".yellow(),
+                self.max_total_context_bytes,
+                dropped.len()
+            );
+            for path in &dropped {
+                println!("  - {}", path.display());
             }
         }
         
@@ -308,21 +685,26 @@ impl WorkspaceContext {
         Ok(context)
     }
 
-    fn is_hidden_file(&self, path: &Path) -> bool {
-        path.components().any(|comp| {
-            comp.as_os_str().to_string_lossy().starts_with('.')
-        })
-    }
+    /// Included files grouped by language, most lines first; missing files are skipped
+    pub fn language_breakdown(&self) -> Vec<LanguageStats> {
+        let mut by_language: HashMap<&'static str, LanguageStats> = HashMap::new();
+        for file in &self.included_files {
+            let Ok(bytes) = fs::read(self.root_path.join(file)) else {
+                continue;
+            };
+            let language = language_for_path(file);
+            let stats = by_language.entry(language).or_insert_with(|| LanguageStats {
+                language: language.to_string(),
+                ..LanguageStats::default()
+            });
+            stats.files += 1;
+            stats.bytes += bytes.len() as u64;
+            stats.lines += count_lines(&bytes);
+        }
 
-    fn should_exclude(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
-        self.excluded_patterns.iter().any(|pattern| {
-            if let Ok(glob_pattern) = glob::Pattern::new(pattern) {
-                glob_pattern.matches(&path_str)
-            } else {
-                path_str.contains(pattern)
-            }
-        })
+        let mut languages: Vec<LanguageStats> = by_language.into_values().collect();
+        languages.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.language.cmp(&b.language)));
+        languages
     }
 
     fn is_text_file(&self, path: &Path) -> bool {
@@ -365,10 +747,10 @@ pub struct WorkspaceManager {
 
 impl WorkspaceManager {
     pub fn new() -> Self {
-        let context_file = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("ollama-cli-assistant")
-            .join("workspace.json");
+        let context_file = crate::paths::data_dir().join("workspace.json");
+        if let Err(e) = Self::migrate_legacy_context(&context_file) {
+            eprintln!("{} Failed to move the old workspace context: {}", "⚠️".yellow(), e);
+        }
         
         Self {
             context: None,
@@ -376,6 +758,30 @@ impl WorkspaceManager {
         }
     }
 
+    /// Move `workspace.json` from the OS config directory, where earlier versions kept
+    /// it, unless the data directory already has one. The old file is renamed, not deleted.
+    fn migrate_legacy_context(context_file: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let legacy_path = dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("ollama-cli-assistant")
+            .join("workspace.json");
+        if !legacy_path.exists() || context_file.exists() {
+            return Ok(());
+        }
+
+        if let Some(parent) = context_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&legacy_path, context_file)?;
+        fs::rename(&legacy_path, legacy_path.with_extension("json.migrated"))?;
+        println!(
+            "{} Moved workspace context to {}",
+            "📦".cyan(),
+            context_file.display()
+        );
+        Ok(())
+    }
+
     pub fn init_workspace(&mut self, path: Option<String>, project_type: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
         let workspace_path = if let Some(path) = path {
             PathBuf::from(path)
@@ -387,12 +793,18 @@ impl WorkspaceManager {
             return Err("Workspace path does not exist".into());
         }
 
+        // A new workspace starts without snapshots from the previous one
+        let snapshot_dir = self.snapshot_dir();
+        if snapshot_dir.exists() {
+            fs::remove_dir_all(&snapshot_dir)?;
+        }
         let mut context = WorkspaceContext::new(workspace_path);
+        context.snapshot_dir = Some(snapshot_dir);
         
+        // Detect even with an explicit type so the build/test/run commands are known
+        context.detect_project_type()?;
         if let Some(proj_type) = project_type {
             context.project_type = Some(proj_type);
-        } else {
-            context.detect_project_type()?;
         }
 
         // Create config directory if it doesn't exist
@@ -408,13 +820,56 @@ impl WorkspaceManager {
         Ok(())
     }
 
+    /// Use the saved context if it belongs to `path` (default: the current
+    /// directory), otherwise initialize a new one there
+    pub fn open_workspace(&mut self, path: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let root = match &path {
+            Some(path) => PathBuf::from(path),
+            None => std::env::current_dir()?,
+        };
+        // An unreadable context is replaced rather than reported
+        if self.load_context().is_err() {
+            self.context = None;
+        }
+
+        let root = fs::canonicalize(&root).ok();
+        let same_root = self.context.as_ref().is_some_and(|context| {
+            root.is_some() && fs::canonicalize(&context.root_path).ok() == root
+        });
+        if !same_root {
+            self.init_workspace(path, None)?;
+        }
+        Ok(())
+    }
+
+    /// Open the workspace for the current directory, rescan it (only changed
+    /// files are re-read) and save the result
+    pub fn refresh_context(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.open_workspace(None)?;
+        if let Some(context) = self.context.as_mut() {
+            // Contexts saved before project commands were recorded
+            if context.projects.is_empty() {
+                context.projects = detect_project_types(&context.root_path);
+            }
+            context.scan_project(false, false)?;
+        }
+        self.save_context()
+    }
+
     pub fn load_context(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if self.context_file.exists() {
-            self.context = Some(WorkspaceContext::load_from_file(&self.context_file)?);
+            let mut context = WorkspaceContext::load_from_file(&self.context_file)?;
+            context.snapshot_dir = Some(self.snapshot_dir());
+            self.context = Some(context);
         }
         Ok(())
     }
 
+    /// Where copies of the scanned files are kept, next to the saved context
+    fn snapshot_dir(&self) -> PathBuf {
+        self.context_file.with_file_name("workspace-snapshot")
+    }
+
     pub fn get_context(&self) -> Option<&WorkspaceContext> {
         self.context.as_ref()
     }
@@ -435,6 +890,286 @@ impl WorkspaceManager {
         if self.context_file.exists() {
             fs::remove_file(&self.context_file)?;
         }
+        let snapshot_dir = self.snapshot_dir();
+        if snapshot_dir.exists() {
+            fs::remove_dir_all(&snapshot_dir)?;
+        }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Directory levels below the root searched for nested projects (monorepos)
+const PROJECT_SEARCH_DEPTH: usize = 2;
+
+/// Find projects by their marker files in `root` and up to two directory levels
+/// below it, skipping gitignored, hidden and dependency directories. The root's
+/// projects come first.
+pub fn detect_project_types(root: &Path) -> Vec<DetectedProject> {
+    let mut projects = projects_in(root, Path::new(""));
+
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(PROJECT_SEARCH_DEPTH))
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            !matches!(name.as_ref(), "node_modules" | "target" | "vendor" | "build" | "dist" | "venv" | ".venv")
+        })
+        .build();
+    let mut dirs: Vec<PathBuf> = walker
+        .flatten()
+        .filter(|entry| entry.depth() > 0 && entry.file_type().is_some_and(|t| t.is_dir()))
+        .map(|entry| entry.into_path())
+        .collect();
+    dirs.sort();
+    for dir in dirs {
+        let relative = dir.strip_prefix(root).unwrap_or(&dir).to_path_buf();
+        projects.extend(projects_in(&dir, &relative));
+    }
+
+    projects
+}
+
+/// File count, line count and size of one language's files in the workspace
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: usize,
+    pub lines: usize,
+    pub bytes: u64,
+}
+
+/// Language of a file, from its extension or a well-known file name
+pub fn language_for_path(path: &Path) -> &'static str {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    match name {
+        "Dockerfile" => return "Dockerfile",
+        "Makefile" | "makefile" | "GNUmakefile" => return "Makefile",
+        "CMakeLists.txt" => return "CMake",
+        _ => {}
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    match extension.as_str() {
+        "rs" => "Rust",
+        "py" | "pyi" => "Python",
+        "js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+        "ts" | "mts" | "cts" | "tsx" => "TypeScript",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => "C++",
+        "cs" => "C#",
+        "php" => "PHP",
+        "rb" => "Ruby",
+        "swift" => "Swift",
+        "dart" => "Dart",
+        "scala" => "Scala",
+        "clj" | "cljs" => "Clojure",
+        "sh" | "bash" | "zsh" | "fish" => "Shell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "md" | "markdown" => "Markdown",
+        "json" => "JSON",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "xml" => "XML",
+        "gradle" => "Gradle",
+        _ => "Other",
+    }
+}
+
+/// Lines in a file, counting a final line without a trailing newline
+fn count_lines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&byte| byte == b'\n').count();
+    newlines + usize::from(bytes.last().is_some_and(|&byte| byte != b'\n'))
+}
+
+fn projects_in(dir: &Path, relative: &Path) -> Vec<DetectedProject> {
+    let has = |name: &str| dir.join(name).exists();
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap_or_default();
+    let mut projects = Vec::new();
+
+    // Rust
+    if has("Cargo.toml") {
+        let manifest = read("Cargo.toml");
+        let runnable = has("src/main.rs") || manifest.contains("[[bin]]");
+        projects.push(
+            DetectedProject::new(relative, "rust", "cargo")
+                .commands(Some("cargo build"), Some("cargo test"), runnable.then_some("cargo run")),
+        );
+    }
+
+    // JavaScript/TypeScript
+    if has("package.json") {
+        let package: serde_json::Value = serde_json::from_str(&read("package.json")).unwrap_or_default();
+        let scripts = &package["scripts"];
+        let manager = if has("pnpm-lock.yaml") {
+            "pnpm"
+        } else if has("yarn.lock") {
+            "yarn"
+        } else if has("bun.lockb") || has("bun.lock") {
+            "bun"
+        } else {
+            "npm"
+        };
+        let script = |name: &str| scripts.get(name).map(|_| format!("{} run {}", manager, name));
+        let test = scripts.get("test").map(|_| format!("{} test", manager));
+        let run = scripts
+            .get("start")
+            .map(|_| format!("{} start", manager))
+            .or_else(|| script("dev"));
+        let project_type = if has("tsconfig.json") { "typescript" } else { "javascript" };
+        projects.push(DetectedProject::new(relative, project_type, manager).commands(
+            script("build").as_deref(),
+            test.as_deref(),
+            run.as_deref(),
+        ));
+    }
+
+    // Python
+    if has("pyproject.toml") || has("setup.py") || has("requirements.txt") {
+        let pyproject = read("pyproject.toml");
+        let (tool, prefix, install) = if pyproject.contains("[tool.poetry]") {
+            ("poetry", "poetry run ", "poetry install")
+        } else if has("uv.lock") {
+            ("uv", "uv run ", "uv sync")
+        } else if has("requirements.txt") {
+            ("pip", "", "pip install -r requirements.txt")
+        } else {
+            ("pip", "", "pip install -e .")
+        };
+        let run = if has("manage.py") {
+            Some(format!("{}python manage.py runserver", prefix))
+        } else {
+            ["main.py", "app.py"]
+                .into_iter()
+                .find(|file| has(file))
+                .map(|file| format!("{}python {}", prefix, file))
+        };
+        projects.push(DetectedProject::new(relative, "python", tool).commands(
+            Some(install),
+            Some(&format!("{}pytest", prefix)),
+            run.as_deref(),
+        ));
+    }
+
+    // Go
+    if has("go.mod") {
+        projects.push(
+            DetectedProject::new(relative, "go", "go")
+                .commands(Some("go build ./..."), Some("go test ./..."), Some("go run .")),
+        );
+    }
+
+    // Java/Kotlin
+    if has("pom.xml") {
+        projects.push(
+            DetectedProject::new(relative, "java", "maven").commands(Some("mvn package"), Some("mvn test"), None),
+        );
+    } else if has("build.gradle") || has("build.gradle.kts") {
+        let gradle = if has("gradlew") { "./gradlew" } else { "gradle" };
+        let project_type = if has("build.gradle.kts") && has("src/main/kotlin") { "kotlin" } else { "java" };
+        let run = (read("build.gradle") + &read("build.gradle.kts")).contains("application");
+        projects.push(DetectedProject::new(relative, project_type, "gradle").commands(
+            Some(&format!("{} build", gradle)),
+            Some(&format!("{} test", gradle)),
+            run.then(|| format!("{} run", gradle)).as_deref(),
+        ));
+    }
+
+    // C/C++
+    if has("CMakeLists.txt") {
+        projects.push(DetectedProject::new(relative, "cpp", "cmake").commands(
+            Some("cmake -B build && cmake --build build"),
+            Some("ctest --test-dir build"),
+            None,
+        ));
+    } else if has("Makefile") {
+        let has_test_target = read("Makefile").lines().any(|line| line.starts_with("test:"));
+        projects.push(
+            DetectedProject::new(relative, "c", "make")
+                .commands(Some("make"), has_test_target.then_some("make test"), None),
+        );
+    }
+
+    // C#
+    let dotnet = fs::read_dir(dir).into_iter().flatten().flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        name.ends_with(".sln") || name.ends_with(".csproj")
+    });
+    if dotnet {
+        projects.push(
+            DetectedProject::new(relative, "csharp", "dotnet")
+                .commands(Some("dotnet build"), Some("dotnet test"), Some("dotnet run")),
+        );
+    }
+
+    // PHP
+    if has("composer.json") {
+        let composer: serde_json::Value = serde_json::from_str(&read("composer.json")).unwrap_or_default();
+        let test = if composer["scripts"].get("test").is_some() { "composer test" } else { "vendor/bin/phpunit" };
+        projects.push(
+            DetectedProject::new(relative, "php", "composer").commands(Some("composer install"), Some(test), None),
+        );
+    }
+
+    // Ruby
+    if has("Gemfile") {
+        let test = if has("spec") { "bundle exec rspec" } else { "bundle exec rake test" };
+        let run = has("config.ru").then_some("bundle exec rackup");
+        projects.push(
+            DetectedProject::new(relative, "ruby", "bundle").commands(Some("bundle install"), Some(test), run),
+        );
+    }
+
+    // Dart/Flutter
+    if has("pubspec.yaml") {
+        let project = if read("pubspec.yaml").contains("flutter:") {
+            DetectedProject::new(relative, "dart", "flutter")
+                .commands(Some("flutter build"), Some("flutter test"), Some("flutter run"))
+        } else {
+            DetectedProject::new(relative, "dart", "pub")
+                .commands(Some("dart pub get"), Some("dart test"), Some("dart run"))
+        };
+        projects.push(project);
+    }
+
+    // Swift
+    if has("Package.swift") {
+        projects.push(
+            DetectedProject::new(relative, "swift", "swift")
+                .commands(Some("swift build"), Some("swift test"), Some("swift run")),
+        );
+    }
+
+    projects
+}
+
+/// Whether the file is binary, by the same heuristic as git: a null byte among its
+/// first [`BINARY_SNIFF_BYTES`] bytes
+pub(crate) fn is_binary(path: &Path) -> std::io::Result<bool> {
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    fs::File::open(path)?
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)?;
+    Ok(head.contains(&0))
+}
+
+/// Decode at most `limit` bytes, backing off to the last complete UTF-8 character.
+fn utf8_prefix(bytes: &[u8], limit: usize) -> String {
+    let slice = &bytes[..limit.min(bytes.len())];
+    match std::str::from_utf8(slice) {
+        Ok(text) => text.to_string(),
+        Err(e) if e.error_len().is_none() => {
+            String::from_utf8_lossy(&slice[..e.valid_up_to()]).into_owned()
+        }
+        Err(_) => String::from_utf8_lossy(slice).into_owned(),
+    }
+}